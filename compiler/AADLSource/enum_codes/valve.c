@@ -0,0 +1,32 @@
+#include <stdio.h>
+#include "valve.h"
+
+static uint8_t valve_state = VALVE_CLOSED;
+static int cycle = 0;
+
+/* 模拟阀门：依次打开、全开，第 8 个周期出现故障 */
+void read_valve(uint8_t* state)
+{
+  cycle++;
+  if (cycle % 8 == 0) {
+    valve_state = VALVE_FAULT;
+  } else if (valve_state == VALVE_CLOSED) {
+    valve_state = VALVE_OPENING;
+  } else if (valve_state == VALVE_OPENING) {
+    valve_state = VALVE_OPEN;
+  }
+  *state = valve_state;
+}
+
+void command_valve(uint8_t state)
+{
+  printf("[valve] command for state 0x%02X\n", state);
+  if (state == VALVE_FAULT) {
+    valve_state = VALVE_CLOSED;
+  }
+}
+
+void raise_alarm(int level)
+{
+  printf("[alarm] level %d\n", level);
+}
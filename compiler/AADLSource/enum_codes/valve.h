@@ -0,0 +1,16 @@
+#ifndef _VALVE_H_
+#define _VALVE_H_
+
+#include <stdint.h>
+
+/* 阀门状态编码，与 AADL 中 Valve_State 的 Representation 一致 */
+#define VALVE_CLOSED  0x00
+#define VALVE_OPENING 0x01
+#define VALVE_OPEN    0x02
+#define VALVE_FAULT   0xFF
+
+void read_valve(uint8_t* state);
+void command_valve(uint8_t state);
+void raise_alarm(int level);
+
+#endif /* _VALVE_H_ */
@@ -0,0 +1,546 @@
+// 数据组件的二进制编解码
+// 为数据组件生成的类型实现 crate::codec::Codec（运行时由工程组装时生成的 codec.rs 提供）：
+//   结构体按字段顺序编码；枚举编码变体序号，位数取容纳全部枚举值的最小位数，Data_Size 更大时取 Data_Size，
+//   给出 Representation 的枚举改为编码判别值；
+//   标记联合体先编码变体序号再编码负载；联合体没有标记，按内存字节原样编码。
+// 整数字段的位宽取自字段数据类型的 Data_Size，字节序取自 Byte_Order：字段数据类型上的优先，
+// 其次是所在的数据组件，缺省为大端。
+// 定点数新类型按存储的整数表示编码。
+// 数据类型映射为整数、浮点别名时不能实现 trait，位宽或字节序与 Rust 类型的缺省编码不同时
+// 生成 encode_<类型名>/decode_<类型名> 函数；数组别名由运行时对 [T; N]、Vec<T> 的实现按元素依次编码，
+// 有界字符串（heapless::String<N>）与 String 一样先编码长度。
+// 每个类型同时生成 crate::codec::Sample 样例值，以及由 convert_package 汇总到包内 codec_tests 模块的往返测试。
+use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::intermediate_ast::*;
+use crate::aadl_ast2rust_code::property_lookup::PropertyLookup;
+use crate::ast::aadl_ast_cj::*;
+use crate::diagnostics::{self, codes};
+
+// 生成代码中的运行时类型
+const WRITER: &str = "crate::codec::BitWriter";
+const READER: &str = "crate::codec::BitReader";
+const ERROR: &str = "crate::codec::CodecError";
+
+// 数据组件的来源：类型声明（属性中的 Base_Type/Element_Names）或实现（数据子组件）
+pub enum DataSource<'a> {
+    Type(&'a ComponentType),
+    Impl(&'a ComponentImplementation),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ByteOrder {
+    Big,
+    Little,
+}
+
+impl ByteOrder {
+    fn path(self) -> &'static str {
+        match self {
+            ByteOrder::Big => "crate::codec::ByteOrder::BigEndian",
+            ByteOrder::Little => "crate::codec::ByteOrder::LittleEndian",
+        }
+    }
+}
+
+fn byte_order(lookup: &PropertyLookup) -> Option<ByteOrder> {
+    lookup.enumeration("Byte_Order").map(|order| {
+        if order.eq_ignore_ascii_case("little_endian") {
+            ByteOrder::Little
+        } else {
+            ByteOrder::Big
+        }
+    })
+}
+
+// 整数或浮点标量的编码方式
+#[derive(Debug, Clone)]
+struct Scalar {
+    rust: String,
+    bits: u32,
+    natural: u32, // Rust 类型的缺省位宽
+    signed: bool,
+    float: bool,
+    order: ByteOrder,
+}
+
+impl Scalar {
+    fn of(rust: &str) -> Option<Self> {
+        let (natural, signed, float) = match rust {
+            "i8" => (8, true, false),
+            "i16" => (16, true, false),
+            "i32" => (32, true, false),
+            "i64" | "isize" => (64, true, false),
+            "u8" => (8, false, false),
+            "u16" => (16, false, false),
+            "u32" => (32, false, false),
+            "u64" | "usize" => (64, false, false),
+            "f32" => (32, true, true),
+            "f64" => (64, true, true),
+            _ => return None,
+        };
+        Some(Self { rust: rust.to_string(), bits: natural, natural, signed, float, order: ByteOrder::Big })
+    }
+
+    // 按数据类型上的 Data_Size 与 Byte_Order 调整；浮点只能按 IEEE 754 的位宽编码
+    fn with_properties(mut self, lookup: &PropertyLookup, name: &str, default_order: ByteOrder) -> Self {
+        self.order = byte_order(lookup).unwrap_or(default_order);
+        match lookup.size_bits("Data_Size") {
+            Some(bits) if !self.float && (1..=64).contains(&bits) => self.bits = bits as u32,
+            Some(bits) if bits != u64::from(self.natural) => diagnostics::warning(
+                codes::UNENCODABLE_DATA_SIZE,
+                format!("data {}: Data_Size {} bits cannot be encoded as {}, using {} bits", name, bits, self.rust, self.natural),
+            ),
+            _ => {}
+        }
+        self
+    }
+
+    // 与 Rust 类型的缺省编码（自然位宽、大端）不同，需要逐字段展开
+    fn is_custom(&self) -> bool {
+        self.bits != self.natural || self.order != ByteOrder::Big
+    }
+
+    fn encode(&self, value: &str) -> String {
+        if self.float {
+            format!("w.write_unsigned({}.to_bits() as u64, {}, {})?", value, self.bits, self.order.path())
+        } else if self.signed {
+            format!("w.write_signed({} as i64, {}, {})?", value, self.bits, self.order.path())
+        } else {
+            format!("w.write_unsigned({} as u64, {}, {})?", value, self.bits, self.order.path())
+        }
+    }
+
+    fn decode(&self) -> String {
+        if self.float {
+            format!("{}::from_bits(r.read_unsigned({}, {})? as u{})", self.rust, self.bits, self.order.path(), self.bits)
+        } else if self.signed {
+            format!("r.read_signed({}, {})? as {}", self.bits, self.order.path(), self.rust)
+        } else {
+            format!("r.read_unsigned({}, {})? as {}", self.bits, self.order.path(), self.rust)
+        }
+    }
+
+    // 样例值取位宽内的极值，检验位宽与符号扩展
+    fn sample(&self) -> String {
+        if self.float {
+            "crate::codec::Sample::sample()".to_string()
+        } else if self.signed {
+            format!("crate::codec::sample_signed({}) as {}", self.bits, self.rust)
+        } else {
+            format!("crate::codec::sample_unsigned({}) as {}", self.bits, self.rust)
+        }
+    }
+}
+
+// 数据组件生成的 Codec 实现、别名编解码函数与往返测试
+pub struct CodecItems {
+    pub items: Vec<Item>,
+    pub tests: Vec<FunctionDef>,
+}
+
+pub fn codec_items(converter: &AadlConverter, source: &DataSource, items: &[Item]) -> CodecItems {
+    let lookup = match source {
+        DataSource::Type(comp) => PropertyLookup::for_type(converter, comp),
+        DataSource::Impl(impl_) => PropertyLookup::for_impl(converter, impl_),
+    };
+    let order = byte_order(&lookup).unwrap_or(ByteOrder::Big);
+    let field_types = field_type_names(source);
+
+    let mut result = CodecItems { items: Vec::new(), tests: Vec::new() };
+    for item in items {
+        match item {
+            // 定点新类型按存储的整数表示编码，位宽与字节序同整数别名
+            Item::Struct(def) if converter.fixed_types.contains_key(&def.name) => {
+                let Some(scalar) = Scalar::of(&converter.fixed_types[&def.name]) else { continue };
+                let scalar = scalar.with_properties(&lookup, &def.name, ByteOrder::Big);
+                result.items.push(codec_impl(
+                    &def.name,
+                    vec![scalar.encode("self.value.to_bits()"), "return Ok(())".to_string()],
+                    vec![format!("return Ok(Self::from_bits({}))", scalar.decode())],
+                ));
+                result.items.push(sample_impl(&def.name, format!("Self::from_bits({})", scalar.sample())));
+                result.tests.push(round_trip_test(&def.name));
+            }
+            Item::Struct(def) if def.generics.is_empty() => {
+                let fields: Vec<(String, Option<Scalar>)> = def
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| {
+                        let type_name = field_types.get(index).map(String::as_str);
+                        (field.name.clone(), field_scalar(converter, &field.ty, type_name, order))
+                    })
+                    .collect();
+                result.items.push(struct_codec(&def.name, &fields));
+                result.items.push(sample_impl(&def.name, struct_sample(&fields)));
+                result.tests.push(round_trip_test(&def.name));
+            }
+            // 带 Representation 的 #[repr] 枚举编码其判别值，位宽与字节序同整数别名
+            Item::Enum(def) if def.repr.is_some() => {
+                let Some(scalar) = def.repr.as_deref().and_then(Scalar::of) else { continue };
+                let scalar = scalar.with_properties(&lookup, &def.name, order);
+                result.items.push(codec_impl(
+                    &def.name,
+                    vec![scalar.encode(&format!("(*self as {})", scalar.rust)), "return Ok(())".to_string()],
+                    vec![format!(
+                        "return Self::try_from({}).map_err(|code| {}::InvalidDiscriminant(code as u64))",
+                        scalar.decode(),
+                        ERROR
+                    )],
+                ));
+                result.items.push(sample_impl(&def.name, enum_sample(def)));
+                result.tests.push(round_trip_test(&def.name));
+            }
+            Item::Enum(def) if def.generics.is_empty() && !def.variants.is_empty() => {
+                let bits = discriminant_bits(def.variants.len(), lookup.size_bits("Data_Size"), &def.name);
+                result.items.push(enum_codec(def, bits, order));
+                result.items.push(sample_impl(&def.name, enum_sample(def)));
+                result.tests.push(round_trip_test(&def.name));
+            }
+            Item::Union(def) if def.generics.is_empty() => {
+                result.items.push(union_codec(&def.name));
+                result.items.push(sample_impl(&def.name, "unsafe { std::mem::zeroed() }".to_string()));
+            }
+            // 只有数据类型声明产生的别名是数据类型；实现中的 XxxShared 别名是共享数据的容器
+            Item::TypeAlias(alias) if matches!(source, DataSource::Type(_)) => {
+                // 数组、Vec 与有界字符串由运行时中的实现编码，只需生成往返测试
+                if matches!(&alias.target, Type::Array(..) | Type::Generic(..)) {
+                    result.tests.push(round_trip_test(&alias.name));
+                    continue;
+                }
+                let Type::Named(rust) = &alias.target else { continue };
+                if rust.starts_with("heapless::String") {
+                    result.tests.push(round_trip_test(&alias.name));
+                    continue;
+                }
+                let Some(scalar) = Scalar::of(rust).map(|s| s.with_properties(&lookup, &alias.name, ByteOrder::Big)) else {
+                    continue;
+                };
+                if scalar.is_custom() {
+                    result.items.extend(alias_codec(&alias.name, &scalar));
+                    result.tests.push(alias_round_trip_test(&alias.name, &scalar));
+                }
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+// 各字段的 AADL 数据类型名，与生成的结构体字段一一对应
+fn field_type_names(source: &DataSource) -> Vec<String> {
+    match source {
+        DataSource::Type(comp) => {
+            let PropertyClause::Properties(props) = &comp.properties else {
+                return Vec::new();
+            };
+            props
+                .iter()
+                .filter_map(|prop| match prop {
+                    Property::BasicProperty(bp) if bp.identifier.name.eq_ignore_ascii_case("base_type") => Some(&bp.value),
+                    _ => None,
+                })
+                .flat_map(|value| match value {
+                    PropertyValue::List(elements) => elements.iter().collect::<Vec<_>>(),
+                    PropertyValue::Single(_) => Vec::new(),
+                })
+                .filter_map(|element| match element {
+                    PropertyListElement::Value(PropertyExpression::ComponentClassifier(term)) => {
+                        Some(classifier_type_name(&term.unique_component_classifier_reference))
+                    }
+                    _ => None,
+                })
+                .collect()
+        }
+        DataSource::Impl(impl_) => match &impl_.subcomponents {
+            SubcomponentClause::Items(subcomponents) => subcomponents
+                .iter()
+                .filter(|sub| sub.category == ComponentCategory::Data)
+                .map(|sub| match &sub.classifier {
+                    SubcomponentClassifier::ClassifierReference(reference) => classifier_type_name(reference),
+                    _ => String::new(),
+                })
+                .collect(),
+            _ => Vec::new(),
+        },
+    }
+}
+
+fn classifier_type_name(reference: &UniqueComponentClassifierReference) -> String {
+    match reference {
+        UniqueComponentClassifierReference::Type(r) | UniqueComponentClassifierReference::Implementation(r) => {
+            r.implementation_name.type_identifier.split('.').next().unwrap_or_default().to_string()
+        }
+    }
+}
+
+// 标量字段的编码方式；字段数据类型上的 Data_Size/Byte_Order 优先于所在数据组件的字节序
+fn field_scalar(converter: &AadlConverter, ty: &Type, type_name: Option<&str>, order: ByteOrder) -> Option<Scalar> {
+    let Type::Named(rust) = ty else { return None };
+    let mut scalar = Scalar::of(rust)?;
+    scalar.order = order;
+    let data_type = type_name.and_then(|name| {
+        converter
+            .component_types
+            .values()
+            .find(|t| t.category == ComponentCategory::Data && t.identifier.eq_ignore_ascii_case(name))
+    });
+    if let Some(data_type) = data_type {
+        scalar = scalar.with_properties(&PropertyLookup::for_type(converter, data_type), &data_type.identifier, order);
+    }
+    scalar.is_custom().then_some(scalar)
+}
+
+// 枚举序号的位数：容纳全部变体的最小位数；Data_Size 更大时按 Data_Size
+fn discriminant_bits(variants: usize, data_size: Option<u64>, name: &str) -> u32 {
+    let minimal = usize::BITS - (variants.max(2) - 1).leading_zeros();
+    match data_size {
+        Some(bits) if bits >= u64::from(minimal) && bits <= 64 => bits as u32,
+        Some(bits) => {
+            diagnostics::warning(
+                codes::UNENCODABLE_DATA_SIZE,
+                format!("data {}: Data_Size {} bits cannot hold {} enumerators, using {} bits", name, bits, variants, minimal),
+            );
+            minimal
+        }
+        None => minimal,
+    }
+}
+
+fn method(name: &str, params: Vec<(&str, Type)>, return_type: Type, stmts: Vec<String>) -> ImplItem {
+    ImplItem::Method(FunctionDef {
+        name: name.to_string(),
+        params: params.into_iter().map(|(name, ty)| Param { name: name.to_string(), ty }).collect(),
+        return_type,
+        body: Block { stmts: stmts.into_iter().map(|line| Statement::Expr(Expr::Ident(line))).collect(), expr: None },
+        asyncness: false,
+        vis: Visibility::None,
+        docs: Vec::new(),
+        attrs: Vec::new(),
+    })
+}
+
+fn codec_impl(name: &str, encode: Vec<String>, decode: Vec<String>) -> Item {
+    let self_ref = Type::Reference(Box::new(Type::Named("Self".to_string())), true, false);
+    let writer = Type::Reference(Box::new(Type::Named(WRITER.to_string())), true, true);
+    let reader = Type::Reference(Box::new(Type::Named(READER.to_string())), true, true);
+    Item::Impl(ImplBlock {
+        target: Type::Named(name.to_string()),
+        generics: Vec::new(),
+        items: vec![
+            method("encode", vec![("self", self_ref), ("w", writer)], Type::Named(format!("Result<(), {}>", ERROR)), encode),
+            method("decode", vec![("r", reader)], Type::Named(format!("Result<Self, {}>", ERROR)), decode),
+        ],
+        trait_impl: Some(Type::Named("crate::codec::Codec".to_string())),
+    })
+}
+
+fn struct_codec(name: &str, fields: &[(String, Option<Scalar>)]) -> Item {
+    let mut encode: Vec<String> = fields
+        .iter()
+        .map(|(field, scalar)| match scalar {
+            Some(scalar) => scalar.encode(&format!("self.{}", field)),
+            None => format!("crate::codec::Codec::encode(&self.{}, w)?", field),
+        })
+        .collect();
+    encode.push("return Ok(())".to_string());
+    let values: Vec<String> = fields
+        .iter()
+        .map(|(field, scalar)| match scalar {
+            Some(scalar) => format!("{}: {}", field, scalar.decode()),
+            None => format!("{}: crate::codec::Codec::decode(r)?", field),
+        })
+        .collect();
+    codec_impl(name, encode, vec![format!("return Ok(Self {{ {} }})", values.join(", "))])
+}
+
+fn variant_pattern(variant: &Variant) -> (String, Vec<String>) {
+    let vars: Vec<String> = (0..variant.data.as_ref().map_or(0, Vec::len)).map(|i| format!("v{}", i)).collect();
+    if vars.is_empty() {
+        (format!("Self::{}", variant.name), vars)
+    } else {
+        (format!("Self::{}({})", variant.name, vars.join(", ")), vars)
+    }
+}
+
+// 枚举与标记联合体：先编码变体序号，标记联合体再编码各负载
+fn enum_codec(def: &EnumDef, bits: u32, order: ByteOrder) -> Item {
+    let encode_arms: Vec<String> = def
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(index, variant)| {
+            let (pattern, vars) = variant_pattern(variant);
+            let payload: String = vars.iter().map(|v| format!(" crate::codec::Codec::encode({}, w)?;", v)).collect();
+            format!("{} => {{ w.write_unsigned({}, {}, {})?;{} }}", pattern, index, bits, order.path(), payload)
+        })
+        .collect();
+    let decode_arms: Vec<String> = def
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(index, variant)| {
+            let (_, vars) = variant_pattern(variant);
+            if vars.is_empty() {
+                format!("{} => Ok(Self::{}),", index, variant.name)
+            } else {
+                let payload = vec!["crate::codec::Codec::decode(r)?"; vars.len()].join(", ");
+                format!("{} => Ok(Self::{}({})),", index, variant.name, payload)
+            }
+        })
+        .collect();
+    codec_impl(
+        &def.name,
+        vec![format!("match self {{ {} }}", encode_arms.join(" ")), "return Ok(())".to_string()],
+        vec![format!(
+            "return match r.read_unsigned({}, {})? {{ {} other => Err({}::InvalidDiscriminant(other)) }}",
+            bits,
+            order.path(),
+            decode_arms.join(" "),
+            ERROR
+        )],
+    )
+}
+
+// 联合体没有标记，按内存字节原样编码；各字段均为 Copy，任意字节模式都可读回
+fn union_codec(name: &str) -> Item {
+    codec_impl(
+        name,
+        vec![
+            "let bytes = unsafe { std::slice::from_raw_parts(self as *const Self as *const u8, std::mem::size_of::<Self>()) }".to_string(),
+            "w.write_bytes(bytes)".to_string(),
+            "return Ok(())".to_string(),
+        ],
+        vec![
+            "let bytes = r.read_bytes(std::mem::size_of::<Self>())?".to_string(),
+            "let mut value = std::mem::MaybeUninit::<Self>::uninit()".to_string(),
+            "return Ok(unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), value.as_mut_ptr() as *mut u8, bytes.len()); value.assume_init() })".to_string(),
+        ],
+    )
+}
+
+fn sample_impl(name: &str, value: String) -> Item {
+    Item::Impl(ImplBlock {
+        target: Type::Named(name.to_string()),
+        generics: Vec::new(),
+        items: vec![method("sample", Vec::new(), Type::Named("Self".to_string()), vec![format!("return {}", value)])],
+        trait_impl: Some(Type::Named("crate::codec::Sample".to_string())),
+    })
+}
+
+fn struct_sample(fields: &[(String, Option<Scalar>)]) -> String {
+    let values: Vec<String> = fields
+        .iter()
+        .map(|(field, scalar)| match scalar {
+            Some(scalar) => format!("{}: {}", field, scalar.sample()),
+            None => format!("{}: crate::codec::Sample::sample()", field),
+        })
+        .collect();
+    format!("Self {{ {} }}", values.join(", "))
+}
+
+// 取最后一个变体，序号的每一位都参与编码
+fn enum_sample(def: &EnumDef) -> String {
+    let variant = def.variants.last().expect("enum without variants");
+    let (_, vars) = variant_pattern(variant);
+    if vars.is_empty() {
+        format!("Self::{}", variant.name)
+    } else {
+        format!("Self::{}({})", variant.name, vec!["crate::codec::Sample::sample()"; vars.len()].join(", "))
+    }
+}
+
+fn alias_codec(name: &str, scalar: &Scalar) -> Vec<Item> {
+    let function = |prefix: &str, params: Vec<Param>, return_type: String, stmts: Vec<String>| {
+        Item::Function(FunctionDef {
+            name: format!("{}_{}", prefix, name.to_lowercase()),
+            params,
+            return_type: Type::Named(return_type),
+            body: Block { stmts: stmts.into_iter().map(|line| Statement::Expr(Expr::Ident(line))).collect(), expr: None },
+            asyncness: false,
+            vis: Visibility::Public,
+            docs: Vec::new(),
+            attrs: Vec::new(),
+        })
+    };
+    let mut encode = function(
+        "encode",
+        vec![
+            Param { name: "value".to_string(), ty: Type::Reference(Box::new(Type::Named(name.to_string())), true, false) },
+            Param { name: "w".to_string(), ty: Type::Reference(Box::new(Type::Named(WRITER.to_string())), true, true) },
+        ],
+        format!("Result<(), {}>", ERROR),
+        vec![scalar.encode("*value"), "return Ok(())".to_string()],
+    );
+    if let Item::Function(f) = &mut encode {
+        f.docs.push(format!(
+            "// {} 编码为 {} 位{}",
+            name,
+            scalar.bits,
+            if scalar.order == ByteOrder::Little { "（小端）" } else { "" }
+        ));
+    }
+    let decode = function(
+        "decode",
+        vec![Param { name: "r".to_string(), ty: Type::Reference(Box::new(Type::Named(READER.to_string())), true, true) }],
+        format!("Result<{}, {}>", name, ERROR),
+        vec![format!("return Ok({})", scalar.decode())],
+    );
+    vec![encode, decode]
+}
+
+fn test_function(name: String, stmts: Vec<String>) -> FunctionDef {
+    FunctionDef {
+        name,
+        params: Vec::new(),
+        return_type: Type::Unit,
+        body: Block { stmts: stmts.into_iter().map(|line| Statement::Expr(Expr::Ident(line))).collect(), expr: None },
+        asyncness: false,
+        vis: Visibility::None,
+        docs: Vec::new(),
+        attrs: vec![Attribute { name: "test".to_string(), args: Vec::new() }],
+    }
+}
+
+// 样例值编码后再解码，Debug 输出与重新编码的字节都应一致
+fn round_trip_test(name: &str) -> FunctionDef {
+    test_function(
+        format!("{}_round_trip", name.to_lowercase()),
+        vec![
+            format!("let value = <{} as crate::codec::Sample>::sample()", name),
+            "let bytes = crate::codec::Codec::to_bytes(&value).unwrap()".to_string(),
+            format!("let decoded = <{} as crate::codec::Codec>::from_bytes(&bytes).unwrap()", name),
+            "assert_eq!(format!(\"{:?}\", decoded), format!(\"{:?}\", value))".to_string(),
+            "assert_eq!(crate::codec::Codec::to_bytes(&decoded).unwrap(), bytes)".to_string(),
+        ],
+    )
+}
+
+fn alias_round_trip_test(name: &str, scalar: &Scalar) -> FunctionDef {
+    let snake = name.to_lowercase();
+    test_function(
+        format!("{}_round_trip", snake),
+        vec![
+            format!("let value: {} = {}", name, scalar.sample()),
+            format!("let mut w = {}::new()", WRITER),
+            format!("encode_{}(&value, &mut w).unwrap()", snake),
+            format!("assert_eq!(w.bit_len(), {})", scalar.bits),
+            "let bytes = w.into_bytes()".to_string(),
+            format!("assert_eq!(decode_{}(&mut {}::new(&bytes)).unwrap(), value)", snake, READER),
+        ],
+    )
+}
+
+// 包内全部往返测试组成的 #[cfg(test)] 模块
+pub fn test_module(tests: Vec<FunctionDef>) -> Item {
+    Item::Mod(Box::new(RustModule {
+        name: "codec_tests".to_string(),
+        docs: Vec::new(),
+        items: std::iter::once(Item::Use(UseStatement { path: vec!["super".to_string()], kind: UseKind::Glob }))
+            .chain(tests.into_iter().map(Item::Function))
+            .collect(),
+        attrs: vec![Attribute { name: "cfg".to_string(), args: vec![AttributeArg::Ident("test".to_string())] }],
+        vis: Visibility::Private,
+        withs: Vec::new(),
+    }))
+}
@@ -5,14 +5,16 @@
     clippy::vec_init_then_push,
 )]
 use crate::aadl_ast2rust_code::intermediate_ast::*;
-use crate::aadl_ast2rust_code::tool;
 use crate::ast::aadl_ast_cj::*;
-use std::collections::HashMap;
+use crate::diagnostics::{self, codes};
+use crate::instance_model::{ComponentInstance, ConnectionKind, FeatureRef, InstancePath, SemanticConnection, SystemInstance};
+use std::collections::{HashMap, HashSet};
 /**
- * 收集器：收集AADL模型中的组件类型信息、process之间的多连接关系、thread之间的多连接关系
+ * 收集器：收集AADL模型中的组件类型信息，并由实例模型的语义连接规划线程/设备端口之间的通道
  * collect_component_types: 收集所有组件类型信息
- * collect_process_connections: 收集system内process之间的多连接关系
- * collect_thread_connections: 收集process内和thread之间的多连接关系
+ * collect_component_implementations: 收集所有组件实现信息
+ * collect_package_classifiers: 按包收集组件类型与实现
+ * collect_port_channels: 由语义连接规划端口通道（根系统与进程内部）及广播端口
 */
 
 // 收集所有组件类型信息
@@ -39,220 +41,354 @@ pub fn collect_component_types(
     }
 }
 
-//收集system内process之间的多连接关系
-pub fn collect_process_connections(
-    process_broadcast_send: &mut Vec<(String, String)>,
-    process_broadcast_receive: &mut HashMap<(String, String), Vec<(String, String)>>,
-    system_subcomponent_identify_to_type: &mut HashMap<String, String>,
+// 收集所有组件实现信息，key为 Type.Impl
+pub fn collect_component_implementations(
+    component_impls: &mut HashMap<String, ComponentImplementation>,
     pkg: &Package,
 ) {
-    //创建一个映射，存储已经出现的，key为子组件名称，value为端口名称。用来判断是否是重复出现的端口
-    // let mut subcomponent_ports = HashMap::new();
-
-    if let Some(public_section) = &pkg.public_section {
-        for decl in &public_section.declarations {
+    let sections = [&pkg.public_section, &pkg.private_section];
+    for section in sections.into_iter().flatten() {
+        for decl in &section.declarations {
             if let AadlDeclaration::ComponentImplementation(impl_) = decl {
-                if impl_.category == ComponentCategory::System {
-                    if let ConnectionClause::Items(connections) = &impl_.connections {
-                        for conn in connections {
-                            if let Connection::Port(port_conn) = conn {
-                                if let PortEndpoint::SubcomponentPort { subcomponent, port } =
-                                    &port_conn.source
-                                {
-                                    process_broadcast_send
-                                        .push((subcomponent.clone(), port.clone()));
-                                }
-                            }
-                        }
-                    }
-                }
+                component_impls.insert(impl_.name.to_string(), impl_.clone());
             }
         }
     }
-    //筛选重复出现的（发送）端口。保留。
-    let temp_dedup_with_min_two_unique = tool::dedup_with_min_two_unique(process_broadcast_send);
-    //现在process_connections_alias中存储的是重复出现的（发送）端口。
-    //还需要根据它，再次遍历连接关系connections，找到所有与它们连接的接收端口，加入process_connections_alias
-    for ele in &temp_dedup_with_min_two_unique {
-        if let Some(public_section) = &pkg.public_section {
-            for decl in &public_section.declarations {
-                if let AadlDeclaration::ComponentImplementation(impl_) = decl {
-                    if impl_.category == ComponentCategory::System {
-                        if let ConnectionClause::Items(connections) = &impl_.connections {
-                            for conn in connections {
-                                if let Connection::Port(port_conn) = conn {
-                                    if let PortEndpoint::SubcomponentPort { subcomponent, port } =
-                                        &port_conn.source
-                                    {
-                                        if subcomponent.eq(&ele.0) && port.eq(&ele.1) {
-                                            if let PortEndpoint::SubcomponentPort {
-                                                subcomponent: d_subcomponent,
-                                                port: d_port,
-                                            } = &port_conn.destination
-                                            {
-                                                //self.process_broadcast_send.push((d_subcomponent.clone(), d_port.clone()));
-                                                process_broadcast_receive
-                                                    .entry(ele.clone())
-                                                    .or_default()
-                                                    .push((d_subcomponent.clone(), d_port.clone()));
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+}
+
+// 按包收集组件类型与实现，key为(包名, 标识符)，均为小写；同名分类器在不同包中各自保留
+pub fn collect_package_classifiers(
+    component_types: &mut HashMap<(String, String), ComponentType>,
+    component_impls: &mut HashMap<(String, String), ComponentImplementation>,
+    pkg: &Package,
+) {
+    let pkg_name = pkg.name.to_string().to_lowercase();
+    let sections = [&pkg.public_section, &pkg.private_section];
+    for section in sections.into_iter().flatten() {
+        for decl in &section.declarations {
+            match decl {
+                AadlDeclaration::ComponentType(comp) => {
+                    component_types.insert((pkg_name.clone(), comp.identifier.to_lowercase()), comp.clone());
+                }
+                AadlDeclaration::ComponentImplementation(impl_) => {
+                    component_impls.insert((pkg_name.clone(), impl_.name.to_string().to_lowercase()), impl_.clone());
                 }
+                _ => {}
             }
         }
     }
-    //遍历process_broadcast_receive的键值对中所有键和值中的组件，查看该系统中的subcomponent部分，把有广播发送和接收的端口，找到它的真实类型，并记录下来存储到system_subcomponent_identify_to_type。
-    for (sendcomp, vercport) in process_broadcast_receive {
-        for (comp, _) in vercport {
-            if let Some(public_section) = &pkg.public_section {
-                for decl in &public_section.declarations {
-                    if let AadlDeclaration::ComponentImplementation(impl_) = decl {
-                        if impl_.category == ComponentCategory::System {
-                            if let SubcomponentClause::Items(subcomponents) = &impl_.subcomponents {
-                                for sub in subcomponents {
-                                    if sub.identifier.eq(&comp.clone()) {
-                                        if let SubcomponentClassifier::ClassifierReference(
-                                            classifier,
-                                        ) = &sub.classifier
-                                        {
-                                            if let UniqueComponentClassifierReference::Implementation(unirf) = classifier {
-                                                system_subcomponent_identify_to_type.insert(sub.identifier.clone(), unirf.implementation_name.type_identifier.clone());
-                                            }
-                                        }
-                                    } else if sub.identifier.eq(&sendcomp.0.clone()) {
-                                        if let SubcomponentClassifier::ClassifierReference(
-                                            classifier,
-                                        ) = &sub.classifier
-                                        {
-                                            if let UniqueComponentClassifierReference::Implementation(unirf) = classifier {
-                                                system_subcomponent_identify_to_type.insert(sub.identifier.clone(), unirf.implementation_name.type_identifier.clone());
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+}
+
+// 端口通道：一组经语义连接相连的线程/设备端口共用一个通道。
+// 同一源扇出到多个目的端口时使用广播通道，多个源汇入同一目的端口时共享同一发送端；
+// 通道内每个源都连到每个目的端口，目的端口不会收到未与之相连的源的消息。
+#[derive(Debug, Clone)]
+pub struct PortChannel {
+    pub senders: Vec<FeatureRef>,
+    pub receivers: Vec<FeatureRef>,
+    pub broadcast: bool,
+    pub declarations: Vec<String>, // 途经的连接声明，写入生成代码的注释
+    pub bus: Option<InstancePath>, // Actual_Connection_Binding 绑定的总线，通道经该总线转发
+}
+
+// 各根系统的端口通道，key 为根系统实现名（小写，形如 t.impl）
+pub type PortChannelPlan = HashMap<String, Vec<PortChannel>>;
+
+// 根系统无法直接访问的进程（如嵌套子系统中的进程）内部的端口通道，改在进程实现的 new() 中建立
+#[derive(Debug, Clone)]
+pub struct ProcessChannels {
+    pub instance: usize,       // 所在根系统实例的下标
+    pub process: InstancePath, // 规划所依据的进程实例；同一进程实现的其他实例连接相同
+    pub channels: Vec<PortChannel>,
+}
+
+// key 为进程实现名（小写，形如 control.impl）
+pub type ProcessChannelPlan = HashMap<String, ProcessChannels>;
+
+// 组件实例在根系统 new() 中的访问路径：系统直接持有设备，进程持有线程（如 proc.thr）
+pub fn component_field_path(instance: &SystemInstance, path: &[String]) -> Option<String> {
+    let component = instance.component(path)?;
+    let materialized = match (&component.category, path.len()) {
+        (ComponentCategory::Device, 1) => true,
+        (ComponentCategory::Thread, 2) => matches!(
+            instance.component(&path[..1]).map(|parent| &parent.category),
+            Some(ComponentCategory::Process)
+        ),
+        _ => false,
+    };
+    if !materialized {
+        return None;
+    }
+    // 数组元素 sensors[3] 对应 Rust 数组字段 sensors[2]
+    let segments: Vec<String> = (1..=path.len())
+        .filter_map(|len| instance.component(&path[..len]))
+        .map(field_segment)
+        .collect();
+    Some(segments.join("."))
+}
+
+// 组件实例在父组件中的字段名，数组元素带从 0 开始的下标
+fn field_segment(component: &ComponentInstance) -> String {
+    let index: String = component.array_index.iter().map(|i| format!("[{}]", i - 1)).collect();
+    format!("{}{}", component.base_name().to_lowercase(), index)
+}
+
+// 端口字段在生成代码中的访问路径（如 proc.thr.port）
+pub fn port_field_path(instance: &SystemInstance, port: &FeatureRef) -> Option<String> {
+    if port.feature.is_empty() {
+        return None;
+    }
+    let component = component_field_path(instance, &port.component)?;
+    Some(format!("{}.{}", component, port.feature.to_lowercase()))
+}
+
+// 进程 new() 中线程端口的访问路径（如 thr.port），端口须属于该进程的直属线程
+pub fn process_port_path(instance: &SystemInstance, process: &[String], port: &FeatureRef) -> Option<String> {
+    if port.feature.is_empty() || port.component.len() != process.len() + 1 || !port.component.starts_with(process) {
+        return None;
+    }
+    let thread = instance.component(&port.component)?;
+    if thread.category != ComponentCategory::Thread {
+        return None;
+    }
+    Some(format!("{}.{}", field_segment(thread), port.feature.to_lowercase()))
+}
+
+// 诊断中的特征引用，如 p1.prod.outp
+fn feature_ref_string(feature: &FeatureRef) -> String {
+    let mut segments = feature.component.clone();
+    if !feature.feature.is_empty() {
+        segments.push(feature.feature.clone());
+    }
+    segments.join(".")
+}
+
+// 端口所属组件的类型名（小写）与端口名（小写），决定线程/设备结构体中端口字段的通道类型
+fn port_type_key(instance: &SystemInstance, port: &FeatureRef) -> Option<(String, String)> {
+    let classifier = instance.component(&port.component)?.classifier.as_ref()?;
+    Some((classifier.type_name.to_lowercase(), port.feature.to_lowercase()))
+}
+
+// 组件实例的实现名（小写，形如 t.impl），与 ComponentImplementation::name 的字符串形式对应
+fn impl_key(component: &ComponentInstance) -> Option<String> {
+    let classifier = component.classifier.as_ref()?;
+    Some(format!("{}.{}", classifier.type_name, classifier.impl_name.as_ref()?).to_lowercase())
+}
+
+// 根系统实现名（小写，形如 t.impl）
+pub fn root_impl_key(instance: &SystemInstance) -> Option<String> {
+    impl_key(&instance.root)
+}
+
+// 报告无法生成通道的语义连接
+fn report_unwired(conn: &SemanticConnection, reason: &str) {
+    diagnostics::warning(
+        codes::UNMAPPED_CONNECTION,
+        format!(
+            "semantic connection {} -> {} ({}): {}, no channel generated",
+            feature_ref_string(&conn.source),
+            feature_ref_string(&conn.destination),
+            conn.declarations.join(", "),
+            reason
+        ),
+    );
+}
+
+// 把一组语义连接分为通道：目的端口集合相同的源共用一个通道。
+// 端口字段只能持有一个通道端，目的端口已归入前面的通道时，后面的源到它的连接报告后跳过，
+// 因此不同的扇出/汇入组不会合并，目的端口只收到与之相连的源的消息
+fn group_channels(instance: &SystemInstance, conns: &[&SemanticConnection]) -> Vec<PortChannel> {
+    let mut sources: Vec<(&FeatureRef, Vec<&SemanticConnection>)> = Vec::new();
+    for conn in conns {
+        match sources.iter_mut().find(|(source, _)| *source == &conn.source) {
+            Some((_, outgoing)) => outgoing.push(conn),
+            None => sources.push((&conn.source, vec![conn])),
+        }
+    }
+
+    let mut groups: Vec<Vec<&SemanticConnection>> = Vec::new();
+    for (_, outgoing) in sources {
+        let mut destinations: Vec<&FeatureRef> = outgoing.iter().map(|conn| &conn.destination).collect();
+        destinations.sort_by_key(|port| feature_ref_string(port));
+        destinations.dedup();
+        let same_destinations = |conns: &Vec<&SemanticConnection>| {
+            let mut others: Vec<&FeatureRef> = conns.iter().map(|conn| &conn.destination).collect();
+            others.sort_by_key(|port| feature_ref_string(port));
+            others.dedup();
+            others == destinations
+        };
+        match groups.iter_mut().find(|conns| same_destinations(conns)) {
+            Some(conns) => conns.extend(outgoing),
+            None => groups.push(outgoing),
+        }
+    }
+
+    let mut claimed: HashSet<&FeatureRef> = HashSet::new();
+    let mut channels = Vec::new();
+    for conns in groups {
+        let wired: Vec<&SemanticConnection> = conns
+            .into_iter()
+            .filter(|conn| {
+                let free = !claimed.contains(&conn.destination);
+                if !free {
+                    report_unwired(conn, "destination port already receives from a channel with other sources");
+                }
+                free
+            })
+            .collect();
+        let mut channel = PortChannel {
+            senders: Vec::new(),
+            receivers: Vec::new(),
+            broadcast: false,
+            declarations: Vec::new(),
+            bus: None,
+        };
+        for conn in &wired {
+            if !channel.senders.contains(&conn.source) {
+                channel.senders.push(conn.source.clone());
+            }
+            if !channel.receivers.contains(&conn.destination) {
+                channel.receivers.push(conn.destination.clone());
+            }
+            for decl in &conn.declarations {
+                if !channel.declarations.contains(decl) {
+                    channel.declarations.push(decl.clone());
+                }
+            }
+            if let Some(bus) = instance.connection_bus(conn) {
+                match &channel.bus {
+                    None => channel.bus = Some(bus.path.clone()),
+                    Some(existing) if existing != &bus.path => diagnostics::warning(
+                        codes::CONFLICTING_BUS_BINDING,
+                        format!(
+                            "connections {} are bound to different buses {} and {}; the channel is relayed through the former",
+                            channel.declarations.join(", "),
+                            existing.join("."),
+                            bus.path.join(".")
+                        ),
+                    ),
+                    Some(_) => {}
                 }
             }
         }
+        // 源的全部目的端口都已被占用时整组不生成通道
+        if channel.receivers.is_empty() {
+            continue;
+        }
+        claimed.extend(wired.iter().map(|conn| &conn.destination));
+        channels.push(channel);
     }
+    channels
 }
 
-//收集process内和thread之间的多连接关系
-pub fn collect_thread_connections(
-    thread_broadcast_receive: &mut HashMap<(String, String), Vec<(String, String)>>,
-    process_subcomponent_identify_to_type: &mut HashMap<String, String>,
-    pkg: &Package,
-) {
-    //List列表存储process内具有多连接关系的端口，每条数据是端口名
-    let mut process_forwarder_broadcast_send: Vec<String> = Vec::new();
+// 由各根系统实例的语义连接规划端口通道。
+// 端点是根系统下进程中的线程或系统直属设备的端口时在根系统 new() 中连通；
+// 端点是根系统无法直接访问的同一进程中的线程（如嵌套子系统中的进程）时在该进程实现的 new() 中连通。
+// 返回的集合记录需要使用广播通道的端口（组件类型名, 端口名）。
+pub fn collect_port_channels(
+    instances: &[SystemInstance],
+) -> (PortChannelPlan, ProcessChannelPlan, HashSet<(String, String)>) {
+    let mut channels_by_system = HashMap::new();
+    let mut channels_by_process: ProcessChannelPlan = HashMap::new();
 
-    if let Some(public_section) = &pkg.public_section {
-        for decl in &public_section.declarations {
-            if let AadlDeclaration::ComponentImplementation(impl_) = decl {
-                if impl_.category == ComponentCategory::Process {
-                    if let ConnectionClause::Items(connections) = &impl_.connections {
-                        for conn in connections {
-                            if let Connection::Port(port_conn) = conn {
-                                if let PortEndpoint::ComponentPort(proc_port) = &port_conn.source {
-                                    process_forwarder_broadcast_send.push(proc_port.clone());
-                                }
-                            }
-                        }
-                    }
-                }
+    for (instance_index, instance) in instances.iter().enumerate() {
+        let mut mapped = Vec::new();
+        let mut by_process: Vec<(InstancePath, Vec<&SemanticConnection>)> = Vec::new();
+        for conn in instance.connections.iter().filter(|c| c.kind == ConnectionKind::Port) {
+            if port_field_path(instance, &conn.source).is_some() && port_field_path(instance, &conn.destination).is_some() {
+                mapped.push(conn);
+                continue;
+            }
+            let process = &conn.source.component[..conn.source.component.len().saturating_sub(1)];
+            let internal = matches!(instance.component(process), Some(parent) if parent.category == ComponentCategory::Process)
+                && process_port_path(instance, process, &conn.source).is_some()
+                && process_port_path(instance, process, &conn.destination).is_some();
+            if !internal {
+                // 如未连到线程的进程端口、跨越嵌套子系统的连接
+                report_unwired(conn, "endpoints are not thread or device ports of the generated system");
+                continue;
             }
+            match by_process.iter_mut().find(|(path, _)| path.as_slice() == process) {
+                Some((_, conns)) => conns.push(conn),
+                None => by_process.push((process.to_vec(), vec![conn])),
+            }
+        }
+        if let Some(key) = root_impl_key(instance) {
+            channels_by_system.insert(key, group_channels(instance, &mapped));
+        }
+        for (process, conns) in by_process {
+            let Some(key) = instance.component(&process).and_then(impl_key) else {
+                continue;
+            };
+            channels_by_process.entry(key).or_insert_with(|| ProcessChannels {
+                instance: instance_index,
+                channels: group_channels(instance, &conns),
+                process,
+            });
         }
     }
-    //println!("process_forwarder_broadcast_send:{:?}",process_forwarder_broadcast_send);
-
-    //筛选重复出现的端口。保留。
-    let temp_process_port =
-        tool::dedup_with_min_two_unique_single_string(&mut process_forwarder_broadcast_send);
-    for port in &temp_process_port {
-        if let Some(public_section) = &pkg.public_section {
-            for decl in &public_section.declarations {
-                if let AadlDeclaration::ComponentImplementation(impl_) = decl {
-                    if impl_.category == ComponentCategory::Process {
-                        if let ConnectionClause::Items(connections) = &impl_.connections {
-                            for conn in connections {
-                                if let Connection::Port(port_conn) = conn {
-                                    if let PortEndpoint::ComponentPort(proc_port) =
-                                        &port_conn.source
-                                    {
-                                        if proc_port.eq(port) {
-                                            if let PortEndpoint::SubcomponentPort {
-                                                subcomponent,
-                                                port:sub_port,
-                                            } = &port_conn.destination
-                                            {
-                                                thread_broadcast_receive
-                                                    .entry((
-                                                        port.clone(),
-                                                        impl_.name.type_identifier.clone(),
-                                                    ))
-                                                    .or_default()
-                                                    .push((subcomponent.clone(), sub_port.clone()));
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+
+    // 端口字段的通道类型按组件类型生成，同一类型的多个实例必须一致：
+    // 任一通道涉及广播端口时整组改为广播，直到不再变化
+    let mut broadcast_ports = HashSet::new();
+    let mark = |instance: &SystemInstance, channel: &mut PortChannel, broadcast_ports: &mut HashSet<(String, String)>| {
+        let keys: Vec<(String, String)> = channel
+            .senders
+            .iter()
+            .chain(&channel.receivers)
+            .filter_map(|port| port_type_key(instance, port))
+            .collect();
+        let mut changed = false;
+        if !channel.broadcast && (channel.receivers.len() > 1 || keys.iter().any(|key| broadcast_ports.contains(key))) {
+            channel.broadcast = true;
+            changed = true;
+        }
+        if channel.broadcast {
+            for key in keys {
+                changed |= broadcast_ports.insert(key);
             }
         }
-    }
-    //遍历thread_broadcast_receive的键值对当中所有的值（不考虑键），把其中的组件名，在process中对应的子组件名，找到它的真实类型，并记录下来存储到process_subcomponent_identify_to_type。
-    for vercport in thread_broadcast_receive.values_mut() {
-        for (comp, _) in vercport {
-            if let Some(public_section) = &pkg.public_section {
-                for decl in &public_section.declarations {
-                    if let AadlDeclaration::ComponentImplementation(impl_) = decl {
-                        if impl_.category == ComponentCategory::Process {
-                            if let SubcomponentClause::Items(subcomponents) = &impl_.subcomponents {
-                                for sub in subcomponents {
-                                    
-                                    if sub.identifier.eq(&comp.clone()) {
-                                        if let SubcomponentClassifier::ClassifierReference(
-                                            classifier,
-                                        ) = &sub.classifier
-                                        {
-                                            if let UniqueComponentClassifierReference::Implementation(unirf) = classifier {
-                                                process_subcomponent_identify_to_type.insert(sub.identifier.clone(), unirf.implementation_name.type_identifier.clone());
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+        changed
+    };
+    loop {
+        let mut changed = false;
+        for instance in instances {
+            let Some(channels) = root_impl_key(instance).and_then(|key| channels_by_system.get_mut(&key)) else {
+                continue;
+            };
+            for channel in channels.iter_mut() {
+                changed |= mark(instance, channel, &mut broadcast_ports);
             }
         }
+        for process in channels_by_process.values_mut() {
+            for channel in process.channels.iter_mut() {
+                changed |= mark(&instances[process.instance], channel, &mut broadcast_ports);
+            }
+        }
+        if !changed {
+            break;
+        }
     }
+
+    (channels_by_system, channels_by_process, broadcast_ports)
 }
 
 // 生成CPU调度策略映射的静态代码
 pub fn convert_cpu_schedule_mapping(
     module: &mut RustModule,
     cpu_scheduling_protocols: &HashMap<String, String>,
+    cpu_sched_quantum_ms: &HashMap<String, u64>,
     cpu_name_to_id_mapping: &HashMap<String, isize>,
+    required_by_threads: bool,
 ) {
-    // 如果没有CPU映射信息，则不生成代码
-    if cpu_name_to_id_mapping.is_empty() {
+    // 如果没有CPU映射信息，且包中没有线程设置调度策略，则不生成代码
+    if cpu_name_to_id_mapping.is_empty() && !required_by_threads {
         return;
     }
 
     // 生成map.insert语句
     let mut map_insertions = Vec::new();
+    let mut quantum_insertions = Vec::new();
 
     for (cpu_name, cpu_id) in cpu_name_to_id_mapping {
         // 获取该CPU的调度协议
@@ -271,6 +407,27 @@ pub fn convert_cpu_schedule_mapping(
             _ => "SCHED_FIFO", // 默认值
         };
 
+        // 轮转调度的时间片：map.insert(cpu_id, quantum_ms);
+        if let Some(quantum) = cpu_sched_quantum_ms.get(cpu_name) {
+            if sched_constant != "SCHED_RR" {
+                diagnostics::warning(
+                    codes::IGNORED_SCHEDULER_QUANTUM,
+                    format!(
+                        "processor {} declares a {} ms Scheduler_Quantum but its Scheduling_Protocol {} is not round-robin; the quantum is ignored",
+                        cpu_name, quantum, scheduling_protocol
+                    ),
+                );
+            }
+            quantum_insertions.push(Statement::Expr(Expr::MethodCall(
+                Box::new(Expr::Ident("map".to_string())),
+                "insert".to_string(),
+                vec![
+                    Expr::Literal(Literal::Int(*cpu_id as i64)),
+                    Expr::Literal(Literal::Int(*quantum as i64)),
+                ],
+            )));
+        }
+
         // 生成 map.insert(cpu_id, sched_constant);
         map_insertions.push(Statement::Expr(Expr::MethodCall(
             Box::new(Expr::Ident("map".to_string())),
@@ -331,14 +488,49 @@ pub fn convert_cpu_schedule_mapping(
 
     // 将 LazyStatic 添加到模块中
     module.items.push(Item::LazyStatic(lazy_static_def));
+
+    // CPU ID到轮转调度时间片（毫秒）的映射；线程按所在CPU查询，未声明时间片的CPU不在表中
+    let quantum_map_ty = Type::Generic(
+        "HashMap".to_string(),
+        vec![
+            Type::Named("isize".to_string()),
+            Type::Named("u64".to_string()),
+        ],
+    );
+    let mut quantum_stmts = vec![Statement::Let(LetStmt {
+        ifmut: true,
+        name: "map".to_string(),
+        ty: Some(quantum_map_ty.clone()),
+        init: Some(Expr::Call(
+            Box::new(Expr::Path(
+                vec!["HashMap".to_string(), "new".to_string()],
+                PathType::Namespace,
+            )),
+            Vec::new(),
+        )),
+    })];
+    quantum_stmts.extend(quantum_insertions);
+    quantum_stmts.push(Statement::Expr(Expr::Ident("return map".to_string())));
+
+    module.items.push(Item::LazyStatic(LazyStaticDef {
+        name: "CPU_ID_TO_SCHED_QUANTUM".to_string(),
+        ty: quantum_map_ty,
+        init: Block {
+            stmts: quantum_stmts,
+            expr: None,
+        },
+        vis: Visibility::Public,
+        docs: vec!["// CPU ID到轮转调度时间片（毫秒）的映射".to_string()],
+    }));
 }
 
 /// 添加 period_to_priority 函数到模块中
 /// 该函数根据周期计算优先级：prio(P)=max(1,min(99,99−⌊k⋅log10(P)⌋))
-/// 只有在检测到 RMS 或 DMS 调度协议时才生成此函数
+/// 在检测到 RMS 或 DMS 调度协议，或包中有线程缺省 Priority 而需按周期计算时生成此函数
 pub fn add_period_to_priority_function(
     module: &mut RustModule,
     cpu_scheduling_protocols: &HashMap<String, String>,
+    required_by_threads: bool,
 ) {
     // 检查是否有 RMS 或 DMS 调度协议
     let has_rms_or_dms = cpu_scheduling_protocols.values().any(|protocol| {
@@ -351,8 +543,8 @@ pub fn add_period_to_priority_function(
             || protocol_upper.contains("DM")
     });
 
-    // 如果没有 RMS 或 DMS 调度协议，则不生成函数
-    if !has_rms_or_dms {
+    // 如果没有 RMS 或 DMS 调度协议，且没有线程用到，则不生成函数
+    if !has_rms_or_dms && !required_by_threads {
         return;
     }
 
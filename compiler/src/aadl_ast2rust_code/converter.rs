@@ -3,36 +3,92 @@ use crate::aadl_ast2rust_code::intermediate_ast::*;
 use crate::aadl_ast2rust_code::converter_annex::AnnexConverter;
 
 use crate::ast::aadl_ast_cj::*;
-use std::collections::HashMap;
+use crate::instance_model::{FeatureRef, SystemInstance};
+use std::collections::{HashMap, HashSet};
 use crate::aadl_ast2rust_code::collector;
+use crate::aadl_ast2rust_code::tool::{computed_function_name, source_location_doc, to_upper_camel_case};
+use crate::aadl_ast2rust_code::embedded;
+use crate::aadl_ast2rust_code::deployment::{self, DeploymentNode};
+use crate::aadl_ast2rust_code::property_lookup::PropertyLookup;
+use crate::aadl_ast2rust_code::codec;
+use crate::diagnostics::{self, codes};
 use crate::aadl_ast2rust_code::types::*;
 use crate::aadl_ast2rust_code::implementations::*;
+use std::str::FromStr;
+
+// 代码生成后端：决定AADL线程映射为何种运行时结构
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CodegenBackend {
+    #[default]
+    Threads, // 每个AADL线程对应一个OS线程（默认）
+    Cyclic,  // 每个进程一个单线程循环执行器，按静态调度表调用各线程的计算逻辑
+    Async,   // 每个AADL线程对应一个tokio异步任务，端口使用tokio异步通道
+    Embedded, // no_std 单核微控制器：heapless 静态队列 + 运行至完成调度器
+}
+
+impl FromStr for CodegenBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "threads" => Ok(CodegenBackend::Threads),
+            "cyclic" => Ok(CodegenBackend::Cyclic),
+            "async" => Ok(CodegenBackend::Async),
+            "embedded" => Ok(CodegenBackend::Embedded),
+            other => Err(format!("unknown backend `{}` (expected: threads, cyclic, async, embedded)", other)),
+        }
+    }
+}
 
 // AADL到Rust中间表示的转换器
 pub struct AadlConverter {
+    pub backend: CodegenBackend, // 代码生成后端
     pub type_mappings: HashMap<String, Type>, //初始是根据AADL库文件Base_Types.aadl，将AADL Data组件名称映射到对应的Rust类型，后续会根据AADL模型文件，添加新的映射关系
 
     pub component_types: HashMap<String, ComponentType>, // 存储组件类型信息，（为了有些情况下，需要在组件实现中，根据组件类型来获取端口信息）
+    pub component_impls: HashMap<String, ComponentImplementation>, // 存储组件实现信息，key为 Type.Impl（为了在进程中查找线程实现的时间属性）
+    package_component_types: HashMap<(String, String), ComponentType>, // 按包存储的组件类型，key为(包名, 类型名)，均为小写；解析带包前缀的分类器引用
+    package_component_impls: HashMap<(String, String), ComponentImplementation>, // 按包存储的组件实现，key为(包名, Type.Impl)，均为小写
+    current_package: String, // 正在转换的包名（小写）；未带包前缀的分类器引用先在该包中查找
     pub annex_converter: AnnexConverter, // Behavior Annex 转换器
-    cpu_scheduling_protocols: HashMap<String, String>, // 存储CPU实现的调度协议信息
+    pub cpu_scheduling_protocols: HashMap<String, String>, // 存储CPU实现的调度协议信息
+    pub cpu_sched_quantum_ms: HashMap<String, u64>, // 存储CPU实现的轮转调度时间片（毫秒）
     pub cpu_name_to_id_mapping: HashMap<String, isize>, // 存储CPU名称到ID的映射关系
     data_comp_type: HashMap<String, String>, // 存储数据组件类型信息，key是数据组件名称，value是数据组件类型。是为了处理数据组件类型为结构体、联合体时，需要根据组件实现impl来获取属性信息
     
     pub thread_field_values: HashMap<String, HashMap<String, StruPropertyValue>>,// 存储线程类型字段对应的属性值，key为线程结构体名(如 fooThread)，value为字段名到属性值的映射
     pub thread_field_types: HashMap<String, HashMap<String, Type>>,// 存储线程类型字段对应的类型，key为线程结构体名(如 fooThread)，value为字段名到类型的映射。为了Shared的字段作为参数的依据
 
-    //List列表存储system内process之间的多连接关系，每条数据是组件和端口
-    pub process_broadcast_send: Vec<(String, String)>,
-    //HashMap存储system内process之间的多连接关系，key为组件和端口，value为接收(组件和端口)列表
-    process_broadcast_receive: HashMap<(String, String), Vec<(String, String)>>,
-    //HashMap存储system内subcomponent的identify和真实实现类型的映射关系
-    system_subcomponent_identify_to_type: HashMap<String, String>,
+    //由语义连接规划的端口通道，key为根系统实现名（小写，形如 t.impl）
+    pub port_channels: collector::PortChannelPlan,
+    //根系统无法直接访问的进程内部的端口通道，key为进程实现名（小写，形如 control.impl）
+    pub process_channels: collector::ProcessChannelPlan,
+    //需要使用广播通道的端口，元素为(组件类型名, 端口名)，均为小写
+    broadcast_ports: HashSet<(String, String)>,
+
+    //从各根系统实现实例化得到的实例模型
+    pub instances: Vec<SystemInstance>,
+
+    //模型中生成模块的包名（小写）；with 其他名字（属性集、未找到而被忽略的包）时不生成 use
+    pub module_packages: HashSet<String>,
+    //属性集中声明的常量，key为小写的 属性集::常量名
+    pub property_constants: HashMap<String, PropertyValue>,
+    //属性集中的属性定义，key为小写的 属性集::属性名
+    pub property_definitions: HashMap<String, PropertyDefinition>,
 
+    //分布式部署的节点，key为根系统实现名（小写）；承载进程的处理器不少于两个时才有
+    pub deployments: HashMap<String, Vec<DeploymentNode>>,
 
-    //HashMap存储process内和thread之间的多连接关系，key为process上的(端口名,process名称），value为接收线程上的(组件和端口)列表
-    pub thread_broadcast_receive: HashMap<(String, String), Vec<(String, String)>>,
-    //HashMap存储process内subcomponent(thread)的identify和真实实现类型的映射关系
-    process_subcomponent_identify_to_type: HashMap<String, String>,
+    //当前包中数据组件编解码的往返测试，包转换结束时汇总为 codec_tests 模块
+    codec_tests: Vec<FunctionDef>,
+    //当前包中 compute() 属性引用的取值函数：(函数名, 返回类型)，包转换结束时生成待用户实现的函数
+    computed_functions: Vec<(String, String)>,
+
+    //带取值范围而生成为新类型的数据类型名，端口接收时需经 on_receive 检查
+    pub ranged_types: HashSet<String>,
+
+    //定点数据类型名 -> 存储使用的整数类型（如 i64），编解码按存储的整数表示
+    pub fixed_types: HashMap<String, String>,
 }
 
 
@@ -53,7 +109,7 @@ impl Default for AadlConverter {
         type_mappings.insert("unsigned_32".to_string(), Type::Named("u32".to_string()));
         type_mappings.insert("unsigned_64".to_string(), Type::Named("u64".to_string()));
 
-        type_mappings.insert("natural".to_string(), Type::Named("usize".to_string()));
+        type_mappings.insert("natural".to_string(), Type::Named("u64".to_string()));
 
         type_mappings.insert("float".to_string(), Type::Named("f32".to_string()));
         type_mappings.insert("float_32".to_string(), Type::Named("f32".to_string()));
@@ -64,19 +120,32 @@ impl Default for AadlConverter {
         type_mappings.insert("string".to_string(), Type::Named("String".to_string()));
 
         Self {
+            backend: CodegenBackend::default(),
             type_mappings,
             component_types: HashMap::new(),
+            component_impls: HashMap::new(),
             annex_converter: AnnexConverter::default(),
             cpu_scheduling_protocols: HashMap::new(),
+            cpu_sched_quantum_ms: HashMap::new(),
             cpu_name_to_id_mapping: HashMap::new(),
             data_comp_type: HashMap::new(),
             thread_field_values: HashMap::new(),
             thread_field_types: HashMap::new(),
-            process_broadcast_send: Vec::new(),
-            process_broadcast_receive: HashMap::new(),
-            system_subcomponent_identify_to_type: HashMap::new(),
-            thread_broadcast_receive: HashMap::new(),
-            process_subcomponent_identify_to_type: HashMap::new(),
+            port_channels: HashMap::new(),
+            process_channels: HashMap::new(),
+            broadcast_ports: HashSet::new(),
+            instances: Vec::new(),
+            module_packages: HashSet::new(),
+            property_constants: HashMap::new(),
+            property_definitions: HashMap::new(),
+            package_component_types: HashMap::new(),
+            package_component_impls: HashMap::new(),
+            current_package: String::new(),
+            deployments: HashMap::new(),
+            codec_tests: Vec::new(),
+            computed_functions: Vec::new(),
+            ranged_types: HashSet::new(),
+            fixed_types: HashMap::new(),
         }
     }
 }
@@ -86,26 +155,162 @@ impl AadlConverter {
     pub fn type_for_property(&self, value: &StruPropertyValue) -> String {
         match value {
             StruPropertyValue::Boolean(_) => "bool".to_string(),
+            StruPropertyValue::Integer(value) if *value < 0 => "i64".to_string(),
             StruPropertyValue::Integer(_) => "u64".to_string(),
             StruPropertyValue::Float(_) => "f64".to_string(),
             StruPropertyValue::String(_) => "String".to_string(),
             StruPropertyValue::Duration(_, _) => "u64".to_string(),
+            StruPropertyValue::Range(min, _, _) if *min < 0 => "(i64, i64)".to_string(),
             StruPropertyValue::Range(_, _, _) => "(u64, u64)".to_string(),
+            StruPropertyValue::RealRange(_, _, _) => "(f64, f64)".to_string(),
+            StruPropertyValue::List(elements) => {
+                format!("Vec<{}>", self.list_element_type(elements).unwrap_or_default())
+            }
+            StruPropertyValue::Record(fields) => {
+                let types: Vec<String> = fields.iter().map(|(_, value)| self.type_for_property(value)).collect();
+                if types.len() == 1 { format!("({},)", types[0]) } else { format!("({})", types.join(", ")) }
+            }
+            StruPropertyValue::Computed(_) => "String".to_string(),
             StruPropertyValue::None => "None".to_string(),
             StruPropertyValue::Custom(s) => s.to_string(),
         }
     }
+
+    // 列表元素的统一类型；整数元素中有负数时统一为 i64，类型不一致或列表为空时返回 None
+    fn list_element_type(&self, elements: &[StruPropertyValue]) -> Option<String> {
+        let types: Vec<String> = elements.iter().map(|element| self.type_for_property(element)).collect();
+        let first = types.first()?;
+        if types.iter().all(|ty| ty == first) {
+            return Some(first.clone());
+        }
+        ["i64", "(i64, i64)"]
+            .into_iter()
+            .find(|signed| types.iter().all(|ty| *ty == signed.replace('i', "u") || ty == signed))
+            .map(str::to_string)
+    }
+
+    // 线程属性字段的类型；compute() 的字段是取值函数指针 fn() -> 属性类型，函数留到包转换结束时生成
+    pub fn property_field_type(&mut self, identifier: &PropertyIdentifier, value: &StruPropertyValue) -> String {
+        let StruPropertyValue::Computed(function) = value else {
+            return self.type_for_property(value);
+        };
+        let return_type = self.computed_return_type(identifier, function);
+        let name = computed_function_name(function);
+        if !self.computed_functions.iter().any(|(existing, _)| *existing == name) {
+            self.computed_functions.push((name, return_type.clone()));
+        }
+        format!("fn() -> {}", return_type)
+    }
+
+    // compute() 的返回类型取属性集中该属性定义的类型；找不到定义时报告后按字符串处理
+    fn computed_return_type(&self, identifier: &PropertyIdentifier, function: &str) -> String {
+        let Some(definition) = self.property_definition(identifier.property_set.as_deref(), &identifier.name) else {
+            diagnostics::warning(
+                codes::UNTYPED_COMPUTED_PROPERTY,
+                format!("{} => compute({}): property definition not found, the function returns String", identifier.name, function),
+            );
+            return "String".to_string();
+        };
+        fn rust_type(ty: &PropertyType) -> String {
+            match ty {
+                PropertyType::Integer => "i64".to_string(),
+                PropertyType::Real => "f64".to_string(),
+                PropertyType::Boolean => "bool".to_string(),
+                PropertyType::List(element) => format!("Vec<{}>", rust_type(element)),
+                PropertyType::Range(element) => format!("({0}, {0})", rust_type(element)),
+                _ => "String".to_string(),
+            }
+        }
+        rust_type(&definition.property_type)
+    }
+
+    // 属性集中的属性定义；不带属性集前缀时按属性名在所有属性集中查找
+    pub fn property_definition(&self, property_set: Option<&str>, name: &str) -> Option<&PropertyDefinition> {
+        match property_set {
+            Some(set) => self.property_definitions.get(&format!("{}::{}", set, name).to_lowercase()),
+            None => {
+                let suffix = format!("::{}", name.to_lowercase());
+                self.property_definitions
+                    .iter()
+                    .filter(|(key, _)| key.ends_with(&suffix))
+                    .min_by_key(|(key, _)| key.as_str())
+                    .map(|(_, definition)| definition)
+            }
+        }
+    }
+
+    // 属性字段的文档注释；记录值列出字段名，compute() 注明计算函数
+    pub fn property_field_doc(&self, prefix: &str, name: &str, value: &StruPropertyValue) -> String {
+        match value {
+            StruPropertyValue::Record(fields) => {
+                let names: Vec<&str> = fields.iter().map(|(field, _)| field.as_str()).collect();
+                format!("// {}: {} [{}]", prefix, name, names.join(", "))
+            }
+            StruPropertyValue::Computed(function) => format!("// {}: {} => compute({})", prefix, name, function),
+            _ => format!("// {}: {}", prefix, name),
+        }
+    }
+    // 预先收集模型中所有包的组件类型与实现，使跨包引用（如系统引用其他包中的进程实现）在转换任意包时都可查到
+    pub fn collect_packages<'a>(&mut self, packages: impl IntoIterator<Item = &'a Package>) {
+        for pkg in packages {
+            collector::collect_component_types(&mut self.component_types, pkg);
+            collector::collect_component_implementations(&mut self.component_impls, pkg);
+            collector::collect_package_classifiers(&mut self.package_component_types, &mut self.package_component_impls, pkg);
+        }
+    }
+
+    // 解析分类器引用的组件实现：带包前缀时只在该包中查找，否则先查正在转换的包，再按名称查找
+    pub fn resolve_component_impl(&self, reference: &UniqueImplementationReference) -> Option<&ComponentImplementation> {
+        let name = reference.implementation_name.to_string();
+        match &reference.package_prefix {
+            Some(prefix) => self.package_component_impls.get(&(prefix.to_string().to_lowercase(), name.to_lowercase())),
+            None => self
+                .package_component_impls
+                .get(&(self.current_package.clone(), name.to_lowercase()))
+                .or_else(|| self.component_impls.get(&name)),
+        }
+    }
+
+    // 解析分类器引用的组件类型，查找顺序同 resolve_component_impl
+    pub fn resolve_component_type(&self, reference: &UniqueImplementationReference) -> Option<&ComponentType> {
+        let name = &reference.implementation_name.type_identifier;
+        match &reference.package_prefix {
+            Some(prefix) => self.package_component_types.get(&(prefix.to_string().to_lowercase(), name.to_lowercase())),
+            None => self
+                .package_component_types
+                .get(&(self.current_package.clone(), name.to_lowercase()))
+                .or_else(|| self.component_types.get(name)),
+        }
+    }
+
+    // 由加载的 Base_Types 包中各数据类型的属性确定其Rust类型，覆盖默认映射表中的同名项
+    pub fn register_base_types(&mut self, pkg: &Package) {
+        let sections = pkg.public_section.iter().chain(pkg.private_section.iter());
+        for decl in sections.flat_map(|section| section.declarations.iter()) {
+            if let AadlDeclaration::ComponentType(comp) = decl {
+                if let Some(rust_type) = conv_data_type::base_type_mapping(comp) {
+                    self.type_mappings.insert(comp.identifier.to_lowercase(), rust_type);
+                }
+            }
+        }
+    }
+
     // 主转换入口
     pub fn convert_package(&mut self, pkg: &Package) -> RustModule {
         // 首先收集所有组件类型信息
         collector::collect_component_types(&mut self.component_types, pkg);
+        collector::collect_component_implementations(&mut self.component_impls, pkg);
+        collector::collect_package_classifiers(&mut self.package_component_types, &mut self.package_component_impls, pkg);
+        self.current_package = pkg.name.to_string().to_lowercase();
+
+        // 嵌入式后端中字符串数据映射为固定容量的 heapless::String
+        if self.backend == CodegenBackend::Embedded {
+            self.type_mappings.insert(
+                "string".to_string(),
+                Type::Named(format!("heapless::String<{}>", embedded::STRING_CAPACITY)),
+            );
+        }
 
-        //收集system内process之间的多连接关系
-        collector::collect_process_connections(&mut self.process_broadcast_send,&mut self.process_broadcast_receive,&mut self.system_subcomponent_identify_to_type,pkg);
-        //收集process内和thread之间的多连接关系
-        collector::collect_thread_connections(&mut self.thread_broadcast_receive,&mut self.process_subcomponent_identify_to_type,pkg);
-        // println!("thread_broadcast_receive: {:?}", self.thread_broadcast_receive);
-        // println!("process_subcomponent_identify_to_type: {:?}", self.process_subcomponent_identify_to_type);
 
 
         let mut module = RustModule {
@@ -136,20 +341,63 @@ impl AadlConverter {
         }
 
         //处理CPU和分配ID的映射关系，生成的Rust代码中，初始化<ID,调度协议>的映射关系
-        collector::convert_cpu_schedule_mapping(&mut module, &self.cpu_scheduling_protocols, &self.cpu_name_to_id_mapping);
-        collector::add_period_to_priority_function(&mut module, &self.cpu_scheduling_protocols);
+        //嵌入式后端由运行至完成调度器按Priority分派，不需要POSIX调度策略
+        if self.backend != CodegenBackend::Embedded {
+            //调度协议按处理器分类器记录，而CPU映射的键是绑定中的处理器子组件名，借助实例模型换算
+            let mut protocols_by_cpu = HashMap::new();
+            let mut quanta_by_cpu = HashMap::new();
+            for cpu_name in self.cpu_name_to_id_mapping.keys() {
+                let type_name = self.processor_type_name(cpu_name);
+                if let Some(protocol) = self.cpu_scheduling_protocols.get(&type_name) {
+                    protocols_by_cpu.insert(cpu_name.clone(), protocol.clone());
+                }
+                if let Some(quantum) = self.cpu_sched_quantum_ms.get(&type_name) {
+                    quanta_by_cpu.insert(cpu_name.clone(), *quantum);
+                }
+            }
+            let (sched_threads, period_priority_threads) = self.package_thread_scheduling(pkg);
+            collector::convert_cpu_schedule_mapping(&mut module, &protocols_by_cpu, &quanta_by_cpu, &self.cpu_name_to_id_mapping, sched_threads);
+            collector::add_period_to_priority_function(&mut module, &self.cpu_scheduling_protocols, period_priority_threads);
+        }
+        for (name, return_type) in std::mem::take(&mut self.computed_functions) {
+            module.items.push(Item::Function(computed_function_stub(name, return_type)));
+        }
+        if !self.codec_tests.is_empty() {
+            module.items.push(codec::test_module(std::mem::take(&mut self.codec_tests)));
+        }
         //println!("cpu_scheduling_protocols: {:?}", self.cpu_scheduling_protocols);
         //println!("cpu_name_to_id_mapping: {:?}", self.cpu_name_to_id_mapping);
         module
     }
 
+    // 包中线程实现的调度设置需求：(是否有线程设置调度策略, 是否有线程缺省 Priority 而需由 period_to_priority 按周期计算)
+    // 与 conv_thread_impl 中生成调度设置的条件一致
+    fn package_thread_scheduling(&self, pkg: &Package) -> (bool, bool) {
+        let sections = pkg.public_section.iter().chain(pkg.private_section.iter());
+        let mut needs = (false, false);
+        for decl in sections.flat_map(|section| section.declarations.iter()) {
+            let AadlDeclaration::ComponentImplementation(impl_) = decl else {
+                continue;
+            };
+            if impl_.category != ComponentCategory::Thread {
+                continue;
+            }
+            let lookup = PropertyLookup::for_impl(self, impl_);
+            let (priority, period) = (lookup.value("priority").is_some(), lookup.value("period").is_some());
+            needs.0 |= priority || period;
+            needs.1 |= !priority && period;
+        }
+        needs
+    }
+
     fn convert_withs(&self, pkg: &Package) -> Vec<RustWith> {
         let mut withs = Vec::new();
         for ele in pkg.visibility_decls.iter() {
             if let VisibilityDeclaration::Import { packages, property_sets: _ } = ele {
                         //println!("packages: {:?}", packages);
                         //withs.push(RustWith { path: packages.iter().map(|p| p.to_string()).collect(), glob: true });
-                        for pkg_name in packages.iter() {
+                        // 语法上无法区分包名与属性集名，只为生成了模块的包生成 use
+                        for pkg_name in packages.iter().filter(|p| self.module_packages.contains(&p.to_string().to_lowercase())) {
                             // 关键点：不使用 to_string()
                             // print!("pkg0:{:?}",pkg_name.0.clone());
                             let segments = pkg_name.0.iter().map(|s| s.to_ascii_lowercase()).collect();
@@ -165,26 +413,27 @@ impl AadlConverter {
         withs
     }
     // 根据实现获取组件类型
-    pub fn get_component_type(&self, impl_: &ComponentImplementation) -> Option<&ComponentType> {
-        self.component_types.get(&impl_.name.type_identifier)
+    // 处理器子组件名（可为点分路径）对应的处理器分类器类型名；实例模型中找不到时沿用原名
+    fn processor_type_name(&self, cpu_name: &str) -> String {
+        let suffix: Vec<&str> = cpu_name.split('.').collect();
+        self.instances
+            .iter()
+            .flat_map(|instance| instance.root.walk())
+            .find(|comp| {
+                comp.category == ComponentCategory::Processor
+                    && comp.path.len() >= suffix.len()
+                    && comp.path[comp.path.len() - suffix.len()..]
+                        .iter()
+                        .zip(&suffix)
+                        .all(|(a, b)| a.eq_ignore_ascii_case(b))
+            })
+            .and_then(|comp| comp.classifier.as_ref())
+            .map(|classifier| classifier.type_name.clone())
+            .unwrap_or_else(|| cpu_name.to_string())
     }
 
-    // 根据端口名称获取端口方向
-    fn get_port_direction(&self, port_name: &str) -> PortDirection {
-        // 遍历所有组件类型，查找包含该端口的组件 TODO:这里如果有两个组件都包含同名端口，且该端口的方向不同，就会有问题，
-        for comp_type in self.component_types.values() {
-            if let FeatureClause::Items(features) = &comp_type.features {
-                for feature in features {
-                    if let Feature::Port(port) = feature {
-                        if port.identifier.to_lowercase() == port_name.to_lowercase() {
-                            return port.direction;
-                        }
-                    }
-                }
-            }
-        }
-        // 如果找不到，默认返回 Out
-        PortDirection::Out
+    pub fn get_component_type(&self, impl_: &ComponentImplementation) -> Option<&ComponentType> {
+        self.component_types.get(&impl_.name.type_identifier)
     }
 
     // 根据类型生成合适的默认值
@@ -199,7 +448,11 @@ impl AadlConverter {
                     "f32" | "f64" => Expr::Literal(Literal::Float(0.0)),
                     "char" => Expr::Literal(Literal::Char('\0')),
                     "String" => Expr::Literal(Literal::Str("".to_string())),
+                    name if name.starts_with("heapless::String") => Expr::Ident("heapless::String::new()".to_string()),
                     _ => {
+                        if let Some(variant) = self.enum_default(type_name) {
+                            return variant;
+                        }
                         // 检查是否是自定义类型，通过type_mappings查找对应的Rust类型
                         if let Some(mapped_type) = self.type_mappings.get(&type_name.to_string().to_lowercase()) {
                             // 递归调用，使用映射后的类型
@@ -215,6 +468,7 @@ impl AadlConverter {
                     }
                 }
             }
+            Type::Generic(name, _) if name == "Vec" => Expr::Ident("Vec::new()".to_string()),
             _ => Expr::Literal(Literal::Int(0)), // 对于复杂类型，默认使用0
         }
     }
@@ -223,39 +477,199 @@ impl AadlConverter {
         match decl {
             AadlDeclaration::ComponentType(comp) => {
                 // 转换组件类型声明，生成对应的Rust结构体或类型定义
-                module.items.extend(self.convert_component(comp, package));
+                let params = prototype_type_params([&comp.prototypes]);
+                let items = self.convert_template(&params, |this| this.convert_component(comp, package));
+                module.items.extend(items);
             }
             AadlDeclaration::ComponentImplementation(impl_) => {
+                // 保留了未绑定原型的实现是模板：被使用时都已特化，这里只输出数据组件的泛型结构体
+                let type_prototypes = self.get_component_type(impl_).map(|comp| comp.prototypes.clone()).unwrap_or(PrototypeClause::None);
+                let params = prototype_type_params([&impl_.prototypes, &type_prototypes]);
+                if !params.is_empty() && impl_.category != ComponentCategory::Data {
+                    return;
+                }
                 // 转换组件实现声明，生成对应的Rust实现块
-                module.items.extend(self.convert_implementation(impl_, package));
+                let items = self.convert_template(&params, |this| this.convert_implementation(impl_, package));
+                module.items.extend(items);
             }
             _ => {} // TODO:忽略其他声明类型
         }
     }
 
+    // 模板的编解码实现随 impl 块一起被 generic_template 丢弃，转换时登记的往返测试也一并撤回
+    fn convert_template(&mut self, params: &[GenericParam], convert: impl FnOnce(&mut Self) -> Vec<Item>) -> Vec<Item> {
+        let tests = self.codec_tests.len();
+        let items = convert(self);
+        if !params.is_empty() {
+            self.codec_tests.truncate(tests);
+        }
+        generic_template(items, params)
+    }
+
     fn convert_component(&mut self, comp: &ComponentType, package: &Package) -> Vec<Item> {
+        if self.backend == CodegenBackend::Embedded {
+            // 线程、进程、系统的结构体由实现生成；设备只有类型，直接生成驱动骨架；总线等平台组件不生成
+            match comp.category {
+                ComponentCategory::Thread | ComponentCategory::Process | ComponentCategory::System => return Vec::default(),
+                ComponentCategory::Bus | ComponentCategory::VirtualBus | ComponentCategory::Memory => return Vec::default(),
+                ComponentCategory::Device => return embedded::convert_device_component(self, comp),
+                _ => {}
+            }
+        }
         match comp.category {
-            ComponentCategory::Data => conv_data_type::convert_data_component(&mut self.type_mappings, comp,&mut self.data_comp_type),
+            ComponentCategory::Data => {
+                // Base_Types 中预声明的类型已有映射，不生成带范围的新类型
+                let predeclared = self.type_mappings.contains_key(&comp.identifier.to_lowercase());
+                if !predeclared {
+                    if let Some(spec) = conv_data_fixed::fixed_spec(self, comp) {
+                        self.type_mappings.insert(comp.identifier.to_lowercase(), Type::Named(comp.identifier.clone()));
+                        self.fixed_types.insert(comp.identifier.clone(), spec.bits_type());
+                        let items = conv_data_fixed::fixed_items(&comp.identifier, &spec);
+                        return self.with_codec_impls(&codec::DataSource::Type(comp), items);
+                    }
+                }
+                let mut items = conv_data_type::convert_data_component(&mut self.type_mappings, comp,&mut self.data_comp_type);
+                // 嵌入式后端没有堆分配，不定长数组要求给出 Dimension；没有 Dimension 的字符串按缺省容量截断
+                if self.backend == CodegenBackend::Embedded {
+                    if let [Item::TypeAlias(alias)] = items.as_slice() {
+                        if conv_data_type::contains_unsized(&alias.target) {
+                            diagnostics::error(
+                                codes::UNSIZED_ARRAY,
+                                format!("data {}: unsized array needs Data_Model::Dimension in the embedded backend", comp.identifier),
+                            );
+                        }
+                    }
+                    if !predeclared && conv_data_type::is_unbounded_string(comp) {
+                        diagnostics::warning(
+                            codes::UNBOUNDED_STRING,
+                            format!(
+                                "data {}: string without Data_Model::Dimension is bounded to heapless::String<{}> in the embedded backend",
+                                comp.identifier,
+                                embedded::STRING_CAPACITY
+                            ),
+                        );
+                    }
+                }
+                if !predeclared {
+                    if let Some(ranged) = conv_data_range::convert_ranged_component(self, comp, &items) {
+                        self.type_mappings.insert(comp.identifier.to_lowercase(), Type::Named(comp.identifier.clone()));
+                        self.ranged_types.insert(comp.identifier.clone());
+                        items = ranged;
+                    }
+                }
+                self.with_codec_impls(&codec::DataSource::Type(comp), items)
+            }
             ComponentCategory::Thread => conv_thread_type::convert_thread_component(self, comp),
             ComponentCategory::Subprogram => conv_subprogram_type::convert_subprogram_component(self,comp, package),
             ComponentCategory::System => conv_system_type::convert_system_component(self, comp),
             ComponentCategory::Process => conv_process_type::convert_process_component(self, comp),
             ComponentCategory::Device => conv_device_type::convert_device_component(self,comp),
+            ComponentCategory::Bus | ComponentCategory::VirtualBus | ComponentCategory::Memory => conv_bus_type::convert_bus_component(self, comp),
             _ => Vec::default(), //TODO:其他组件类型还需要处理
         }
     }
 
     fn convert_implementation(&mut self, impl_: &ComponentImplementation, package: &Package) -> Vec<Item> {
+        if self.backend == CodegenBackend::Embedded {
+            match impl_.category {
+                ComponentCategory::Process => return embedded::convert_process_implementation(impl_),
+                ComponentCategory::Thread => return embedded::convert_thread_implementation(self, impl_),
+                ComponentCategory::System => return embedded::convert_system_implementation(self, impl_),
+                ComponentCategory::Data => {
+                    let items = conv_data_impl::convert_data_implementation(&self.type_mappings, &self.data_comp_type, impl_, package);
+                    return embedded::single_core_shared_data(items);
+                }
+                _ => {}
+            }
+        }
         match impl_.category {
             ComponentCategory::Process => conv_process_impl::convert_process_implementation(self,impl_),
             ComponentCategory::Thread => conv_thread_impl::convert_thread_implemenation(self,impl_),
             ComponentCategory::System => conv_system_impl::convert_system_implementation(self,impl_),
-            ComponentCategory::Data => conv_data_impl::convert_data_implementation(&self.type_mappings,&self.data_comp_type,impl_,package),
-            ComponentCategory::Processor => conv_processor_impl::convert_processor_implementation(&mut self.cpu_scheduling_protocols,impl_),
+            ComponentCategory::Data => {
+                let items = conv_data_impl::convert_data_implementation(&self.type_mappings,&self.data_comp_type,impl_,package);
+                self.with_codec_impls(&codec::DataSource::Impl(impl_), items)
+            }
+            ComponentCategory::Processor => conv_processor_impl::convert_processor_implementation(self, impl_),
             _ => Vec::default(), // 默认实现
         }
     }
 
+    // 为数据组件生成的类型追加二进制编解码实现，往返测试留到包转换结束时统一输出
+    // 嵌入式后端不生成 codec.rs（编解码依赖 Vec、String），不追加
+    fn with_codec_impls(&mut self, source: &codec::DataSource, mut items: Vec<Item>) -> Vec<Item> {
+        if self.backend == CodegenBackend::Embedded {
+            return items;
+        }
+        let generated = codec::codec_items(self, source, &items);
+        items.extend(generated.items);
+        self.codec_tests.extend(generated.tests);
+        items
+    }
+
+    // 带 Representation 的枚举数据类型的 #[repr] 整数类型
+    pub fn enum_repr(&self, type_name: &str) -> Option<String> {
+        let comp = self
+            .component_types
+            .values()
+            .find(|t| t.category == ComponentCategory::Data && t.identifier.eq_ignore_ascii_case(type_name))?;
+        let PropertyClause::Properties(props) = &comp.properties else { return None };
+        conv_data_type::enum_repr(comp, props)
+    }
+
+    // C 包装函数经 &mut 写回带 Representation 的枚举时返回 Result，调用方只在编码有效时发送
+    pub fn is_checked_out_param(&self, ty: &Type) -> bool {
+        matches!(ty, Type::Named(type_name) if self.enum_repr(type_name).is_some())
+    }
+
+    // 枚举数据类型的默认值：第一个枚举值，如 Valve_State::Closed
+    fn enum_default(&self, type_name: &str) -> Option<Expr> {
+        let comp = self
+            .component_types
+            .values()
+            .find(|t| t.category == ComponentCategory::Data && t.identifier.eq_ignore_ascii_case(type_name))?;
+        let PropertyClause::Properties(props) = &comp.properties else { return None };
+        let variant = conv_data_type::first_enumerator(comp, props)?;
+        Some(Expr::Ident(format!("{}::{}", comp.identifier, variant)))
+    }
+
+    // 组件类型中数据类型为带范围新类型的输入端口：端口名（小写） -> 新类型名
+    pub fn ranged_in_ports(&self, comp_identifier: &str) -> HashMap<String, String> {
+        let mut ports = HashMap::new();
+        let Some(comp) = self.component_types.values().find(|t| t.identifier.eq_ignore_ascii_case(comp_identifier)) else {
+            return ports;
+        };
+        if let FeatureClause::Items(features) = &comp.features {
+            for feature in features {
+                if let Feature::Port(port) = feature {
+                    // InOut 端口按发送端生成，只有 In 端口会接收
+                    let (PortDirection::In, PortType::Data { classifier: Some(classifier) } | PortType::EventData { classifier: Some(classifier) }) =
+                        (port.direction, &port.port_type)
+                    else {
+                        continue;
+                    };
+                    if let Type::Named(name) = self.classifier_to_type(classifier) {
+                        if self.ranged_types.contains(&name) {
+                            ports.insert(port.identifier.to_lowercase(), name);
+                        }
+                    }
+                }
+            }
+        }
+        ports
+    }
+
+    // 端口接收表达式 receive（结果为 Result）按数据类型加上范围检查：带范围的新类型经 on_receive 重新检查，
+    // 结果仍是 Result，被拒绝的越界值与没有收到数据一样走 Err 分支
+    pub fn checked_receive(receive: Expr, ranged_type: Option<&String>) -> Expr {
+        let Some(ranged_type) = ranged_type else {
+            return receive;
+        };
+        let ok = Expr::MethodCall(Box::new(receive), "ok".to_string(), Vec::new());
+        let checked = Expr::MethodCall(Box::new(ok), "and_then".to_string(), vec![Expr::Ident(format!("{}::on_receive", ranged_type))]);
+        Expr::MethodCall(Box::new(checked), "ok_or".to_string(), vec![Expr::Ident("()".to_string())])
+    }
+
     pub fn convert_type_features(&self, features: &FeatureClause, comp_identifier: String) -> Vec<Field> {
         let mut fields = Vec::new();
 
@@ -268,6 +682,7 @@ impl AadlConverter {
                             ty: self.convert_port_type(port,comp_identifier.clone()),
                             docs: vec![format!("// Port: {} {:?}", port.identifier, port.direction)],
                             attrs: Vec::new(),
+                            vis: Visibility::Public,
                         });
                     }
                     Feature::SubcomponentAccess(sub_access) => {
@@ -313,12 +728,14 @@ impl AadlConverter {
                                                 }
                                             )],
                                             attrs: Vec::new(),
+                                            vis: Visibility::Public,
                                         });
                                     }
                                 }
                             }
                         }
                     }
+                    Feature::Abstract(_) => {} // 未绑定的抽象特征没有对应的通道
                 }
             }
         }
@@ -327,67 +744,17 @@ impl AadlConverter {
     }
 
     pub fn convert_port_type(&self, port: &PortSpec, comp_identifier: String) -> Type {
-        // 确定通道类型（Sender/Receiver）
-        let mut channel_type = String::new();
-        //如果comp_identifier不为空，首先需要查看process_broadcast_receive中的键值对中的键或值中是否包含port.identifier
-        //如果有，则根据该项的键，在system_subcomponent_identify_to_type中找到它对应的组件identify值，判断该值和comp_identifier是否相同
-        //如果相同，则说明该端口是广播端口，通道类型需要设置为BcReceiver或BcSender，否则设置为Receiver或Sender
-        if !comp_identifier.is_empty() {
-            for (subcomponent_port, vercport) in &self.process_broadcast_receive {
-                //先判断键（发送）是否包含port.identifier
-                
-                if subcomponent_port.1.eq(&port.identifier) {
-                    if let Some(subcomponent_identify) = self.system_subcomponent_identify_to_type.get(&subcomponent_port.0.clone()) {
-                        if subcomponent_identify.eq(&comp_identifier) {
-                            channel_type = match port.direction {
-                                PortDirection::Out => "BcSender".to_string(),
-                                _ => panic!("error, In port is not allowed in broadcast send ports"),
-                            };
-                            continue;
-                        }
-                    }
-                };
-                //再判断值（接收）是否包含port.identifier
-                for (comp, port_identifier) in vercport {
-                    if port_identifier.eq(&port.identifier) {
-                        if let Some(subcomponent_identify) = self.system_subcomponent_identify_to_type.get(&comp.clone()) {
-                            if subcomponent_identify.eq(&comp_identifier) {
-                                channel_type = match port.direction {
-                                        PortDirection::In => "BcReceiver".to_string(),
-                                        _ => panic!("error, Out port is not allowed in broadcast receive ports"),
-                                };
-                            }
-                        };
-                        
-                    }
-                }
-            }
-
-            //针对process中线程端口是广播类型的情况进行处理
-            //只可能在thread_broadcast_receive的值中存在
-            for vercport in self.thread_broadcast_receive.values() {
-                for (comp, port_identifier) in vercport {
-                    if port_identifier.eq(&port.identifier) {
-                        if let Some(subcomponent_identify) = self.process_subcomponent_identify_to_type.get(&comp.clone()) {
-                            if subcomponent_identify.eq(&comp_identifier) {
-                                channel_type = match port.direction {
-                                    PortDirection::In => "BcReceiver".to_string(),
-                                    _ => panic!("error, Out port is not allowed in broadcast receive ports"),
-                                };
-                                continue;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        if channel_type.is_empty() {
-            channel_type = match port.direction {
-                PortDirection::In => "Receiver".to_string(),
-                PortDirection::Out => "Sender".to_string(),
-                PortDirection::InOut => "Sender".to_string(), //TODO:不支持双向通道，暂时这样写
-            };
-        }
+        // 确定通道类型（Sender/Receiver）；语义连接规划为广播的端口使用BcSender/BcReceiver
+        let is_broadcast = !comp_identifier.is_empty()
+            && self
+                .broadcast_ports
+                .contains(&(comp_identifier.to_lowercase(), port.identifier.to_lowercase()));
+        let channel_type = match (port.direction, is_broadcast) {
+            (PortDirection::In, false) => "Receiver",
+            (PortDirection::In, true) => "BcReceiver",
+            (_, false) => "Sender", //TODO:不支持双向通道，InOut暂时按发送端处理
+            (_, true) => "BcSender",
+        };
 
         // 确定内部数据类型
         let inner_type = match &port.port_type {
@@ -413,6 +780,8 @@ impl AadlConverter {
         //println!("classifier: {:?}", classifier);
         //println!("-------------------------------");
         match classifier {
+            // 未绑定的数据原型对应所在泛型结构体的类型参数
+            PortDataTypeReference::Prototype(name) => Type::Named(to_upper_camel_case(name)),
             PortDataTypeReference::Classifier(UniqueComponentClassifierReference::Type(
                 ref type_ref,
             )) => {
@@ -458,11 +827,12 @@ impl AadlConverter {
             return None; // 跳过非基础属性
         };
 
-        let docs = vec![format!("// AADL属性: {}", bp.identifier.name)];
+        let value = self.parse_property_value(&bp.value)?;
+        let docs = vec![self.property_field_doc("AADL属性", &bp.identifier.name, &value)];
 
         Some(StruProperty {
             name: bp.identifier.name.clone(),
-            value: self.parse_property_value(&bp.value)?,
+            value,
             docs,
         })
     }
@@ -471,7 +841,86 @@ impl AadlConverter {
     pub fn parse_property_value(&self, value: &PropertyValue) -> Option<StruPropertyValue> {
         match value {
             PropertyValue::Single(expr) => self.parse_property_expression(expr),
-            _ => None, // 忽略其他复杂属性
+            // 区间值在解析时被包装为单元素列表，仍按区间处理
+            PropertyValue::List(elements) => match elements.as_slice() {
+                [PropertyListElement::Value(expr @ PropertyExpression::IntegerRange(_))] => {
+                    self.parse_property_expression(expr)
+                }
+                _ => self.parse_property_list(elements),
+            },
+        }
+    }
+
+    // 列表值：所有元素都能解析且类型一致时才生成，引用等无法表示的元素使整个列表被忽略
+    fn parse_property_list(&self, elements: &[PropertyListElement]) -> Option<StruPropertyValue> {
+        let values = elements
+            .iter()
+            .map(|element| match element {
+                PropertyListElement::Value(expr) => self.parse_property_expression(expr),
+                PropertyListElement::NestedList(nested) => self.parse_property_value(&PropertyValue::List(nested.clone())),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        self.list_element_type(&values)?;
+        Some(StruPropertyValue::List(values))
+    }
+
+    // 查属性集中的常量并带上符号；不带属性集前缀时按常量名在所有属性集中查找
+    fn resolve_property_constant(&self, constant: &PropertyConstantTerm, sign: Option<Sign>) -> Option<StruPropertyValue> {
+        let value = match &constant.property_set {
+            Some(property_set) => self
+                .property_constants
+                .get(&format!("{}::{}", property_set, constant.name).to_lowercase()),
+            None => {
+                let suffix = format!("::{}", constant.name.to_lowercase());
+                self.property_constants
+                    .iter()
+                    .filter(|(key, _)| key.ends_with(&suffix))
+                    .min_by_key(|(key, _)| key.as_str())
+                    .map(|(_, value)| value)
+            }
+        }?;
+        match (sign, self.parse_property_value(value)?) {
+            (Some(Sign::Minus), StruPropertyValue::Integer(value)) => Some(StruPropertyValue::Integer(-value)),
+            (Some(Sign::Minus), StruPropertyValue::Float(value)) => Some(StruPropertyValue::Float(-value)),
+            (Some(Sign::Minus), _) => None,
+            (_, value) => Some(value),
+        }
+    }
+
+    // 区间的界值：整数、实数或（可带符号的）属性常量
+    fn parse_range_bound(&self, bound: &str) -> Option<StruPropertyValue> {
+        let number = bound.replace('_', "");
+        if let Ok(value) = number.parse::<i64>() {
+            return Some(StruPropertyValue::Integer(value));
+        }
+        if let Ok(value) = number.parse::<f64>() {
+            return Some(StruPropertyValue::Float(value));
+        }
+        let (sign, name) = match bound.strip_prefix('-') {
+            Some(name) => (Some(Sign::Minus), name),
+            None => (None, bound.trim_start_matches('+')),
+        };
+        let constant = match name.rsplit_once("::") {
+            Some((property_set, name)) => PropertyConstantTerm {
+                property_set: Some(property_set.to_string()),
+                name: name.to_string(),
+            },
+            None => PropertyConstantTerm { property_set: None, name: name.to_string() },
+        };
+        self.resolve_property_constant(&constant, sign)
+    }
+
+    // 区间值：两端都是整数时为整数区间，否则为实数区间
+    fn parse_range(&self, range: &IntegerRangeTerm) -> Option<StruPropertyValue> {
+        let unit = range.lower.unit.clone().or_else(|| range.upper.unit.clone());
+        let as_float = |value: StruPropertyValue| match value {
+            StruPropertyValue::Integer(value) => Some(value as f64),
+            StruPropertyValue::Float(value) => Some(value),
+            _ => None,
+        };
+        match (self.parse_range_bound(&range.lower.value)?, self.parse_range_bound(&range.upper.value)?) {
+            (StruPropertyValue::Integer(min), StruPropertyValue::Integer(max)) => Some(StruPropertyValue::Range(min, max, unit)),
+            (min, max) => Some(StruPropertyValue::RealRange(as_float(min)?, as_float(max)?, unit)),
         }
     }
 
@@ -485,11 +934,16 @@ impl AadlConverter {
             PropertyExpression::String(string_term) => self.parse_string_term(string_term),
 
             // 范围类型处理
-            PropertyExpression::IntegerRange(range_term) => Some(StruPropertyValue::Range(
-                range_term.lower.value.parse().ok()?,
-                range_term.upper.value.parse().ok()?,
-                range_term.lower.unit.clone(),
-            )),
+            PropertyExpression::IntegerRange(range_term) => self.parse_range(range_term),
+
+            // 记录值：所有字段都能解析时才生成
+            PropertyExpression::Record(record) => record
+                .fields
+                .iter()
+                .map(|field| Some((field.name.to_lowercase(), self.parse_property_value(&field.value)?)))
+                .collect::<Option<Vec<_>>>()
+                .map(StruPropertyValue::Record),
+            PropertyExpression::Computed(computed) => Some(StruPropertyValue::Computed(computed.function.clone())),
 
             // 其他复杂类型暂不处理
             _ => None,
@@ -500,7 +954,7 @@ impl AadlConverter {
     fn parse_boolean_term(&self, term: &BooleanTerm) -> Option<StruPropertyValue> {
         match term {
             BooleanTerm::Literal(b) => Some(StruPropertyValue::Boolean(*b)),
-            BooleanTerm::Constant(_) => None, // 常量需要查表解析，此处简化
+            BooleanTerm::Constant(constant) => self.resolve_property_constant(constant, None),
         }
     }
 
@@ -514,7 +968,7 @@ impl AadlConverter {
                 }) * signed_real.value;
                 Some(StruPropertyValue::Float(value))
             }
-            SignedRealOrConstant::Constant { .. } => None, // TODO:常量需要查表
+            SignedRealOrConstant::Constant { sign, constant } => self.resolve_property_constant(constant, *sign),
         }
     }
 
@@ -528,7 +982,7 @@ impl AadlConverter {
                 }) * signed_int.value;
                 Some(StruPropertyValue::Integer(value))
             }
-            SignedIntergerOrConstant::Constant { .. } => None, // 常量需要查表
+            SignedIntergerOrConstant::Constant { sign, constant } => self.resolve_property_constant(constant, *sign),
         }
     }
 
@@ -536,267 +990,415 @@ impl AadlConverter {
     fn parse_string_term(&self, term: &StringTerm) -> Option<StruPropertyValue> {
         match term {
             StringTerm::Literal(s) => Some(StruPropertyValue::String(s.clone())),
-            StringTerm::Constant(_) => None, // 常量需要查表
+            StringTerm::Constant(constant) => self.resolve_property_constant(constant, None),
+        }
+    }
+
+
+    // 由实例模型规划端口通道：在 collect_packages 之后、转换各包之前调用
+    pub fn plan_port_channels(&mut self) {
+        let (port_channels, process_channels, broadcast_ports) = collector::collect_port_channels(&self.instances);
+        self.port_channels = port_channels;
+        self.process_channels = process_channels;
+        self.broadcast_ports = broadcast_ports;
+    }
+
+    // 预先规划处理器：为根系统绑定的CPU分配ID，并记录各处理器实现的调度协议与时间片。
+    // 线程所在的包可能先于系统与处理器所在的包转换，这样各包生成的调度映射才一致
+    pub fn plan_processors(&mut self) {
+        let bindings: Vec<(String, String)> = self
+            .instances
+            .iter()
+            .filter_map(collector::root_impl_key)
+            .filter_map(|key| {
+                self.component_impls
+                    .values()
+                    .find(|impl_| impl_.name.to_string().to_lowercase() == key)
+            })
+            .flat_map(conv_system_impl::extract_processor_bindings)
+            .collect();
+        self.assign_cpu_ids(&bindings);
+
+        let mut processors: Vec<&ComponentImplementation> = self
+            .component_impls
+            .values()
+            .filter(|impl_| impl_.category == ComponentCategory::Processor)
+            .collect();
+        processors.sort_by_key(|impl_| impl_.name.to_string());
+        let mut quanta = HashMap::new();
+        let mut protocols = HashMap::new();
+        for impl_ in processors {
+            let (quantum, protocol) = conv_processor_impl::processor_scheduling(self, impl_);
+            let cpu_name = impl_.name.type_identifier.clone();
+            if let Some(quantum) = quantum {
+                quanta.insert(cpu_name.clone(), quantum);
+            }
+            if let Some(protocol) = protocol {
+                protocols.insert(cpu_name, protocol);
+            }
         }
+        self.cpu_sched_quantum_ms.extend(quanta);
+        self.cpu_scheduling_protocols.extend(protocols);
     }
 
+    // 规划分布式部署：在 plan_processors 之后调用，节点的 CPU ID 与进程的 cpu_id 字段一致。
+    // 嵌入式后端整体运行在一个 MCU 上，不做分布式部署
+    pub fn plan_deployment(&mut self) {
+        if self.backend == CodegenBackend::Embedded {
+            return;
+        }
+        let mut deployments = HashMap::new();
+        for instance in &self.instances {
+            let Some(key) = collector::root_impl_key(instance) else {
+                continue;
+            };
+            let nodes = deployment::plan_nodes(self, instance);
+            if nodes.len() >= 2 {
+                deployments.insert(key, nodes);
+            }
+        }
+        self.deployments = deployments;
+    }
 
-    pub fn create_channel_connection(&self, conn: &PortConnection, comp_name: String) -> Vec<Statement> {
+    // 为 (组件, CPU名) 绑定中每个新出现的CPU名称分配一个ID
+    pub fn assign_cpu_ids(&mut self, processor_bindings: &[(String, String)]) {
+        for (_, cpu_name) in processor_bindings {
+            if !self.cpu_name_to_id_mapping.contains_key(cpu_name) {
+                let next_id: isize = self.cpu_name_to_id_mapping.len().try_into().expect("length does not fit into isize");
+                self.cpu_name_to_id_mapping.insert(cpu_name.clone(), next_id);
+            }
+        }
+    }
+
+    // 在根系统的 new() 中按语义连接直接连通线程/设备端口：每组端口一个通道，扇出时使用广播通道
+    pub fn create_system_channels(&self, impl_: &ComponentImplementation) -> Vec<Statement> {
         let mut stmts = Vec::new();
+        let key = impl_.name.to_string().to_lowercase();
+        let Some(instance) = self.instances.iter().find(|i| collector::root_impl_key(i).as_deref() == Some(key.as_str())) else {
+            return stmts;
+        };
+        let Some(channels) = self.port_channels.get(&key) else {
+            return stmts;
+        };
+
+        // 分布式部署：先登记节点，跨节点的通道再交给 crate::net 转发
+        let nodes = self.deployments.get(&key);
+        if let Some(nodes) = nodes {
+            stmts.push(deployment::deploy_statement(nodes));
+        }
 
-        // 定义标志位，标志是否创建了通道
-        let mut is_channel_created = false;
-
-        // 根据连接是否是广播，创建适当的channel。
-        // 目前这种检查只存在于针对system中进程之间的连接。
-        let mut is_broadcast = false;
-        if let PortEndpoint::SubcomponentPort { subcomponent, port } = &conn.source {
-            if self.process_broadcast_send.contains(&(subcomponent.clone(), port.clone())) {
-                //广播的channel使用tokio::sync::broadcast::channel::<>。
-                is_broadcast = true;
-                stmts.push(Statement::Let(LetStmt {
-                    ifmut: false,
-                    name: "channel".to_string(),
-                    ty: None,
-                    init: Some(Expr::Call(
-                        Box::new(Expr::Path(vec!["broadcast".to_string(), "channel".to_string(), "<>".to_string()], PathType::Namespace)),
-                        vec![Expr::Literal(Literal::Int(100))],
-                    )),
-                }));
-                is_channel_created = true;
-            }
-        } else if let PortEndpoint::ComponentPort (proc_port) = &conn.source {
-            if self.thread_broadcast_receive.contains_key(&(proc_port.clone(), comp_name.clone())){
-                is_broadcast = true;
-                stmts.push(Statement::Let(LetStmt {
-                    ifmut: false,
-                    name: "channel".to_string(),
-                    ty: None,
-                    init: Some(Expr::Call(
-                        Box::new(Expr::Path(vec!["broadcast".to_string(), "channel".to_string(), "<>".to_string()], PathType::Namespace)),
-                        vec![Expr::Literal(Literal::Int(100))],
-                    )),
-                }));
-                is_channel_created = true;
-            }
-        }
-
-        if !is_channel_created {
-            //非广播的channel使用crossbeam_channel::unbounded。
+        let mut created_buses = HashSet::new();
+        for (index, channel) in channels.iter().enumerate() {
+            let var_name = format!("conn{}", index);
+            stmts.push(Statement::Expr(Expr::Ident(format!(
+                "// semantic connection: {}",
+                channel.declarations.join(", ")
+            ))));
+
+            // 绑定到总线的通道：端口两端各持一段通道，由 crate::bus::Bus 转发；每条总线只创建一次
+            let bus_var = channel.bus.as_ref().map(|path| format!("bus_{}", path.join("_").to_lowercase()));
+            if let (Some(path), Some(bus_var)) = (&channel.bus, &bus_var) {
+                stmts.push(Statement::Expr(Expr::Ident(format!("// bound to bus: {}", path.join(".")))));
+                if created_buses.insert(bus_var.clone()) {
+                    stmts.push(Statement::Let(LetStmt {
+                        ifmut: false,
+                        name: bus_var.clone(),
+                        ty: None,
+                        init: Some(self.bus_constructor(instance, path)),
+                    }));
+                }
+            }
+
+            //广播的channel使用tokio::sync::broadcast::channel::<>；非广播的channel使用crossbeam_channel::unbounded，异步后端使用tokio::sync::mpsc::unbounded_channel。
+            //经总线转发时改为 bus.broadcast(100) / bus.connect()，返回值的形状与直接建立的通道相同
+            let remote = nodes.and_then(|nodes| {
+                let cpu_ids = |ports: &[FeatureRef]| {
+                    let mut ids: Vec<isize> = ports.iter().map(|port| deployment::port_cpu_id(nodes, instance, port)).collect();
+                    ids.sort();
+                    ids.dedup();
+                    ids
+                };
+                let (senders, receivers) = (cpu_ids(&channel.senders), cpu_ids(&channel.receivers));
+                let local = senders.len() == 1 && senders == receivers;
+                (!local).then_some((senders, receivers))
+            });
+            let channel_ctor = if let (Some(_), true, None) = (&remote, channel.broadcast, &bus_var) {
+                // 跨节点的广播：与总线一样分出发送段与本地投递段，由 crate::net::broadcast_link 转发
+                Expr::Call(
+                    Box::new(Expr::Path(vec!["crate".to_string(), "net".to_string(), "broadcast_pair".to_string()], PathType::Namespace)),
+                    vec![Expr::Literal(Literal::Int(100))],
+                )
+            } else if let Some(bus_var) = &bus_var {
+                let (method, args) = if channel.broadcast {
+                    ("broadcast", vec![Expr::Literal(Literal::Int(100))])
+                } else {
+                    ("connect", Vec::new())
+                };
+                Expr::MethodCall(Box::new(Expr::Ident(bus_var.clone())), method.to_string(), args)
+            } else {
+                self.local_channel_ctor(channel.broadcast)
+            };
+            let channel_ctor = match &remote {
+                Some((senders, receivers)) => {
+                    stmts.push(Statement::Expr(Expr::Ident(format!(
+                        "// remote link {}: cpu {:?} -> cpu {:?}",
+                        index, senders, receivers
+                    ))));
+                    let ids = |ids: &[isize]| Expr::Ident(format!("&{:?}", ids));
+                    let function = if channel.broadcast { "broadcast_link" } else { "link" };
+                    Expr::Call(
+                        Box::new(Expr::Path(vec!["crate".to_string(), "net".to_string(), function.to_string()], PathType::Namespace)),
+                        vec![channel_ctor, Expr::Literal(Literal::Int(index as i64)), ids(senders), ids(receivers)],
+                    )
+                }
+                None => channel_ctor,
+            };
             stmts.push(Statement::Let(LetStmt {
                 ifmut: false,
-                name: conn.identifier.clone(),
+                name: var_name.clone(),
                 ty: None, //这里的通道类型由编译器自动推导
-                init: Some(Expr::Call(
-                    Box::new(Expr::Path(
-                        vec!["crossbeam_channel".to_string(), "unbounded".to_string()],
-                        PathType::Namespace,
-                    )),
-                    Vec::new(),
-                )),
+                init: Some(channel_ctor),
+            }));
+
+            let relayed = bus_var.is_some() || remote.is_some();
+            stmts.extend(Self::assign_channel_ends(&var_name, channel, relayed, |port| {
+                collector::port_field_path(instance, port)
             }));
         }
 
-        // 处理源端和目标端
-        match (&conn.source, &conn.destination) {
-            (
-                PortEndpoint::SubcomponentPort {
-                    subcomponent: src_comp,
-                    port: src_port,
-                },
-                PortEndpoint::SubcomponentPort {
-                    subcomponent: dst_comp,
-                    port: dst_port,
-                },
-            ) => {
-                // 分配发送端
-                stmts.push(Statement::Expr(Expr::MethodCall(
-                    Box::new(Expr::Ident(format!(
-                        "{}.{}",
-                        src_comp.to_lowercase(),
-                        src_port.to_lowercase()
-                    ))),
-                    "send".to_string(), //这个关键字的固定的，例如cnx: port the_sender.p -> the_receiver.p;，前者发送，后者接收
-                    //vec![Expr::Ident("channel.0".to_string())],
-                    //需要根据标志位if_broadcast判断是否是广播端口，它的语法是channel.0.clone()
-                    vec![Expr::Call(
-                        Box::new(Expr::Path(vec!["Some".to_string()], PathType::Member)),
-                        vec![if is_broadcast { Expr::MethodCall(Box::new(Expr::Ident("channel.0.clone".to_string())), "".to_string(), Vec::new()) } 
-                            else{ Expr::Ident(format!("{}.0", conn.identifier.clone())) }],
-                    )],
-                )));
-
-                // 分配接收端
-                //需要根据标志位if_broadcast判断是否是广播端口，如果是，暂不处理，如果不是，生成channel.1
-                if !is_broadcast {
-                    stmts.push(Statement::Expr(Expr::MethodCall(
-                        Box::new(Expr::Ident(format!(
-                            "{}.{}",
-                            dst_comp.to_lowercase(),
-                            dst_port.to_lowercase()
-                        ))),
-                        "receive".to_string(),
-                        //vec![Expr::Ident("channel.1".to_string())],
-                        
-                        vec![Expr::Call(
-                            Box::new(Expr::Path(vec!["Some".to_string()], PathType::Member)),
-                            vec![Expr::Ident(format!("{}.1", conn.identifier.clone()))],
-                        )],
-                    )));
-                }
-                
-            }
-            (
-                PortEndpoint::ComponentPort(port_name),
-                PortEndpoint::SubcomponentPort {
-                    subcomponent: dst_comp,
-                    port: dst_port,
-                },
-            ) => {
-                // 处理组件端口到子组件端口的连接
-                // 根据端口方向确定内部端口名称
-                let internal_port_name = match self.get_port_direction(port_name) {
-                    PortDirection::In => format!("{}Send", port_name.to_lowercase()),
-                    PortDirection::Out => format!("{}Send", port_name.to_lowercase()), // 输出端口生成 Send
-                    PortDirection::InOut => format!("{}Send", port_name.to_lowercase()), // InOut 暂时按 In 处理
+        stmts
+    }
+
+    // 进程内部的端口通道：进程位于嵌套子系统等根系统无法直接访问的位置时，在进程实现的 new() 中连通其线程端口
+    pub fn create_process_channels(&self, impl_: &ComponentImplementation) -> Vec<Statement> {
+        let mut stmts = Vec::new();
+        let key = impl_.name.to_string().to_lowercase();
+        let Some(plan) = self.process_channels.get(&key) else {
+            return stmts;
+        };
+        let instance = &self.instances[plan.instance];
+        for (index, channel) in plan.channels.iter().enumerate() {
+            let var_name = format!("c{}", index + 1);
+            stmts.push(Statement::Expr(Expr::Ident(format!(
+                "// semantic connection: {}",
+                channel.declarations.join(", ")
+            ))));
+            stmts.push(Statement::Let(LetStmt {
+                ifmut: false,
+                name: var_name.clone(),
+                ty: None,
+                init: Some(self.local_channel_ctor(channel.broadcast)),
+            }));
+            stmts.extend(Self::assign_channel_ends(&var_name, channel, false, |port| {
+                collector::process_port_path(instance, &plan.process, port)
+            }));
+        }
+        stmts
+    }
+
+    //广播的channel使用tokio::sync::broadcast::channel::<>；非广播的channel使用crossbeam_channel::unbounded，异步后端使用tokio::sync::mpsc::unbounded_channel。
+    fn local_channel_ctor(&self, broadcast: bool) -> Expr {
+        if broadcast {
+            Expr::Call(
+                Box::new(Expr::Path(vec!["broadcast".to_string(), "channel".to_string(), "<>".to_string()], PathType::Namespace)),
+                vec![Expr::Literal(Literal::Int(100))],
+            )
+        } else {
+            let path = match self.backend {
+                CodegenBackend::Async => vec!["tokio".to_string(), "sync".to_string(), "mpsc".to_string(), "unbounded_channel".to_string()],
+                _ => vec!["crossbeam_channel".to_string(), "unbounded".to_string()],
+            };
+            Expr::Call(Box::new(Expr::Path(path, PathType::Namespace)), Vec::new())
+        }
+    }
+
+    // 把通道两端分配给端口字段：field_path 给出端口的访问路径；
+    // relayed 表示广播经总线或网络转发，接收端从投递段（.1）订阅
+    fn assign_channel_ends(
+        var_name: &str,
+        channel: &collector::PortChannel,
+        relayed: bool,
+        field_path: impl Fn(&FeatureRef) -> Option<String>,
+    ) -> Vec<Statement> {
+        let mut stmts = Vec::new();
+        let assign = |field: String, method: &str, value: String| {
+            Statement::Expr(Expr::MethodCall(
+                Box::new(Expr::Ident(field)),
+                method.to_string(),
+                vec![Expr::Call(
+                    Box::new(Expr::Path(vec!["Some".to_string()], PathType::Member)),
+                    vec![Expr::Ident(value)],
+                )],
+            ))
+        };
+
+        // 分配发送端：多个源汇入同一通道时各自持有发送端的克隆
+        for sender in &channel.senders {
+            if let Some(field) = field_path(sender) {
+                let tx = if channel.broadcast || channel.senders.len() > 1 {
+                    format!("{}.0.clone()", var_name)
+                } else {
+                    format!("{}.0", var_name)
                 };
-                
-                // 直接赋值给内部端口变量
-                if is_broadcast {
-                    stmts.push(Statement::Expr(Expr::BinaryOp(
-                        Box::new(Expr::Ident(internal_port_name)),
-                        "=".to_string(),
-                        Box::new(Expr::Call(
-                            Box::new(Expr::Path(vec!["Some".to_string()], PathType::Member)),
-                            vec![Expr::Ident("channel.0.clone()".to_string())],
-                        )),
-                    )));
+                stmts.push(assign(field, "send", tx));
+            }
+        }
+
+        // 分配接收端：广播时每个目的端口各自订阅
+        for receiver in &channel.receivers {
+            if let Some(field) = field_path(receiver) {
+                let rx = if channel.broadcast && relayed {
+                    format!("{}.1.subscribe()", var_name)
+                } else if channel.broadcast {
+                    format!("{}.0.subscribe()", var_name)
                 } else {
-                    stmts.push(Statement::Expr(Expr::BinaryOp(
-                        Box::new(Expr::Ident(internal_port_name)),
-                        "=".to_string(),
-                        Box::new(Expr::Call(
-                            Box::new(Expr::Path(vec!["Some".to_string()], PathType::Member)),
-                            vec![Expr::Ident(format!("{}.0", conn.identifier.clone()))],
-                        )),
-                    )));
-                }
-                
-                if !is_broadcast {
-                    stmts.push(Statement::Expr(Expr::MethodCall(
-                        Box::new(Expr::Ident(format!("{}.{}", dst_comp, dst_port))),
-                        "receive".to_string(),
-                        vec![Expr::Call(
-                            Box::new(Expr::Path(vec!["Some".to_string()], PathType::Member)),
-                            vec![Expr::Ident(format!("{}.1", conn.identifier.clone()))],
-                        )],
-                    )));
-                }
+                    format!("{}.1", var_name)
+                };
+                stmts.push(assign(field, "receive", rx));
             }
-            (
-                PortEndpoint::SubcomponentPort {
-                    subcomponent: src_comp,
-                    port: src_port,
-                },
-                PortEndpoint::ComponentPort(port_name),
-            ) => {
-                // 处理子组件端口到组件端口的连接（如 th_c.evenement -> evenement）
-                // 发送端给线程
-                stmts.push(Statement::Expr(Expr::MethodCall(
-                    Box::new(Expr::Ident(format!("{}.{}", src_comp, src_port))),
-                    "send".to_string(),
-                    vec![Expr::Call(
-                        Box::new(Expr::Path(vec!["Some".to_string()], PathType::Member)),
-                        vec![Expr::Ident(format!("{}.0", conn.identifier.clone()))],
-                    )],
-                )));
-
-                // 接收端给内部端口
-                //似乎这种的分配没必要，一定是Rece
-                //而且get_port_direction()有bug
-                // let internal_port_name = match self.get_port_direction(port_name) {
-                //     PortDirection::In => {println!("In port: {}", port_name);format!("{}Send", port_name.to_lowercase())},
-                //     PortDirection::Out => {println!("Out port: {}", port_name); format!("{}Rece", port_name.to_lowercase())}, // 输出端口生成 Send
-                //     PortDirection::InOut => {println!("InOut port: {}", port_name);format!("{}Send", port_name.to_lowercase())}, // InOut 暂时按 In 处理
-                // };
-                // 直接改为以下
-                let internal_port_name = format!("{}Rece", port_name.to_lowercase());
-                
-                // 直接赋值给内部端口变量
-                stmts.push(Statement::Expr(Expr::BinaryOp(
-                    Box::new(Expr::Ident(internal_port_name)),
-                    "=".to_string(),
-                    Box::new(Expr::Call(
-                        Box::new(Expr::Path(vec!["Some".to_string()], PathType::Member)),
-                        vec![Expr::Ident(format!("{}.1", conn.identifier.clone()))],
-                    )),
-                )));
-            }
-            // 可以继续添加其他端点类型的组合处理
-            _ => {
-                // 对于不支持的连接类型，生成TODO注释
-                stmts.push(Statement::Expr(Expr::Ident(format!(
-                    "// TODO: Unsupported connection type: {:?} -> {:?}",
-                    conn.source, conn.destination
-                ))));
-            }
-        }
-
-        //如果is_broadcast为true，则在此处一次性解决所有它的订阅者的订阅操作，根据process_broadcast_receive中的记录，依次订阅channel.0.subscribe()
-        if is_broadcast {
-            if let PortEndpoint::SubcomponentPort { subcomponent, port } = &conn.source {
-                if let Some(vercport) = self.process_broadcast_receive.get(&(subcomponent.clone(), port.clone())) {
-                    for (subcomponent, port) in vercport {
-                        stmts.push(Statement::Expr(Expr::MethodCall(
-                            Box::new(Expr::Ident(format!("{}.{}", subcomponent, port))),
-                            "receive".to_string(),
-                            vec![Expr::Call(
-                                Box::new(Expr::Path(vec!["Some".to_string()], PathType::Member)),
-                                vec![Expr::Ident("channel.0.subscribe()".to_string())],
-                            )],
-                        )));
-                    }
+        }
+        stmts
+    }
+
+    // 总线仿真参数：crate::bus::Bus::new(名称) 之后按总线实例上的属性依次追加
+    //   Latency => 传播时延区间；Transmission_Time => 固定开销与每字节传输时间区间；Bandwidth => 每秒字节数
+    fn bus_constructor(&self, instance: &SystemInstance, path: &[String]) -> Expr {
+        let lookup = PropertyLookup::for_instance(self, instance, path);
+        let int = |value: u64| Expr::Literal(Literal::Int(i64::try_from(value).unwrap_or(i64::MAX)));
+        let mut expr = Expr::Call(
+            Box::new(Expr::Path(
+                vec!["crate".to_string(), "bus".to_string(), "Bus".to_string(), "new".to_string()],
+                PathType::Namespace,
+            )),
+            vec![Expr::Literal(Literal::Str(path.join(".")))],
+        );
+        if let Some((min, max)) = lookup.time_range_ns("Latency", None) {
+            expr = Expr::MethodCall(Box::new(expr), "with_latency".to_string(), vec![int(min), int(max)]);
+        }
+        let fixed = lookup.time_range_ns("Transmission_Time", Some("Fixed"));
+        let per_byte = lookup.time_range_ns("Transmission_Time", Some("Per_Byte"));
+        if fixed.is_some() || per_byte.is_some() {
+            let (fixed_min, fixed_max) = fixed.unwrap_or_default();
+            let (per_byte_min, per_byte_max) = per_byte.unwrap_or_default();
+            expr = Expr::MethodCall(
+                Box::new(expr),
+                "with_transmission_time".to_string(),
+                vec![int(fixed_min), int(fixed_max), int(per_byte_min), int(per_byte_max)],
+            );
+        }
+        if let Some(rate) = lookup.bytes_per_sec("Bandwidth").filter(|rate| *rate >= 1.0) {
+            expr = Expr::MethodCall(Box::new(expr), "with_bandwidth".to_string(), vec![int(rate.round() as u64)]);
+        }
+        expr
+    }
+
+    // 线程结构体上由属性生成的字段（小写名）：线程类型及其各实现上可解析的属性
+    fn thread_property_fields(&self, type_name: &str) -> HashSet<String> {
+        let type_props = self
+            .component_types
+            .values()
+            .filter(|t| t.identifier.eq_ignore_ascii_case(type_name))
+            .map(|t| &t.properties);
+        let impl_props = self
+            .component_impls
+            .values()
+            .filter(|i| i.name.type_identifier.eq_ignore_ascii_case(type_name))
+            .map(|i| &i.properties);
+        type_props
+            .chain(impl_props)
+            .flat_map(|clause| match clause {
+                PropertyClause::Properties(props) => props.as_slice(),
+                PropertyClause::ExplicitNone => &[],
+            })
+            .filter_map(|prop| match prop {
+                Property::BasicProperty(bp) if self.parse_property_value(&bp.value).is_some() => {
+                    Some(bp.identifier.name.to_lowercase())
                 }
+                _ => None,
+            })
+            .collect()
+    }
+
+    // 根系统中按实例覆盖、且线程结构体上有对应字段的线程属性：(线程类型名, 字段访问路径, 属性名, 属性值)
+    // 覆盖来自子组件声明中的属性、外层组件的 applies to 关联，以及线程自身未声明时从容器继承的 inherit 属性
+    fn thread_property_overrides(&self, instance: &SystemInstance) -> Vec<(String, String, String, StruPropertyValue)> {
+        let mut overrides = Vec::new();
+        for component in instance.root.walk() {
+            if component.category != ComponentCategory::Thread {
+                continue;
             }
-            if let PortEndpoint::ComponentPort (proc_port) = &conn.source {
-                if self.thread_broadcast_receive.contains_key(&(proc_port.clone(), comp_name.clone())){
-                    if let Some(vercport) = self.thread_broadcast_receive.get(&(proc_port.clone(), comp_name.clone())) {
-                        for (subcomponent, port) in vercport {
-                            stmts.push(Statement::Expr(Expr::MethodCall(
-                                Box::new(Expr::Ident(format!("{}.{}", subcomponent, port))),
-                                "receive".to_string(),
-                                vec![Expr::Call(
-                                    Box::new(Expr::Path(vec!["Some".to_string()], PathType::Member)),
-                                    vec![Expr::Ident("channel.0.subscribe()".to_string())],
-                                )],
-                            )));
-                        }
-                    }
+            let (Some(classifier), Some(path)) = (
+                &component.classifier,
+                collector::component_field_path(instance, &component.path),
+            ) else {
+                continue;
+            };
+            let lookup = PropertyLookup::for_instance(self, instance, &component.path);
+            let mut fields: Vec<String> = self.thread_property_fields(&classifier.type_name).into_iter().collect();
+            fields.sort();
+            let explicit = component.instance_properties.iter().map(|bp| bp.identifier.name.clone());
+            let inherited = fields
+                .iter()
+                .filter(|field| component.property(field).is_none())
+                .filter_map(|field| lookup.inherited(field).map(|bp| bp.identifier.name.clone()));
+            for name in explicit.chain(inherited) {
+                let field = name.to_lowercase();
+                if !fields.contains(&field) {
+                    continue;
+                }
+                if let Some(value) = lookup.typed(&name) {
+                    overrides.push((classifier.type_name.clone(), format!("{}.{}", path, field), name, value));
                 }
             }
-            
-            
         }
-        
+        overrides
+    }
+
+    // 是否有线程实例覆盖了该线程类型的属性字段：是则 run() 中读取 self.<字段>，由根系统按实例赋值
+    pub fn is_thread_property_overridden(&self, type_name: &str, field: &str) -> bool {
+        let suffix = format!(".{}", field.to_lowercase());
+        self.instances.iter().any(|instance| {
+            self.thread_property_overrides(instance)
+                .iter()
+                .any(|(ty, path, _, _)| ty.eq_ignore_ascii_case(type_name) && path.ends_with(&suffix))
+        })
+    }
+
+    // 在根系统的 new() 中写入按实例覆盖的线程属性字段，如 proc.t1.period = 50
+    pub fn create_system_property_overrides(&self, impl_: &ComponentImplementation) -> Vec<Statement> {
+        let mut stmts = Vec::new();
+        let key = impl_.name.to_string().to_lowercase();
+        let Some(instance) = self.instances.iter().find(|i| collector::root_impl_key(i).as_deref() == Some(key.as_str())) else {
+            return stmts;
+        };
+        for (_, field, name, value) in self.thread_property_overrides(instance) {
+            stmts.push(Statement::Expr(Expr::Ident(format!("// instance property: {}", name))));
+            stmts.push(Statement::Expr(Expr::Assign(
+                Box::new(Expr::Ident(field)),
+                Box::new(Expr::Ident(conv_thread_impl::property_value_to_initializer(&value))),
+            )));
+        }
         stmts
     }
 
     pub fn create_component_type_docs(&self, comp: &ComponentType) -> Vec<String> {
-        let docs = vec![format!(
+        let mut docs = vec![format!(
             "// AADL {:?}: {}",
             comp.category,
             comp.identifier.to_lowercase()
         )];
+        docs.extend(source_location_doc(&comp.location));
 
         docs
     }
 
     pub fn create_component_impl_docs(&self, impl_: &ComponentImplementation) -> Vec<String> {
-        let docs = vec![format!(
+        let mut docs = vec![format!(
             "// AADL {:?}: {}",
             impl_.category,
             impl_.name.type_identifier.to_lowercase()
         )];
+        docs.extend(source_location_doc(&impl_.location));
 
         docs
     }
@@ -825,3 +1427,80 @@ impl AadlConverter {
     
 
 }
+
+// 未绑定的组件原型（数据原型）-> 泛型参数，名称与字段类型中的原型引用一致
+fn prototype_type_params<'a>(clauses: impl IntoIterator<Item = &'a PrototypeClause>) -> Vec<GenericParam> {
+    clauses
+        .into_iter()
+        .filter_map(|clause| match clause {
+            PrototypeClause::Items(items) => Some(items),
+            _ => None,
+        })
+        .flatten()
+        .filter(|p| matches!(&p.prototype, Prototype::Component(c) if matches!(c.category, ComponentCategory::Data | ComponentCategory::Abstract)))
+        .map(|p| GenericParam { name: to_upper_camel_case(&p.identifier), bounds: Vec::new() })
+        .collect()
+}
+
+// compute() 属性的取值函数骨架：线程的属性字段保存其函数指针，函数体由用户实现
+fn computed_function_stub(name: String, return_type: String) -> FunctionDef {
+    FunctionDef {
+        name,
+        params: Vec::new(),
+        return_type: Type::Named(return_type),
+        body: Block {
+            stmts: vec![
+                Statement::Expr(Expr::Ident("// TODO: 在此处计算属性值".to_string())),
+                Statement::Expr(Expr::Ident("return Default::default()".to_string())),
+            ],
+            expr: None,
+        },
+        asyncness: false,
+        vis: Visibility::Public,
+        docs: vec!["// compute() 属性的取值函数，由用户实现".to_string()],
+        attrs: Vec::new(),
+    }
+}
+
+// 模板分类器输出为泛型定义：结构体/联合体/枚举只声明字段中用到的类型参数（联合体字段还要求 Copy），
+// 方法体按具体类型生成，不适用于模板，由特化后的副本提供
+fn generic_template(items: Vec<Item>, params: &[GenericParam]) -> Vec<Item> {
+    if params.is_empty() {
+        return items;
+    }
+    let used = |types: Vec<&Type>| -> Vec<GenericParam> {
+        params.iter().filter(|p| types.iter().any(|ty| mentions_type(ty, &p.name))).cloned().collect()
+    };
+    items
+        .into_iter()
+        .filter_map(|item| match item {
+            Item::Struct(mut s) => {
+                s.generics = used(s.fields.iter().map(|f| &f.ty).collect());
+                Some(Item::Struct(s))
+            }
+            Item::Union(mut u) => {
+                u.generics = used(u.fields.iter().map(|f| &f.ty).collect());
+                for generic in &mut u.generics {
+                    generic.bounds.push("Copy".to_string());
+                }
+                Some(Item::Union(u))
+            }
+            Item::Enum(mut e) => {
+                e.generics = used(e.variants.iter().flat_map(|v| v.data.iter().flatten()).collect());
+                Some(Item::Enum(e))
+            }
+            Item::Impl(_) => None,
+            other => Some(other),
+        })
+        .collect()
+}
+
+fn mentions_type(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Named(n) => n == name,
+        Type::Path(segments) => segments.last().is_some_and(|s| s == name),
+        Type::Generic(_, args) | Type::Tuple(args) => args.iter().any(|t| mentions_type(t, name)),
+        Type::Reference(inner, _, _) | Type::Slice(inner) | Type::Array(inner, _) => mentions_type(inner, name),
+        Type::Unit | Type::Never => false,
+    }
+}
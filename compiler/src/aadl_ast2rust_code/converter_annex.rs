@@ -15,6 +15,8 @@ pub struct AnnexConverter {
     state_info: HashMap<String, bool>, // state_name -> needs_continue
     /// 存储需要默认分支的状态（有条件判断的状态）
     states_with_conditions: std::collections::HashSet<String>,
+    /// 数据类型为带范围新类型的输入端口（端口名小写 -> 类型名），接收时重新检查
+    pub ranged_ports: HashMap<String, String>,
 }
 
 
@@ -128,6 +130,7 @@ impl AnnexConverter {
                     name: state_id.clone(),
                     data: None, // 状态枚举通常没有数据
                     docs: vec![format!("// State: {}", state_id)],
+                    discriminant: None,
                 });
             }
         }
@@ -140,6 +143,7 @@ impl AnnexConverter {
             derives: vec![], // vec!["Debug".to_string(), "Clone".to_string()],
             docs: vec!["// Behavior Annex state machine states".to_string()],
             vis: Visibility::Private, // 在函数内部定义
+            repr: None,
         };
 
         vec![Statement::Item(Box::new(Item::Enum(enum_def)))]
@@ -322,6 +326,7 @@ impl AnnexConverter {
     }
 
     /// 构造端口接收表达式：self.port.as_mut().and_then(|rx| rx.try_recv().ok()).unwrap_or_default()
+    /// 带范围的新类型在 unwrap 之前再接 .and_then(T::on_receive)
     fn build_port_receive_expr(&self, port_name: &str) -> Expr {
         let base_expr = Expr::Path(
             vec!["self".to_string(), port_name.to_string()],
@@ -343,11 +348,19 @@ impl AnnexConverter {
             Vec::new(),
         );
         let closure_expr = Expr::Closure(vec!["rx".to_string()], Box::new(ok_expr));
-        let and_then_expr = Expr::MethodCall(
+        let mut and_then_expr = Expr::MethodCall(
             Box::new(as_mut_expr),
             "and_then".to_string(),
             vec![closure_expr],
         );
+        // 带范围的新类型：越界而被拒绝的值按没有收到处理
+        if let Some(ranged_type) = self.ranged_ports.get(&port_name.to_lowercase()) {
+            and_then_expr = Expr::MethodCall(
+                Box::new(and_then_expr),
+                "and_then".to_string(),
+                vec![Expr::Ident(format!("{}::on_receive", ranged_type))],
+            );
+        }
 
         Expr::MethodCall(
             Box::new(and_then_expr),
@@ -534,8 +547,7 @@ impl AnnexConverter {
                 match trigger {
                     DispatchTrigger::InEventPort(port_name) => {
                         let port_var = port_name.to_lowercase().to_string();
-                        let mut condition = if use_less_than {
-                            let number_literal = parsed_number.as_ref().unwrap().clone();
+                        let mut condition = if let (true, Some(number_literal)) = (use_less_than, parsed_number.clone()) {
                             Expr::BinaryOp(
                                 Box::new(Expr::Literal(number_literal)),
                                 "<".to_string(),
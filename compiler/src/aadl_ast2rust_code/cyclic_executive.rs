@@ -0,0 +1,217 @@
+// 静态循环执行器（cyclic executive）后端
+// 根据进程内各线程的 Period / Compute_Execution_Time / Deadline / Dispatch_Offset，
+// 在超周期内离线计算静态调度表，生成的进程只用一个OS线程按表依次调用各线程的计算逻辑。
+use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::intermediate_ast::*;
+use crate::aadl_ast2rust_code::property_lookup::PropertyLookup;
+use crate::ast::aadl_ast_cj::*;
+use crate::diagnostics::{self, codes};
+use crate::instance_model::array_dimensions;
+
+// 参与调度的任务（对应进程中的一个线程子组件），时间单位均为毫秒
+#[derive(Debug, Clone)]
+pub struct CyclicTask {
+    pub name: String, // 线程子组件名（小写）
+    pub period: u64,
+    pub wcet: u64,
+    pub deadline: u64,
+    pub offset: u64,
+}
+
+// 调度表中的一个时间槽：在超周期内 start 时刻执行 task
+#[derive(Debug, Clone)]
+pub struct ScheduleSlot {
+    pub start: u64,
+    pub task: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduleTable {
+    pub hyperperiod: u64,
+    pub slots: Vec<ScheduleSlot>,
+    pub deadline_misses: Vec<String>, // 离线调度时发现的截止期错失
+}
+
+// 周期线程未给出周期时使用的默认值，与线程后端的默认周期一致
+const DEFAULT_PERIOD_MS: u64 = 1000;
+
+// 收集进程中所有线程子组件的时间属性，顺序与子组件声明顺序一致
+pub fn collect_process_tasks(temp_converter: &AadlConverter, impl_: &ComponentImplementation) -> Vec<CyclicTask> {
+    let mut tasks = Vec::new();
+
+    if let SubcomponentClause::Items(subcomponents) = &impl_.subcomponents {
+        for sub in subcomponents {
+            if sub.category != ComponentCategory::Thread {
+                continue;
+            }
+
+            // 属性按 子组件声明 -> 线程实现 -> 线程类型 的优先级查找
+            let lookup = PropertyLookup::for_subcomponent(temp_converter, sub);
+            let period = match lookup.time_ms("period").filter(|p| *p > 0) {
+                Some(period) => period,
+                // 偶发线程按 Period（最小到达间隔）预留时间槽，没有时无法放入调度表
+                None if lookup.enumeration("dispatch_protocol").is_some_and(|p| p.eq_ignore_ascii_case("sporadic")) => {
+                    diagnostics::error_in(
+                        impl_.location.as_ref(),
+                        codes::UNBOUNDED_SPORADIC_THREAD,
+                        format!(
+                            "process {}: sporadic thread {} has no Period (minimum inter-arrival time) to reserve schedule slots for",
+                            impl_.name.type_identifier, sub.identifier
+                        ),
+                    );
+                    continue;
+                }
+                None => DEFAULT_PERIOD_MS,
+            };
+            // 线程数组的每个元素各是一个任务，顺序与数组按行优先展开的顺序一致
+            let dimensions = array_dimensions(sub).unwrap_or_default();
+            let element_count = dimensions.iter().product::<u32>();
+            for element in 0..element_count {
+                let name = if dimensions.is_empty() {
+                    sub.identifier.to_lowercase()
+                } else {
+                    format!("{}[{}]", sub.identifier.to_lowercase(), element)
+                };
+                tasks.push(CyclicTask {
+                    name,
+                    period,
+                    wcet: lookup.time_ms("compute_execution_time").unwrap_or(0),
+                    deadline: lookup.time_ms("deadline").unwrap_or(period),
+                    offset: lookup.time_ms("dispatch_offset").unwrap_or(0),
+                });
+            }
+        }
+    }
+
+    tasks
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+// 超周期：所有任务周期的最小公倍数；溢出 u64 时返回 None
+pub fn hyperperiod(tasks: &[CyclicTask]) -> Option<u64> {
+    tasks
+        .iter()
+        .map(|t| t.period)
+        .try_fold(1u64, |acc, p| (acc / gcd(acc, p)).checked_mul(p))
+}
+
+// 在超周期内以非抢占EDF离线生成调度表
+// 作业 k 的释放时刻为 (offset + k*period) mod 超周期，截止期为释放时刻 + Deadline
+// 超周期溢出时无法生成调度表，返回 None
+pub fn build_schedule_table(tasks: &[CyclicTask]) -> Option<ScheduleTable> {
+    let hyperperiod = hyperperiod(tasks)?;
+
+    // (释放时刻, 绝对截止期, 任务索引)
+    let mut jobs: Vec<(u64, u64, usize)> = Vec::new();
+    for (index, task) in tasks.iter().enumerate() {
+        for k in 0..hyperperiod / task.period {
+            let release = (task.offset + k * task.period) % hyperperiod;
+            jobs.push((release, release + task.deadline, index));
+        }
+    }
+    jobs.sort();
+
+    let mut slots = Vec::new();
+    let mut deadline_misses = Vec::new();
+    let mut ready: Vec<(u64, u64, usize)> = Vec::new();
+    let mut next_job = 0;
+    let mut time = 0;
+
+    while next_job < jobs.len() || !ready.is_empty() {
+        // 将已释放的作业加入就绪队列
+        while next_job < jobs.len() && jobs[next_job].0 <= time {
+            ready.push(jobs[next_job]);
+            next_job += 1;
+        }
+        if ready.is_empty() {
+            time = jobs[next_job].0;
+            continue;
+        }
+
+        // 选择截止期最早的作业（相同则按释放时刻、任务顺序）
+        let (pos, _) = ready
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (release, deadline, task))| (*deadline, *release, *task))
+            .unwrap();
+        let (release, deadline, task) = ready.remove(pos);
+
+        slots.push(ScheduleSlot { start: time, task });
+        time += tasks[task].wcet;
+        if time > deadline {
+            deadline_misses.push(format!(
+                "{} released at {} ms finishes at {} ms, after its deadline {} ms",
+                tasks[task].name, release, time, deadline
+            ));
+        }
+    }
+
+    if time > hyperperiod {
+        deadline_misses.push(format!(
+            "schedule needs {} ms, longer than the hyperperiod {} ms",
+            time, hyperperiod
+        ));
+    }
+
+    Some(ScheduleTable {
+        hyperperiod,
+        slots,
+        deadline_misses,
+    })
+}
+
+// 调度表常量名前缀，如 node_a -> NODE_A
+pub fn schedule_const_prefix(process_type_identifier: &str) -> String {
+    process_type_identifier.to_uppercase().replace('-', "_")
+}
+
+// 生成调度表相关常量：<PREFIX>_HYPERPERIOD_MS 与 <PREFIX>_SCHEDULE
+pub fn create_schedule_items(process_type_identifier: &str, tasks: &[CyclicTask], table: &ScheduleTable) -> Vec<Item> {
+    let prefix = schedule_const_prefix(process_type_identifier);
+
+    let mut docs = vec![format!(
+        "// 进程 {} 的静态调度表：(超周期内起始时刻ms, 任务索引)",
+        process_type_identifier
+    )];
+    for (index, task) in tasks.iter().enumerate() {
+        docs.push(format!(
+            "// 任务 {}: {} (T={}ms, C={}ms, D={}ms, O={}ms)",
+            index, task.name, task.period, task.wcet, task.deadline, task.offset
+        ));
+    }
+    for miss in &table.deadline_misses {
+        docs.push(format!("// 警告: {}", miss));
+    }
+
+    let entries: Vec<String> = table
+        .slots
+        .iter()
+        .map(|slot| format!("    ({}, {}), // {}", slot.start, slot.task, tasks[slot.task].name))
+        .collect();
+
+    vec![
+        Item::Const(ConstDef {
+            name: format!("{}_HYPERPERIOD_MS", prefix),
+            ty: Type::Named("u64".to_string()),
+            value: Expr::Literal(Literal::Int(table.hyperperiod as i64)),
+            vis: Visibility::Public,
+            docs: vec![format!("// 进程 {} 的超周期（ms）", process_type_identifier)],
+        }),
+        Item::Const(ConstDef {
+            name: format!("{}_SCHEDULE", prefix),
+            ty: Type::Array(
+                Box::new(Type::Tuple(vec![
+                    Type::Named("u64".to_string()),
+                    Type::Named("usize".to_string()),
+                ])),
+                table.slots.len(),
+            ),
+            value: Expr::Ident(format!("[\n{}\n]", entries.join("\n"))),
+            vis: Visibility::Public,
+            docs,
+        }),
+    ]
+}
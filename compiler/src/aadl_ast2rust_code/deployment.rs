@@ -0,0 +1,102 @@
+// 分布式部署
+// 根系统的进程绑定到两个及以上处理器时，除了在单个 OS 进程内运行全部组件的 main.rs，
+// 还为每个处理器生成一个节点可执行文件（src/bin/node_<处理器>.rs）与一个本地启动器。
+// 各节点构造完整的系统，但只启动绑定到本处理器的进程；跨处理器的端口连接由 crate::net 经
+// TCP 或 Unix 域套接字转发，端口数据通过 crate::codec::Codec 编码。
+// 节点地址取自处理器的 Deployment::Location / Port_Number（Port_Number 也可声明在所承载的进程上），
+// Location 含 '/' 时视为 Unix 域套接字路径。
+use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::intermediate_ast::*;
+use crate::aadl_ast2rust_code::property_lookup::PropertyLookup;
+use crate::ast::aadl_ast_cj::*;
+use crate::instance_model::{FeatureRef, InstancePath, SystemInstance};
+
+// 未给出 Port_Number 时节点端口从这里依次分配
+const DEFAULT_BASE_PORT: i64 = 40000;
+
+// 一个部署节点：一个处理器及其承载的进程
+#[derive(Debug, Clone)]
+pub struct DeploymentNode {
+    pub processor: InstancePath,
+    pub cpu_id: isize,
+    pub address: String, // host:port 或 Unix 域套接字路径
+}
+
+// 根系统的部署节点，按 CPU ID 排序；承载进程的处理器少于两个时不生成分布式部署
+pub fn plan_nodes(converter: &AadlConverter, instance: &SystemInstance) -> Vec<DeploymentNode> {
+    let mut processors: Vec<(InstancePath, Vec<InstancePath>)> = Vec::new();
+    for component in instance.root.walk() {
+        if component.category != ComponentCategory::Process {
+            continue;
+        }
+        let Some(processor) = instance.bound_processor(&component.path) else {
+            continue;
+        };
+        match processors.iter_mut().find(|(path, _)| same_path(path, processor)) {
+            Some((_, processes)) => processes.push(component.path.clone()),
+            None => processors.push((processor.clone(), vec![component.path.clone()])),
+        }
+    }
+    if processors.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut nodes: Vec<DeploymentNode> = processors
+        .iter()
+        .enumerate()
+        .filter_map(|(index, (processor, processes))| {
+            let name = processor.join(".");
+            let cpu_id = converter
+                .cpu_name_to_id_mapping
+                .iter()
+                .find(|(cpu, _)| cpu.eq_ignore_ascii_case(&name))
+                .map(|(_, id)| *id)?;
+            let lookup = PropertyLookup::for_instance(converter, instance, processor);
+            let location = lookup.enumeration("Location").unwrap_or_else(|| "127.0.0.1".to_string());
+            let address = if location.contains('/') {
+                location
+            } else {
+                let port = lookup
+                    .integer("Port_Number")
+                    .or_else(|| {
+                        processes
+                            .iter()
+                            .find_map(|process| PropertyLookup::for_instance(converter, instance, process).integer("Port_Number"))
+                    })
+                    .unwrap_or(DEFAULT_BASE_PORT + index as i64);
+                format!("{}:{}", location, port)
+            };
+            Some(DeploymentNode { processor: processor.clone(), cpu_id, address })
+        })
+        .collect();
+    nodes.sort_by_key(|node| node.cpu_id);
+    nodes
+}
+
+// 端口所在节点的 CPU ID；未绑定处理器的组件（如设备）由首个节点运行
+pub fn port_cpu_id(nodes: &[DeploymentNode], instance: &SystemInstance, port: &FeatureRef) -> isize {
+    instance
+        .bound_processor(&port.component)
+        .and_then(|processor| nodes.iter().find(|node| same_path(&node.processor, processor)))
+        .or(nodes.first())
+        .map_or(-1, |node| node.cpu_id)
+}
+
+// 绑定中引用的处理器名不区分大小写（reference (CPU) 与 reference (cpu) 是同一个处理器）
+fn same_path(a: &[String], b: &[String]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.eq_ignore_ascii_case(y))
+}
+
+// crate::net::deploy(&[(CPU ID, 节点名, 地址), ...])：登记各节点，节点可执行文件据此确定本节点的监听地址
+pub fn deploy_statement(nodes: &[DeploymentNode]) -> Statement {
+    let entries: Vec<String> = nodes
+        .iter()
+        .map(|node| format!("({}, \"{}\", \"{}\")", node.cpu_id, node_name(node), node.address))
+        .collect();
+    Statement::Expr(Expr::Ident(format!("crate::net::deploy(&[{}])", entries.join(", "))))
+}
+
+// 节点名，同时是节点可执行文件名（node_ 前缀由工程组装时加上）
+pub fn node_name(node: &DeploymentNode) -> String {
+    node.processor.join("_").to_lowercase()
+}
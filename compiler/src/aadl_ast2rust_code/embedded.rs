@@ -0,0 +1,812 @@
+// 嵌入式（no_std）后端
+// 面向单核微控制器：生成的代码不依赖 std / libc / crossbeam。
+// 端口映射为 heapless 静态队列，时间由用户针对硬件实现的 Clock trait 提供，
+// 系统以固定优先级、运行至完成（run-to-completion）的调度器依次执行所有线程与设备，
+// 输出端口的数据在一次分派完成后由调度器转发到目的端口队列。
+use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::converter_annex::AnnexConverter;
+use crate::aadl_ast2rust_code::property_lookup::PropertyLookup;
+use crate::aadl_ast2rust_code::implementations::conv_thread_impl::{
+    extract_property_value, extract_subprogram_calls,
+};
+use crate::aadl_ast2rust_code::intermediate_ast::*;
+use crate::aadl_ast2rust_code::tool::*;
+use crate::ast::aadl_ast_cj::*;
+use crate::diagnostics::{self, codes};
+use crate::instance_model::{ClassifierIndex, ComponentInstance, SystemInstance};
+use std::collections::HashSet;
+
+// 字符串数据的固定容量（heapless::String<N>）
+pub const STRING_CAPACITY: usize = 64;
+
+// AADL 的 Queue_Size 默认值
+const DEFAULT_QUEUE_SIZE: u64 = 1;
+
+// 线程/设备未给出周期时使用的默认值，与线程后端的默认周期一致
+const DEFAULT_PERIOD_MS: u64 = 1000;
+
+// 组件端口在嵌入式后端中的信息
+struct PortInfo {
+    name: String, // 端口名（小写）
+    direction: PortDirection,
+    queued: bool, // 事件/事件数据端口按队列消费，数据端口只读取最新值
+    ty: Type,
+}
+
+// 调度器中的一个任务（线程或设备实例）
+struct EmbeddedTask {
+    name: String,
+    path: String, // 实例在系统中的访问路径，如 self.node_a.task1
+    dispatch: String,
+    period: u64,
+    offset: u64,
+    priority: u64,
+    triggers: Vec<String>, // 触发非周期/偶发分派的输入端口
+    outputs: Vec<(String, Vec<(String, String)>)>, // (输出端口, [(目的实例路径, 目的端口)])
+}
+
+fn component_ports(temp_converter: &AadlConverter, comp_type: Option<&ComponentType>) -> Vec<PortInfo> {
+    let mut ports = Vec::new();
+    if let Some(FeatureClause::Items(features)) = comp_type.map(|t| &t.features) {
+        for feature in features {
+            if let Feature::Port(port) = feature {
+                let (queued, ty) = match &port.port_type {
+                    PortType::Data { classifier } => (false, classifier.as_ref().map(|c| temp_converter.classifier_to_type(c))),
+                    PortType::EventData { classifier } => (true, classifier.as_ref().map(|c| temp_converter.classifier_to_type(c))),
+                    PortType::Event => (true, None),
+                };
+                ports.push(PortInfo {
+                    name: port.identifier.to_lowercase(),
+                    direction: port.direction,
+                    queued,
+                    ty: ty.unwrap_or(Type::Unit),
+                });
+            }
+        }
+    }
+    ports
+}
+
+// 输入端口为 Deque<T, Queue_Size>，输出端口为 Option<T>（分派完成时由调度器取走）
+fn port_fields(ports: &[PortInfo], queue_size: u64) -> Vec<Field> {
+    ports
+        .iter()
+        .map(|port| match port.direction {
+            PortDirection::In => Field {
+                name: port.name.clone(),
+                ty: Type::Generic(
+                    "Deque".to_string(),
+                    vec![port.ty.clone(), Type::Named(queue_size.to_string())],
+                ),
+                docs: vec![format!("// Port: {} In（静态队列，容量 {}）", port.name, queue_size)],
+                attrs: Vec::new(),
+                vis: Visibility::Public,
+            },
+            _ => Field {
+                name: port.name.clone(),
+                ty: Type::Generic("Option".to_string(), vec![port.ty.clone()]),
+                docs: vec![format!("// Port: {} Out（分派完成后由调度器转发）", port.name)],
+                attrs: Vec::new(),
+                vis: Visibility::Public,
+            },
+        })
+        .collect()
+}
+
+// Self { p1: Deque::new(), p2: None }
+fn port_new_method(ports: &[PortInfo]) -> FunctionDef {
+    let inits: Vec<String> = ports
+        .iter()
+        .map(|port| match port.direction {
+            PortDirection::In => format!("{}: Deque::new()", port.name),
+            _ => format!("{}: None", port.name),
+        })
+        .collect();
+
+    FunctionDef {
+        name: "new".to_string(),
+        params: Vec::new(),
+        return_type: Type::Named("Self".to_string()),
+        body: Block {
+            stmts: vec![Statement::Expr(Expr::Ident(format!("Self {{ {} }} // finalize", inits.join(", "))))],
+            expr: None,
+        },
+        asyncness: false,
+        vis: Visibility::Public,
+        docs: vec!["// 创建组件，端口队列为空".to_string()],
+        attrs: Vec::new(),
+    }
+}
+
+fn component_struct(name: String, fields: Vec<Field>, docs: Vec<String>) -> Item {
+    Item::Struct(StructDef {
+        name,
+        fields,
+        properties: Vec::new(),
+        generics: Vec::new(),
+        derives: vec!["Debug".to_string()],
+        docs,
+        vis: Visibility::Public,
+    })
+}
+
+fn inherent_impl(target: String, items: Vec<ImplItem>) -> Item {
+    Item::Impl(ImplBlock {
+        target: Type::Named(target),
+        generics: Vec::new(),
+        items,
+        trait_impl: None,
+    })
+}
+
+// 共享数据：单核运行至完成调度下不存在并发访问，Arc<Mutex<T>> 改为 core::cell::RefCell<T>
+pub fn single_core_shared_data(items: Vec<Item>) -> Vec<Item> {
+    items
+        .into_iter()
+        .map(|item| match item {
+            Item::TypeAlias(mut alias) => {
+                if let Type::Generic(arc, inner) = &alias.target {
+                    if let [Type::Generic(mutex, data)] = inner.as_slice() {
+                        if arc == "Arc" && mutex == "Mutex" {
+                            alias.target = Type::Generic("core::cell::RefCell".to_string(), data.clone());
+                        }
+                    }
+                }
+                Item::TypeAlias(alias)
+            }
+            other => other,
+        })
+        .collect()
+}
+
+// 嵌入式后端支持的模型范围：根系统 -> 进程 -> 线程 的扁平层次（设备与平台组件直接位于根系统），
+// 线程不带行为附件。超出范围的构造报告为错误，而不是生成只覆盖部分实例的调度器；生成与 check 动作共用
+pub fn check_profile(packages: &[Package], instances: &[SystemInstance]) {
+    let index = ClassifierIndex::new(packages);
+    // 同一线程实现的多个实例（如线程数组）只报告一次
+    let mut reported = HashSet::new();
+    for instance in instances {
+        for sub in &instance.root.subcomponents {
+            match sub.category {
+                ComponentCategory::Process => {
+                    for inner in &sub.subcomponents {
+                        match inner.category {
+                            ComponentCategory::Thread => check_thread(&index, inner, &mut reported),
+                            ComponentCategory::Data | ComponentCategory::Subprogram | ComponentCategory::SubprogramGroup => {}
+                            _ => reject_nesting(&index, sub, inner),
+                        }
+                    }
+                }
+                ComponentCategory::Device
+                | ComponentCategory::Data
+                | ComponentCategory::Processor
+                | ComponentCategory::VirtualProcessor
+                | ComponentCategory::Memory
+                | ComponentCategory::Bus
+                | ComponentCategory::VirtualBus => {}
+                _ => reject_nesting(&index, &instance.root, sub),
+            }
+        }
+    }
+}
+
+fn instance_impl<'a>(index: &ClassifierIndex<'a>, instance: &ComponentInstance) -> Option<&'a ComponentImplementation> {
+    let classifier = instance.classifier.as_ref()?;
+    let name = ImplementationName {
+        type_identifier: classifier.type_name.clone(),
+        implementation_identifier: classifier.impl_name.clone()?,
+    };
+    index.find_impl(&classifier.package, None, &name).map(|(_, impl_)| impl_)
+}
+
+fn check_thread(index: &ClassifierIndex, thread: &ComponentInstance, reported: &mut HashSet<String>) {
+    let Some(impl_) = instance_impl(index, thread) else {
+        return;
+    };
+    if AnnexConverter::default().find_behavior_annex(impl_).is_some() && reported.insert(impl_.name.to_string().to_lowercase()) {
+        diagnostics::error_in(
+            impl_.location.as_ref(),
+            codes::UNSUPPORTED_BY_BACKEND,
+            format!(
+                "thread {} ({}): behavior annex is not supported by the embedded backend",
+                thread.path.join("."),
+                impl_.name.to_string()
+            ),
+        );
+    }
+}
+
+fn reject_nesting(index: &ClassifierIndex, parent: &ComponentInstance, sub: &ComponentInstance) {
+    let location = instance_impl(index, parent).and_then(|impl_| impl_.location.clone());
+    diagnostics::error_in(
+        location.as_ref(),
+        codes::UNSUPPORTED_BY_BACKEND,
+        format!(
+            "{} subcomponent {} in {}: the embedded backend only supports a root system with processes of threads",
+            format!("{:?}", sub.category).to_lowercase(),
+            sub.path.join("."),
+            parent.name
+        ),
+    );
+}
+
+// 线程实现：端口结构体 + new() + step()（单次分派的计算逻辑）
+pub fn convert_thread_implementation(temp_converter: &AadlConverter, impl_: &ComponentImplementation) -> Vec<Item> {
+    let struct_name = format!("{}Thread", to_upper_camel_case(&impl_.name.type_identifier));
+    let ports = component_ports(temp_converter, temp_converter.get_component_type(impl_));
+    let queue_size = extract_property_value(temp_converter, impl_, "queue_size").unwrap_or(DEFAULT_QUEUE_SIZE);
+
+    let mut docs = vec![
+        format!("// AADL Thread: {}", impl_.name.type_identifier.to_lowercase()),
+        "// no_std：由系统调度器调用 step() 运行至完成".to_string(),
+    ];
+    // 行为附件已由 check_profile 报告为错误，这里只在生成的代码中留下说明
+    if AnnexConverter::default().find_behavior_annex(impl_).is_some() {
+        docs.push("// 错误: 嵌入式后端不支持行为附件（Behavior Annex），step() 未实现其行为".to_string());
+    }
+
+    vec![
+        component_struct(struct_name.clone(), port_fields(&ports, queue_size), docs),
+        inherent_impl(
+            struct_name,
+            vec![
+                ImplItem::Method(port_new_method(&ports)),
+                ImplItem::Method(FunctionDef {
+                    name: "step".to_string(),
+                    params: vec![Param {
+                        name: "".to_string(),
+                        ty: Type::Reference(Box::new(Type::Named("self".to_string())), true, true),
+                    }],
+                    return_type: Type::Unit,
+                    body: Block {
+                        stmts: create_thread_step_stmts(temp_converter, impl_, &ports),
+                        expr: None,
+                    },
+                    asyncness: false,
+                    vis: Visibility::Public,
+                    docs: vec!["// 单次分派：按调用序列执行子程序，输出写入端口槽位".to_string()],
+                    attrs: Vec::new(),
+                }),
+            ],
+        ),
+    ]
+}
+
+fn create_thread_step_stmts(temp_converter: &AadlConverter, impl_: &ComponentImplementation, ports: &[PortInfo]) -> Vec<Statement> {
+    let mut stmts = Vec::new();
+    let subprogram_calls = extract_subprogram_calls(temp_converter, impl_);
+
+    if let CallSequenceClause::Items(calls_clause) = &impl_.calls {
+        for call_clause in calls_clause {
+            for subprocall in &call_clause.calls {
+                let CalledSubprogram::Classifier(UniqueComponentClassifierReference::Implementation(temp)) =
+                    &subprocall.called
+                else {
+                    continue;
+                };
+                let call_id = subprocall.identifier.to_lowercase();
+                let subprogram_name = temp.implementation_name.type_identifier.to_lowercase();
+                stmts.push(Statement::Expr(Expr::Ident(format!("// {}", call_id))));
+
+                let connected: Vec<_> = subprogram_calls
+                    .iter()
+                    .filter(|(_, spg_identifier, _, _, _)| spg_identifier == &call_id)
+                    .collect();
+                if connected.is_empty() {
+                    // subprogram::execute();
+                    stmts.push(Statement::Expr(Expr::Call(
+                        Box::new(Expr::Path(vec![subprogram_name.clone(), "execute".to_string()], PathType::Namespace)),
+                        Vec::new(),
+                    )));
+                    continue;
+                }
+
+                for (_, _, thread_port_name, is_send, port_type) in connected {
+                    if *is_send {
+                        // let mut val = <默认值>; subprogram::send(&mut val); self.port = Some(val);
+                        // C 写回的枚举编码无效时包装函数返回 Err，此次不输出
+                        let call = Expr::Call(
+                            Box::new(Expr::Path(vec![subprogram_name.clone(), "send".to_string()], PathType::Namespace)),
+                            vec![Expr::Reference(Box::new(Expr::Ident("val".to_string())), true, true)],
+                        );
+                        let output = Statement::Expr(Expr::Ident(format!("self.{} = Some(val)", thread_port_name)));
+                        let mut block = vec![Statement::Let(LetStmt {
+                            ifmut: true,
+                            name: "val".to_string(),
+                            ty: None,
+                            init: Some(temp_converter.generate_default_value_for_type(port_type)),
+                        })];
+                        if temp_converter.is_checked_out_param(port_type) {
+                            block.push(Statement::Expr(Expr::If {
+                                condition: Box::new(Expr::MethodCall(Box::new(call), "is_ok".to_string(), Vec::new())),
+                                then_branch: Block { stmts: vec![output], expr: None },
+                                else_branch: None,
+                            }));
+                        } else {
+                            block.push(Statement::Expr(call));
+                            block.push(output);
+                        }
+                        stmts.push(Statement::Expr(Expr::Block(Block { stmts: block, expr: None })));
+                    } else {
+                        // 事件数据端口按队列消费；数据端口读取最新值但不移出
+                        let queued = ports.iter().any(|p| &p.name == thread_port_name && p.queued);
+                        let value = if queued {
+                            format!("self.{}.pop_front()", thread_port_name)
+                        } else {
+                            format!("self.{}.back().cloned()", thread_port_name)
+                        };
+                        stmts.push(Statement::Expr(Expr::IfLet {
+                            pattern: "Some(val)".to_string(),
+                            value: Box::new(Expr::Ident(value)),
+                            then_branch: Block {
+                                stmts: vec![Statement::Expr(Expr::Call(
+                                    Box::new(Expr::Path(vec![subprogram_name.clone(), "receive".to_string()], PathType::Namespace)),
+                                    vec![Expr::Ident("val".to_string())],
+                                ))],
+                                expr: None,
+                            },
+                            else_branch: None,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+
+    stmts
+}
+
+// 设备类型：设备驱动由用户在 step() 中实现，调度器按 Period 周期调用
+pub fn convert_device_component(temp_converter: &AadlConverter, comp: &ComponentType) -> Vec<Item> {
+    let struct_name = format!("{}Device", to_upper_camel_case(&comp.identifier));
+    let ports = component_ports(temp_converter, Some(comp));
+
+    vec![
+        component_struct(
+            struct_name.clone(),
+            port_fields(&ports, DEFAULT_QUEUE_SIZE),
+            vec![
+                format!("// AADL Device: {}", comp.identifier.to_lowercase()),
+                "// no_std：由系统调度器按周期调用 step()".to_string(),
+            ],
+        ),
+        inherent_impl(
+            struct_name,
+            vec![
+                ImplItem::Method(port_new_method(&ports)),
+                ImplItem::Method(FunctionDef {
+                    name: "step".to_string(),
+                    params: vec![Param {
+                        name: "".to_string(),
+                        ty: Type::Reference(Box::new(Type::Named("self".to_string())), true, true),
+                    }],
+                    return_type: Type::Unit,
+                    body: Block {
+                        stmts: vec![Statement::Expr(Expr::Ident(
+                            "// TODO: 在此访问硬件，读取输入端口队列并写入输出端口".to_string(),
+                        ))],
+                        expr: None,
+                    },
+                    asyncness: false,
+                    vis: Visibility::Public,
+                    docs: vec!["// 设备驱动入口".to_string()],
+                    attrs: Vec::new(),
+                }),
+            ],
+        ),
+    ]
+}
+
+fn implementation_of<'a>(temp_converter: &'a AadlConverter, sub: &Subcomponent) -> Option<&'a ComponentImplementation> {
+    match &sub.classifier {
+        SubcomponentClassifier::ClassifierReference(UniqueComponentClassifierReference::Implementation(reference)) => {
+            temp_converter.resolve_component_impl(reference)
+        }
+        _ => None,
+    }
+}
+
+fn classifier_type_name(sub: &Subcomponent) -> String {
+    match &sub.classifier {
+        SubcomponentClassifier::ClassifierReference(UniqueComponentClassifierReference::Implementation(reference)) => {
+            reference.implementation_name.type_identifier.clone()
+        }
+        SubcomponentClassifier::ClassifierReference(UniqueComponentClassifierReference::Type(reference)) => {
+            reference.implementation_name.type_identifier.clone()
+        }
+        _ => "UnsupportedComponent".to_string(),
+    }
+}
+
+fn subcomponents(impl_: &ComponentImplementation) -> &[Subcomponent] {
+    match &impl_.subcomponents {
+        SubcomponentClause::Items(items) => items,
+        _ => &[],
+    }
+}
+
+fn port_connections(impl_: &ComponentImplementation) -> Vec<&PortConnection> {
+    match &impl_.connections {
+        ConnectionClause::Items(connections) => connections
+            .iter()
+            .filter_map(|conn| match conn {
+                Connection::Port(port_conn) => Some(port_conn),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// 进程实现：包含线程子组件实例
+pub fn convert_process_implementation(impl_: &ComponentImplementation) -> Vec<Item> {
+    let struct_name = format!("{}Process", to_upper_camel_case(&impl_.name.type_identifier));
+    let threads: Vec<&Subcomponent> = subcomponents(impl_)
+        .iter()
+        .filter(|sub| sub.category == ComponentCategory::Thread)
+        .collect();
+
+    let fields = threads
+        .iter()
+        .map(|sub| Field {
+            name: sub.identifier.to_lowercase(),
+            ty: Type::Named(format!("{}Thread", to_upper_camel_case(&classifier_type_name(sub)))),
+            docs: vec![format!("// 子组件线程({} : thread {})", sub.identifier, classifier_type_name(sub))],
+            attrs: Vec::new(),
+            vis: Visibility::Public,
+        })
+        .collect();
+    let inits: Vec<String> = threads
+        .iter()
+        .map(|sub| format!("{}: {}Thread::new()", sub.identifier.to_lowercase(), to_upper_camel_case(&classifier_type_name(sub))))
+        .collect();
+
+    // 进程端口只是连接的中转点，数据由系统调度器直接在线程端口之间转发
+    vec![
+        component_struct(struct_name.clone(), fields, vec![format!("// AADL Process: {}", impl_.name.type_identifier.to_lowercase())]),
+        inherent_impl(
+            struct_name,
+            vec![ImplItem::Method(FunctionDef {
+                name: "new".to_string(),
+                params: Vec::new(),
+                return_type: Type::Named("Self".to_string()),
+                body: Block {
+                    stmts: vec![Statement::Expr(Expr::Ident(format!("Self {{ {} }} // finalize", inits.join(", "))))],
+                    expr: None,
+                },
+                asyncness: false,
+                vis: Visibility::Public,
+                docs: vec!["// Creates a new process instance".to_string()],
+                attrs: Vec::new(),
+            })],
+        ),
+    ]
+}
+
+// 系统实现：包含进程与设备实例，run() 为运行至完成的调度循环
+pub fn convert_system_implementation(temp_converter: &AadlConverter, impl_: &ComponentImplementation) -> Vec<Item> {
+    let struct_name = format!("{}System", to_upper_camel_case(&impl_.name.type_identifier));
+
+    let members: Vec<(&Subcomponent, &str)> = subcomponents(impl_)
+        .iter()
+        .filter_map(|sub| match sub.category {
+            ComponentCategory::Process => Some((sub, "Process")),
+            ComponentCategory::Device => Some((sub, "Device")),
+            _ => None,
+        })
+        .collect();
+
+    let fields = members
+        .iter()
+        .map(|(sub, suffix)| Field {
+            name: sub.identifier.to_lowercase(),
+            ty: Type::Named(format!("{}{}", to_upper_camel_case(&classifier_type_name(sub)), suffix)),
+            docs: vec![format!("// 子组件({} : {})", sub.identifier, classifier_type_name(sub))],
+            attrs: Vec::new(),
+            vis: Visibility::Public,
+        })
+        .collect();
+    let inits: Vec<String> = members
+        .iter()
+        .map(|(sub, suffix)| format!("{}: {}{}::new()", sub.identifier.to_lowercase(), to_upper_camel_case(&classifier_type_name(sub)), suffix))
+        .collect();
+
+    let tasks = collect_system_tasks(temp_converter, impl_);
+    let mut run_docs = vec!["// 运行至完成调度：每轮选择已就绪且优先级最高的任务执行一次分派".to_string()];
+    for (index, task) in tasks.iter().enumerate() {
+        run_docs.push(format!(
+            "// 任务 {}: {} ({}, T={}ms, O={}ms, Priority={})",
+            index, task.name, task.dispatch, task.period, task.offset, task.priority
+        ));
+    }
+
+    vec![
+        component_struct(struct_name.clone(), fields, vec![format!("// AADL System: {}", impl_.name.type_identifier.to_lowercase())]),
+        inherent_impl(
+            struct_name,
+            vec![
+                ImplItem::Method(FunctionDef {
+                    name: "new".to_string(),
+                    params: Vec::new(),
+                    return_type: Type::Named("Self".to_string()),
+                    body: Block {
+                        stmts: vec![Statement::Expr(Expr::Ident(format!("Self {{ {} }} // finalize", inits.join(", "))))],
+                        expr: None,
+                    },
+                    asyncness: false,
+                    vis: Visibility::Public,
+                    docs: vec!["// Creates a new system instance".to_string()],
+                    attrs: Vec::new(),
+                }),
+                ImplItem::Method(FunctionDef {
+                    name: "run<C: Clock>".to_string(),
+                    params: vec![
+                        Param {
+                            name: "".to_string(),
+                            ty: Type::Reference(Box::new(Type::Named("self".to_string())), false, true),
+                        },
+                        Param {
+                            name: "clock".to_string(),
+                            ty: Type::Reference(Box::new(Type::Named("C".to_string())), true, true),
+                        },
+                    ],
+                    return_type: Type::Never,
+                    body: Block {
+                        stmts: create_scheduler_stmts(&tasks),
+                        expr: None,
+                    },
+                    asyncness: false,
+                    vis: Visibility::Public,
+                    docs: run_docs,
+                    attrs: Vec::new(),
+                }),
+            ],
+        ),
+    ]
+}
+
+// 收集系统中所有线程与设备实例，并解析输出端口到目的端口的语义连接
+fn collect_system_tasks(temp_converter: &AadlConverter, system: &ComponentImplementation) -> Vec<EmbeddedTask> {
+    let mut tasks = Vec::new();
+
+    for sub in subcomponents(system) {
+        let sub_name = sub.identifier.to_lowercase();
+        match sub.category {
+            ComponentCategory::Process => {
+                let Some(process) = implementation_of(temp_converter, sub) else {
+                    continue;
+                };
+                for thread in subcomponents(process).iter().filter(|t| t.category == ComponentCategory::Thread) {
+                    let Some(thread_impl) = implementation_of(temp_converter, thread) else {
+                        continue;
+                    };
+                    let thread_type = temp_converter.get_component_type(thread_impl);
+                    let ports = component_ports(temp_converter, thread_type);
+                    // 属性按 子组件声明 -> 线程实现 -> 线程类型 的优先级查找
+                    let lookup = PropertyLookup::for_subcomponent(temp_converter, thread);
+
+                    let outputs = ports
+                        .iter()
+                        .filter(|p| p.direction != PortDirection::In)
+                        .map(|p| {
+                            let dsts = thread_output_destinations(temp_converter, system, sub, process, &thread.identifier, &p.name);
+                            (p.name.clone(), dsts)
+                        })
+                        .collect();
+
+                    tasks.push(EmbeddedTask {
+                        name: format!("{}.{}", sub_name, thread.identifier.to_lowercase()),
+                        path: format!("self.{}.{}", sub_name, thread.identifier.to_lowercase()),
+                        dispatch: lookup.enumeration("dispatch_protocol").unwrap_or_else(|| "Periodic".to_string()),
+                        period: lookup.time_ms("period").filter(|p| *p > 0).unwrap_or(DEFAULT_PERIOD_MS),
+                        offset: lookup.time_ms("dispatch_offset").unwrap_or(0),
+                        priority: lookup.integer("priority").map_or(0, |p| p as u64),
+                        triggers: ports
+                            .iter()
+                            .filter(|p| p.direction == PortDirection::In && p.queued)
+                            .map(|p| p.name.clone())
+                            .collect(),
+                        outputs,
+                    });
+                }
+            }
+            ComponentCategory::Device => {
+                let device_type = temp_converter.component_types.get(&classifier_type_name(sub));
+                let ports = component_ports(temp_converter, device_type);
+                let outputs = ports
+                    .iter()
+                    .filter(|p| p.direction != PortDirection::In)
+                    .map(|p| (p.name.clone(), system_destinations(temp_converter, system, &sub.identifier, &p.name)))
+                    .collect();
+
+                tasks.push(EmbeddedTask {
+                    name: sub_name.clone(),
+                    path: format!("self.{}", sub_name),
+                    dispatch: "Periodic".to_string(),
+                    period: PropertyLookup::for_subcomponent(temp_converter, sub)
+                        .time_ms("period")
+                        .filter(|p| *p > 0)
+                        .unwrap_or(DEFAULT_PERIOD_MS),
+                    offset: 0,
+                    priority: 0,
+                    triggers: Vec::new(),
+                    outputs,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // 优先级高者在前；同优先级按周期（RM）排序，保持声明顺序
+    tasks.sort_by_key(|t| (std::cmp::Reverse(t.priority), t.period));
+    tasks
+}
+
+// 线程输出端口的目的端口：同进程内的线程端口，或经进程端口到达系统中的其他组件
+fn thread_output_destinations(
+    temp_converter: &AadlConverter,
+    system: &ComponentImplementation,
+    process_sub: &Subcomponent,
+    process: &ComponentImplementation,
+    thread: &str,
+    port: &str,
+) -> Vec<(String, String)> {
+    let mut dsts = Vec::new();
+    let process_name = process_sub.identifier.to_lowercase();
+
+    for conn in port_connections(process) {
+        let PortEndpoint::SubcomponentPort { subcomponent, port: src_port } = &conn.source else {
+            continue;
+        };
+        if !subcomponent.eq_ignore_ascii_case(thread) || !src_port.eq_ignore_ascii_case(port) {
+            continue;
+        }
+        match &conn.destination {
+            PortEndpoint::SubcomponentPort { subcomponent, port } => {
+                dsts.push((format!("self.{}.{}", process_name, subcomponent.to_lowercase()), port.to_lowercase()));
+            }
+            PortEndpoint::ComponentPort(process_port) => {
+                dsts.extend(system_destinations(temp_converter, system, &process_sub.identifier, process_port));
+            }
+            _ => {}
+        }
+    }
+
+    dsts
+}
+
+// 系统内从 (子组件, 端口) 出发的连接所到达的线程或设备端口
+fn system_destinations(temp_converter: &AadlConverter, system: &ComponentImplementation, source: &str, port: &str) -> Vec<(String, String)> {
+    let mut dsts = Vec::new();
+
+    for conn in port_connections(system) {
+        let PortEndpoint::SubcomponentPort { subcomponent, port: src_port } = &conn.source else {
+            continue;
+        };
+        if !subcomponent.eq_ignore_ascii_case(source) || !src_port.eq_ignore_ascii_case(port) {
+            continue;
+        }
+        let PortEndpoint::SubcomponentPort { subcomponent: dst, port: dst_port } = &conn.destination else {
+            continue;
+        };
+        let Some(dst_sub) = subcomponents(system).iter().find(|s| s.identifier.eq_ignore_ascii_case(dst)) else {
+            continue;
+        };
+        match dst_sub.category {
+            ComponentCategory::Device => dsts.push((format!("self.{}", dst.to_lowercase()), dst_port.to_lowercase())),
+            ComponentCategory::Process => {
+                // 进入进程后沿进程内连接到达线程端口
+                let Some(process) = implementation_of(temp_converter, dst_sub) else {
+                    continue;
+                };
+                for inner in port_connections(process) {
+                    let PortEndpoint::ComponentPort(process_port) = &inner.source else {
+                        continue;
+                    };
+                    if !process_port.eq_ignore_ascii_case(dst_port) {
+                        continue;
+                    }
+                    if let PortEndpoint::SubcomponentPort { subcomponent, port } = &inner.destination {
+                        dsts.push((format!("self.{}.{}", dst.to_lowercase(), subcomponent.to_lowercase()), port.to_lowercase()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    dsts
+}
+
+// 生成调度循环：
+// let epoch = clock.now_ms();
+// let mut next_release: [u64; N] = [epoch + O0, ...];
+// loop { let now = clock.now_ms(); if <任务0就绪> { 分派; 转发输出; continue; } ... clock.idle(); }
+fn create_scheduler_stmts(tasks: &[EmbeddedTask]) -> Vec<Statement> {
+    let mut stmts = Vec::new();
+
+    stmts.push(Statement::Let(LetStmt {
+        ifmut: false,
+        name: "epoch".to_string(),
+        ty: None,
+        init: Some(Expr::MethodCall(Box::new(Expr::Ident("clock".to_string())), "now_ms".to_string(), Vec::new())),
+    }));
+    let releases: Vec<String> = tasks.iter().map(|t| format!("epoch + {}", t.offset)).collect();
+    stmts.push(Statement::Let(LetStmt {
+        ifmut: true,
+        name: "next_release".to_string(),
+        ty: Some(Type::Array(Box::new(Type::Named("u64".to_string())), tasks.len())),
+        init: Some(Expr::Ident(format!("[{}]", releases.join(", ")))),
+    }));
+
+    let mut loop_stmts = vec![Statement::Let(LetStmt {
+        ifmut: false,
+        name: "now".to_string(),
+        ty: None,
+        init: Some(Expr::MethodCall(Box::new(Expr::Ident("clock".to_string())), "now_ms".to_string(), Vec::new())),
+    })];
+
+    for (index, task) in tasks.iter().enumerate() {
+        let released = format!("now >= next_release[{}]", index);
+        let pending: Vec<String> = task.triggers.iter().map(|p| format!("!{}.{}.is_empty()", task.path, p)).collect();
+
+        // 周期任务按释放时刻分派；偶发任务在有事件且满足最小到达间隔时分派；非周期任务有事件即分派
+        let (condition, next) = match task.dispatch.as_str() {
+            "Sporadic" if !pending.is_empty() => (
+                format!("{} && ({})", released, pending.join(" || ")),
+                format!("next_release[{}] = now + {}", index, task.period),
+            ),
+            "Aperiodic" if !pending.is_empty() => (pending.join(" || "), String::new()),
+            _ => (released, format!("next_release[{}] += {}", index, task.period)),
+        };
+
+        let mut body = vec![Statement::Expr(Expr::Ident(format!("{}.step()", task.path)))];
+        if !next.is_empty() {
+            body.push(Statement::Expr(Expr::Ident(next)));
+        }
+        for (port, dsts) in &task.outputs {
+            if dsts.is_empty() {
+                continue;
+            }
+            let mut deliveries = Vec::new();
+            for (i, (dst_path, dst_port)) in dsts.iter().enumerate() {
+                let value = if i + 1 == dsts.len() { "val".to_string() } else { "val.clone()".to_string() };
+                deliveries.push(Statement::Expr(Expr::Call(
+                    Box::new(Expr::Path(vec!["deliver".to_string()], PathType::Namespace)),
+                    vec![
+                        Expr::Ident(format!("&mut {}.{}", dst_path, dst_port)),
+                        Expr::Ident(value),
+                    ],
+                )));
+            }
+            body.push(Statement::Expr(Expr::IfLet {
+                pattern: "Some(val)".to_string(),
+                value: Box::new(Expr::Ident(format!("{}.{}.take()", task.path, port))),
+                then_branch: Block { stmts: deliveries, expr: None },
+                else_branch: None,
+            }));
+        }
+        body.push(Statement::Expr(Expr::Ident("continue".to_string())));
+
+        loop_stmts.push(Statement::Expr(Expr::Ident(format!("// {} ({})", task.name, task.dispatch))));
+        loop_stmts.push(Statement::Expr(Expr::If {
+            condition: Box::new(Expr::Ident(condition)),
+            then_branch: Block { stmts: body, expr: None },
+            else_branch: None,
+        }));
+    }
+
+    // 没有就绪任务时交给用户时钟等待（如 WFI）
+    loop_stmts.push(Statement::Expr(Expr::MethodCall(
+        Box::new(Expr::Ident("clock".to_string())),
+        "idle".to_string(),
+        Vec::new(),
+    )));
+
+    stmts.push(Statement::Expr(Expr::Loop(Box::new(Block {
+        stmts: loop_stmts,
+        expr: None,
+    }))));
+
+    stmts
+}
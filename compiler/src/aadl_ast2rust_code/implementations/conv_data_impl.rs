@@ -1,6 +1,8 @@
 
 use crate::aadl_ast2rust_code::intermediate_ast::*;
 use crate::ast::aadl_ast_cj::*;
+use crate::aadl_ast2rust_code::tool::{array_type, to_upper_camel_case};
+use crate::instance_model::array_dimensions;
 use std::collections::HashMap;
 
 
@@ -22,9 +24,8 @@ pub fn convert_data_implementation(
             .cloned()
             .collect();
 
-        if data_comp_type.contains_key(&impl_.name.type_identifier) {
+        if let Some(data_type_name) = data_comp_type.get(&impl_.name.type_identifier) {
             //说明是复杂数据类型
-            let data_type_name = data_comp_type.get(&impl_.name.type_identifier).unwrap();
             if data_type_name == "struct" {
                 items.push(Item::Struct(determine_struct_impl(
                     type_mappings,
@@ -315,6 +316,8 @@ fn determine_struct_impl(
                     .cloned()
                     .unwrap_or(Type::Named(type_name))
             }
+            // 未绑定的数据原型是泛型结构体的类型参数
+            SubcomponentClassifier::Prototype(prototype_name) => Type::Named(to_upper_camel_case(prototype_name)),
             _ => Type::Named("UnknownType".to_string()),
             // SubcomponentClassifier::ClassifierReference(
             //     UniqueComponentClassifierReference::Type(type_ref),
@@ -328,18 +331,15 @@ fn determine_struct_impl(
             //         .cloned()
             //         .unwrap_or_else(|| Type::Named(type_name))
             // }
-            // SubcomponentClassifier::Prototype(prototype_name) => {
-            //     // 处理原型引用
-            //     Type::Named(prototype_name.clone())
-            // }
         };
 
         // 创建字段
         fields.push(Field {
             name: field_name,
-            ty: field_type,
+            ty: array_type(field_type, &array_dimensions(sub).unwrap_or_default()),
             docs: vec![format!("// 子组件字段: {}", sub.identifier)],
             attrs: vec![],
+            vis: Visibility::Public,
         });
     }
 
@@ -382,6 +382,8 @@ fn determine_union_impl(
                     .cloned()
                     .unwrap_or(Type::Named(type_name))
             }
+            // 未绑定的数据原型是泛型结构体的类型参数
+            SubcomponentClassifier::Prototype(prototype_name) => Type::Named(to_upper_camel_case(prototype_name)),
             _ => Type::Named("UnknownType".to_string()),
             // SubcomponentClassifier::ClassifierReference(
             //     UniqueComponentClassifierReference::Type(type_ref),
@@ -395,18 +397,15 @@ fn determine_union_impl(
             //         .cloned()
             //         .unwrap_or_else(|| Type::Named(type_name))
             // }
-            // SubcomponentClassifier::Prototype(prototype_name) => {
-            //     // 处理原型引用
-            //     Type::Named(prototype_name.clone())
-            // }
         };
 
         // 创建字段
         fields.push(Field {
             name: field_name,
-            ty: field_type,
+            ty: array_type(field_type, &array_dimensions(sub).unwrap_or_default()),
             docs: vec![format!("// 联合体字段: {}", sub.identifier)],
             attrs: vec![],
+            vis: Visibility::Public,
         });
     }
 
@@ -449,6 +448,8 @@ fn determine_taggedunion_impl(
                     .cloned()
                     .unwrap_or(Type::Named(type_name))
             }
+            // 未绑定的数据原型是泛型结构体的类型参数
+            SubcomponentClassifier::Prototype(prototype_name) => Type::Named(to_upper_camel_case(prototype_name)),
             _ => Type::Named("UnknownType".to_string()),
             // SubcomponentClassifier::ClassifierReference(
             //     UniqueComponentClassifierReference::Type(type_ref),
@@ -462,10 +463,6 @@ fn determine_taggedunion_impl(
             //         .cloned()
             //         .unwrap_or_else(|| Type::Named(type_name))
             // }
-            // SubcomponentClassifier::Prototype(prototype_name) => {
-            //     // 处理原型引用
-            //     Type::Named(prototype_name.clone())
-            // }
         };
 
         // 将字段名首字母大写，例如 "f1" -> "F1" 
@@ -478,8 +475,9 @@ fn determine_taggedunion_impl(
         // 创建枚举变体（带数据类型）
         variants.push(Variant {
             name: variant_name,
-            data: Some(vec![field_type]), // 带标签的联合体变体包含数据类型
+            data: Some(vec![array_type(field_type, &array_dimensions(sub).unwrap_or_default())]), // 带标签的联合体变体包含数据类型
             docs: vec![format!("// 标记联合体字段: {}", sub.identifier)],
+            discriminant: None,
         });
     }
 
@@ -491,5 +489,6 @@ fn determine_taggedunion_impl(
         derives: vec!["Debug".to_string(), "Clone".to_string()],
         docs: vec![format!("// AADL Tagged Union: {}", impl_.name.type_identifier)],
         vis: Visibility::Public,
+        repr: None,
     }
 }
\ No newline at end of file
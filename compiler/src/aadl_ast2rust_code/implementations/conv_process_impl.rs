@@ -1,9 +1,12 @@
 #![allow(clippy::single_match)]
-use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::converter::{AadlConverter, CodegenBackend};
+use crate::aadl_ast2rust_code::cyclic_executive::*;
 use crate::aadl_ast2rust_code::intermediate_ast::*;
 
 use crate::ast::aadl_ast_cj::*;
 use crate::aadl_ast2rust_code::tool::*;
+use crate::diagnostics::{self, codes};
+use crate::instance_model::array_dimensions;
 
 pub fn convert_process_implementation(
     temp_converter: &mut AadlConverter,
@@ -16,7 +19,7 @@ pub fn convert_process_implementation(
     
 
     // 1. 生成进程结构体
-    let mut fields = get_process_fields(temp_converter, impl_); //这里是为了取得进程的子组件；生成内部端口也放在这里。
+    let mut fields = get_process_fields(impl_); //这里是为了取得进程的子组件
     
     // 添加 CPU ID 字段
     fields.push(Field {
@@ -24,6 +27,7 @@ pub fn convert_process_implementation(
         ty: Type::Named("isize".to_string()),
         docs: vec!["// 新增 CPU ID".to_string()],
         attrs: Vec::new(),
+        vis: Visibility::Public,
     });
 
     let struct_def = StructDef {
@@ -32,10 +36,13 @@ pub fn convert_process_implementation(
         properties: Vec::new(), //TODO
         generics: Vec::new(),
         derives: vec!["Debug".to_string()],
-        docs: vec![
+        docs: [
             format!("// Process implementation: {}", impl_.name.type_identifier),
             "// Auto-generated from AADL".to_string(),
-        ],
+        ]
+        .into_iter()
+        .chain(source_location_doc(&impl_.location))
+        .collect(),
         vis: Visibility::Public,
     };
     items.push(Item::Struct(struct_def));
@@ -43,6 +50,34 @@ pub fn convert_process_implementation(
     // 2. 生成实现块
     items.push(Item::Impl(create_process_impl_block(temp_converter, impl_)));
 
+    // 3. 循环执行器后端：生成进程的静态调度表
+    if temp_converter.backend == CodegenBackend::Cyclic {
+        let tasks = collect_process_tasks(temp_converter, impl_);
+        if !tasks.is_empty() {
+            // 不可调度的任务集仍生成调度表便于查看，但以错误诊断报告，使生成以非零状态结束
+            match build_schedule_table(&tasks) {
+                Some(table) => {
+                    for miss in &table.deadline_misses {
+                        diagnostics::error_in(
+                            impl_.location.as_ref(),
+                            codes::UNSCHEDULABLE_TASK_SET,
+                            format!("process {}: {}", impl_.name.type_identifier, miss),
+                        );
+                    }
+                    items.extend(create_schedule_items(&impl_.name.type_identifier, &tasks, &table));
+                }
+                None => diagnostics::error_in(
+                    impl_.location.as_ref(),
+                    codes::HYPERPERIOD_OVERFLOW,
+                    format!(
+                        "process {}: hyperperiod of the thread periods overflows u64, no schedule table generated",
+                        impl_.name.type_identifier
+                    ),
+                ),
+            }
+        }
+    }
+
     items
 }
 
@@ -94,212 +129,11 @@ pub fn convert_process_implementation(
 
 }*/
 
-//新增转发端口（内部端口，用于转发数据到子组件）；处理子组件（thread+data）
-fn get_process_fields(
-    temp_converter: &mut AadlConverter,
-    impl_: &ComponentImplementation,
-) -> Vec<Field> {
+//处理子组件（thread+data）。进程端口不再生成字段：端口数据经语义连接直接在线程之间传递
+fn get_process_fields(impl_: &ComponentImplementation) -> Vec<Field> {
     let mut fields = Vec::new();
 
-    // 1. 添加进程的端口字段（对外端口 + 内部端口）
-    if let Some(comp_type) = temp_converter.get_component_type(impl_) {
-        if let FeatureClause::Items(features) = &comp_type.features {
-            for feature in features {
-                if let Feature::Port(port) = feature {
-                    // 添加对外端口
-                    fields.push(Field {
-                        name: port.identifier.to_lowercase(),
-                        ty: temp_converter.convert_port_type(port, "".to_string()),
-                        docs: vec![format!(
-                            "// Port: {} {:?}",
-                            port.identifier, port.direction
-                        )],
-                        attrs: Vec::new(),
-                    });
-
-                    // 添加对应的内部端口
-                    let internal_port_name = match port.direction {
-                        PortDirection::In => format!("{}Send", port.identifier.to_lowercase()),
-                        PortDirection::Out => format!("{}Rece", port.identifier.to_lowercase()),
-                        PortDirection::InOut => {
-                            format!("{}Send", port.identifier.to_lowercase())
-                        } // InOut 暂时按 In 处理
-                    };
-
-                    let internal_port_type = match port.direction {
-                        PortDirection::In => {
-                            // 对外是接收端口，内部需要发送端口
-                            match temp_converter.convert_port_type(port, "".to_string()) {
-                                Type::Generic(option_name, inner_types)
-                                    if option_name == "Option" =>
-                                {
-                                    if let Type::Generic(channel_name, channel_args) =
-                                        &inner_types[0]
-                                    {
-
-                                        let mut send_type = "Sender".to_string();
-                                        // 从 Option<Receiver<T>> 转换为 Option<BcSender<T>>
-                                        if temp_converter.thread_broadcast_receive.contains_key(&(port.identifier.clone(),impl_.name.type_identifier.clone())){
-                                            send_type = "BcSender".to_string();
-                                        }
-
-                                        if channel_name == "Receiver" {
-                                            Type::Generic(
-                                                "Option".to_string(),
-                                                vec![Type::Generic(
-                                                    send_type.clone(),
-                                                    channel_args.clone(),
-                                                )],
-                                            )
-                                        } else {
-                                            Type::Generic(
-                                                "Option".to_string(),
-                                                vec![Type::Generic(
-                                                    channel_name.clone(),
-                                                    channel_args.clone(),
-                                                )],
-                                            )
-                                        }
-                                    } else {
-                                        Type::Generic(
-                                            "Option".to_string(),
-                                            vec![Type::Generic(
-                                                "Sender".to_string(),
-                                                vec![inner_types[0].clone()],
-                                            )],
-                                        )
-                                    }
-                                }
-                                _ => {
-                                    // 如果不是 Option 类型，创建 Option<BcSender<T>>
-                                    Type::Generic(
-                                        "Option".to_string(),
-                                        vec![Type::Generic(
-                                            "Sender".to_string(),
-                                            vec![temp_converter
-                                                .convert_port_type(port, "".to_string())],
-                                        )],
-                                    )
-                                }
-                            }
-                        }
-                        PortDirection::Out => {
-                            // 对外是发送端口，内部需要接收端口
-                            match temp_converter.convert_port_type(port, "".to_string()) {
-                                Type::Generic(option_name, inner_types)
-                                    if option_name == "Option" =>
-                                {
-                                    if let Type::Generic(channel_name, channel_args) =
-                                        &inner_types[0]
-                                    {
-                                        if channel_name == "Sender" {
-                                            // 从 Option<Sender<T>> 转换为 Option<Receiver<T>>
-                                            Type::Generic(
-                                                "Option".to_string(),
-                                                vec![Type::Generic(
-                                                    "Receiver".to_string(),
-                                                    channel_args.clone(),
-                                                )],
-                                            )
-                                        } else {
-                                            Type::Generic(
-                                                "Option".to_string(),
-                                                vec![Type::Generic(
-                                                    channel_name.clone(),
-                                                    channel_args.clone(),
-                                                )],
-                                            )
-                                        }
-                                    } else {
-                                        Type::Generic(
-                                            "Option".to_string(),
-                                            vec![Type::Generic(
-                                                "Receiver".to_string(),
-                                                vec![inner_types[0].clone()],
-                                            )],
-                                        )
-                                    }
-                                }
-                                _ => {
-                                    // 如果不是 Option 类型，创建 Option<Receiver<T>>
-                                    Type::Generic(
-                                        "Option".to_string(),
-                                        vec![Type::Generic(
-                                            "Receiver".to_string(),
-                                            vec![temp_converter
-                                                .convert_port_type(port, "".to_string())],
-                                        )],
-                                    )
-                                }
-                            }
-                        }
-                        PortDirection::InOut => {
-                            // InOut 暂时按 In 处理
-                            match temp_converter.convert_port_type(port, "".to_string()) {
-                                Type::Generic(option_name, inner_types)
-                                    if option_name == "Option" =>
-                                {
-                                    if let Type::Generic(channel_name, channel_args) =
-                                        &inner_types[0]
-                                    {
-                                        if channel_name == "Receiver" {
-                                            // 从 Option<Receiver<T>> 转换为 Option<BcSender<T>>
-                                            Type::Generic(
-                                                "Option".to_string(),
-                                                vec![Type::Generic(
-                                                    "BcSender".to_string(),
-                                                    channel_args.clone(),
-                                                )],
-                                            )
-                                        } else {
-                                            Type::Generic(
-                                                "Option".to_string(),
-                                                vec![Type::Generic(
-                                                    channel_name.clone(),
-                                                    channel_args.clone(),
-                                                )],
-                                            )
-                                        }
-                                    } else {
-                                        Type::Generic(
-                                            "Option".to_string(),
-                                            vec![Type::Generic(
-                                                "BcSender".to_string(),
-                                                vec![inner_types[0].clone()],
-                                            )],
-                                        )
-                                    }
-                                }
-                                _ => {
-                                    // 如果不是 Option 类型，创建 Option<BcSender<T>>
-                                    Type::Generic(
-                                        "Option".to_string(),
-                                        vec![Type::Generic(
-                                            "BcSender".to_string(),
-                                            vec![temp_converter
-                                                .convert_port_type(port, "".to_string())],
-                                        )],
-                                    )
-                                }
-                            }
-                        }
-                    };
-
-                    fields.push(Field {
-                        name: internal_port_name,
-                        ty: internal_port_type,
-                        docs: vec![format!(
-                            "// 内部端口: {} {:?}",
-                            port.identifier, port.direction
-                        )],
-                        attrs: Vec::new(),
-                    });
-                }
-            }
-        }
-    }
-
-    // 2. 添加子组件字段
+    // 添加子组件字段
     if let SubcomponentClause::Items(subcomponents) = &impl_.subcomponents {
         for sub in subcomponents {
             let type_name = match &sub.classifier {
@@ -325,26 +159,30 @@ fn get_process_fields(
                 _ => Type::Named(format!("{}Thread", to_upper_camel_case(&type_name))),
             };
 
+            // 子组件数组生成定长数组字段
+            let dimensions = array_dimensions(sub).unwrap_or_default();
+            let declared = format!("{}{}", sub.identifier, dimensions.iter().map(|d| format!("[{}]", d)).collect::<String>());
             let doc = match sub.category {
                 ComponentCategory::Thread => {
-                    format!("// 子组件线程({} : thread {})", sub.identifier, type_name)
+                    format!("// 子组件线程({} : thread {})", declared, type_name)
                 }
                 ComponentCategory::Data => {
                     // 直接使用原始类型名
-                    format!("// 共享数据({} : data {})", sub.identifier, type_name)
+                    format!("// 共享数据({} : data {})", declared, type_name)
                 }
-                _ => format!("// Subcomponent: {}", sub.identifier),
+                _ => format!("// Subcomponent: {}", declared),
             };
 
             fields.push(Field {
                 name: sub.identifier.to_lowercase(),
-                ty: field_ty,
+                ty: array_type(field_ty, &dimensions),
                 docs: vec![doc],
                 // attrs: vec![Attribute {
                 //     name: "allow".to_string(),
                 //     args: vec![AttributeArg::Ident("dead_code".to_string())],
                 // }],
                 attrs: Vec::new(),
+                vis: Visibility::Public,
             });
         }
     }
@@ -396,10 +234,7 @@ fn create_process_impl_block(
     }
 }
 
-fn create_process_new_body(
-    temp_converter: &mut AadlConverter,
-    impl_: &ComponentImplementation,
-) -> Block {
+fn create_process_new_body(temp_converter: &AadlConverter, impl_: &ComponentImplementation) -> Block {
     let mut stmts = Vec::new();
     // 为每个线程收集需要注入到 new() 的共享变量参数（例如 data access 映射）
     let mut thread_extra_args: std::collections::HashMap<String, Vec<Expr>> =
@@ -461,7 +296,9 @@ fn create_process_new_body(
             };
 
             let var_name = sub.identifier.to_lowercase();
-            // 按类别初始化子组件：线程调用 FooThread::new(cpu_id+共享变量克隆)，数据使用 PosShared::new()
+            let dimensions = array_dimensions(sub).unwrap_or_default();
+            // 按类别初始化子组件：线程调用 FooThread::new(cpu_id+共享变量克隆)，数据使用 PosShared::new()；
+            // 子组件数组逐元素构造
             match sub.category {
                 ComponentCategory::Data => {
                     // 直接使用原始类型名，不进行大小写转换
@@ -483,8 +320,8 @@ fn create_process_new_body(
                     data_inits.push(Statement::Let(LetStmt {
                         ifmut: false,
                         name: var_name.to_string(),
-                        ty: Some(Type::Named(shared_ty.clone())),
-                        init: Some(init_expr),
+                        ty: Some(array_type(Type::Named(shared_ty.clone()), &dimensions)),
+                        init: Some(array_init(init_expr, &dimensions)),
                     }));
                 }
                 ComponentCategory::Thread => {
@@ -493,19 +330,23 @@ fn create_process_new_body(
                     if let Some(extra) = thread_extra_args.get(&sub.identifier.to_lowercase()) {
                         args.extend(extra.clone());
                     }
+                    let thread_ty = Type::Named(format!("{}Thread", to_upper_camel_case(&type_name)));
                     thread_inits.push(Statement::Let(LetStmt {
                         ifmut: true,
                         name: var_name.to_string(),
-                        ty: Some(Type::Named(format!("{}Thread", to_upper_camel_case(&type_name)))),
-                        init: Some(Expr::Call(
-                            Box::new(Expr::Path(
-                                vec![
-                                    format!("{}Thread", to_upper_camel_case(&type_name)),
-                                    "new".to_string(),
-                                ],
-                                PathType::Namespace,
-                            )),
-                            args,
+                        ty: Some(array_type(thread_ty, &dimensions)),
+                        init: Some(array_init(
+                            Expr::Call(
+                                Box::new(Expr::Path(
+                                    vec![
+                                        format!("{}Thread", to_upper_camel_case(&type_name)),
+                                        "new".to_string(),
+                                    ],
+                                    PathType::Namespace,
+                                )),
+                                args,
+                            ),
+                            &dimensions,
                         )),
                     }));
                 }
@@ -535,84 +376,13 @@ fn create_process_new_body(
         stmts.extend(thread_inits);
     }
 
-    // 2. 创建内部端口变量
-    if let Some(comp_type) = temp_converter.get_component_type(impl_) {
-        if let FeatureClause::Items(features) = &comp_type.features {
-            for feature in features {
-                if let Feature::Port(port) = feature {
-                    let internal_port_name = match port.direction {
-                        PortDirection::In => format!("{}Send", port.identifier.to_lowercase()),
-                        PortDirection::Out => format!("{}Rece", port.identifier.to_lowercase()),
-                        PortDirection::InOut => format!("{}Send", port.identifier.to_lowercase()),
-                    };
-
-                    // 创建内部端口变量，初始化为None
-                    stmts.push(Statement::Let(LetStmt {
-                        ifmut: true,
-                        name: internal_port_name.clone(),
-                        ty: None,
-                        init: Some(Expr::Ident("None".to_string())),
-                    }));
-                }
-            }
-        }
-    }
+    // 线程端口之间的通道由根系统按语义连接统一建立（见 create_system_channels）；
+    // 根系统无法直接访问本进程时（如位于嵌套子系统中），在这里连通其线程端口
+    stmts.extend(temp_converter.create_process_channels(impl_));
 
-    // 3. 建立连接
-    // 函数内存储已处理过的广播连接，避免二次处理。
-    let mut processed_broadcast_connections = Vec::new();
-    //println!("thread_broadcast_receive:{:?}",temp_converter.thread_broadcast_receive);
-
-    if let ConnectionClause::Items(connections) = &impl_.connections {
-        for conn in connections {
-            if let Connection::Port(port_conn) = conn {
-                if let PortEndpoint::ComponentPort(proc_port) = &port_conn.source {
-                    if temp_converter
-                        .thread_broadcast_receive
-                        .contains_key(&(proc_port.clone(), impl_.name.type_identifier.clone()))
-                    {
-                        if processed_broadcast_connections
-                            .contains(&(proc_port.clone(), impl_.name.type_identifier.clone()))
-                        {
-                            continue;
-                        } else {
-                            processed_broadcast_connections
-                                .push((proc_port.clone(), impl_.name.type_identifier.clone()));
-                        }
-                    }
-                }
-                stmts.extend(
-                    temp_converter
-                        .create_channel_connection(port_conn, impl_.name.type_identifier.clone()),
-                );
-            }
-        }
-    }
-
-    // 3. 返回结构体实例
+    // 2. 返回结构体实例
     let mut field_inits = Vec::new();
 
-    // 添加端口字段初始化（对外端口初始化为None，内部端口使用变量）
-    if let Some(comp_type) = temp_converter.get_component_type(impl_) {
-        if let FeatureClause::Items(features) = &comp_type.features {
-            for feature in features {
-                if let Feature::Port(port) = feature {
-                    // 对外端口初始化为None
-                    field_inits.push(format!("{}: None", port.identifier.to_lowercase()));
-
-                    // 内部端口使用变量名（将在连接处理中赋值）
-                    let internal_port_name = match port.direction {
-                        PortDirection::In => format!("{}Send", port.identifier.to_lowercase()),
-                        PortDirection::Out => format!("{}Rece", port.identifier.to_lowercase()),
-                        PortDirection::InOut => format!("{}Send", port.identifier.to_lowercase()),
-                    };
-
-                    field_inits.push(internal_port_name.to_string());
-                }
-            }
-        }
-    }
-
     // 添加子组件字段
     if let SubcomponentClause::Items(subcomponents) = &impl_.subcomponents {
         for sub in subcomponents {
@@ -642,54 +412,25 @@ fn create_process_start_body(
     // 1. 解构self，获取所有需要的字段
     let mut destructure_fields = Vec::new();
     let mut thread_fields = Vec::new();
-    let mut port_fields = Vec::new();
-
-    // 1.1 添加端口字段（来自features）
-    if let Some(comp_type) = temp_converter.get_component_type(impl_) {
-        if let FeatureClause::Items(features) = &comp_type.features {
-            for feature in features {
-                if let Feature::Port(port) = feature {
-                    // 添加对外端口
-                    let port_name = port.identifier.to_lowercase();
-                    destructure_fields.push(port_name.clone());
-                    port_fields.push(port_name);
-
-                    // 添加内部端口
-                    let internal_port_name = match port.direction {
-                        PortDirection::In => format!("{}Send", port.identifier.to_lowercase()),
-                        PortDirection::Out => format!("{}Rece", port.identifier.to_lowercase()),
-                        PortDirection::InOut => format!("{}Send", port.identifier.to_lowercase()),
-                    };
-                    destructure_fields.push(internal_port_name.clone());
-                    port_fields.push(internal_port_name);
-                }
-            }
-        }
-    }
 
-    // 1.2 添加子组件字段
+    // 1.1 添加线程子组件字段（线程数组记录各维大小）
     if let SubcomponentClause::Items(subcomponents) = &impl_.subcomponents {
         for sub in subcomponents {
-            let var_name = sub.identifier.to_lowercase();
-            destructure_fields.push(var_name.clone());
-
-            match sub.category {
-                ComponentCategory::Thread => {
-                    thread_fields.push(var_name);
-                }
-                ComponentCategory::Data => {
-                    // 数据组件可能作为端口使用
-                    port_fields.push(var_name);
-                }
-                _ => {}
+            if sub.category == ComponentCategory::Thread {
+                let var_name = sub.identifier.to_lowercase();
+                destructure_fields.push(var_name.clone());
+                thread_fields.push((var_name, array_dimensions(sub).unwrap_or_default()));
             }
         }
     }
 
-    // 1.3 添加cpu_id字段
-    // destructure_fields.push("cpu_id".to_string());
+    // 1.2 添加cpu_id字段（循环执行器需要据此设置执行线程的CPU亲和性）
+    let cyclic = temp_converter.backend == CodegenBackend::Cyclic && !thread_fields.is_empty();
+    if cyclic {
+        destructure_fields.push("cpu_id".to_string());
+    }
 
-    // 创建解构语句：let Self { port1, port1Send, th_c, cpu_id, .. } = self;
+    // 创建解构语句：let Self { th_c, cpu_id, .. } = self;
     let destructure_stmt = Statement::Let(LetStmt {
         ifmut: false,
         name: format!("Self {{ {}, .. }}", destructure_fields.join(", ")),
@@ -698,196 +439,255 @@ fn create_process_start_body(
     });
     stmts.push(destructure_stmt);
 
-    // 2. 启动所有线程子组件（使用解构后的变量）
-    for thread_name in thread_fields {
-        // 构建线程闭包（使用move语义）
-        let closure = Expr::Closure(
-            Vec::new(), // 无参数
-            Box::new(Expr::MethodCall(
-                Box::new(Expr::Ident(thread_name.clone())),
-                "run".to_string(),
-                Vec::new(),
-            )),
-        );
-
-        // 构建线程构建器表达式链
-        let builder_chain = vec![
-            BuilderMethod::Named(format!("\"{}\".to_string()", thread_name)),
-            BuilderMethod::Spawn {
-                closure: Box::new(closure),
-                move_kw: true,
-            },
-        ];
+    if cyclic {
+        stmts.extend(create_cyclic_executive_stmts(impl_, &thread_fields));
+        return Block { stmts, expr: None };
+    }
 
-        stmts.push(Statement::Expr(Expr::MethodCall(
-            Box::new(Expr::BuilderChain(builder_chain)),
-            "unwrap".to_string(),
-            Vec::new(),
-        )));
+    if temp_converter.backend == CodegenBackend::Async {
+        stmts.extend(create_async_task_stmts(&thread_fields));
+        return Block { stmts, expr: None };
     }
 
-    // 3. 启动数据转发循环（使用解构后的变量）
-    let mut forwarding_tasks = create_data_forwarding_tasks(impl_);
-    forwarding_tasks.sort();
-    forwarding_tasks.dedup();
-
-    for (src_field, dst_field) in forwarding_tasks {
-        // 创建接收端变量：let evenementRece_rx = evenementRece.unwrap();
-        let rx_var_name = format!("{}_rx", src_field);
-        stmts.push(Statement::Let(LetStmt {
-            ifmut: true,
-            name: rx_var_name.clone(),
-            ty: None,
-            init: Some(Expr::MethodCall(
-                Box::new(Expr::Ident(src_field.clone())),
-                "unwrap".to_string(),
+    // 2. 启动所有线程子组件（使用解构后的变量）；线程数组的每个元素各启动一个线程，名称带下标
+    for (thread_name, dimensions) in thread_fields {
+        if dimensions.is_empty() {
+            stmts.push(spawn_thread_stmt(&thread_name, format!("\"{}\".to_string()", thread_name)));
+            continue;
+        }
+        stmts.push(Statement::Expr(Expr::For {
+            pattern: format!("(index, {})", thread_name),
+            iter: Box::new(Expr::MethodCall(
+                Box::new(array_elements_iter(&thread_name, &dimensions)),
+                "enumerate".to_string(),
                 Vec::new(),
             )),
-        }));
-
-        // 创建转发线程
-        let forwarding_loop = create_single_forwarding_thread(&rx_var_name, &dst_field);
-        let closure = Expr::Closure(
-            Vec::new(),
-            Box::new(Expr::Block(Block {
-                stmts: forwarding_loop,
+            body: Block {
+                stmts: vec![spawn_thread_stmt(&thread_name, format!("format!(\"{}[{{}}]\", index)", thread_name))],
                 expr: None,
-            })),
-        );
-
-        // 构建线程构建器表达式链
-        let builder_chain = vec![
-            BuilderMethod::Named(format!("\"data_forwarder_{}\".to_string()", src_field)),
-            BuilderMethod::Spawn {
-                closure: Box::new(closure),
-                move_kw: true, // 添加 move 关键字
             },
-        ];
-
-        stmts.push(Statement::Expr(Expr::MethodCall(
-            Box::new(Expr::BuilderChain(builder_chain)),
-            "unwrap".to_string(),
-            Vec::new(),
-        )));
+        }));
     }
 
     Block { stmts, expr: None }
 }
 
-/// 创建数据转发任务列表
-fn create_data_forwarding_tasks(impl_: &ComponentImplementation) -> Vec<(String, String)> {
-    let mut forwarding_tasks = Vec::new();
+// thread::Builder::new().name(name).spawn(move || th.run()).unwrap()
+fn spawn_thread_stmt(thread_name: &str, name_expr: String) -> Statement {
+    // 构建线程闭包（使用move语义）
+    let closure = Expr::Closure(
+        Vec::new(), // 无参数
+        Box::new(Expr::MethodCall(
+            Box::new(Expr::Ident(thread_name.to_string())),
+            "run".to_string(),
+            Vec::new(),
+        )),
+    );
+
+    // 构建线程构建器表达式链
+    let builder_chain = vec![
+        BuilderMethod::Named(name_expr),
+        BuilderMethod::Spawn {
+            closure: Box::new(closure),
+            move_kw: true,
+        },
+    ];
 
-    if let ConnectionClause::Items(connections) = &impl_.connections {
-        for conn in connections {
-            if let Connection::Port(port_conn) = conn {
-                // 解析源和目标端口
-                let (src_field, dst_field) = match (&port_conn.source, &port_conn.destination) {
-                    // 进程端口到子组件端口
-                    (
-                        PortEndpoint::ComponentPort(src_port),
-                        PortEndpoint::SubcomponentPort {
-                            subcomponent: _dst_comp,
-                            port: _dst_port,
-                        },
-                    ) => {
-                        // 对于进程端口，应该使用内部端口字段名（如 evenementSend）
-                        let src_field = src_port.to_lowercase().to_string();
-                        let dst_field = format!("{}Send", src_port.to_lowercase());
-                        (src_field, dst_field)
-                    }
-                    // 子组件端口到进程端口
-                    (
-                        PortEndpoint::SubcomponentPort {
-                            subcomponent: _src_comp,
-                            port: _src_port,
-                        },
-                        PortEndpoint::ComponentPort(dst_port),
-                    ) => {
-                        let src_field = format!("{}Rece", dst_port.to_lowercase());
-                        // 对于进程端口，应该使用内部端口字段名（如 evenementRece）
-                        let dst_field = dst_port.to_lowercase().to_string();
-                        (src_field, dst_field)
-                    }
-                    _ => continue,
-                };
+    Statement::Expr(Expr::MethodCall(
+        Box::new(Expr::BuilderChain(builder_chain)),
+        "unwrap".to_string(),
+        Vec::new(),
+    ))
+}
 
-                forwarding_tasks.push((src_field, dst_field));
-            }
+/// 异步后端：每个线程子组件（线程数组的每个元素）作为一个 tokio 任务运行
+fn create_async_task_stmts(thread_fields: &[(String, Vec<u32>)]) -> Vec<Statement> {
+    let mut stmts = Vec::new();
+
+    // tokio::spawn(async move { th.run_async().await; });
+    for (thread_name, dimensions) in thread_fields {
+        let spawn = Statement::Expr(tokio_spawn(vec![Statement::Expr(Expr::Await(Box::new(
+            Expr::MethodCall(
+                Box::new(Expr::Ident(thread_name.clone())),
+                "run_async".to_string(),
+                Vec::new(),
+            ),
+        )))]));
+        if dimensions.is_empty() {
+            stmts.push(spawn);
+        } else {
+            stmts.push(Statement::Expr(Expr::For {
+                pattern: thread_name.clone(),
+                iter: Box::new(array_elements_iter(thread_name, dimensions)),
+                body: Block { stmts: vec![spawn], expr: None },
+            }));
         }
     }
 
-    forwarding_tasks
+    stmts
+}
+
+// tokio::spawn(async move { ... })
+fn tokio_spawn(body: Vec<Statement>) -> Expr {
+    Expr::Call(
+        Box::new(Expr::Path(
+            vec!["tokio".to_string(), "spawn".to_string()],
+            PathType::Namespace,
+        )),
+        vec![Expr::UnaryOp(
+            "async move ".to_string(),
+            Box::new(Expr::Block(Block { stmts: body, expr: None })),
+        )],
+    )
 }
 
-/// 创建单个转发线程的代码
-fn create_single_forwarding_thread(rx_var_name: &str, dst_field: &str) -> Vec<Statement> {
+/// 循环执行器后端：进程只启动一个执行线程，按静态调度表依次调用各线程的 into_job() 作业
+fn create_cyclic_executive_stmts(impl_: &ComponentImplementation, thread_fields: &[(String, Vec<u32>)]) -> Vec<Statement> {
     let mut stmts = Vec::new();
+    let prefix = schedule_const_prefix(&impl_.name.type_identifier);
 
-    // 创建转发循环：loop { if let Ok(msg) = rx_var_name.try_recv() { ... } }
-    let loop_body = vec![
-        Statement::Expr(Expr::IfLet {
-            pattern: "Ok(msg)".to_string(),
-            value: Box::new(Expr::MethodCall(
-                Box::new(Expr::Ident(rx_var_name.to_string())),
-                "try_recv".to_string(),
+    let mut executive_stmts = Vec::new();
+
+    // if cpu_id > -1 { set_thread_affinity(cpu_id); }
+    executive_stmts.push(Statement::Expr(Expr::If {
+        condition: Box::new(Expr::BinaryOp(
+            Box::new(Expr::Ident("cpu_id".to_string())),
+            ">".to_string(),
+            Box::new(Expr::Literal(Literal::Int(-1))),
+        )),
+        then_branch: Block {
+            stmts: vec![Statement::Expr(Expr::Call(
+                Box::new(Expr::Path(vec!["set_thread_affinity".to_string()], PathType::Namespace)),
+                vec![Expr::Ident("cpu_id".to_string())],
+            ))],
+            expr: None,
+        },
+        else_branch: None,
+    }));
+
+    // let mut jobs: Vec<Box<dyn FnMut()>> = vec![a.into_job(), ...];
+    // 线程数组按行优先展开，作业顺序与调度表中的任务顺序一致
+    let jobs_init = if thread_fields.iter().all(|(_, dimensions)| dimensions.is_empty()) {
+        let jobs: Vec<String> = thread_fields.iter().map(|(t, _)| format!("{}.into_job()", t)).collect();
+        format!("vec![{}]", jobs.join(", "))
+    } else {
+        let jobs: Vec<String> = thread_fields
+            .iter()
+            .map(|(t, dimensions)| match dimensions.len() {
+                0 => format!("vec![{}.into_job()]", t),
+                n => format!(
+                    "{}.into_iter(){}.map(|t| t.into_job()).collect::<Vec<Box<dyn FnMut()>>>()",
+                    t,
+                    ".flatten()".repeat(n - 1)
+                ),
+            })
+            .collect();
+        format!("[{}].into_iter().flatten().collect()", jobs.join(", "))
+    };
+    executive_stmts.push(Statement::Let(LetStmt {
+        ifmut: true,
+        name: "jobs".to_string(),
+        ty: Some(Type::Named("Vec<Box<dyn FnMut()>>".to_string())),
+        init: Some(Expr::Ident(jobs_init)),
+    }));
+    executive_stmts.push(Statement::Let(LetStmt {
+        ifmut: false,
+        name: "hyperperiod".to_string(),
+        ty: None,
+        init: Some(Expr::Call(
+            Box::new(Expr::Path(vec!["Duration".to_string(), "from_millis".to_string()], PathType::Namespace)),
+            vec![Expr::Ident(format!("{}_HYPERPERIOD_MS", prefix))],
+        )),
+    }));
+    executive_stmts.push(Statement::Let(LetStmt {
+        ifmut: true,
+        name: "frame_start".to_string(),
+        ty: None,
+        init: Some(Expr::Call(
+            Box::new(Expr::Path(vec!["system_epoch".to_string()], PathType::Namespace)),
+            Vec::new(),
+        )),
+    }));
+
+    // 时间槽：等待到槽起始时刻 -> 执行作业
+    let mut slot_stmts = vec![
+        Statement::Let(LetStmt {
+            ifmut: false,
+            name: "slot_start".to_string(),
+            ty: None,
+            init: Some(Expr::Ident("frame_start + Duration::from_millis(offset)".to_string())),
+        }),
+        Statement::Let(LetStmt {
+            ifmut: false,
+            name: "now".to_string(),
+            ty: None,
+            init: Some(Expr::Call(
+                Box::new(Expr::Path(vec!["Instant".to_string(), "now".to_string()], PathType::Namespace)),
                 Vec::new(),
             )),
+        }),
+        Statement::Expr(Expr::If {
+            condition: Box::new(Expr::BinaryOp(
+                Box::new(Expr::Ident("now".to_string())),
+                "<".to_string(),
+                Box::new(Expr::Ident("slot_start".to_string())),
+            )),
             then_branch: Block {
-                stmts: vec![Statement::Expr(Expr::IfLet {
-                    pattern: "Some(tx)".to_string(),
-                    value: Box::new(Expr::Reference(
-                        Box::new(Expr::Ident(dst_field.to_string())),
-                        true,
-                        false,
+                stmts: vec![Statement::Expr(Expr::Call(
+                    Box::new(Expr::Path(
+                        vec!["std".to_string(), "thread".to_string(), "sleep".to_string()],
+                        PathType::Namespace,
                     )),
-                    then_branch: Block {
-                        stmts: vec![Statement::Let(LetStmt {
-                            ifmut: false,
-                            name: "_".to_string(),
-                            ty: None,
-                            init: Some(Expr::MethodCall(
-                                Box::new(Expr::Ident("tx".to_string())),
-                                "send".to_string(),
-                                vec![Expr::Ident("msg".to_string())],
-                            )),
-                        })],
-                        expr: None,
-                    },
-                    else_branch: None,
-                })],
+                    vec![Expr::Ident("slot_start - now".to_string())],
+                ))],
                 expr: None,
             },
             else_branch: None,
         }),
-        // 添加睡眠以避免CPU占用过高
-        Statement::Expr(Expr::MethodCall(
-            Box::new(Expr::Path(
-                vec!["std".to_string(), "thread".to_string(), "sleep".to_string()],
-                PathType::Namespace,
-            )),
-            "".to_string(),
-            vec![Expr::MethodCall(
-                Box::new(Expr::Path(
-                    vec![
-                        "std".to_string(),
-                        "time".to_string(),
-                        "Duration".to_string(),
-                        "from_millis".to_string(),
-                    ],
-                    PathType::Namespace,
-                )),
-                "".to_string(),
-                vec![Expr::Literal(Literal::Int(1))],
-            )],
-        )),
     ];
-
-    // 创建无限循环
-    stmts.push(Statement::Expr(Expr::Loop(Box::new(Block {
-        stmts: loop_body,
+    slot_stmts.push(Statement::Expr(Expr::Call(
+        Box::new(Expr::Index(
+            Box::new(Expr::Ident("jobs".to_string())),
+            Box::new(Expr::Ident("job".to_string())),
+        )),
+        Vec::new(),
+    )));
+
+    executive_stmts.push(Statement::Expr(Expr::Loop(Box::new(Block {
+        stmts: vec![
+            Statement::Expr(Expr::For {
+                pattern: "&(offset, job)".to_string(),
+                iter: Box::new(Expr::MethodCall(
+                    Box::new(Expr::Ident(format!("{}_SCHEDULE", prefix))),
+                    "iter".to_string(),
+                    Vec::new(),
+                )),
+                body: Block { stmts: slot_stmts, expr: None },
+            }),
+            Statement::Expr(Expr::BinaryOp(
+                Box::new(Expr::Ident("frame_start".to_string())),
+                "+=".to_string(),
+                Box::new(Expr::Ident("hyperperiod".to_string())),
+            )),
+        ],
         expr: None,
     }))));
 
+    let builder_chain = vec![
+        BuilderMethod::Named(format!("\"{}_executive\".to_string()", impl_.name.type_identifier.to_lowercase())),
+        BuilderMethod::Spawn {
+            closure: Box::new(Expr::Closure(
+                Vec::new(),
+                Box::new(Expr::Block(Block { stmts: executive_stmts, expr: None })),
+            )),
+            move_kw: true,
+        },
+    ];
+    stmts.push(Statement::Expr(Expr::MethodCall(
+        Box::new(Expr::BuilderChain(builder_chain)),
+        "unwrap".to_string(),
+        Vec::new(),
+    )));
+
     stmts
 }
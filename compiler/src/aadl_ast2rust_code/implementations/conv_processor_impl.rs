@@ -1,32 +1,42 @@
 
 use crate::aadl_ast2rust_code::intermediate_ast::*;
 
+use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::property_lookup::PropertyLookup;
 use crate::ast::aadl_ast_cj::*;
-use std::collections::HashMap;
+
+// 轮转调度时间片属性的候选名称（Cheddar 的 Scheduler_Quantum 及常见别名）
+const QUANTUM_PROPERTY_NAMES: [&str; 3] = ["scheduler_quantum", "time_slice", "quantum"];
 
 // 转换CPU实现
 pub fn convert_processor_implementation(
-    cpu_scheduling_protocols: &mut HashMap<String, String>,
+    temp_converter: &mut AadlConverter,
     impl_: &ComponentImplementation,
 ) -> Vec<Item> {
-    // 从CPU实现中提取Scheduling_Protocol属性并保存
+    // 从CPU实现中提取Scheduling_Protocol属性并保存；实现上未声明时沿用处理器类型上的声明
     let cpu_name = impl_.name.type_identifier.clone();
+    let (quantum, scheduling_protocol) = processor_scheduling(temp_converter, impl_);
+
+    if let Some(quantum) = quantum {
+        temp_converter.cpu_sched_quantum_ms.insert(cpu_name.clone(), quantum);
+    }
 
-    if let PropertyClause::Properties(props) = &impl_.properties {
-        for prop in props {
-            if let Property::BasicProperty(bp) = prop {
-                if bp.identifier.name.to_lowercase() == "scheduling_protocol" {
-                    if let PropertyValue::Single(PropertyExpression::String(StringTerm::Literal(
-                        scheduling_protocol,
-                    ))) = &bp.value
-                    {
-                        cpu_scheduling_protocols
-                            .insert(cpu_name.clone(), scheduling_protocol.clone());
-                        return Vec::new(); // CPU实现不生成代码，只保存信息
-                    }}}}}
+    if let Some(scheduling_protocol) = scheduling_protocol {
+        temp_converter
+            .cpu_scheduling_protocols
+            .insert(cpu_name.clone(), scheduling_protocol);
+        return Vec::new(); // CPU实现不生成代码，只保存信息
+    }
 
     // 如果没有找到Scheduling_Protocol属性，使用默认值
-    cpu_scheduling_protocols.insert(cpu_name.clone(), "FIFO".to_string());
+    temp_converter.cpu_scheduling_protocols.insert(cpu_name.clone(), "FIFO".to_string());
     println!("CPU实现 {} 未指定调度协议，使用默认值: FIFO", cpu_name);
     Vec::new() // CPU实现不生成代码，只保存信息
 }
+
+// 处理器的时间片与调度协议，均按 实现 -> 类型 的优先级查找
+pub fn processor_scheduling(temp_converter: &AadlConverter, impl_: &ComponentImplementation) -> (Option<u64>, Option<String>) {
+    let lookup = PropertyLookup::for_impl(temp_converter, impl_);
+    let quantum = QUANTUM_PROPERTY_NAMES.iter().find_map(|name| lookup.time_ms(name));
+    (quantum, lookup.enumeration("scheduling_protocol"))
+}
@@ -1,9 +1,10 @@
 
-use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::converter::{AadlConverter, CodegenBackend};
 use crate::aadl_ast2rust_code::intermediate_ast::*;
 
 use crate::ast::aadl_ast_cj::*;
 use crate::aadl_ast2rust_code::tool::*;
+use crate::instance_model::{array_dimensions, reference_targets};
 
 pub fn convert_system_implementation(
     temp_converter: &mut AadlConverter,
@@ -20,10 +21,13 @@ pub fn convert_system_implementation(
         properties: Vec::new(), // TODO
         generics: Vec::new(),
         derives: vec!["Debug".to_string()],
-        docs: vec![
+        docs: [
             format!("// System implementation: {}", impl_.name.type_identifier),
             "// Auto-generated from AADL".to_string(),
-        ],
+        ]
+        .into_iter()
+        .chain(source_location_doc(&impl_.location))
+        .collect(),
         vis: Visibility::Public,
     };
     items.push(Item::Struct(struct_def));
@@ -56,16 +60,22 @@ fn get_system_fields(impl_: &ComponentImplementation) -> Vec<Field> {
                     _ => unreachable!("Filtered above"),
                 };
 
-                let field_ty = Type::Named(format!("{}{}", to_upper_camel_case(&type_name), type_suffix));
+                // 进程/设备数组生成定长数组字段
+                let dimensions = array_dimensions(sub).unwrap_or_default();
+                let declared = format!("{}{}", sub.identifier, dimensions.iter().map(|d| format!("[{}]", d)).collect::<String>());
+                let field_ty = array_type(
+                    Type::Named(format!("{}{}", to_upper_camel_case(&type_name), type_suffix)),
+                    &dimensions,
+                );
                 let doc = match sub.category {
                     ComponentCategory::Process => {
                         format!(
                             "// 子组件进程({} : process {})",
-                            sub.identifier, type_name
+                            declared, type_name
                         )
                     }
                     ComponentCategory::Device => {
-                        format!("// 子组件设备({} : device {})", sub.identifier, type_name)
+                        format!("// 子组件设备({} : device {})", declared, type_name)
                     }
                     _ => unreachable!("Filtered above"),
                 };
@@ -79,6 +89,7 @@ fn get_system_fields(impl_: &ComponentImplementation) -> Vec<Field> {
                     //     args: vec![AttributeArg::Ident("dead_code".to_string())],
                     // }],
                     attrs: Vec::new(),
+                    vis: Visibility::Public,
                 });
             }
         }
@@ -113,7 +124,11 @@ fn create_system_impl_block(
             ty: Type::Named("Self".to_string()),
         }],
         return_type: Type::Unit,
-        body: create_system_run_body(impl_),
+        body: create_system_run_body(
+            temp_converter.backend,
+            impl_,
+            temp_converter.deployments.contains_key(&impl_.name.to_string().to_lowercase()),
+        ),
         asyncness: false,
         vis: Visibility::None,
         docs: vec!["// Runs the system, starts all processes".to_string()],
@@ -132,19 +147,17 @@ fn create_system_impl_block(
 }
 
 // 提取系统实现中的处理器绑定信息
-fn extract_processor_bindings(impl_: &ComponentImplementation) -> Vec<(String, String)> {
+pub fn extract_processor_bindings(impl_: &ComponentImplementation) -> Vec<(String, String)> {
     let mut bindings = Vec::new();
 
     if let PropertyClause::Properties(properties) = &impl_.properties {
         for property in properties {
-            if let Property::BasicProperty(basic_prop) = property {
+            if let Property::ContainedProperty(basic_prop) = property {
                 if basic_prop.identifier.name.to_lowercase() == "actual_processor_binding" {
-                    if let PropertyValue::Single(PropertyExpression::Reference(ref_term)) =
-                        &basic_prop.value
-                    {
-                        if let Some(applies_to) = &ref_term.applies_to {
-                            // 格式: (进程名, CPU标识符)
-                            bindings.push((applies_to.clone(), ref_term.identifier.clone()));
+                    if let Some(cpu) = reference_targets(&basic_prop.value).first() {
+                        // 格式: (进程名, CPU标识符)
+                        for applies_to in &basic_prop.applies_to {
+                            bindings.push((applies_to.clone(), cpu.to_string()));
                         }
                     }
                 }
@@ -165,14 +178,7 @@ fn create_system_new_body(
     let processor_bindings = extract_processor_bindings(impl_);
 
     // 为每个唯一的CPU名称分配一个ID（如果还没有分配的话）
-    for (_, cpu_name) in &processor_bindings {
-        if !temp_converter.cpu_name_to_id_mapping.contains_key(cpu_name) {
-            let next_id:isize = temp_converter.cpu_name_to_id_mapping.len().try_into().expect("length does not fit into isize");
-            temp_converter
-                .cpu_name_to_id_mapping
-                .insert(cpu_name.clone(), next_id);
-        }
-    }
+    temp_converter.assign_cpu_ids(&processor_bindings);
 
     // 如果没有处理器绑定，默认使用CPU 0
     // if temp_converter.cpu_name_to_id_mapping.is_empty() {
@@ -192,9 +198,11 @@ fn create_system_new_body(
                 _ => "UnsupportedComponent".to_string(),
             };
 
+            let dimensions = array_dimensions(sub).unwrap_or_default();
+
             match sub.category {
                 ComponentCategory::Process => {
-                    // 查找该进程的CPU绑定
+                    // 查找该进程的CPU绑定（绑定到进程数组时各元素共用同一CPU）
                     let cpu_id = processor_bindings
                         .iter()
                         .find(|(process_name, _)| process_name == &sub.identifier)
@@ -203,74 +211,36 @@ fn create_system_new_body(
                         })
                         .unwrap_or(-1); // 默认使用CPU -1
 
-                    let creation_stmt = format!(
-                        "let mut {}: {}Process = {}Process::new({})",
-                        var_name,
-                        to_upper_camel_case(&type_name),
-                        to_upper_camel_case(&type_name),
-                        cpu_id
-                    );
-                    stmts.push(Statement::Expr(Expr::Ident(creation_stmt)));
+                    let ty = format!("{}Process", to_upper_camel_case(&type_name));
+                    stmts.push(create_subcomponent_stmt(
+                        &var_name,
+                        &ty,
+                        format!("{}::new({})", ty, cpu_id),
+                        &dimensions,
+                    ));
                 }
                 ComponentCategory::Device => {
-                    let creation_stmt = format!(
-                        "let mut {}: {}Device = {}Device::new()",
-                        var_name,
-                        to_upper_camel_case(&type_name),
-                        to_upper_camel_case(&type_name)
-                    );
-                    stmts.push(Statement::Expr(Expr::Ident(creation_stmt)));
+                    let ty = format!("{}Device", to_upper_camel_case(&type_name));
+                    stmts.push(create_subcomponent_stmt(
+                        &var_name,
+                        &ty,
+                        format!("{}::new()", ty),
+                        &dimensions,
+                    ));
                 }
                 _ => {}
             }
         }
     }
 
-    // 2. 构建连接（如果有的话）
-    // 函数内存储已处理过的广播连接，避免二次处理。
-    let mut processed_broadcast_connections = Vec::new();
-
-    if let ConnectionClause::Items(connections) = &impl_.connections {
-        for conn in connections {
-            match conn {
-                Connection::Port(port_conn) => {
-                    //查看连接是否是已处理过的广播，如果是，则不需要再处理，跳过。
-                    if let PortEndpoint::SubcomponentPort { subcomponent, port } = &port_conn.source
-                    {
-                        if temp_converter
-                            .process_broadcast_send
-                            .contains(&(subcomponent.clone(), port.clone()))
-                        {
-                            if processed_broadcast_connections
-                                .contains(&(subcomponent.clone(), port.clone()))
-                            {
-                                continue;
-                            } else {
-                                processed_broadcast_connections
-                                    .push((subcomponent.clone(), port.clone()));
-                            }
-                        }
-                    }
-                    // 处理端口连接，使用与进程相同的逻辑
-                    stmts.extend(
-                        temp_converter.create_channel_connection(
-                            port_conn,
-                            impl_.name.type_identifier.clone(),
-                        ),
-                    );
-                }
-                _ => {
-                    // 对于其他类型的连接，生成TODO注释
-                    stmts.push(Statement::Expr(Expr::Ident(format!(
-                        "// TODO: Unsupported connection type in system: {:?}",
-                        conn
-                    ))));
-                }
-            }
-        }
-    }
+    // 2. 构建连接：根系统按语义连接直接连通各进程中的线程端口与设备端口，
+    //    进程边界上不再设置转发端口；嵌套系统的连接由所在根系统统一建立
+    stmts.extend(temp_converter.create_system_channels(impl_));
+
+    // 3. 按实例覆盖线程属性：子组件声明或 applies to 给出的取值写入对应线程的字段
+    stmts.extend(temp_converter.create_system_property_overrides(impl_));
 
-    // 3. 构建返回语句
+    // 4. 构建返回语句
     let mut field_names = Vec::new();
     if let SubcomponentClause::Items(subcomponents) = &impl_.subcomponents {
         for sub in subcomponents {
@@ -292,27 +262,86 @@ fn create_system_new_body(
     Block { stmts, expr: None }
 }
 
+// let mut p: PProcess = PProcess::new(0)；子组件数组逐元素构造
+fn create_subcomponent_stmt(var_name: &str, ty: &str, init: String, dimensions: &[u32]) -> Statement {
+    if dimensions.is_empty() {
+        return Statement::Expr(Expr::Ident(format!("let mut {}: {} = {}", var_name, ty, init)));
+    }
+    Statement::Let(LetStmt {
+        ifmut: true,
+        name: var_name.to_string(),
+        ty: Some(array_type(Type::Named(ty.to_string()), dimensions)),
+        init: Some(array_init(Expr::Ident(init), dimensions)),
+    })
+}
+
 // 创建系统实例中run()方法
-fn create_system_run_body(impl_: &ComponentImplementation) -> Block {
+// distributed 为真时系统部署到多个节点：每个节点只启动绑定到本节点处理器的进程，设备由首个节点运行
+fn create_system_run_body(backend: CodegenBackend, impl_: &ComponentImplementation, distributed: bool) -> Block {
     let mut stmts = Vec::new();
 
+    // 0. 建立系统公共时间基准（epoch），所有周期线程都以此为起点计算释放时刻
+    stmts.push(Statement::Expr(Expr::Call(
+        Box::new(Expr::Path(
+            vec!["set_system_epoch".to_string()],
+            PathType::Namespace,
+        )),
+        vec![Expr::Call(
+            Box::new(Expr::Path(
+                vec!["Instant".to_string(), "now".to_string()],
+                PathType::Namespace,
+            )),
+            Vec::new(),
+        )],
+    )));
+
+    // 分布式部署时先启动本节点的监听与转发，再启动组件
+    if distributed {
+        stmts.push(Statement::Expr(Expr::Ident("crate::net::start()".to_string())));
+    }
+
     if let SubcomponentClause::Items(subcomponents) = &impl_.subcomponents {
         for sub in subcomponents {
             let var_name = sub.identifier.to_lowercase();
+            // 子组件数组在循环中逐个元素启动，循环变量与字段同名
+            let dimensions = array_dimensions(sub).unwrap_or_default();
+            let (receiver, thread_name) = if dimensions.is_empty() {
+                (vec!["self".to_string(), var_name.clone()], format!("\"{}\".to_string()", var_name))
+            } else {
+                (vec![var_name.clone()], format!("format!(\"{}[{{}}]\", index)", var_name))
+            };
+            let mut sub_stmts = Vec::new();
             match sub.category {
                 ComponentCategory::Process => {
-                    let start_stmt = format!("self.{}.run()", var_name);
-                    stmts.push(Statement::Expr(Expr::Ident(start_stmt)));
+                    let start_stmt = format!("{}.run()", receiver.join("."));
+                    sub_stmts.push(Statement::Expr(Expr::Ident(start_stmt)));
+                }
+                ComponentCategory::Device if backend == CodegenBackend::Async => {
+                    // 异步后端：设备的 run() 是阻塞循环，放到 tokio 的阻塞线程池中执行
+                    sub_stmts.push(Statement::Expr(Expr::Call(
+                        Box::new(Expr::Path(
+                            vec!["tokio".to_string(), "task".to_string(), "spawn_blocking".to_string()],
+                            PathType::Namespace,
+                        )),
+                        vec![Expr::UnaryOp(
+                            "move ".to_string(),
+                            Box::new(Expr::Closure(
+                                Vec::new(),
+                                Box::new(Expr::MethodCall(
+                                    Box::new(Expr::Path(receiver.clone(), PathType::Member)),
+                                    "run".to_string(),
+                                    Vec::new(),
+                                )),
+                            )),
+                        )],
+                    )));
                 }
                 ComponentCategory::Device => {
                     // 构建线程闭包（使用move语义捕获self）
                     let closure = Expr::Closure(
                         Vec::new(), // 无参数
                         Box::new(Expr::MethodCall(
-                            Box::new(Expr::Path(
-                                vec!["self".to_string(), var_name.clone()],
-                                PathType::Member,
-                            )),
+                            Box::new(Expr::Path(receiver.clone(), PathType::Member)),
                             "run".to_string(),
                             Vec::new(),
                         )),
@@ -320,14 +349,14 @@ fn create_system_run_body(impl_: &ComponentImplementation) -> Block {
 
                     // 构建线程构建器表达式链
                     let builder_chain = vec![
-                        BuilderMethod::Named(format!("\"{}\".to_string()", var_name)),
+                        BuilderMethod::Named(thread_name),
                         BuilderMethod::Spawn {
                             closure: Box::new(closure),
                             move_kw: true, // 使用move关键字捕获self
                         },
                     ];
 
-                    stmts.push(Statement::Expr(Expr::MethodCall(
+                    sub_stmts.push(Statement::Expr(Expr::MethodCall(
                         Box::new(Expr::BuilderChain(builder_chain)),
                         "unwrap".to_string(),
                         Vec::new(),
@@ -335,6 +364,36 @@ fn create_system_run_body(impl_: &ComponentImplementation) -> Block {
                 }
                 _ => {}
             }
+
+            if distributed && !sub_stmts.is_empty() {
+                let cpu_id = match sub.category {
+                    ComponentCategory::Process => format!("{}.cpu_id", receiver.join(".")),
+                    _ => "-1".to_string(),
+                };
+                sub_stmts = vec![Statement::Expr(Expr::If {
+                    condition: Box::new(Expr::Ident(format!("crate::net::is_local({})", cpu_id))),
+                    then_branch: Block { stmts: sub_stmts, expr: None },
+                    else_branch: None,
+                })];
+            }
+
+            if dimensions.is_empty() || sub_stmts.is_empty() {
+                stmts.extend(sub_stmts);
+            } else {
+                // 只有设备线程的名称用到元素下标
+                let elements = array_elements_iter(&format!("self.{}", var_name), &dimensions);
+                let (pattern, iter) = if sub.category == ComponentCategory::Device && backend != CodegenBackend::Async {
+                    let enumerated = Expr::MethodCall(Box::new(elements), "enumerate".to_string(), Vec::new());
+                    (format!("(index, {})", var_name), enumerated)
+                } else {
+                    (var_name.clone(), elements)
+                };
+                stmts.push(Statement::Expr(Expr::For {
+                    pattern,
+                    iter: Box::new(iter),
+                    body: Block { stmts: sub_stmts, expr: None },
+                }));
+            }
         }
     }
 
@@ -1,12 +1,14 @@
 #![allow(clippy::vec_init_then_push)]
 #![allow(clippy::single_match)]
 use crate::aadl_ast2rust_code::intermediate_ast::*;
-use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::converter::{AadlConverter, CodegenBackend};
 use crate::aadl_ast2rust_code::converter_annex::AnnexConverter;
 
 use crate::ast::aadl_ast_cj::*;
 use std::collections::HashMap;
 use crate::aadl_ast2rust_code::tool::*;
+use crate::aadl_ast2rust_code::property_lookup::PropertyLookup;
+use crate::diagnostics::{self, codes};
 
 
 pub fn convert_thread_implemenation(temp_converter: &mut AadlConverter, impl_: &ComponentImplementation) -> Vec<Item> {
@@ -23,15 +25,16 @@ pub fn convert_thread_implemenation(temp_converter: &mut AadlConverter, impl_: &
             if let Property::BasicProperty(bp) = prop {
                 if let Some(val) = temp_converter.parse_property_value(&bp.value) {
                     let field_name = bp.identifier.name.to_lowercase();
-                    let ty_name = temp_converter.type_for_property(&val);
+                    let ty_name = temp_converter.property_field_type(&bp.identifier, &val);
                     
                     // 存储属性值到 thread_field_values
                     field_values.insert(field_name.clone(), val.clone());
                     fields.push(Field {
                         name: field_name,
                         ty: Type::Named(ty_name),
-                        docs: vec![format!("// AADL属性(impl): {}", bp.identifier.name)],
+                        docs: vec![temp_converter.property_field_doc("AADL属性(impl)", &bp.identifier.name, &val)],
                         attrs: Vec::new(),
+                        vis: Visibility::Public,
                     });
                 }
             }
@@ -100,9 +103,21 @@ pub fn convert_thread_implemenation(temp_converter: &mut AadlConverter, impl_: &
     };
     items.push(Item::Impl(impl_block));
 
-    // 新增impl块，生成不包含在trait中的方法，带共享变量参数的new()方法。
+    // 新增impl块，生成不包含在trait中的方法：带共享变量参数的new()方法、循环执行器的作业入口、异步后端的任务入口。
+    let mut items_no_trait = Vec::new();
     if flag_need_shared_variable_param {
-        let items_no_trait = vec![ImplItem::Method(create_thread_new_method(temp_converter, impl_, &mut flag_need_shared_variable_param))];
+        items_no_trait.push(ImplItem::Method(create_thread_new_method(temp_converter, impl_, &mut flag_need_shared_variable_param)));
+    }
+    match temp_converter.backend {
+        CodegenBackend::Cyclic => {
+            items_no_trait.push(ImplItem::Method(create_thread_job_method(temp_converter, impl_)));
+        }
+        CodegenBackend::Async => {
+            items_no_trait.push(ImplItem::Method(create_thread_run_async_method(temp_converter, impl_)));
+        }
+        CodegenBackend::Threads | CodegenBackend::Embedded => {}
+    }
+    if !items_no_trait.is_empty() {
         let impl_block_no_trait = ImplBlock {
             target: Type::Named(format!("{}Thread", to_upper_camel_case(&impl_.name.type_identifier))),
             generics: Vec::new(),
@@ -122,9 +137,17 @@ fn create_thread_new_method(temp_converter: &mut AadlConverter, impl_: &Componen
     let struct_name = format!("{}Thread", to_upper_camel_case(&impl_.name.type_identifier));
     let key = struct_name.clone();
     
-    // 获取存储的属性值
-    let field_values = temp_converter.thread_field_values.get(&key).cloned().unwrap_or_default();
+    // 获取存储的属性值；属性字段（不在特征字段类型表中）按 实现 -> 类型 的优先级重新查找
+    let mut field_values = temp_converter.thread_field_values.get(&key).cloned().unwrap_or_default();
     let field_types = temp_converter.thread_field_types.get(&key).cloned().unwrap_or_default();
+    let lookup = PropertyLookup::for_impl(temp_converter, impl_);
+    for (field_name, prop_value) in field_values.iter_mut() {
+        if !field_types.contains_key(field_name) {
+            if let Some(value) = lookup.typed(field_name) {
+                *prop_value = value;
+            }
+        }
+    }
     
     // 生成结构体字面量初始化字符串
     let mut field_initializations = Vec::new();
@@ -141,20 +164,18 @@ fn create_thread_new_method(temp_converter: &mut AadlConverter, impl_: &Componen
         // 对以"Shared"结尾的字段类型添加参数（已删去），并修改初始化值
         if let Some(field_type) = field_types.get(field_name) {
             match field_type {
-                Type::Named(type_name) => {
-                    if type_name.ends_with("Shared") {
-                        if !*flag_need_shared_variable_param {
-                            *flag_need_shared_variable_param = true;
-
-                             // 生成 Arc::new(Mutex::new(TypeName::new())) 格式的初始化值
-                            let base_type_name = type_name.trim_end_matches("Shared");
-                            init_value = format!("Arc::new(Mutex::new({}::new()))", base_type_name);
-                        } else{
-                            params.push(Param {
-                                name: field_name.clone(),
-                                ty: field_type.clone(),
-                            });
-                        }
+                Type::Named(type_name) if type_name.ends_with("Shared") => {
+                    if !*flag_need_shared_variable_param {
+                        *flag_need_shared_variable_param = true;
+
+                         // 生成 Arc::new(Mutex::new(TypeName::new())) 格式的初始化值
+                        let base_type_name = type_name.trim_end_matches("Shared");
+                        init_value = format!("Arc::new(Mutex::new({}::new()))", base_type_name);
+                    } else{
+                        params.push(Param {
+                            name: field_name.clone(),
+                            ty: field_type.clone(),
+                        });
                     }
                 }
                 _ => {
@@ -191,8 +212,85 @@ fn create_thread_new_method(temp_converter: &mut AadlConverter, impl_: &Componen
 }
 
 
+/// 创建循环执行器后端使用的 into_job() 方法
+/// 返回一次分派的计算逻辑（子程序调用 + BA 单步），BA 状态变量被闭包捕获，在多次分派之间保持
+/// 非周期线程在此模式下按其 Period 轮询输入端口
+fn create_thread_job_method(temp_converter: &AadlConverter, impl_: &ComponentImplementation) -> FunctionDef {
+    let mut stmts = Vec::new();
+
+    let mut annex_converter = AnnexConverter::default();
+    annex_converter.ranged_ports = temp_converter.ranged_in_ports(&impl_.name.type_identifier);
+    let mut ba_stmts = Vec::new();
+    if let Some(annex) = annex_converter.find_behavior_annex(impl_) {
+        stmts.extend(annex_converter.generate_ba_variables_states(impl_, annex));
+        if let Some(transitions) = annex.transitions.clone() {
+            ba_stmts.extend(annex_converter.generate_state_machine_loop(&transitions));
+        }
+    }
+
+    let mut job_stmts = Vec::new();
+    let subprogram_handling_stmts = create_subprogram_call_logic(temp_converter, impl_);
+    if !subprogram_handling_stmts.is_empty() {
+        job_stmts.push(Statement::Expr(Expr::Block(Block {
+            stmts: subprogram_handling_stmts,
+            expr: None,
+        })));
+    }
+    if !ba_stmts.is_empty() {
+        job_stmts.push(Statement::Expr(Expr::Block(Block {
+            stmts: ba_stmts,
+            expr: None,
+        })));
+    }
+
+    // return Box::new(move || { ... });
+    stmts.push(Statement::Expr(Expr::UnaryOp(
+        "return ".to_string(),
+        Box::new(Expr::Call(
+            Box::new(Expr::Path(
+                vec!["Box".to_string(), "new".to_string()],
+                PathType::Namespace,
+            )),
+            vec![Expr::UnaryOp(
+                "move ".to_string(),
+                Box::new(Expr::Closure(
+                    Vec::new(),
+                    Box::new(Expr::Block(Block {
+                        stmts: job_stmts,
+                        expr: None,
+                    })),
+                )),
+            )],
+        )),
+    )));
+
+    FunctionDef {
+        name: "into_job".to_string(),
+        params: vec![Param {
+            name: "".to_string(),
+            ty: Type::Reference(Box::new(Type::Named("self".to_string())), false, true),
+        }],
+        return_type: Type::Named("Box<dyn FnMut()>".to_string()),
+        body: Block { stmts, expr: None },
+        asyncness: false,
+        vis: Visibility::Public,
+        docs: vec!["// 循环执行器作业入口：返回单次分派的计算逻辑".to_string()],
+        attrs: Vec::new(),
+    }
+}
+
+/// 线程参数的取值表达式：有实例覆盖该属性时读取字段 self.<name>（由根系统按实例赋值），
+/// 否则直接内联分类器上的取值
+fn thread_parameter_expr(temp_converter: &AadlConverter, impl_: &ComponentImplementation, name: &str, value: u64) -> Expr {
+    if temp_converter.is_thread_property_overridden(&impl_.name.type_identifier, name) {
+        Expr::Ident(format!("self.{}", name))
+    } else {
+        Expr::Literal(Literal::Int(value as i64))
+    }
+}
+
 /// 将属性值转换为初始化表达式字符串
-fn property_value_to_initializer(val: &StruPropertyValue) -> String {
+pub fn property_value_to_initializer(val: &StruPropertyValue) -> String {
     match val {
         StruPropertyValue::Boolean(b) => b.to_string(),
         StruPropertyValue::Integer(i) => i.to_string(),
@@ -203,6 +301,20 @@ fn property_value_to_initializer(val: &StruPropertyValue) -> String {
         StruPropertyValue::String(s) => format!("\"{}\".to_string()", s),
         StruPropertyValue::Duration(v, _unit) => v.to_string(),
         StruPropertyValue::Range(min, max, _unit) => format!("({}, {})", min, max),
+        StruPropertyValue::RealRange(min, max, _unit) => format!(
+            "({}, {})",
+            property_value_to_initializer(&StruPropertyValue::Float(*min)),
+            property_value_to_initializer(&StruPropertyValue::Float(*max))
+        ),
+        StruPropertyValue::List(elements) => {
+            let elements: Vec<String> = elements.iter().map(property_value_to_initializer).collect();
+            format!("vec![{}]", elements.join(", "))
+        }
+        StruPropertyValue::Record(fields) => {
+            let values: Vec<String> = fields.iter().map(|(_, value)| property_value_to_initializer(value)).collect();
+            if values.len() == 1 { format!("({},)", values[0]) } else { format!("({})", values.join(", ")) }
+        }
+        StruPropertyValue::Computed(function) => computed_function_name(function),
         StruPropertyValue::None => "None".to_string(),
         StruPropertyValue::Custom(s) => s.to_string(),
     }
@@ -210,215 +322,13 @@ fn property_value_to_initializer(val: &StruPropertyValue) -> String {
 
 /// 创建线程的 run() 方法体
 /// 该方法生成线程的执行逻辑，包括：
-/// 1. 线程优先级和CPU亲和性设置
+/// 1. CPU亲和性和线程调度策略设置（先亲和性，后调度策略）
 /// 2. 根据调度协议生成不同的执行逻辑
 /// 3. 子程序调用处理（参数端口、共享变量、普通调用）
 fn create_thread_run_body(temp_converter: &mut AadlConverter, impl_: &ComponentImplementation) -> Block {
     let mut stmts = Vec::new();
     
-    //======================= 线程优先级设置 ========================
-    // 检查是否有优先级属性
-    let priority = extract_property_value(temp_converter, impl_, "priority");
-    let period = extract_property_value(temp_converter, impl_, "period");
-    
-    // 如果线程有 priority 属性，则设置线程优先级
-    if let Some(priority) = priority {
-        // 添加优先级设置代码 - 使用unsafe块和完整的错误处理
-        stmts.push(Statement::Expr(Expr::Unsafe(Box::new(Block {
-            stmts: vec![
-                // let mut param = sched_param { sched_priority: self.priority as i32 };
-                Statement::Let(LetStmt {
-                    ifmut: false,
-                    name: "param".to_string(),
-                    ty: Some(Type::Named("sched_param".to_string())),
-                    init: Some(Expr::Ident(format!("sched_param {{ sched_priority: {} }}", priority as i32))),
-                }),
-                // let ret = pthread_setschedparam(pthread_self(), *, &param);
-                Statement::Let(LetStmt {
-                    ifmut: false,
-                    name: "ret".to_string(),
-                    ty: None,
-                    init: Some(Expr::Call(
-                        Box::new(Expr::Path(
-                            vec!["pthread_setschedparam".to_string()],
-                            PathType::Namespace,
-                        )),
-                        vec![
-                            Expr::Call(
-                                Box::new(Expr::Path(
-                                    vec!["pthread_self".to_string()],
-                                    PathType::Namespace,
-                                )),
-                                Vec::new(),
-                            ),
-                            Expr::MethodCall(
-                                Box::new(Expr::MethodCall(
-                                    Box::new(Expr::Path(
-                                        vec!["*CPU_ID_TO_SCHED_POLICY".to_string()],
-                                        PathType::Namespace,
-                                    )),
-                                    "get".to_string(),
-                                    vec![Expr::Reference(
-                                        Box::new(Expr::Path(
-                                            vec!["self".to_string(), "cpu_id".to_string()],
-                                            PathType::Member,
-                                        )),
-                                        true,
-                                        false,
-                                    )],
-                                )),
-                                "unwrap_or".to_string(),
-                                vec![Expr::Reference(
-                                    Box::new(Expr::Path(
-                                        vec!["SCHED_FIFO".to_string()],
-                                        PathType::Namespace,
-                                    )),
-                                    true,
-                                    false,
-                                )],
-                            ),
-                            Expr::Reference(
-                                Box::new(Expr::Ident("param".to_string())),
-                                true,
-                                false,
-                            ),
-                        ],
-                    )),
-                }),
-                // if ret != 0 { eprintln!("..."); }
-                Statement::Expr(Expr::If {
-                    condition: Box::new(Expr::BinaryOp(
-                        Box::new(Expr::Ident("ret".to_string())),
-                        "!=".to_string(),
-                        Box::new(Expr::Literal(Literal::Int(0))),
-                    )),
-                    then_branch: Block {
-                        stmts: vec![
-                            Statement::Expr(Expr::Call(
-                                Box::new(Expr::Path(
-                                    vec!["eprintln!".to_string()],
-                                    PathType::Namespace,
-                                )),
-                                vec![
-                                    Expr::Literal(Literal::Str(format!("{}Thread: Failed to set thread priority: {{}}", to_upper_camel_case(&impl_.name.type_identifier)))),
-                                    Expr::Ident("ret".to_string()),
-                                ],
-                            )),
-                        ],
-                        expr: None,
-                    },
-                    else_branch: None,
-                }),
-            ],
-            expr: None,
-        }))));
-    } else if period.is_some() {
-        // 如果没有优先级但有周期，则根据周期计算优先级(RMS)
-        // TODO:不知道这里为什么没有使用period
-        stmts.push(Statement::Expr(Expr::Unsafe(Box::new(Block {
-            stmts: vec![
-                // let prio = period_to_priority(self.period as f64); 不知道这句为什么要注释
-                Statement::Let(LetStmt {
-                    ifmut: false,
-                    name: "prio".to_string(),
-                    ty: None,
-                    init: Some(Expr::Call(
-                        Box::new(Expr::Path(
-                            vec!["period_to_priority".to_string()],
-                            PathType::Namespace,
-                        )),
-                        vec![Expr::Ident("self.period as f64".to_string())],
-                    )),
-                }),
-                // let mut param: sched_param = sched_param { sched_priority: prio };
-                Statement::Let(LetStmt {
-                    ifmut: true,
-                    name: "param".to_string(),
-                    ty: Some(Type::Named("sched_param".to_string())),
-                    init: Some(Expr::Ident("sched_param { sched_priority: prio }".to_string())),
-                }),
-                // let ret = pthread_setschedparam(pthread_self(), *, &mut param);
-                Statement::Let(LetStmt {
-                    ifmut: false,
-                    name: "ret".to_string(),
-                    ty: None,
-                    init: Some(Expr::Call(
-                        Box::new(Expr::Path(
-                            vec!["pthread_setschedparam".to_string()],
-                            PathType::Namespace,
-                        )),
-                        vec![
-                            Expr::Call(
-                                Box::new(Expr::Path(
-                                    vec!["pthread_self".to_string()],
-                                    PathType::Namespace,
-                                )),
-                                Vec::new(),
-                            ),
-                            Expr::MethodCall(
-                                Box::new(Expr::MethodCall(
-                                    Box::new(Expr::Path(
-                                        vec!["*CPU_ID_TO_SCHED_POLICY".to_string()],
-                                        PathType::Namespace,
-                                    )),
-                                    "get".to_string(),
-                                    vec![Expr::Reference(
-                                        Box::new(Expr::Path(
-                                            vec!["self".to_string(), "cpu_id".to_string()],
-                                            PathType::Member,
-                                        )),
-                                        true,
-                                        false,
-                                    )],
-                                )),
-                                "unwrap_or".to_string(),
-                                vec![Expr::Reference(
-                                    Box::new(Expr::Path(
-                                        vec!["SCHED_FIFO".to_string()],
-                                        PathType::Namespace,
-                                    )),
-                                    true,
-                                    false,
-                                )],
-                            ),
-                            Expr::Reference(
-                                Box::new(Expr::Ident("param".to_string())),
-                                true,
-                                true,
-                            ),
-                        ],
-                    )),
-                }),
-                // if ret != 0 { eprintln!("..."); }
-                Statement::Expr(Expr::If {
-                    condition: Box::new(Expr::BinaryOp(
-                        Box::new(Expr::Ident("ret".to_string())),
-                        "!=".to_string(),
-                        Box::new(Expr::Literal(Literal::Int(0))),
-                    )),
-                    then_branch: Block {
-                        stmts: vec![
-                            Statement::Expr(Expr::Call(
-                                Box::new(Expr::Path(
-                                    vec!["eprintln!".to_string()],
-                                    PathType::Namespace,
-                                )),
-                                vec![
-                                    Expr::Literal(Literal::Str(format!("{}Thread: Failed to set thread priority from period: {{}}", to_upper_camel_case(&impl_.name.type_identifier)))),
-                                    Expr::Ident("ret".to_string()),
-                                ],
-                            )),
-                        ],
-                        expr: None,
-                    },
-                    else_branch: None,
-                }),
-            ],
-            expr: None,
-        }))));
-    }
-
-    // ==================== 步骤 0.5: CPU亲和性设置 ====================
+    // ==================== 步骤 0: CPU亲和性设置 ====================
     // 如果 cpu_id > -1，则设置线程绑定到指定CPU
     stmts.push(Statement::Expr(Expr::If {
         condition: Box::new(Expr::BinaryOp(
@@ -447,6 +357,108 @@ fn create_thread_run_body(temp_converter: &mut AadlConverter, impl_: &ComponentI
         else_branch: None,
     }));
 
+    //======================= 线程调度策略设置 ========================
+    // 须在设置亲和性之后：Linux 拒绝对 SCHED_DEADLINE 线程调用 sched_setaffinity
+    stmts.extend(create_sched_setup_stmts(temp_converter, impl_));
+
+    // ==================== 步骤 1-2: 根据调度协议生成执行逻辑 ====================
+    stmts.extend(create_dispatch_logic(temp_converter, impl_, false));
+
+    Block { stmts, expr: None }
+}
+
+/// 生成线程调度策略设置代码
+/// 调度策略由所在CPU的 Scheduling_Protocol 决定（CPU_ID_TO_SCHED_POLICY），优先级取 Priority，
+/// 缺省时按周期计算(RMS)；EDF 处理器使用 Compute_Execution_Time/Deadline/Period 作为 SCHED_DEADLINE 参数，
+/// 轮转调度处理器附带时间片（CPU_ID_TO_SCHED_QUANTUM）。两者都没有时不设置
+fn create_sched_setup_stmts(temp_converter: &AadlConverter, impl_: &ComponentImplementation) -> Vec<Statement> {
+    let priority = extract_property_value(temp_converter, impl_, "priority");
+    let period = extract_property_value(temp_converter, impl_, "period");
+
+    let prio_expr = match (priority, period) {
+        // 有实例覆盖优先级时读取字段
+        _ if temp_converter.is_thread_property_overridden(&impl_.name.type_identifier, "priority") => {
+            Expr::Ident("self.priority as i32".to_string())
+        }
+        (Some(priority), _) => Expr::Literal(Literal::Int(priority as i64)),
+        // 如果没有优先级但有周期，则根据周期计算优先级(RMS)
+        (None, Some(_)) => Expr::Call(
+            Box::new(Expr::Path(vec!["period_to_priority".to_string()], PathType::Namespace)),
+            vec![Expr::Ident("self.period as f64".to_string())],
+        ),
+        (None, None) => return Vec::new(),
+    };
+
+    // EDF 参数：Deadline 缺省等于 Period，执行时间取 Compute_Execution_Time 区间上界；
+    // 有实例覆盖 Period/Deadline 时与优先级一样读取字段（字段值已换算为毫秒）
+    let lookup = PropertyLookup::for_impl(temp_converter, impl_);
+    let period_ms = lookup.time_ms("period").unwrap_or(0);
+    let period_expr = timing_field_expr(temp_converter, impl_, "period", period_ms);
+    let deadline_expr = match lookup.time_ms("deadline") {
+        Some(deadline_ms) if lookup.is_declared("deadline") => timing_field_expr(temp_converter, impl_, "deadline", deadline_ms),
+        _ => period_expr.clone(),
+    };
+    let runtime_ms = lookup.time_ms("compute_execution_time").unwrap_or(0);
+
+    vec![
+        // let prio: i32 = ...;
+        Statement::Let(LetStmt {
+            ifmut: false,
+            name: "prio".to_string(),
+            ty: Some(Type::Named("i32".to_string())),
+            init: Some(prio_expr),
+        }),
+        // let policy = *CPU_ID_TO_SCHED_POLICY.get(&self.cpu_id).unwrap_or(&SCHED_FIFO);
+        Statement::Let(LetStmt {
+            ifmut: false,
+            name: "policy".to_string(),
+            ty: None,
+            init: Some(Expr::Ident(
+                "*CPU_ID_TO_SCHED_POLICY.get(&self.cpu_id).unwrap_or(&SCHED_FIFO)".to_string(),
+            )),
+        }),
+        // let quantum = CPU_ID_TO_SCHED_QUANTUM.get(&self.cpu_id).copied();
+        Statement::Let(LetStmt {
+            ifmut: false,
+            name: "quantum".to_string(),
+            ty: None,
+            init: Some(Expr::MethodCall(
+                Box::new(Expr::Ident("CPU_ID_TO_SCHED_QUANTUM.get(&self.cpu_id)".to_string())),
+                "copied".to_string(),
+                Vec::new(),
+            )),
+        }),
+        // apply_thread_sched("XThread", policy, prio, SchedTiming { .. }, quantum);
+        Statement::Expr(Expr::Call(
+            Box::new(Expr::Path(vec!["apply_thread_sched".to_string()], PathType::Namespace)),
+            vec![
+                Expr::Literal(Literal::Str(format!("{}Thread", to_upper_camel_case(&impl_.name.type_identifier)))),
+                Expr::Ident("policy".to_string()),
+                Expr::Ident("prio".to_string()),
+                Expr::Ident(format!(
+                    "SchedTiming {{ runtime_ms: {}, deadline_ms: {}, period_ms: {} }}",
+                    runtime_ms, deadline_expr, period_expr
+                )),
+                Expr::Ident("quantum".to_string()),
+            ],
+        )),
+    ]
+}
+
+/// SchedTiming 中的时间参数：有实例覆盖时读取字段 self.<name>，否则内联分类器上的毫秒数
+fn timing_field_expr(temp_converter: &AadlConverter, impl_: &ComponentImplementation, name: &str, value_ms: u64) -> String {
+    if temp_converter.is_thread_property_overridden(&impl_.name.type_identifier, name) {
+        format!("self.{}", name)
+    } else {
+        value_ms.to_string()
+    }
+}
+
+/// 根据线程的调度协议（Dispatch_Protocol）生成分派执行逻辑，run() 与 run_async() 共用
+/// is_async 为 true 时周期线程改用 tokio::time::interval 推进释放时刻
+fn create_dispatch_logic(temp_converter: &AadlConverter, impl_: &ComponentImplementation, is_async: bool) -> Vec<Statement> {
+    let mut stmts = Vec::new();
+
     // ==================== 步骤 1: 获取调度协议 ====================
     let dispatch_protocol = extract_dispatch_protocol(temp_converter, impl_);
     
@@ -454,7 +466,7 @@ fn create_thread_run_body(temp_converter: &mut AadlConverter, impl_: &ComponentI
     match dispatch_protocol.as_deref() {
         Some("Periodic") => {
             // 周期性调度：生成周期性执行循环
-            stmts.extend(create_periodic_execution_logic(temp_converter, impl_));
+            stmts.extend(create_periodic_execution_logic(temp_converter, impl_, is_async));
         }
         Some("Aperiodic") => {
             // 非周期性调度：生成事件驱动执行逻辑
@@ -470,15 +482,37 @@ fn create_thread_run_body(temp_converter: &mut AadlConverter, impl_: &ComponentI
         }
         _ => {
             // 默认使用周期性调度
-            stmts.extend(create_periodic_execution_logic(temp_converter, impl_));
+            stmts.extend(create_periodic_execution_logic(temp_converter, impl_, is_async));
         }
     }
 
-    Block { stmts, expr: None }
+    stmts
+}
+
+/// 创建异步后端使用的 run_async() 方法
+/// 分派逻辑与 run() 相同，阻塞休眠改写为 tokio::time::sleep(..).await，
+/// 线程作为 tokio 任务运行在运行时的工作线程上，因此不再设置优先级与CPU亲和性
+fn create_thread_run_async_method(temp_converter: &AadlConverter, impl_: &ComponentImplementation) -> FunctionDef {
+    FunctionDef {
+        name: "run_async".to_string(),
+        params: vec![Param {
+            name: "".to_string(),
+            ty: Type::Reference(Box::new(Type::Named("self".to_string())), false, true),
+        }],
+        return_type: Type::Unit,
+        body: Block {
+            stmts: asyncify_statements(create_dispatch_logic(temp_converter, impl_, true)),
+            expr: None,
+        },
+        asyncness: true,
+        vis: Visibility::Public,
+        docs: vec!["// 异步后端任务入口：由 tokio::spawn 调度执行".to_string()],
+        attrs: Vec::new(),
+    }
 }
 
 /// 创建周期性执行逻辑
-fn create_periodic_execution_logic(temp_converter: &AadlConverter, impl_: &ComponentImplementation) -> Vec<Statement> {
+fn create_periodic_execution_logic(temp_converter: &AadlConverter, impl_: &ComponentImplementation, use_interval: bool) -> Vec<Statement> {
     let mut stmts = Vec::new();
     
     // 从AADL属性中提取周期值，默认为1000ms
@@ -497,39 +531,81 @@ fn create_periodic_execution_logic(temp_converter: &AadlConverter, impl_: &Compo
                 vec!["Duration".to_string(), "from_millis".to_string()],
                 PathType::Namespace,
             )),
-            vec![Expr::Literal(Literal::Int(period as i64))],
+            vec![thread_parameter_expr(temp_converter, impl_, "period", period)],
         )),
     }));
 
-    //添加let mut next_release = Instant::now() + period;
+    // 释放时刻相对系统公共基准计算：epoch + First_Dispatch_Time + Dispatch_Offset + k*period
+    let dispatch_offset = extract_property_value(temp_converter, impl_, "dispatch_offset").unwrap_or(0);
+    let first_dispatch_time = extract_property_value(temp_converter, impl_, "first_dispatch_time");
+    let dispatch_jitter = extract_property_value(temp_converter, impl_, "dispatch_jitter").unwrap_or(0);
+
+    //添加let dispatch_offset = Duration::from_millis(offset);
     stmts.push(Statement::Let(LetStmt {
-        ifmut: true,
+        ifmut: false,
+        name: "dispatch_offset".to_string(),
+        ty: Some(Type::Path(vec![
+            "std".to_string(),
+            "time".to_string(),
+            "Duration".to_string(),
+        ])),
+        init: Some(Expr::Call(
+            Box::new(Expr::Path(
+                vec!["Duration".to_string(), "from_millis".to_string()],
+                PathType::Namespace,
+            )),
+            vec![thread_parameter_expr(temp_converter, impl_, "dispatch_offset", dispatch_offset)],
+        )),
+    }));
+
+    //添加let mut next_release = system_epoch() [+ Duration::from_millis(first_dispatch_time)] + dispatch_offset;
+    // First_Dispatch_Time 与 Period 一样可按实例覆盖，此时读取 self.first_dispatch_time
+    let mut release_base = Expr::Call(
+        Box::new(Expr::Path(
+            vec!["system_epoch".to_string()],
+            PathType::Namespace,
+        )),
+        Vec::new(),
+    );
+    if let Some(first_dispatch_time) = first_dispatch_time {
+        release_base = Expr::BinaryOp(
+            Box::new(release_base),
+            "+".to_string(),
+            Box::new(Expr::Call(
+                Box::new(Expr::Path(
+                    vec!["Duration".to_string(), "from_millis".to_string()],
+                    PathType::Namespace,
+                )),
+                vec![thread_parameter_expr(temp_converter, impl_, "first_dispatch_time", first_dispatch_time)],
+            )),
+        );
+    }
+    // 异步任务由 tokio::time::interval 推进释放时刻
+    stmts.push(Statement::Let(LetStmt {
+        ifmut: !use_interval,
         name: "next_release".to_string(),
         ty: None,
         init: Some(Expr::BinaryOp(
-            Box::new(Expr::Ident("Instant::now()".to_string())),
+            Box::new(release_base),
             "+".to_string(),
-            Box::new(Expr::Ident("period".to_string())),
+            Box::new(Expr::Ident("dispatch_offset".to_string())),
         )),
     }));
     
     
     // 处理BA
     let mut annex_converter = AnnexConverter::default();
+    annex_converter.ranged_ports = temp_converter.ranged_in_ports(&impl_.name.type_identifier);
     // 检查是否有Behavior Annex
-    let mut if_has_ba = false;
-    
-    if annex_converter.find_behavior_annex(impl_).is_some(){
-        if_has_ba = true;
-        stmts.extend(annex_converter.generate_ba_variables_states(impl_, annex_converter.find_behavior_annex(impl_).unwrap()));
-    } 
+    let behavior_annex = annex_converter.find_behavior_annex(impl_);
 
     let mut ba_stmts = Vec::new();
-    if if_has_ba {
-        if let Some(transitions) = annex_converter.find_behavior_annex(impl_).unwrap().transitions.clone() {
-            ba_stmts.extend(annex_converter.generate_state_machine_loop(&transitions));
+    if let Some(behavior_annex) = behavior_annex {
+        stmts.extend(annex_converter.generate_ba_variables_states(impl_, behavior_annex));
+        if let Some(transitions) = &behavior_annex.transitions {
+            ba_stmts.extend(annex_converter.generate_state_machine_loop(transitions));
         }
-    };
+    }
 
     // 子程序调用处理代码
     let subprogram_handling_stmts = create_subprogram_call_logic(temp_converter, impl_);
@@ -538,44 +614,100 @@ fn create_periodic_execution_logic(temp_converter: &AadlConverter, impl_: &Compo
     // 构造 loop 内部的语句列表,调度控制+子程序调用+BA执行
     let mut loop_stmts: Vec<Statement> = Vec::new();
 
-    // 1. let now = Instant::now();
-    loop_stmts.push(Statement::Let(LetStmt {
-        ifmut: false,
-        name: "now".to_string(),
-        ty: None,
-        init: Some(Expr::Call(
-            Box::new(Expr::Path(
-                vec!["Instant".to_string(), "now".to_string()],
-                PathType::Namespace,
+    if use_interval {
+        // let mut interval = tokio::time::interval_at(tokio::time::Instant::from_std(next_release), period);
+        stmts.push(Statement::Let(LetStmt {
+            ifmut: true,
+            name: "interval".to_string(),
+            ty: None,
+            init: Some(Expr::Call(
+                Box::new(Expr::Path(
+                    vec!["tokio".to_string(), "time".to_string(), "interval_at".to_string()],
+                    PathType::Namespace,
+                )),
+                vec![
+                    Expr::Call(
+                        Box::new(Expr::Path(
+                            vec!["tokio".to_string(), "time".to_string(), "Instant".to_string(), "from_std".to_string()],
+                            PathType::Namespace,
+                        )),
+                        vec![Expr::Ident("next_release".to_string())],
+                    ),
+                    Expr::Ident("period".to_string()),
+                ],
             )),
-            Vec::new(),
-        )),
-    }));
+        }));
 
-    // 2. if now < next_release { std::thread::sleep(next_release - now); }
-    loop_stmts.push(Statement::Expr(Expr::If {
-        condition: Box::new(Expr::BinaryOp(
-            Box::new(Expr::Ident("now".to_string())),
-            "<".to_string(),
-            Box::new(Expr::Ident("next_release".to_string())),
-        )),
-        then_branch: Block {
-            stmts: vec![Statement::Expr(Expr::MethodCall(
+        // interval.tick().await;
+        loop_stmts.push(Statement::Expr(Expr::Await(Box::new(Expr::MethodCall(
+            Box::new(Expr::Ident("interval".to_string())),
+            "tick".to_string(),
+            Vec::new(),
+        )))));
+    } else {
+        // 1. let now = Instant::now();
+        loop_stmts.push(Statement::Let(LetStmt {
+            ifmut: false,
+            name: "now".to_string(),
+            ty: None,
+            init: Some(Expr::Call(
                 Box::new(Expr::Path(
-                    vec![
-                        "std".to_string(),
-                        "thread".to_string(),
-                        "sleep".to_string(),
-                    ],
+                    vec!["Instant".to_string(), "now".to_string()],
                     PathType::Namespace,
                 )),
-                "".to_string(),
-                vec![Expr::Ident("next_release - now".to_string())],
+                Vec::new(),
+            )),
+        }));
+
+        // 2. if now < next_release { std::thread::sleep(next_release - now); }
+        loop_stmts.push(Statement::Expr(Expr::If {
+            condition: Box::new(Expr::BinaryOp(
+                Box::new(Expr::Ident("now".to_string())),
+                "<".to_string(),
+                Box::new(Expr::Ident("next_release".to_string())),
+            )),
+            then_branch: Block {
+                stmts: vec![Statement::Expr(Expr::MethodCall(
+                    Box::new(Expr::Path(
+                        vec![
+                            "std".to_string(),
+                            "thread".to_string(),
+                            "sleep".to_string(),
+                        ],
+                        PathType::Namespace,
+                    )),
+                    "".to_string(),
+                    vec![Expr::Ident("next_release - now".to_string())],
+                ))],
+                expr: None,
+            },
+            else_branch: None,
+        }));
+    }
+
+    // 2.5 Dispatch_Jitter：在释放时刻之后随机延迟 [0, jitter] ms，不影响下一次的绝对释放时刻；按实例覆盖时读取 self.dispatch_jitter
+    if dispatch_jitter > 0 {
+        let jitter_bound = if temp_converter.is_thread_property_overridden(&impl_.name.type_identifier, "dispatch_jitter") {
+            "self.dispatch_jitter + 1".to_string()
+        } else {
+            (dispatch_jitter + 1).to_string()
+        };
+        loop_stmts.push(Statement::Expr(Expr::MethodCall(
+            Box::new(Expr::Path(
+                vec![
+                    "std".to_string(),
+                    "thread".to_string(),
+                    "sleep".to_string(),
+                ],
+                PathType::Namespace,
+            )),
+            "".to_string(),
+            vec![Expr::Ident(format!(
+                "Duration::from_millis(rand::thread_rng().gen_range(0, {}))",
+                jitter_bound
             ))],
-            expr: None,
-        },
-        else_branch: None,
-    }));
+        )));
+    }
 
     // 3. 端口处理块
     if !subprogram_handling_stmts.is_empty() {
@@ -594,11 +726,13 @@ fn create_periodic_execution_logic(temp_converter: &AadlConverter, impl_: &Compo
     }
 
     // 5. next_release += period;
-    loop_stmts.push(Statement::Expr(Expr::BinaryOp(
-        Box::new(Expr::Ident("next_release".to_string())),
-        "+=".to_string(),
-        Box::new(Expr::Ident("period".to_string())),
-    )));
+    if !use_interval {
+        loop_stmts.push(Statement::Expr(Expr::BinaryOp(
+            Box::new(Expr::Ident("next_release".to_string())),
+            "+=".to_string(),
+            Box::new(Expr::Ident("period".to_string())),
+        )));
+    }
 
     // 6. 构造 loop 表达式并压入外层 stmts
     stmts.push(Statement::Expr(Expr::Loop(Box::new(Block {
@@ -637,11 +771,13 @@ fn create_aperiodic_execution_logic(temp_converter: &AadlConverter, impl_: &Comp
     let subprogram_calls = extract_subprogram_calls(temp_converter, impl_);
     let has_receiving_subprograms = subprogram_calls.iter().any(|(_, _, _, is_send, _)| !is_send);
 
-    // 在循环外定义 events 变量
+    // 在循环外定义 events 变量；没有接收端口时无法推断元素类型，显式标注
     stmts.push(Statement::Let(LetStmt {
         ifmut: true,
         name: "events".to_string(),
-        ty: None,
+        ty: receive_ports
+            .is_empty()
+            .then(|| Type::Named("Vec<((), u32, Instant)>".to_string())),
         init: Some(Expr::Call(
             Box::new(Expr::Path(vec!["Vec".to_string(), "new".to_string()], PathType::Namespace)),
             Vec::new(),
@@ -654,7 +790,7 @@ fn create_aperiodic_execution_logic(temp_converter: &AadlConverter, impl_: &Comp
             let mut loop_stmts = Vec::new();
             
             // 添加生成事件收集逻辑
-            loop_stmts.extend(create_event_collection_logic(&port_urgency, &receive_ports));
+            loop_stmts.extend(create_event_collection_logic(&port_urgency, &receive_ports, &temp_converter.ranged_in_ports(&impl_.name.type_identifier)));
             
             // 如果事件队列中有事件，则挑选出优先级最高的进行处理
             loop_stmts.push(Statement::Expr(Expr::IfLet {
@@ -742,7 +878,7 @@ fn create_sporadic_execution_logic(temp_converter: &AadlConverter, impl_: &Compo
                 vec!["Duration".to_string(), "from_millis".to_string()],
                 PathType::Namespace,
             )),
-            vec![Expr::Literal(Literal::Int(min_interval as i64))],
+            vec![thread_parameter_expr(temp_converter, impl_, "period", min_interval)],
         )),
     }));
 
@@ -788,11 +924,13 @@ fn create_sporadic_execution_logic(temp_converter: &AadlConverter, impl_: &Compo
     // println!("subprogram_calls{:?}",subprogram_calls);
     let has_receiving_subprograms = subprogram_calls.iter().any(|(_, _, _, is_send, _)| !is_send); //标志位，是否有需要传入数据的子程序
 
-    // 在循环外定义 events 变量
+    // 在循环外定义 events 变量；没有接收端口时无法推断元素类型，显式标注
     stmts.push(Statement::Let(LetStmt {
         ifmut: true,
         name: "events".to_string(),
-        ty: None,
+        ty: receive_ports
+            .is_empty()
+            .then(|| Type::Named("Vec<((), u32, Instant)>".to_string())),
         init: Some(Expr::Call(
             Box::new(Expr::Path(vec!["Vec".to_string(), "new".to_string()], PathType::Namespace)),
             Vec::new(),
@@ -805,7 +943,7 @@ fn create_sporadic_execution_logic(temp_converter: &AadlConverter, impl_: &Compo
             let mut loop_stmts = Vec::new();
             
             // 添加生成事件收集逻辑
-            loop_stmts.extend(create_event_collection_logic(&port_urgency, &receive_ports));
+            loop_stmts.extend(create_event_collection_logic(&port_urgency, &receive_ports, &temp_converter.ranged_in_ports(&impl_.name.type_identifier)));
             
             // 如果事件队列中有事件，则挑选出优先级最高的进行处理
             loop_stmts.push(Statement::Expr(Expr::IfLet {
@@ -954,7 +1092,7 @@ fn create_timed_execution_logic(temp_converter: &AadlConverter, impl_: &Componen
                 vec!["Duration".to_string(), "from_millis".to_string()],
                 PathType::Namespace,
             )),
-            vec![Expr::Literal(Literal::Int(period as i64))],
+            vec![thread_parameter_expr(temp_converter, impl_, "period", period)],
         )),
     }));
 
@@ -999,11 +1137,13 @@ fn create_timed_execution_logic(temp_converter: &AadlConverter, impl_: &Componen
     let subprogram_calls = extract_subprogram_calls(temp_converter,impl_);
     let has_receiving_subprograms = subprogram_calls.iter().any(|(_, _, _, is_send, _)| !is_send);
 
-    // 在循环外定义 events 变量
+    // 在循环外定义 events 变量；没有接收端口时无法推断元素类型，显式标注
     stmts.push(Statement::Let(LetStmt {
         ifmut: true,
         name: "events".to_string(),
-        ty: None,
+        ty: receive_ports
+            .is_empty()
+            .then(|| Type::Named("Vec<((), u32, Instant)>".to_string())),
         init: Some(Expr::Call(
             Box::new(Expr::Path(vec!["Vec".to_string(), "new".to_string()], PathType::Namespace)),
             Vec::new(),
@@ -1016,7 +1156,7 @@ fn create_timed_execution_logic(temp_converter: &AadlConverter, impl_: &Componen
             let mut loop_stmts = Vec::new();
             
             // 添加生成事件收集逻辑
-            loop_stmts.extend(create_event_collection_logic(&port_urgency, &receive_ports));
+            loop_stmts.extend(create_event_collection_logic(&port_urgency, &receive_ports, &temp_converter.ranged_in_ports(&impl_.name.type_identifier)));
             
             // 如果事件队列中有事件，则挑选出优先级最高的进行处理
             loop_stmts.push(Statement::Expr(Expr::IfLet {
@@ -1128,6 +1268,7 @@ fn create_subprogram_call_logic_with_data(temp_converter: &AadlConverter, impl_:
 
     // 提取有参数端口的子程序调用信息
     let subprogram_calls = extract_subprogram_calls(temp_converter, impl_);
+    let ranged_ports = temp_converter.ranged_in_ports(&impl_.name.type_identifier);
     
     // 从AADL的calls部分提取子程序调用序列
     let mut mycalls_sequence = Vec::new();
@@ -1196,7 +1337,7 @@ fn create_subprogram_call_logic_with_data(temp_converter: &AadlConverter, impl_:
                         ifmut: true,
                     }));
                     
-                    send_stmts.push(Statement::Expr(Expr::Call(
+                    let call = Expr::Call(
                         Box::new(Expr::Path(
                             vec![subprogram_name.clone(), "send".to_string()],
                             PathType::Namespace,
@@ -1206,9 +1347,8 @@ fn create_subprogram_call_logic_with_data(temp_converter: &AadlConverter, impl_:
                             true,
                             true,
                         )],
-                    )));
-                    
-                    send_stmts.push(Statement::Expr(Expr::MethodCall(
+                    );
+                    let send = Statement::Expr(Expr::MethodCall(
                         Box::new(Expr::MethodCall(
                             Box::new(Expr::Ident("sender".to_string())),
                             "send".to_string(),
@@ -1216,7 +1356,19 @@ fn create_subprogram_call_logic_with_data(temp_converter: &AadlConverter, impl_:
                         )),
                         "unwrap".to_string(),
                         Vec::new(),
-                    )));
+                    ));
+
+                    // C 写回的枚举编码无效时包装函数返回 Err，此次不发送
+                    if temp_converter.is_checked_out_param(port_type) {
+                        send_stmts.push(Statement::Expr(Expr::If {
+                            condition: Box::new(Expr::MethodCall(Box::new(call), "is_ok".to_string(), Vec::new())),
+                            then_branch: Block { stmts: vec![send], expr: None },
+                            else_branch: None,
+                        }));
+                    } else {
+                        send_stmts.push(Statement::Expr(call));
+                        send_stmts.push(send);
+                    }
                     
                     port_handling_stmts.push(Statement::Expr(Expr::IfLet {
                         pattern: "Some(sender)".to_string(),
@@ -1250,10 +1402,9 @@ fn create_subprogram_call_logic_with_data(temp_converter: &AadlConverter, impl_:
                         let mut receive_stmts = Vec::new();
 
                         let match_expr = Expr::Match {
-                            expr: Box::new(Expr::MethodCall(
-                                Box::new(Expr::Ident("receiver".to_string())),
-                                "try_recv".to_string(),
-                                Vec::new(),
+                            expr: Box::new(AadlConverter::checked_receive(
+                                Expr::MethodCall(Box::new(Expr::Ident("receiver".to_string())), "try_recv".to_string(), Vec::new()),
+                                ranged_ports.get(&thread_port_name.to_lowercase()),
                             )),
                             arms: vec![
                                 MatchArm {
@@ -1383,47 +1534,30 @@ fn create_subprogram_call_logic_with_data(temp_converter: &AadlConverter, impl_:
     port_handling_stmts
 }
 
-// 辅助函数：提取属性值
-fn extract_property_value(temp_converter: &AadlConverter, impl_: &ComponentImplementation, name: &str) -> Option<u64> {
-    let target_name = name.to_lowercase();
-    for prop in temp_converter.convert_properties(ComponentRef::Impl(impl_)) {
-        if prop.name.to_lowercase() == target_name {
-            match prop.value {
-                StruPropertyValue::Integer(val) => {
-                    return Some(val as u64);
-                }
-                StruPropertyValue::Duration(val, unit) => {
-                    println!(
-                        "Warning: Found duration {} {} for property {}, expected integer",
-                        val, unit, name
-                    );
-                    return Some(val); // 假设duration的数值部分可用
-                }
-                _ => {
-                    println!("Warning: Property {} has unsupported type", name);
-                    return None;
-                }
-            }
+// 辅助函数：提取属性值（实现 -> 类型 -> 属性集缺省值）
+pub fn extract_property_value(temp_converter: &AadlConverter, impl_: &ComponentImplementation, name: &str) -> Option<u64> {
+    match PropertyLookup::for_impl(temp_converter, impl_).typed(name)? {
+        StruPropertyValue::Integer(val) => Some(val as u64),
+        StruPropertyValue::Duration(val, unit) => {
+            diagnostics::warning(
+                codes::UNEXPECTED_PROPERTY_TYPE,
+                format!("thread {}: property {} is the duration {} {}, using its numeric part", impl_.name.to_string(), name, val, unit),
+            );
+            Some(val) // 假设duration的数值部分可用
+        }
+        _ => {
+            diagnostics::warning(
+                codes::UNEXPECTED_PROPERTY_TYPE,
+                format!("thread {}: property {} is not an integer or time value, ignored", impl_.name.to_string(), name),
+            );
+            None
         }
     }
-    None
 }
 
-// 辅助函数：提取调度协议
-fn extract_dispatch_protocol(temp_converter: &AadlConverter, impl_: &ComponentImplementation) -> Option<String> {
-    let target_name = "dispatch_protocol";
-    for prop in temp_converter.convert_properties(ComponentRef::Impl(impl_)) {
-        if prop.name.to_lowercase() == target_name {
-            match prop.value {
-                StruPropertyValue::String(val) => return Some(val),
-                _ => {
-                    println!("Warning: Property {} has unsupported type", target_name);
-                    return None;
-                }
-            }
-        }
-    }
-    None
+// 辅助函数：提取调度协议（实现 -> 类型）
+pub fn extract_dispatch_protocol(temp_converter: &AadlConverter, impl_: &ComponentImplementation) -> Option<String> {
+    PropertyLookup::for_impl(temp_converter, impl_).enumeration("dispatch_protocol")
 }
 
 // 提取事件端口和事件数据端口
@@ -1458,15 +1592,14 @@ fn extract_event_port_urgency(impl_: &ComponentImplementation) -> Vec<(String, u
         _ => return port_priorities,
     };
     
-    // 查找 urgency 属性
+    // 查找 urgency 属性（Urgency => 10 applies to port）
     for prop in properties {
-        if let Property::BasicProperty(bp) = prop {
+        if let Property::ContainedProperty(bp) = prop {
             if bp.identifier.name.to_lowercase() == "urgency" {
-                // 解析属性值
-                if let PropertyValue::Single(PropertyExpression::Apply(apply_term)) = &bp.value {
-                    // 解析优先级数值
-                    if let Ok(priority) = apply_term.number.parse::<u32>() {
-                        port_priorities.push((apply_term.applies_to.clone(), priority));
+                // 解析优先级数值
+                if let PropertyValue::Single(PropertyExpression::Integer(SignedIntergerOrConstant::Real(number))) = &bp.value {
+                    if let Ok(priority) = u32::try_from(number.value) {
+                        port_priorities.extend(bp.applies_to.iter().map(|port| (port.clone(), priority)));
                     }
                 }
             }
@@ -1474,11 +1607,11 @@ fn extract_event_port_urgency(impl_: &ComponentImplementation) -> Vec<(String, u
     }
     
     // 按优先级降序排序（优先级高的在前）
-    port_priorities.sort_by(|a, b| b.1.cmp(&a.1));
+    port_priorities.sort_by_key(|b| std::cmp::Reverse(b.1));
     port_priorities
 }
 
-fn extract_subprogram_calls(
+pub fn extract_subprogram_calls(
     temp_converter: &AadlConverter,
     impl_: &ComponentImplementation,
 ) -> Vec<(String, String, String, bool, Type)> {
@@ -1590,7 +1723,7 @@ fn get_subprogram_port_type(temp_converter: &AadlConverter, subprogram_name: &st
 
 
     /// 创建事件收集代码
-    fn create_event_collection_logic(port_urgency: &[(String, u32)], receive_ports: &[String]) -> Vec<Statement> {
+    fn create_event_collection_logic(port_urgency: &[(String, u32)], receive_ports: &[String], ranged_ports: &HashMap<String, String>) -> Vec<Statement> {
         let mut stmts = Vec::new();
         
         // 只有当事件队列为空时才尝试接收新消息
@@ -1617,17 +1750,16 @@ fn get_subprogram_port_type(temp_converter: &AadlConverter, subprogram_name: &st
                                     PathType::Member,
                                 )),
                                 true,
-                                false,
+                                true,
                             )),
                             then_branch: Block {
                                 stmts: vec![
                                     // 生成 if let Ok(val) = rx.try_recv() 的代码
                                     Statement::Expr(Expr::IfLet {
                                         pattern: "Ok(val)".to_string(),
-                                        value: Box::new(Expr::MethodCall(
-                                            Box::new(Expr::Ident("rx".to_string())),
-                                            "try_recv".to_string(),
-                                            Vec::new(),
+                                        value: Box::new(AadlConverter::checked_receive(
+                                            Expr::MethodCall(Box::new(Expr::Ident("rx".to_string())), "try_recv".to_string(), Vec::new()),
+                                            ranged_ports.get(&port_field_name),
                                         )),
                                         then_branch: Block {
                                             stmts: vec![
@@ -1660,6 +1792,7 @@ fn get_subprogram_port_type(temp_converter: &AadlConverter, subprogram_name: &st
                     // 如果没有优先级信息，使用原来的逻辑处理接收端口
                     if port_urgency.is_empty() && !receive_ports.is_empty() {
                         let port_field_name = receive_ports[0].to_lowercase();
+                        let ranged_type = ranged_ports.get(&port_field_name);
                         collect_stmts.push(Statement::Expr(Expr::IfLet {
                             pattern: "Some(rx)".to_string(),
                             value: Box::new(Expr::Reference(
@@ -1668,16 +1801,15 @@ fn get_subprogram_port_type(temp_converter: &AadlConverter, subprogram_name: &st
                                     PathType::Member,
                                 )),
                                 true,
-                                false,
+                                true,
                             )),
                             then_branch: Block {
                                 stmts: vec![
                                     Statement::Expr(Expr::IfLet {
                                         pattern: "Ok(val)".to_string(),
-                                        value: Box::new(Expr::MethodCall(
-                                            Box::new(Expr::Ident("rx".to_string())),
-                                            "try_recv".to_string(),
-                                            Vec::new(),
+                                        value: Box::new(AadlConverter::checked_receive(
+                                            Expr::MethodCall(Box::new(Expr::Ident("rx".to_string())), "try_recv".to_string(), Vec::new()),
+                                            ranged_type,
                                         )),
                                         then_branch: Block {
                                             stmts: vec![
@@ -67,6 +67,7 @@ pub struct EnumDef {
     pub derives: Vec<String>,
     pub docs: Vec<String>,
     pub vis: Visibility,
+    pub repr: Option<String>, // #[repr(...)]，变体带显式判别值时给出整数类型
 }
 
 /// 函数定义
@@ -163,6 +164,16 @@ pub enum Expr {
         then_branch: Block,
         else_branch: Option<Block>,
     },
+    WhileLet {
+        pattern: String,
+        value: Box<Expr>,
+        body: Block,
+    }, // while let pattern = value { body }
+    For {
+        pattern: String,
+        iter: Box<Expr>,
+        body: Block,
+    }, // for pattern in iter { body }
     Match {
         expr: Box<Expr>,
         arms: Vec<MatchArm>,
@@ -214,6 +225,10 @@ pub enum StruPropertyValue {
     Boolean(bool),
     Duration(u64, String),      // (值, 单位)
     Range(i64, i64, Option<String>), // (最小值, 最大值, 单位)
+    RealRange(f64, f64, Option<String>), // 实数区间 (最小值, 最大值, 单位)
+    List(Vec<StruPropertyValue>),       // 列表值，元素类型一致（含嵌套列表）
+    Record(Vec<(String, StruPropertyValue)>), // 记录值 [字段 => 值; ...]，按声明顺序
+    Computed(String),                   // compute(函数名)，取值由该函数在运行时计算
     None,                       // 表示没有属性值
     Custom(String),             //自定义类型(如 Shared)
 }
@@ -274,6 +289,7 @@ pub struct Field {
     pub ty: Type,
     pub docs: Vec<String>,
     pub attrs: Vec<Attribute>,
+    pub vis: Visibility, // 字段可见性，取值受约束的字段为私有
 }
 
 #[derive(Debug,Clone)]
@@ -281,6 +297,7 @@ pub struct Variant {
     pub name: String,
     pub data: Option<Vec<Type>>, // Some for tuple variant
     pub docs: Vec<String>,
+    pub discriminant: Option<i64>, // 显式判别值：Name = N
 }
 
 #[derive(Debug,Clone)]
@@ -1,11 +1,14 @@
 #![allow(clippy::only_used_in_recursion)]
+use super::converter::CodegenBackend;
 use super::intermediate_ast::*;
+use super::tool::computed_function_name;
 use chrono::Local;
 
 // Rust代码生成器
 pub struct RustCodeGenerator {
     buffer: String,
     indent_level: usize,
+    backend: CodegenBackend, // 决定文件头中端口通道类型的导入
 }
 
 impl Default for RustCodeGenerator {
@@ -19,6 +22,14 @@ impl RustCodeGenerator {
         Self {
             buffer: String::new(),
             indent_level: 0,
+            backend: CodegenBackend::default(),
+        }
+    }
+
+    pub fn with_backend(backend: CodegenBackend) -> Self {
+        Self {
+            backend,
+            ..Self::new()
         }
     }
 
@@ -38,22 +49,34 @@ impl RustCodeGenerator {
         self.writeln("#![allow(non_camel_case_types)]");
         self.writeln("#![allow(non_snake_case)]");
         self.writeln("#![allow(unused_assignments)]");
-        self.writeln("use crossbeam_channel::{Receiver, Sender};");
-        self.writeln("use std::sync::{Arc,Mutex};");
-        self.writeln("use std::thread;");
-        self.writeln("use std::time::{Duration, Instant};");
-        self.writeln("use lazy_static::lazy_static;");
-        self.writeln("use std::collections::HashMap;");
-        self.writeln("use crate::common_traits::*;");
-        self.writeln("use crate::posix::*;");
-        self.writeln("use tokio::sync::broadcast::{self,Sender as BcSender, Receiver as BcReceiver};");
-        self.writeln("use libc::{self, syscall, SYS_gettid};");
-        self.writeln("use rand::{Rng};");
-        self.writeln("use libc::{");
-        self.writeln("    pthread_self, sched_param, pthread_setschedparam, SCHED_FIFO,");
-        // self.writeln("    cpu_set_t, CPU_SET, CPU_ZERO, sched_setaffinity,");
-        self.writeln("};");
-        self.writeln("include!(concat!(env!(\"OUT_DIR\"), \"/aadl_c_bindings.rs\"));"); //绑定的函数通过 include! 注入到根模块
+        if self.backend == CodegenBackend::Embedded {
+            // no_std：端口为 heapless 静态队列，时间与调度由 rtc 模块提供
+            self.writeln("use heapless::Deque;");
+            self.writeln("use crate::rtc::*;");
+            self.writeln("include!(concat!(env!(\"OUT_DIR\"), \"/aadl_c_bindings.rs\"));");
+        } else {
+            if self.backend == CodegenBackend::Async {
+                // 异步后端的端口通道为 tokio 无界通道，沿用 Receiver/Sender 名称
+                self.writeln("use tokio::sync::mpsc::{UnboundedReceiver as Receiver, UnboundedSender as Sender};");
+            } else {
+                self.writeln("use crossbeam_channel::{Receiver, Sender};");
+            }
+            self.writeln("use std::sync::{Arc,Mutex};");
+            self.writeln("use std::thread;");
+            self.writeln("use std::time::{Duration, Instant};");
+            self.writeln("use lazy_static::lazy_static;");
+            self.writeln("use std::collections::HashMap;");
+            self.writeln("use crate::common_traits::*;");
+            self.writeln("use crate::posix::*;");
+            self.writeln("use tokio::sync::broadcast::{self,Sender as BcSender, Receiver as BcReceiver};");
+            self.writeln("use libc::{self, syscall, SYS_gettid};");
+            self.writeln("use rand::{Rng};");
+            self.writeln("use libc::{");
+            self.writeln("    pthread_self, sched_param, pthread_setschedparam, SCHED_FIFO, SCHED_RR,");
+            // self.writeln("    cpu_set_t, CPU_SET, CPU_ZERO, sched_setaffinity,");
+            self.writeln("};");
+            self.writeln("include!(concat!(env!(\"OUT_DIR\"), \"/aadl_c_bindings.rs\"));"); //绑定的函数通过 include! 注入到根模块
+        }
 
         self.writeln("");
         self.generate_withs(&module.withs);
@@ -132,6 +155,11 @@ impl RustCodeGenerator {
     }
 
     fn generate_nested_module(&mut self, m: &RustModule) {
+        // 模块属性（如 #[cfg(test)]）作用于整个 mod 项，写在声明行之前
+        for attr in &m.attrs {
+            self.generate_attribute(attr);
+        }
+
         // 生成模块声明行
         match &m.vis {
             Visibility::Public => self.write("pub "),
@@ -143,13 +171,10 @@ impl RustCodeGenerator {
         self.writeln(&format!("mod {} {{", m.name));
         self.indent();
 
-        // 模块级文档和属性
+        // 模块级文档
         for doc in &m.docs {
             self.writeln(doc);
         }
-        for attr in &m.attrs {
-            self.generate_attribute(attr);
-        }
 
         // 模块内容
         self.generate_items(&m.items);
@@ -273,16 +298,7 @@ impl RustCodeGenerator {
 
         // 2属性字段初始化
         for prop in &s.properties {
-            let init_value = match &prop.value {
-                StruPropertyValue::Boolean(b) => b.to_string(),
-                StruPropertyValue::Integer(i) => i.to_string(),
-                StruPropertyValue::Float(f) => f.to_string(),
-                StruPropertyValue::String(s) => format!("\"{}\".to_string()", s),
-                StruPropertyValue::Duration(val, _) => val.to_string(),
-                StruPropertyValue::Range(min, max, _) => format!("({}, {})", min, max),
-                StruPropertyValue::None => "Default::default()".to_string(),
-                StruPropertyValue::Custom(s) => s.to_string(),
-            };
+            let init_value = self.property_init_value(&prop.value);
             self.writeln(&format!(
                 "            {}: {}, // {}",
                 prop.name.to_lowercase(),
@@ -296,6 +312,30 @@ impl RustCodeGenerator {
         self.writeln("}");
     }
 
+    // 属性字段的初始化表达式
+    fn property_init_value(&self, value: &StruPropertyValue) -> String {
+        match value {
+            StruPropertyValue::Boolean(b) => b.to_string(),
+            StruPropertyValue::Integer(i) => i.to_string(),
+            StruPropertyValue::Float(f) => f.to_string(),
+            StruPropertyValue::String(s) => format!("\"{}\".to_string()", s),
+            StruPropertyValue::Duration(val, _) => val.to_string(),
+            StruPropertyValue::Range(min, max, _) => format!("({}, {})", min, max),
+            StruPropertyValue::RealRange(min, max, _) => format!("({:?}, {:?})", min, max),
+            StruPropertyValue::List(elements) => {
+                let elements: Vec<String> = elements.iter().map(|e| self.property_init_value(e)).collect();
+                format!("vec![{}]", elements.join(", "))
+            }
+            StruPropertyValue::Record(fields) => {
+                let values: Vec<String> = fields.iter().map(|(_, v)| self.property_init_value(v)).collect();
+                format!("({},)", values.join(", "))
+            }
+            StruPropertyValue::Computed(function) => computed_function_name(function),
+            StruPropertyValue::None => "Default::default()".to_string(),
+            StruPropertyValue::Custom(s) => s.to_string(),
+        }
+    }
+
     // 根据属性值推断Rust类型
     // 2025.10.10 将这部分功能移动到converter.rs中，此处废弃
     #[allow(unused)]
@@ -307,6 +347,10 @@ impl RustCodeGenerator {
             StruPropertyValue::String(_) => "String".to_string(),
             StruPropertyValue::Duration(_, _) => "u64".to_string(),
             StruPropertyValue::Range(_, _, _) => "(u64, u64)".to_string(),
+            StruPropertyValue::RealRange(_, _, _) => "(f64, f64)".to_string(),
+            StruPropertyValue::List(_) => "Vec<_>".to_string(),
+            StruPropertyValue::Record(_) => "(_,)".to_string(),
+            StruPropertyValue::Computed(_) => "String".to_string(),
             StruPropertyValue::None => "Default".to_string(),
             StruPropertyValue::Custom(s) => s.to_string(),
         }
@@ -317,7 +361,8 @@ impl RustCodeGenerator {
             self.generate_attribute(attr);
         }
         self.write(&format!(
-            "pub {}: {},",
+            "{}{}: {},",
+            self.visibility(&field.vis),
             field.name,
             self.type_to_string(&field.ty)
         ));
@@ -366,7 +411,9 @@ impl RustCodeGenerator {
             match item {
                 ImplItem::Method(m) => self.generate_function(m),
                 ImplItem::AssocConst(name, ty, expr) => {
-                    self.writeln(&format!("const {}: {} = ", name, self.type_to_string(ty)));
+                    // 固有实现中的关联常量对外公开（如取值范围的 MIN/MAX/UNIT）；trait 实现中不能带可见性
+                    let vis = if i.trait_impl.is_none() { "pub " } else { "" };
+                    self.write(&format!("{}const {}: {} = ", vis, name, self.type_to_string(ty)));
                     self.generate_expr(expr);
                     self.writeln(";");
                 }
@@ -703,6 +750,36 @@ impl RustCodeGenerator {
                     self.write("}");
                 }
             }
+            Expr::WhileLet {
+                pattern,
+                value,
+                body,
+            } => {
+                self.write("while let ");
+                self.write(pattern);
+                self.write(" = ");
+                self.generate_expr(value);
+                self.writeln(" {");
+                self.indent();
+                self.generate_block(body);
+                self.dedent();
+                self.write("}");
+            }
+            Expr::For {
+                pattern,
+                iter,
+                body,
+            } => {
+                self.write("for ");
+                self.write(pattern);
+                self.write(" in ");
+                self.generate_expr(iter);
+                self.writeln(" {");
+                self.indent();
+                self.generate_block(body);
+                self.dedent();
+                self.write("}");
+            }
             Expr::Reference(inner_expr, is_reference, mutable) => {
                 if *is_reference {
                     self.write("&");
@@ -780,6 +857,9 @@ impl RustCodeGenerator {
             }
             self.writeln(")]");
         }
+        if let Some(repr) = &e.repr {
+            self.writeln(&format!("#[repr({})]", repr));
+        }
 
         self.write(&format!("{}enum {} ", self.visibility(&e.vis), e.name));
 
@@ -821,6 +901,9 @@ impl RustCodeGenerator {
                 }
                 self.write(")");
             }
+            if let Some(discriminant) = variant.discriminant {
+                self.write(&format!(" = {}", discriminant));
+            }
             self.writeln(",");
         }
         self.dedent();
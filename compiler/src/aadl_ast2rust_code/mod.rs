@@ -5,6 +5,11 @@ pub mod intermediate_print;
 pub mod merge_utils;
 pub mod tool;
 pub mod collector;
+pub mod cyclic_executive;
+pub mod embedded;
+pub mod deployment;
+pub mod property_lookup;
+pub mod codec;
 
 pub mod types;
 pub mod implementations;
@@ -0,0 +1,267 @@
+// 属性取值服务：各 conv_* 模块统一通过这里按 AADL 标准的优先级查找属性值，得到带类型的结果。
+// 优先级由高到低：
+//   1. applies to 关联（由实例模型解析到目标实例）
+//   2. 子组件声明中的属性
+//   3. 组件实现（extends 祖先实现上的属性已由 inheritance.rs 合并进来）
+//   4. 组件类型（同样包含祖先类型）
+//   5. inherit 属性取所在容器（父组件实例）上的值
+//   6. 属性集中的缺省值
+// 是否 inherit 及缺省值取自 model_loader 加载的属性集中的属性定义。
+// 只有分类器（生成组件结构体时）从第 3 层开始查；实例模型中 ComponentInstance::properties
+// 已按 1-4 合并，实例级查找再补上第 5、6 层。
+use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::intermediate_ast::StruPropertyValue;
+use crate::aadl_ast2rust_code::tool::{data_rate_to_bytes_per_sec, size_to_bits, time_to_millis, time_to_nanos};
+use crate::ast::aadl_ast_cj::*;
+use crate::instance_model::{is_unconditional, SystemInstance};
+
+pub struct PropertyLookup<'a> {
+    converter: &'a AadlConverter,
+    layers: Vec<Vec<&'a BasicPropertyAssociation>>,     // 第 1-4 层，优先级高的在前
+    containers: Vec<Vec<&'a BasicPropertyAssociation>>, // 第 5 层：由近及远的容器实例
+}
+
+impl<'a> PropertyLookup<'a> {
+    // 组件实现：实现 -> 类型 -> 缺省值
+    pub fn for_impl(converter: &'a AadlConverter, impl_: &'a ComponentImplementation) -> Self {
+        let mut layers = vec![clause_associations(&impl_.properties)];
+        if let Some(comp_type) = converter.get_component_type(impl_) {
+            layers.push(clause_associations(&comp_type.properties));
+        }
+        Self { converter, layers, containers: Vec::new() }
+    }
+
+    // 组件类型：类型 -> 缺省值
+    pub fn for_type(converter: &'a AadlConverter, comp: &'a ComponentType) -> Self {
+        Self {
+            converter,
+            layers: vec![clause_associations(&comp.properties)],
+            containers: Vec::new(),
+        }
+    }
+
+    // 子组件：子组件声明 -> 所引用的实现 -> 类型 -> 缺省值；带包前缀的引用在该包中解析
+    pub fn for_subcomponent(converter: &'a AadlConverter, sub: &'a Subcomponent) -> Self {
+        let mut lookup = match &sub.classifier {
+            SubcomponentClassifier::ClassifierReference(UniqueComponentClassifierReference::Implementation(reference)) => {
+                match converter.resolve_component_impl(reference) {
+                    Some(impl_) => Self::for_impl(converter, impl_),
+                    None => Self { converter, layers: Vec::new(), containers: Vec::new() },
+                }
+            }
+            SubcomponentClassifier::ClassifierReference(UniqueComponentClassifierReference::Type(reference)) => {
+                match converter.resolve_component_type(reference) {
+                    Some(comp) => Self::for_type(converter, comp),
+                    None => Self { converter, layers: Vec::new(), containers: Vec::new() },
+                }
+            }
+            SubcomponentClassifier::Prototype(_) => Self { converter, layers: Vec::new(), containers: Vec::new() },
+        };
+        lookup.layers.insert(0, property_associations(&sub.properties));
+        lookup
+    }
+
+    // 组件实例：实例上合并后的属性 -> 容器实例（inherit 属性）-> 缺省值
+    pub fn for_instance(converter: &'a AadlConverter, system: &'a SystemInstance, path: &[String]) -> Self {
+        let instance_associations = |len: usize| {
+            system
+                .component(&path[..len])
+                .map(|instance| instance.properties.iter().filter(|p| is_unconditional(p)).collect())
+                .unwrap_or_default()
+        };
+        Self {
+            converter,
+            layers: vec![instance_associations(path.len())],
+            containers: (0..path.len()).rev().map(instance_associations).collect(),
+        }
+    }
+
+    // 按优先级查找属性值；+=> 关联的列表值追加在更低一层的取值之后
+    pub fn value(&self, name: &str) -> Option<PropertyValue> {
+        if let Some(value) = layered_value(&self.layers, name) {
+            return Some(value);
+        }
+        if let Some(bp) = self.inherited(name) {
+            return Some(bp.value.clone());
+        }
+        match self.converter.property_definition(None, name)?.default.as_ref()? {
+            PropertyDefault::Value(value) => Some(value.clone()),
+            PropertyDefault::ValueOf(other) => self.value(other),
+        }
+    }
+
+    // 属性是否有显式关联（不计属性集中的缺省值）
+    pub fn is_declared(&self, name: &str) -> bool {
+        layered_value(&self.layers, name).is_some() || self.inherited(name).is_some()
+    }
+
+    // inherit 属性在最近的容器实例上的关联；分类器级查找没有容器，总是 None
+    pub fn inherited(&self, name: &str) -> Option<&'a BasicPropertyAssociation> {
+        if !self.converter.property_definition(None, name).is_some_and(|d| d.inherit) {
+            return None;
+        }
+        self.containers
+            .iter()
+            .find_map(|layer| layer.iter().rev().find(|bp| bp.identifier.name.eq_ignore_ascii_case(name)))
+            .copied()
+    }
+
+    // 转换为生成代码使用的属性值（数值、字符串、区间等）；带时间单位的整数统一换算为毫秒
+    pub fn typed(&self, name: &str) -> Option<StruPropertyValue> {
+        let value = self.value(name)?;
+        if let PropertyValue::Single(PropertyExpression::Integer(SignedIntergerOrConstant::Real(int))) = &value {
+            if int.sign.is_none() && int.unit.as_deref().is_some_and(is_time_unit) {
+                return i64::try_from(time_to_millis(int.value as f64, int.unit.as_deref()))
+                    .ok()
+                    .map(StruPropertyValue::Integer);
+            }
+        }
+        self.converter.parse_property_value(&value)
+    }
+
+    // 整数值（带单位时只取数值部分）
+    pub fn integer(&self, name: &str) -> Option<i64> {
+        match self.typed(name)? {
+            StruPropertyValue::Integer(value) => Some(value),
+            StruPropertyValue::Duration(value, _) => i64::try_from(value).ok(),
+            _ => None,
+        }
+    }
+
+    // 枚举值或字符串；列表值（如 Scheduling_Protocol => (EDF)）取第一个元素
+    pub fn enumeration(&self, name: &str) -> Option<String> {
+        match first_expression(&self.value(name)?)? {
+            PropertyExpression::String(StringTerm::Literal(value)) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    // 时间值换算为毫秒；区间值（如 Compute_Execution_Time => 0 ms .. 50 ms）取上界
+    pub fn time_ms(&self, name: &str) -> Option<u64> {
+        let value = self.value(name)?;
+        match first_expression(&value)? {
+            PropertyExpression::Integer(SignedIntergerOrConstant::Real(int)) => {
+                Some(time_to_millis(int.value as f64, int.unit.as_deref()))
+            }
+            PropertyExpression::Real(SignedRealOrConstant::Real(real)) => {
+                Some(time_to_millis(real.value, real.unit.as_deref()))
+            }
+            PropertyExpression::IntegerRange(range) => {
+                let unit = range.upper.unit.as_deref().or(range.lower.unit.as_deref());
+                range.upper.value.parse::<f64>().ok().map(|v| time_to_millis(v, unit))
+            }
+            _ => None,
+        }
+    }
+
+    // 时间区间换算为纳秒 (下界, 上界)，单个时间值上下界相同；
+    // field 取记录值中的字段，如 Transmission_Time => [Fixed => 1 ms .. 2 ms; Per_Byte => 1 us .. 2 us;]
+    pub fn time_range_ns(&self, name: &str, field: Option<&str>) -> Option<(u64, u64)> {
+        let value = self.value(name)?;
+        let value = match field {
+            Some(field) => match first_expression(&value)? {
+                PropertyExpression::Record(record) => record
+                    .fields
+                    .iter()
+                    .find(|f| f.name.eq_ignore_ascii_case(field))
+                    .map(|f| f.value.clone())?,
+                _ => return None,
+            },
+            None => value,
+        };
+        match first_expression(&value)? {
+            PropertyExpression::Integer(SignedIntergerOrConstant::Real(int)) => {
+                let ns = time_to_nanos(int.value as f64, int.unit.as_deref());
+                Some((ns, ns))
+            }
+            PropertyExpression::Real(SignedRealOrConstant::Real(real)) => {
+                let ns = time_to_nanos(real.value, real.unit.as_deref());
+                Some((ns, ns))
+            }
+            PropertyExpression::IntegerRange(range) => {
+                let unit = range.upper.unit.as_deref().or(range.lower.unit.as_deref());
+                let lower = range.lower.value.replace('_', "").parse::<f64>().ok()?;
+                let upper = range.upper.value.replace('_', "").parse::<f64>().ok()?;
+                Some((
+                    time_to_nanos(lower, range.lower.unit.as_deref().or(unit)),
+                    time_to_nanos(upper, unit),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    // 数据率（Data_Volume，如 Bandwidth => 10 Mbitsps）换算为每秒字节数
+    pub fn bytes_per_sec(&self, name: &str) -> Option<f64> {
+        match first_expression(&self.value(name)?)? {
+            PropertyExpression::Integer(SignedIntergerOrConstant::Real(int)) => {
+                Some(data_rate_to_bytes_per_sec(int.value as f64, int.unit.as_deref()))
+            }
+            PropertyExpression::Real(SignedRealOrConstant::Real(real)) => {
+                Some(data_rate_to_bytes_per_sec(real.value, real.unit.as_deref()))
+            }
+            _ => None,
+        }
+    }
+
+    // 大小（Size 类型，如 Data_Size => 30 bits）换算为位数
+    pub fn size_bits(&self, name: &str) -> Option<u64> {
+        match first_expression(&self.value(name)?)? {
+            PropertyExpression::Integer(SignedIntergerOrConstant::Real(int)) => {
+                Some(size_to_bits(int.value as f64, int.unit.as_deref()))
+            }
+            PropertyExpression::Real(SignedRealOrConstant::Real(real)) => Some(size_to_bits(real.value, real.unit.as_deref())),
+            _ => None,
+        }
+    }
+}
+
+// Time_Units 中的单位
+fn is_time_unit(unit: &str) -> bool {
+    ["ps", "ns", "us", "ms", "sec", "min", "hr"].iter().any(|u| u.eq_ignore_ascii_case(unit))
+}
+
+fn clause_associations(clause: &PropertyClause) -> Vec<&BasicPropertyAssociation> {
+    match clause {
+        PropertyClause::Properties(props) => property_associations(props),
+        PropertyClause::ExplicitNone => Vec::new(),
+    }
+}
+
+// 只取无条件直接作用于所在组件的关联；applies to 由实例模型解析，in binding / in modes 限定的取值不作为缺省
+fn property_associations(props: &[Property]) -> Vec<&BasicPropertyAssociation> {
+    props
+        .iter()
+        .filter_map(|prop| match prop {
+            Property::BasicProperty(bp) | Property::SubcomponentProperty(bp) => Some(bp),
+            _ => None,
+        })
+        .filter(|bp| is_unconditional(bp))
+        .collect()
+}
+
+fn layered_value(layers: &[Vec<&BasicPropertyAssociation>], name: &str) -> Option<PropertyValue> {
+    let (found, rest) = layers.split_first()?;
+    // 同一层内后写的关联覆盖先写的
+    let Some(bp) = found.iter().rev().find(|bp| bp.identifier.name.eq_ignore_ascii_case(name)) else {
+        return layered_value(rest, name);
+    };
+    match (&bp.operator, &bp.value, layered_value(rest, name)) {
+        (PropertyOperator::Append, PropertyValue::List(appended), Some(PropertyValue::List(mut base))) => {
+            base.extend(appended.iter().cloned());
+            Some(PropertyValue::List(base))
+        }
+        _ => Some(bp.value.clone()),
+    }
+}
+
+// 单值本身，或列表中的第一个元素（区间值在解析时被包装为单元素列表）
+fn first_expression(value: &PropertyValue) -> Option<&PropertyExpression> {
+    match value {
+        PropertyValue::Single(expr) => Some(expr),
+        PropertyValue::List(elements) => elements.iter().find_map(|element| match element {
+            PropertyListElement::Value(expr) => Some(expr),
+            PropertyListElement::NestedList(_) => None,
+        }),
+    }
+}
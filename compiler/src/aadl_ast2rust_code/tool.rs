@@ -1,41 +1,8 @@
 
-use std::collections::{HashMap, HashSet};
+use crate::aadl_ast2rust_code::intermediate_ast::*;
+use crate::ast::aadl_ast_cj::SourceLocation;
 
 
-    pub fn dedup_with_min_two_unique(vec: &mut Vec<(String, String)>) -> Vec<(String, String)> {
-        let mut freq: HashMap<(String, String), usize> = HashMap::new();
-
-        // 统计频次
-        for item in vec.iter() {
-            *freq.entry(item.clone()).or_default() += 1;
-        }
-
-        // 保留出现次数 >= 2 的
-        vec.retain(|item| freq.get(item).copied().unwrap_or(0) >= 2);
-
-        // 对剩余的每种项只保留一个
-        let mut seen = HashSet::new();
-        vec.retain(|item| seen.insert(item.clone()));
-        vec.clone()
-    }
-    pub fn dedup_with_min_two_unique_single_string(vec: &mut Vec<String>) -> Vec<String> {
-        let mut freq: HashMap<String, usize> = HashMap::new();
-    
-        // 统计频次
-        for item in vec.iter() {
-            *freq.entry(item.clone()).or_default() += 1;
-        }
-    
-        // 保留出现次数 >= 2 的
-        vec.retain(|item| freq.get(item).copied().unwrap_or(0) >= 2);
-    
-        // 对剩余的每个元素只保留一个
-        let mut seen = HashSet::new();
-        vec.retain(|item| seen.insert(item.clone()));
-    
-        vec.clone()
-    }
-
     pub fn to_upper_camel_case(name: &str) -> String {
         name.split('_')
             .filter(|s| !s.is_empty())
@@ -51,3 +18,174 @@ use std::collections::{HashMap, HashSet};
             })
             .collect::<String>()
     }
+
+    // 将 AADL Time_Units 表示的时间换算为毫秒（无单位时按毫秒处理）
+    pub fn time_to_millis(value: f64, unit: Option<&str>) -> u64 {
+        let factor = match unit.map(|u| u.to_lowercase()).as_deref() {
+            Some("ps") => 1e-9,
+            Some("ns") => 1e-6,
+            Some("us") => 1e-3,
+            Some("sec") => 1e3,
+            Some("min") => 6e4,
+            Some("hr") => 3.6e6,
+            _ => 1.0, // ms 或缺省
+        };
+        (value * factor).round().max(0.0) as u64
+    }
+
+    // 将 AADL Time_Units 表示的时间换算为纳秒，用于总线传输时间等亚毫秒量（无单位时按毫秒处理）
+    pub fn time_to_nanos(value: f64, unit: Option<&str>) -> u64 {
+        let factor = match unit.map(|u| u.to_lowercase()).as_deref() {
+            Some("ps") => 1e-3,
+            Some("ns") => 1.0,
+            Some("us") => 1e3,
+            Some("sec") => 1e9,
+            Some("min") => 6e10,
+            Some("hr") => 3.6e12,
+            _ => 1e6, // ms 或缺省
+        };
+        (value * factor).round().max(0.0) as u64
+    }
+
+    // 将 AADL Data_Volume 表示的数据率换算为每秒字节数（无单位时按 bitsps 处理）
+    pub fn data_rate_to_bytes_per_sec(value: f64, unit: Option<&str>) -> f64 {
+        let factor = match unit.map(|u| u.to_lowercase()).as_deref() {
+            Some("bytesps") => 1.0,
+            Some("kbytesps") => 1e3,
+            Some("mbytesps") => 1e6,
+            Some("gbytesps") => 1e9,
+            Some("kbitsps") => 1e3 / 8.0,
+            Some("mbitsps") => 1e6 / 8.0,
+            Some("gbitsps") => 1e9 / 8.0,
+            _ => 1.0 / 8.0, // bitsps 或缺省
+        };
+        value * factor
+    }
+
+    // 将 AADL Size_Units 表示的大小换算为位数（无单位时按字节处理）
+    pub fn size_to_bits(value: f64, unit: Option<&str>) -> u64 {
+        let factor = match unit.map(|u| u.to_lowercase()).as_deref() {
+            Some("bits") => 1.0,
+            Some("kbyte") => 8e3,
+            Some("mbyte") => 8e6,
+            Some("gbyte") => 8e9,
+            Some("tbyte") => 8e12,
+            _ => 8.0, // Bytes 或缺省
+        };
+        (value * factor).round().max(0.0) as u64
+    }
+
+    // 子组件数组 -> 定长数组类型，多维时嵌套：[[T; 3]; 2]
+    pub fn array_type(element: Type, dimensions: &[u32]) -> Type {
+        dimensions
+            .iter()
+            .rev()
+            .fold(element, |ty, &size| Type::Array(Box::new(ty), size as usize))
+    }
+
+    // 逐元素构造子组件数组：std::array::from_fn(|_| 元素)，多维时嵌套
+    pub fn array_init(element: Expr, dimensions: &[u32]) -> Expr {
+        dimensions.iter().fold(element, |expr, _| {
+            Expr::Call(
+                Box::new(Expr::Path(
+                    vec!["std".to_string(), "array".to_string(), "from_fn".to_string()],
+                    PathType::Namespace,
+                )),
+                vec![Expr::Closure(vec!["_".to_string()], Box::new(expr))],
+            )
+        })
+    }
+
+    // 按行优先顺序遍历子组件数组全部元素的表达式：sensors.into_iter().flatten()
+    pub fn array_elements_iter(field: &str, dimensions: &[u32]) -> Expr {
+        let flatten = ".flatten()".repeat(dimensions.len().saturating_sub(1));
+        Expr::Ident(format!("{}.into_iter(){}", field, flatten))
+    }
+
+    // compute(Pkg::Sensor_Checksum) 对应的生成函数名 sensor_checksum
+    pub fn computed_function_name(function: &str) -> String {
+        function.rsplit("::").next().unwrap_or(function).trim().to_lowercase()
+    }
+
+    // 生成代码中指回 AADL 源文件的来源注释
+    pub fn source_location_doc(location: &Option<SourceLocation>) -> Option<String> {
+        location.as_ref().map(|loc| format!("// 来源: {}:{}", loc.file, loc.line))
+    }
+
+    // 异步后端：将语句中的阻塞休眠 std::thread::sleep(d) 改写为 tokio::time::sleep(d).await
+    pub fn asyncify_statements(stmts: Vec<Statement>) -> Vec<Statement> {
+        stmts.into_iter().map(asyncify_statement).collect()
+    }
+
+    fn asyncify_statement(stmt: Statement) -> Statement {
+        match stmt {
+            Statement::Let(mut ls) => {
+                ls.init = ls.init.map(asyncify_expr);
+                Statement::Let(ls)
+            }
+            Statement::Expr(expr) => Statement::Expr(asyncify_expr(expr)),
+            other => other,
+        }
+    }
+
+    fn asyncify_block(block: Block) -> Block {
+        Block {
+            stmts: asyncify_statements(block.stmts),
+            expr: block.expr.map(|e| Box::new(asyncify_expr(*e))),
+        }
+    }
+
+    fn is_thread_sleep(callee: &Expr) -> bool {
+        matches!(callee, Expr::Path(path, PathType::Namespace) if path == &["std", "thread", "sleep"])
+    }
+
+    fn asyncify_expr(expr: Expr) -> Expr {
+        match expr {
+            Expr::MethodCall(receiver, method, args) if method.is_empty() && is_thread_sleep(&receiver) => {
+                tokio_sleep(args)
+            }
+            Expr::Call(callee, args) if is_thread_sleep(&callee) => tokio_sleep(args),
+            Expr::Block(block) => Expr::Block(asyncify_block(block)),
+            Expr::Loop(block) => Expr::Loop(Box::new(asyncify_block(*block))),
+            Expr::Unsafe(block) => Expr::Unsafe(Box::new(asyncify_block(*block))),
+            Expr::If { condition, then_branch, else_branch } => Expr::If {
+                condition,
+                then_branch: asyncify_block(then_branch),
+                else_branch: else_branch.map(asyncify_block),
+            },
+            Expr::IfLet { pattern, value, then_branch, else_branch } => Expr::IfLet {
+                pattern,
+                value,
+                then_branch: asyncify_block(then_branch),
+                else_branch: else_branch.map(asyncify_block),
+            },
+            Expr::WhileLet { pattern, value, body } => Expr::WhileLet {
+                pattern,
+                value,
+                body: asyncify_block(body),
+            },
+            Expr::For { pattern, iter, body } => Expr::For {
+                pattern,
+                iter,
+                body: asyncify_block(body),
+            },
+            Expr::Match { expr, arms } => Expr::Match {
+                expr,
+                arms: arms
+                    .into_iter()
+                    .map(|arm| MatchArm { body: asyncify_block(arm.body), ..arm })
+                    .collect(),
+            },
+            other => other,
+        }
+    }
+
+    fn tokio_sleep(args: Vec<Expr>) -> Expr {
+        Expr::Await(Box::new(Expr::Call(
+            Box::new(Expr::Path(
+                vec!["tokio".to_string(), "time".to_string(), "sleep".to_string()],
+                PathType::Namespace,
+            )),
+            args,
+        )))
+    }
@@ -0,0 +1,29 @@
+use crate::aadl_ast2rust_code::intermediate_ast::*;
+
+use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::ast::aadl_ast_cj::*;
+use crate::aadl_ast2rust_code::tool::*;
+
+// 总线、虚拟总线与存储器属于执行平台，只生成携带属性的结构体；
+// 端口连接经总线的转发由根系统按 Actual_Connection_Binding 建立（见 create_system_channels）
+pub fn convert_bus_component(temp_converter: &AadlConverter, comp: &ComponentType) -> Vec<Item> {
+    let (suffix, label) = match comp.category {
+        ComponentCategory::VirtualBus => ("VirtualBus", "Virtual Bus"),
+        ComponentCategory::Memory => ("Memory", "Memory"),
+        _ => ("Bus", "Bus"),
+    };
+
+    let struct_def = StructDef {
+        name: format!("{}{}", to_upper_camel_case(&comp.identifier), suffix),
+        fields: vec![],
+        properties: temp_converter.convert_properties(ComponentRef::Type(comp)),
+        generics: Vec::new(),
+        derives: vec!["Debug".to_string()],
+        docs: std::iter::once(format!("// AADL {}: {}", label, comp.identifier))
+            .chain(source_location_doc(&comp.location))
+            .collect(),
+        vis: Visibility::Public,
+    };
+
+    vec![Item::Struct(struct_def)]
+}
@@ -0,0 +1,232 @@
+// 定点数数据类型
+// Data_Representation => Fixed 的数据类型按 Data_Digits（十进制有效位数）与 Data_Scale（十进制小数位数）
+// 映射为 fixed 库的定点类型，并包装为新类型：不同的定点数据类型即使表示相同也互不混用，
+// 同时可以为其实现运算符与 crate::codec::Codec。
+// 十进制位数换算为二进制位数向上取整，存储宽度取能容纳符号位、整数位与小数位的最小整数宽度（至多 64 位）。
+// 生成工程中引用了 fixed:: 时 Cargo.toml 才加入 fixed 依赖，参见 build_project_tool::generate_cargo_toml。
+use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::intermediate_ast::*;
+use crate::aadl_ast2rust_code::property_lookup::PropertyLookup;
+use crate::ast::aadl_ast_cj::*;
+use crate::diagnostics::{self, codes};
+
+// 未给出 Data_Digits 时的有效位数，恰好容纳于 32 位
+const DEFAULT_DIGITS: i64 = 9;
+
+// 定点数的表示
+pub struct FixedSpec {
+    pub digits: i64,
+    pub scale: i64,
+    pub storage: u32, // 存储宽度：8/16/32/64
+    pub frac: u32,    // 二进制小数位数
+}
+
+impl FixedSpec {
+    // 存储使用的有符号整数类型，如 i64
+    pub fn bits_type(&self) -> String {
+        format!("i{}", self.storage)
+    }
+
+    // fixed 库中的定点类型，如 fixed::FixedI64<fixed::types::extra::U27>
+    pub fn fixed_type(&self) -> String {
+        format!("fixed::FixedI{}<fixed::types::extra::U{}>", self.storage, self.frac)
+    }
+}
+
+// 十进制位数对应的二进制位数
+fn decimal_bits(digits: i64) -> u32 {
+    if digits <= 0 {
+        0
+    } else {
+        (digits as f64 * std::f64::consts::LOG2_10).ceil() as u32
+    }
+}
+
+// Data_Representation 为 Fixed 时计算定点表示，否则返回 None
+pub fn fixed_spec(converter: &AadlConverter, comp: &ComponentType) -> Option<FixedSpec> {
+    let lookup = PropertyLookup::for_type(converter, comp);
+    if !lookup.enumeration("Data_Representation").is_some_and(|repr| repr.eq_ignore_ascii_case("fixed")) {
+        return None;
+    }
+    let digits = match lookup.integer("Data_Digits") {
+        Some(digits) if digits > 0 => digits,
+        _ => {
+            diagnostics::warning(
+                codes::ADJUSTED_FIXED_POINT,
+                format!("data {}: Fixed without a positive Data_Digits, using {} digits", comp.identifier, DEFAULT_DIGITS),
+            );
+            DEFAULT_DIGITS
+        }
+    };
+    let scale = lookup.integer("Data_Scale").unwrap_or(0);
+    // Data_Scale 为负时数值是整数的 10^-scale 倍，没有小数部分
+    let int_bits = decimal_bits(digits - scale.max(0)) + decimal_bits(-scale.min(0));
+    let mut frac = decimal_bits(scale);
+    let needed = 1 + int_bits + frac;
+    let storage = match [8, 16, 32, 64].into_iter().find(|&bits| bits >= needed) {
+        Some(bits) => bits,
+        None => {
+            // 超出 64 位时优先保留整数位，减少小数位
+            let reduced = 63u32.saturating_sub(int_bits);
+            diagnostics::warning(
+                codes::ADJUSTED_FIXED_POINT,
+                format!(
+                    "data {}: Data_Digits {} with Data_Scale {} needs {} bits, reducing to {} fractional bits in 64 bits",
+                    comp.identifier, digits, scale, needed, reduced
+                ),
+            );
+            frac = reduced;
+            64
+        }
+    };
+    Some(FixedSpec { digits, scale, storage, frac })
+}
+
+fn method(name: &str, params: Vec<Param>, return_type: &str, stmts: Vec<String>, doc: Option<&str>, vis: Visibility) -> ImplItem {
+    ImplItem::Method(FunctionDef {
+        name: name.to_string(),
+        params,
+        return_type: Type::Named(return_type.to_string()),
+        body: Block { stmts: stmts.into_iter().map(|line| Statement::Expr(Expr::Ident(line))).collect(), expr: None },
+        asyncness: false,
+        vis,
+        docs: doc.map(|doc| vec![doc.to_string()]).unwrap_or_default(),
+        attrs: Vec::new(),
+    })
+}
+
+fn param(name: &str, ty: &str) -> Param {
+    Param { name: name.to_string(), ty: Type::Named(ty.to_string()) }
+}
+
+fn trait_impl(name: &str, trait_name: &str, items: Vec<ImplItem>) -> Item {
+    Item::Impl(ImplBlock {
+        target: Type::Named(name.to_string()),
+        generics: Vec::new(),
+        items,
+        trait_impl: Some(Type::Named(trait_name.to_string())),
+    })
+}
+
+// 定点数据类型生成的新类型、算术辅助函数与运算符实现
+pub fn fixed_items(name: &str, spec: &FixedSpec) -> Vec<Item> {
+    let fixed_type = spec.fixed_type();
+    let fixed_path = format!("fixed::FixedI{}", spec.storage); // 构造时由字段类型推断小数位数
+    let bits_type = spec.bits_type();
+    let struct_def = StructDef {
+        name: name.to_string(),
+        fields: vec![Field { name: "value".to_string(), ty: Type::Named(fixed_type.clone()), docs: Vec::new(), attrs: Vec::new(), vis: Visibility::Public }],
+        properties: Vec::new(),
+        generics: Vec::new(),
+        derives: ["Debug", "Clone", "Copy", "Default", "PartialEq", "Eq", "PartialOrd", "Ord"].iter().map(|d| d.to_string()).collect(),
+        docs: vec![
+            format!("// AADL Data Type: {}", name),
+            if spec.scale >= 0 {
+                format!("// 定点数: {} 位十进制有效数字，其中 {} 位小数（Data_Digits / Data_Scale）", spec.digits, spec.scale)
+            } else {
+                format!("// 定点数: {} 位十进制有效数字，数值为 10^{} 的倍数（Data_Digits / Data_Scale）", spec.digits, -spec.scale)
+            },
+            format!("// 表示: {} 位有符号数，{} 位二进制小数，分辨率 2^-{}", spec.storage, spec.frac, spec.frac),
+        ],
+        vis: Visibility::Public,
+    };
+
+    let public = || Visibility::Public;
+    let binary = |op: &str| -> ImplItem {
+        method(
+            &format!("checked_{}", op),
+            vec![param("self", "Self"), param("rhs", "Self")],
+            "Option<Self>",
+            vec![format!("return self.value.checked_{}(rhs.value).map(|value| Self {{ value }})", op)],
+            None,
+            public(),
+        )
+    };
+    let saturating = |op: &str| -> ImplItem {
+        method(
+            &format!("saturating_{}", op),
+            vec![param("self", "Self"), param("rhs", "Self")],
+            "Self",
+            vec![format!("return Self {{ value: self.value.saturating_{}(rhs.value) }}", op)],
+            None,
+            public(),
+        )
+    };
+    let mut helpers = vec![
+        method(
+            "from_f64",
+            vec![param("value", "f64")],
+            "Option<Self>",
+            vec![format!("return {}::checked_from_num(value).map(|value| Self {{ value }})", fixed_path)],
+            Some("// 换算为最接近的定点值，超出表示范围或为 NaN 时返回 None"),
+            public(),
+        ),
+        method(
+            "saturating_from_f64",
+            vec![param("value", "f64")],
+            "Self",
+            vec![format!(
+                "return if value.is_nan() {{ Self::default() }} else {{ Self {{ value: {}::saturating_from_num(value) }} }}",
+                fixed_path
+            )],
+            Some("// 超出表示范围时取最近的极值，NaN 取零"),
+            public(),
+        ),
+        method("to_f64", vec![param("self", "Self")], "f64", vec!["return self.value.to_num::<f64>()".to_string()], None, public()),
+        method(
+            "from_bits",
+            vec![param("bits", &bits_type)],
+            "Self",
+            vec![format!("return Self {{ value: {}::from_bits(bits) }}", fixed_path)],
+            Some("// 存储的整数表示，数值为 bits * 2^-frac"),
+            public(),
+        ),
+        method("to_bits", vec![param("self", "Self")], &bits_type, vec!["return self.value.to_bits()".to_string()], None, public()),
+    ];
+    helpers.extend(["add", "sub", "mul", "div"].map(binary));
+    helpers.extend(["add", "sub", "mul"].map(saturating));
+
+    let mut items = vec![
+        Item::Struct(struct_def),
+        Item::Impl(ImplBlock { target: Type::Named(name.to_string()), generics: Vec::new(), items: helpers, trait_impl: None }),
+    ];
+    // 运算符与整数一致：溢出在调试构建中 panic，需要检查时使用 checked_*/saturating_*
+    for (trait_name, op) in [("Add", "add"), ("Sub", "sub"), ("Mul", "mul"), ("Div", "div")] {
+        items.push(trait_impl(
+            name,
+            &format!("core::ops::{}", trait_name),
+            vec![
+                ImplItem::AssocType("Output".to_string(), Type::Named("Self".to_string())),
+                method(
+                    op,
+                    vec![param("self", "Self"), param("rhs", "Self")],
+                    "Self",
+                    vec![format!("return Self {{ value: core::ops::{}::{}(self.value, rhs.value) }}", trait_name, op)],
+                    None,
+                    Visibility::None,
+                ),
+            ],
+        ));
+    }
+    items.push(trait_impl(
+        name,
+        "core::ops::Neg",
+        vec![
+            ImplItem::AssocType("Output".to_string(), Type::Named("Self".to_string())),
+            method("neg", vec![param("self", "Self")], "Self", vec!["return Self { value: -self.value }".to_string()], None, Visibility::None),
+        ],
+    ));
+    items.push(trait_impl(
+        name,
+        "core::fmt::Display",
+        vec![method(
+            "fmt",
+            vec![param("", "&self"), param("f", "&mut core::fmt::Formatter<'_>")],
+            "core::fmt::Result",
+            vec!["return core::fmt::Display::fmt(&self.value, f)".to_string()],
+            None,
+            Visibility::None,
+        )],
+    ));
+    items
+}
@@ -0,0 +1,212 @@
+// 带取值范围的数值数据类型
+// Data_Model::Integer_Range / Real_Range 约束的整数、实数数据类型生成新类型（而不是类型别名），
+// 由检查构造函数保证取值在范围内；越界时按 Range_Check::Out_Of_Range 饱和（Saturate）或报错（Error，缺省）。
+// Data_Model::Measurement_Unit 写入类型文档与 UNIT 常量。
+// 端口接收时再经 on_receive 检查一次：字段是公开的，值也可能来自总线、网络的解码，参见 AadlConverter::checked_receive。
+use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::intermediate_ast::*;
+use crate::aadl_ast2rust_code::property_lookup::PropertyLookup;
+use crate::ast::aadl_ast_cj::*;
+use crate::diagnostics::{self, codes};
+
+// 新类型的取值范围，界值已按 Rust 类型格式化为字面量
+struct RangeSpec {
+    rust: String,
+    float: bool,
+    min: String,
+    max: String,
+    unit: Option<String>,
+    saturate: bool,
+}
+
+// 数据类型转换得到数值别名且带取值范围时，改为生成新类型；否则返回 None，沿用别名
+pub fn convert_ranged_component(converter: &AadlConverter, comp: &ComponentType, items: &[Item]) -> Option<Vec<Item>> {
+    let [Item::TypeAlias(alias)] = items else { return None };
+    let Type::Named(rust) = &alias.target else { return None };
+    let spec = range_spec(converter, comp, rust)?;
+    Some(newtype_items(&comp.identifier, &spec))
+}
+
+fn range_spec(converter: &AadlConverter, comp: &ComponentType, rust: &str) -> Option<RangeSpec> {
+    let lookup = PropertyLookup::for_type(converter, comp);
+    let float = matches!(rust, "f32" | "f64");
+    let (type_min, type_max): (i128, i128) = match rust {
+        "i8" => (i8::MIN.into(), i8::MAX.into()),
+        "i16" => (i16::MIN.into(), i16::MAX.into()),
+        "i32" => (i32::MIN.into(), i32::MAX.into()),
+        "i64" | "isize" => (i64::MIN.into(), i64::MAX.into()),
+        "u8" => (0, u8::MAX.into()),
+        "u16" => (0, u16::MAX.into()),
+        "u32" => (0, u32::MAX.into()),
+        "u64" | "usize" => (0, u64::MAX.into()),
+        "f32" | "f64" => (0, 0),
+        _ => return None,
+    };
+    let range = if float {
+        lookup.typed("Real_Range").or_else(|| lookup.typed("Integer_Range"))
+    } else {
+        lookup.typed("Integer_Range")
+    };
+    let (min, max, range_unit) = match range {
+        Some(StruPropertyValue::Range(min, max, unit)) if float => (format!("{:?}", min as f64), format!("{:?}", max as f64), unit),
+        Some(StruPropertyValue::RealRange(min, max, unit)) if float => (format!("{:?}", min), format!("{:?}", max), unit),
+        // 超出 Rust 类型表示范围的界值按类型的极值处理
+        Some(StruPropertyValue::Range(min, max, unit)) => {
+            let clamp = |bound: i64| i128::from(bound).clamp(type_min, type_max);
+            if clamp(min) != i128::from(min) || clamp(max) != i128::from(max) {
+                diagnostics::warning(
+                    codes::IGNORED_VALUE_RANGE,
+                    format!("data {}: Integer_Range {} .. {} exceeds {}, clamped to the type range", comp.identifier, min, max, rust),
+                );
+            }
+            (clamp(min).to_string(), clamp(max).to_string(), unit)
+        }
+        Some(StruPropertyValue::RealRange(..)) => {
+            diagnostics::warning(
+                codes::IGNORED_VALUE_RANGE,
+                format!("data {}: Real_Range on integer type {} is ignored", comp.identifier, rust),
+            );
+            return None;
+        }
+        _ => return None,
+    };
+    let saturate = lookup.enumeration("Out_Of_Range").is_some_and(|policy| policy.eq_ignore_ascii_case("saturate"));
+    Some(RangeSpec {
+        rust: rust.to_string(),
+        float,
+        min,
+        max,
+        unit: lookup.enumeration("Measurement_Unit").or(range_unit),
+        saturate,
+    })
+}
+
+fn method(name: &str, params: Vec<Param>, return_type: Type, stmts: Vec<String>, doc: &str) -> ImplItem {
+    ImplItem::Method(FunctionDef {
+        name: name.to_string(),
+        params,
+        return_type,
+        body: Block { stmts: stmts.into_iter().map(|line| Statement::Expr(Expr::Ident(line))).collect(), expr: None },
+        asyncness: false,
+        vis: Visibility::Public,
+        docs: vec![doc.to_string()],
+        attrs: Vec::new(),
+    })
+}
+
+fn param(name: &str, ty: &str) -> Param {
+    Param { name: name.to_string(), ty: Type::Named(ty.to_string()) }
+}
+
+fn newtype_items(name: &str, spec: &RangeSpec) -> Vec<Item> {
+    let value_ty = Type::Named(spec.rust.clone());
+    let unit_doc = spec.unit.as_ref().map_or(String::new(), |unit| format!(" {}", unit));
+    let struct_def = StructDef {
+        name: name.to_string(),
+        fields: vec![Field {
+            name: "value".to_string(),
+            ty: value_ty.clone(),
+            docs: vec!["// 取值须经 new / saturating / checked 构造，经 get 读取".to_string()],
+            attrs: Vec::new(),
+            vis: Visibility::Private,
+        }],
+        properties: Vec::new(),
+        generics: Vec::new(),
+        derives: vec!["Debug".to_string(), "Clone".to_string(), "Copy".to_string(), "PartialEq".to_string(), "PartialOrd".to_string()],
+        docs: vec![
+            format!("// AADL Data Type: {}", name),
+            format!("// 取值范围: {} .. {}{}", spec.min, spec.max, unit_doc),
+            format!("// 越界处理: {}", if spec.saturate { "饱和到最近的界值（Saturate）" } else { "报错（Error）" }),
+        ],
+        vis: Visibility::Public,
+    };
+
+    let error = "crate::range::RangeError";
+    let mut items = vec![
+        ImplItem::AssocConst("MIN".to_string(), value_ty.clone(), Expr::Ident(spec.min.clone())),
+        ImplItem::AssocConst("MAX".to_string(), value_ty.clone(), Expr::Ident(spec.max.clone())),
+    ];
+    if let Some(unit) = &spec.unit {
+        items.push(ImplItem::AssocConst(
+            "UNIT".to_string(),
+            Type::Named("&'static str".to_string()),
+            Expr::Literal(Literal::Str(unit.clone())),
+        ));
+    }
+    items.push(method(
+        "new",
+        vec![param("value", &spec.rust)],
+        Type::Named(format!("Result<Self, {}>", error)),
+        vec![
+            format!(
+                "if !(Self::MIN..=Self::MAX).contains(&value) {{ return Err({} {{ type_name: \"{}\", value: value as f64, min: Self::MIN as f64, max: Self::MAX as f64 }}); }}",
+                error, name
+            ),
+            "return Ok(Self { value })".to_string(),
+        ],
+        "// 检查取值范围，越界时返回错误",
+    ));
+    let clamp = if spec.float {
+        // NaN 不在任何范围内，饱和到下界
+        "return Self { value: if value.is_nan() { Self::MIN } else { value.clamp(Self::MIN, Self::MAX) } }"
+    } else {
+        "return Self { value: value.clamp(Self::MIN, Self::MAX) }"
+    };
+    items.push(method(
+        "saturating",
+        vec![param("value", &spec.rust)],
+        Type::Named("Self".to_string()),
+        vec![clamp.to_string()],
+        "// 越界时取最近的界值",
+    ));
+    items.push(method(
+        "checked",
+        vec![param("value", &spec.rust)],
+        Type::Named(format!("Result<Self, {}>", error)),
+        vec![if spec.saturate { "return Ok(Self::saturating(value))" } else { "return Self::new(value)" }.to_string()],
+        "// 按 Out_Of_Range 策略构造",
+    ));
+    items.push(method(
+        "get",
+        vec![param("self", "Self")],
+        value_ty.clone(),
+        vec!["return self.value".to_string()],
+        "// 取出数值",
+    ));
+    items.push(method(
+        "on_receive",
+        vec![param("value", "Self")],
+        Type::Named("Option<Self>".to_string()),
+        vec![
+            "return match Self::checked(value.value) { Ok(value) => Some(value), Err(error) => { crate::range::report(&error); None } }"
+                .to_string(),
+        ],
+        "// 端口接收时重新检查；按策略报错的越界值被丢弃，视为没有收到",
+    ));
+
+    let zero = if spec.float { "0.0" } else { "0" };
+    let default_impl = ImplBlock {
+        target: Type::Named(name.to_string()),
+        generics: Vec::new(),
+        items: vec![ImplItem::Method(FunctionDef {
+            name: "default".to_string(),
+            params: Vec::new(),
+            return_type: Type::Named("Self".to_string()),
+            body: Block {
+                stmts: vec![Statement::Expr(Expr::Ident(format!("return Self::saturating({})", zero)))],
+                expr: None,
+            },
+            asyncness: false,
+            vis: Visibility::None,
+            docs: Vec::new(),
+            attrs: Vec::new(),
+        })],
+        trait_impl: Some(Type::Named("Default".to_string())),
+    };
+
+    vec![
+        Item::Struct(struct_def),
+        Item::Impl(ImplBlock { target: Type::Named(name.to_string()), generics: Vec::new(), items, trait_impl: None }),
+        Item::Impl(default_impl),
+    ]
+}
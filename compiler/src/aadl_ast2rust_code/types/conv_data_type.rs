@@ -1,7 +1,9 @@
 
 #![allow(clippy::collapsible_match)]
 use crate::aadl_ast2rust_code::intermediate_ast::*;
+use crate::aadl_ast2rust_code::tool::size_to_bits;
 use crate::ast::aadl_ast_cj::*;
+use crate::diagnostics::{self, codes};
 use std::collections::HashMap;
 
 pub fn convert_data_component(
@@ -67,7 +69,9 @@ pub fn convert_data_component(
         } else if unit_type.to_lowercase() == "enum" {
             // 从组件属性中提取属性列表
             if let PropertyClause::Properties(props) = &comp.properties {
-                return vec![Item::Enum(determine_enum_type(comp, props))];
+                let enum_def = determine_enum_type(comp, props);
+                let conversions = enum_conversions(&enum_def);
+                return std::iter::once(Item::Enum(enum_def)).chain(conversions).collect();
             }
             // } else {
             //     // 如果没有属性，返回空的枚举
@@ -125,6 +129,72 @@ fn determine_data_type(type_mappings: &HashMap<String, Type>, comp: &ComponentTy
     determine_complex_data_type(type_mappings, comp)
 }
 
+/// 预声明 Base_Types 包中的数据类型 -> Rust 基本类型：按 Data_Representation 区分类别，
+/// 整数再结合 Number_Representation、Data_Size 与 Integer_Range 确定符号和宽度
+pub fn base_type_mapping(comp: &ComponentType) -> Option<Type> {
+    if comp.category != ComponentCategory::Data {
+        return None;
+    }
+    let PropertyClause::Properties(props) = &comp.properties else {
+        return None;
+    };
+
+    let mut representation = None;
+    let mut signed = true;
+    let mut double = false;
+    let mut size_in_bytes = None;
+    let mut non_negative = false;
+    for prop in props {
+        let Property::BasicProperty(bp) = prop else { continue };
+        match (bp.identifier.name.to_lowercase().as_str(), &bp.value) {
+            ("data_representation", PropertyValue::Single(PropertyExpression::String(StringTerm::Literal(v)))) => {
+                representation = Some(v.to_lowercase());
+            }
+            ("number_representation", PropertyValue::Single(PropertyExpression::String(StringTerm::Literal(v)))) => {
+                signed = !v.eq_ignore_ascii_case("unsigned");
+            }
+            ("ieee754_precision", PropertyValue::Single(PropertyExpression::String(StringTerm::Literal(v)))) => {
+                double = v.eq_ignore_ascii_case("double");
+            }
+            ("data_size", PropertyValue::Single(PropertyExpression::Integer(SignedIntergerOrConstant::Real(size)))) => {
+                size_in_bytes = match size.unit.as_deref().map(str::to_lowercase).as_deref() {
+                    Some("bits") => Some(size.value / 8),
+                    Some("kbyte") => Some(size.value * 1000),
+                    _ => Some(size.value),
+                };
+            }
+            ("integer_range", PropertyValue::List(items)) => {
+                non_negative = matches!(
+                    items.first(),
+                    Some(PropertyListElement::Value(PropertyExpression::IntegerRange(range))) if range.lower.value.trim() == "0"
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let rust_type = match representation?.as_str() {
+        "boolean" => "bool",
+        "character" => "char",
+        "string" => "String",
+        "float" if double || size_in_bytes == Some(8) => "f64",
+        "float" => "f32",
+        "integer" => match (signed, size_in_bytes) {
+            (true, Some(1)) => "i8",
+            (true, Some(2)) => "i16",
+            (true, Some(8)) => "i64",
+            (false, Some(1)) => "u8",
+            (false, Some(2)) => "u16",
+            (false, Some(4)) => "u32",
+            (false, Some(8)) => "u64",
+            (true, None) if non_negative => "u64",
+            _ => "i32",
+        },
+        _ => return None,
+    };
+    Some(Type::Named(rust_type.to_string()))
+}
+
 /// 处理复杂数据类型，包括数组、结构体、联合体、枚举等
 fn determine_complex_data_type(
     type_mappings: &HashMap<String, Type>,
@@ -159,6 +229,15 @@ fn determine_complex_data_type(
                                 "taggedunion" => {
                                     return Type::Named("taggedunion".to_string());
                                 }
+                                // 给出 Dimension 的字符串是容量固定的有界字符串
+                                "string" => {
+                                    if let Some(&[capacity]) = dimensions(props).as_deref() {
+                                        if capacity > 0 {
+                                            return Type::Named(format!("heapless::String<{}>", capacity));
+                                        }
+                                    }
+                                    return type_mappings.get("string").cloned().unwrap_or_else(|| Type::Named("String".to_string()));
+                                }
                                 _ => {
                                     // 使用 type_mappings 查找对应的类型，如果没有找到则使用原值
                                     return type_mappings
@@ -192,7 +271,6 @@ fn determine_complex_data_type(
 /// 处理数组类型
 fn determine_array_type(type_mappings: &HashMap<String, Type>, props: &[Property]) -> Type {
     let mut base_type = Type::Named("i32".to_string()); // 默认基础类型
-    let mut dimensions = Vec::new();
 
     // 查找 Base_Type 属性
     for prop in props {
@@ -200,63 +278,105 @@ fn determine_array_type(type_mappings: &HashMap<String, Type>, props: &[Property
         if let Property::BasicProperty(bp) = prop {
             // 查找base_type属性
             if bp.identifier.name.to_lowercase() == "base_type" {
-                if let PropertyValue::Single(PropertyExpression::ComponentClassifier(
-                    ComponentClassifierTerm {
-                        unique_component_classifier_reference:uccr,
-                    },
-                )) = &bp.value
+                // Base_Type 既可写作单个分类器，也可写作只含一个分类器的列表
+                let classifier = match &bp.value {
+                    PropertyValue::Single(expr) => Some(expr),
+                    PropertyValue::List(elements) => elements.iter().find_map(|element| match element {
+                        PropertyListElement::Value(expr) => Some(expr),
+                        _ => None,
+                    }),
+                };
+                if let Some(PropertyExpression::ComponentClassifier(ComponentClassifierTerm {
+                    unique_component_classifier_reference: uccr,
+                })) = classifier
                 {
-                    if let UniqueComponentClassifierReference::Type(impl_ref) = uccr {
-                        let type_name = impl_ref.implementation_name.type_identifier.clone();
+                    let (UniqueComponentClassifierReference::Type(impl_ref)
+                    | UniqueComponentClassifierReference::Implementation(impl_ref)) = uccr;
+                    {
+                        // 属性值中的 classifier (T.Impl) 整体记在 type_identifier 中，元素类型取组件类型名
+                        let type_name = impl_ref.implementation_name.type_identifier.split('.').next().unwrap_or_default().to_string();
                         // println!("type_mappings:{:?}",type_mappings);
                         base_type = type_mappings
                             .get(&type_name.to_lowercase())
                             .cloned()
-                            //.unwrap()
-                            .expect("type_mappings must contain the base type");
-                            //.unwrap_or_else(|| Type::Named(type_name.clone()));
-                        }
-                }
-            }
-
-            // 查找 Dimension 属性
-            if bp.identifier.name.to_lowercase() == "dimension" {
-                match &bp.value {
-                    PropertyValue::Single(PropertyExpression::Integer(
-                        SignedIntergerOrConstant::Real(int_val),
-                    )) => {
-                        dimensions.push(int_val.value as usize);
-                    }
-                    PropertyValue::List(dim_list) => {
-                        for dim_item in dim_list {
-                            if let PropertyListElement::Value(PropertyExpression::Integer(
-                                SignedIntergerOrConstant::Real(int_val),
-                            )) = dim_item
-                            {
-                                dimensions.push(int_val.value as usize);
-                            }
+                            .unwrap_or_else(|| {
+                                // 本包的结构体、枚举等数据类型不进入类型映射，按同名类型引用；
+                                // 带包前缀而又没有映射的才是无法解析的类型
+                                if impl_ref.package_prefix.is_some() {
+                                    diagnostics::error(
+                                        codes::UNRESOLVED_DATA_TYPE,
+                                        format!("array Base_Type {} has no Rust type mapping", type_name),
+                                    );
+                                }
+                                Type::Named(type_name.clone())
+                            });
                         }
-                    }
-                    _ => {}
                 }
             }
         }
     }
 
-    // 如果没有找到维度信息，默认为一维数组
-    if dimensions.is_empty() {
-        dimensions.push(1);
-    }
-
-    // 构建数组类型：从内到外构建嵌套数组
+    // 构建数组类型：从内到外构建嵌套数组，如 Dimension => (3, 4) 为 [[T; 4]; 3]；
+    // 未给出 Dimension 或某一维为 0 时该维不定长，映射为 Vec
     let mut array_type = base_type;
-    for &dim in dimensions.iter().rev() {
-        array_type = Type::Array(Box::new(array_type), dim);
+    let dims = dimensions(props).filter(|dims| !dims.is_empty()).unwrap_or_else(|| vec![0]);
+    for &dim in dims.iter().rev() {
+        array_type = if dim == 0 {
+            Type::Generic("Vec".to_string(), vec![array_type])
+        } else {
+            Type::Array(Box::new(array_type), dim)
+        };
     }
 
     array_type
 }
 
+// Data_Model::Dimension 的各维大小；未给出时返回 None
+fn dimensions(props: &[Property]) -> Option<Vec<usize>> {
+    let value = props.iter().find_map(|prop| match prop {
+        Property::BasicProperty(bp) if bp.identifier.name.eq_ignore_ascii_case("dimension") => Some(&bp.value),
+        _ => None,
+    })?;
+    let size = |expr: &PropertyExpression| match expr {
+        PropertyExpression::Integer(SignedIntergerOrConstant::Real(int_val)) => Some(int_val.value as usize),
+        _ => None,
+    };
+    Some(match value {
+        PropertyValue::Single(expr) => size(expr).into_iter().collect(),
+        PropertyValue::List(dim_list) => dim_list
+            .iter()
+            .filter_map(|dim_item| match dim_item {
+                PropertyListElement::Value(expr) => size(expr),
+                _ => None,
+            })
+            .collect(),
+    })
+}
+
+// 类型中是否含有不定长的 Vec（no_std 的嵌入式后端无法分配）
+pub fn contains_unsized(ty: &Type) -> bool {
+    match ty {
+        Type::Generic(name, args) => name == "Vec" || args.iter().any(contains_unsized),
+        Type::Array(inner, _) => contains_unsized(inner),
+        _ => false,
+    }
+}
+
+// 是否为没有给出 Dimension 的字符串（嵌入式后端只能按缺省容量映射为有界字符串）
+pub fn is_unbounded_string(comp: &ComponentType) -> bool {
+    let PropertyClause::Properties(props) = &comp.properties else {
+        return false;
+    };
+    let is_string = props.iter().any(|prop| match prop {
+        Property::BasicProperty(bp) if bp.identifier.name.eq_ignore_ascii_case("data_representation") => matches!(
+            &bp.value,
+            PropertyValue::Single(PropertyExpression::String(StringTerm::Literal(repr))) if repr.eq_ignore_ascii_case("string")
+        ),
+        _ => false,
+    });
+    is_string && !matches!(dimensions(props).as_deref(), Some(&[capacity]) if capacity > 0)
+}
+
 /// 处理结构体类型
 fn determine_struct_type(
     type_mappings: &HashMap<String, Type>,
@@ -342,6 +462,7 @@ fn determine_struct_type(
             ty: ty.clone(),
             docs: vec!["".to_string()],
             attrs: vec![],
+            vis: Visibility::Public,
         });
     }
 
@@ -444,6 +565,7 @@ fn determine_union_type(
             ty: ty.clone(),
             docs: vec!["".to_string()],
             attrs: vec![],
+            vis: Visibility::Public,
         });
     }
 
@@ -459,9 +581,8 @@ fn determine_union_type(
     }
 }
 
-/// 处理枚举类型
-fn determine_enum_type(comp: &ComponentType, props: &[Property]) -> EnumDef {
-    // 解析枚举值名称
+// 解析 Enumerators 属性获取枚举值名称
+fn enumerator_names(props: &[Property]) -> Vec<String> {
     let mut variant_names = Vec::new();
 
     for prop in props {
@@ -485,28 +606,193 @@ fn determine_enum_type(comp: &ComponentType, props: &[Property]) -> EnumDef {
             }
         }
     }
+    variant_names
+}
+
+/// 处理枚举类型
+fn determine_enum_type(comp: &ComponentType, props: &[Property]) -> EnumDef {
+    let variant_names = enumerator_names(props);
+
+    // Representation 给出各枚举值的编码时生成带显式判别值的 #[repr] 枚举
+    let codes = enum_representation(props, variant_names.len()).unwrap_or_else(|reason| {
+        diagnostics::warning(codes::IGNORED_ENUM_REPRESENTATION, format!("data {}: {}, ignored", comp.identifier, reason));
+        None
+    });
+    let repr = codes.as_ref().map(|codes| enum_repr_type(codes, data_size_bits(props)));
 
     // 创建枚举变体（无数据类型）
     let mut variants = Vec::new();
-    for name in variant_names {
+    for (index, name) in variant_names.into_iter().enumerate() {
         variants.push(Variant {
             name: name.clone(),
             data: None, // 枚举变体不包含数据类型
             docs: vec![],
+            discriminant: codes.as_ref().map(|codes| codes[index]),
         });
     }
 
     // 创建枚举定义
+    let mut derives = vec!["Debug".to_string(), "Clone".to_string()];
+    if repr.is_some() {
+        derives.extend(["Copy", "PartialEq", "Eq"].map(String::from));
+    }
     EnumDef {
         name: comp.identifier.clone(),
         variants,
         generics: vec![],
-        derives: vec!["Debug".to_string(), "Clone".to_string()],
+        derives,
         docs: vec![format!("// AADL Enum: {}", comp.identifier)],
         vis: Visibility::Public,
+        repr,
+    }
+}
+
+// 枚举数据类型给出 Representation 时的 #[repr] 整数类型（Representation 无效的警告在转换类型时给出）
+pub fn enum_repr(comp: &ComponentType, props: &[Property]) -> Option<String> {
+    if !matches!(determine_complex_data_type(&HashMap::new(), comp), Type::Named(kind) if kind == "enum") {
+        return None;
+    }
+    let codes = enum_representation(props, enumerator_names(props).len()).ok()??;
+    Some(enum_repr_type(&codes, data_size_bits(props)))
+}
+
+// 枚举数据类型的第一个枚举值，用作端口与参数的默认值；不是枚举时返回 None
+pub fn first_enumerator(comp: &ComponentType, props: &[Property]) -> Option<String> {
+    if !matches!(determine_complex_data_type(&HashMap::new(), comp), Type::Named(kind) if kind == "enum") {
+        return None;
+    }
+    enumerator_names(props).into_iter().next()
+}
+
+// 解析 Data_Model::Representation 中各枚举值的编码：字符串可带 0x/0o/0b 前缀，也可直接写整数；
+// 未给出时为 Ok(None)；个数与 Enumerators 不一致、无法解析或有重复时返回原因
+fn enum_representation(props: &[Property], variants: usize) -> Result<Option<Vec<i64>>, String> {
+    let value = props.iter().find_map(|prop| match prop {
+        Property::BasicProperty(bp) if bp.identifier.name.eq_ignore_ascii_case("representation") => Some(&bp.value),
+        _ => None,
+    });
+    let Some(PropertyValue::List(elements)) = value else { return Ok(None) };
+    let parse = |text: &str| {
+        let text = text.trim().replace('_', "");
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest.to_string()),
+            None => (false, text),
+        };
+        let lower = digits.to_lowercase();
+        let magnitude = if let Some(hex) = lower.strip_prefix("0x") {
+            i64::from_str_radix(hex, 16)
+        } else if let Some(octal) = lower.strip_prefix("0o") {
+            i64::from_str_radix(octal, 8)
+        } else if let Some(binary) = lower.strip_prefix("0b") {
+            i64::from_str_radix(binary, 2)
+        } else {
+            lower.parse::<i64>()
+        };
+        magnitude.ok().map(|value| if negative { -value } else { value })
+    };
+    let codes: Option<Vec<i64>> = elements
+        .iter()
+        .map(|element| match element {
+            PropertyListElement::Value(PropertyExpression::String(StringTerm::Literal(text))) => parse(text),
+            PropertyListElement::Value(PropertyExpression::Integer(SignedIntergerOrConstant::Real(int))) => {
+                Some(if matches!(int.sign, Some(Sign::Minus)) { -int.value } else { int.value })
+            }
+            _ => None,
+        })
+        .collect();
+    let codes = codes.ok_or("Representation values must be integers")?;
+    if codes.len() != variants {
+        return Err(format!("Representation has {} values for {} enumerators", codes.len(), variants));
+    }
+    if codes.iter().enumerate().any(|(i, code)| codes[..i].contains(code)) {
+        return Err("Representation values are not unique".to_string());
+    }
+    Ok(Some(codes))
+}
+
+// Data_Size 换算为位数
+fn data_size_bits(props: &[Property]) -> Option<u64> {
+    props.iter().find_map(|prop| match prop {
+        Property::BasicProperty(bp) if bp.identifier.name.eq_ignore_ascii_case("data_size") => match &bp.value {
+            PropertyValue::Single(PropertyExpression::Integer(SignedIntergerOrConstant::Real(size))) => {
+                Some(size_to_bits(size.value as f64, size.unit.as_deref()))
+            }
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+// 枚举的 #[repr] 整数类型：给出 Data_Size 时取能容纳它的最小宽度（有负编码时为有符号），
+// 否则与 C 的 int 一致取 i32，编码超出 i32 时取 i64
+fn enum_repr_type(codes: &[i64], data_size: Option<u64>) -> String {
+    let signed = codes.iter().any(|&code| code < 0);
+    let fits = |bits: u32| {
+        codes.iter().all(|&code| {
+            if signed {
+                bits >= 64 || (-(1i64 << (bits - 1))..(1i64 << (bits - 1))).contains(&code)
+            } else {
+                bits >= 64 || code < (1i64 << bits)
+            }
+        })
+    };
+    match data_size {
+        Some(size) => {
+            let bits = [8, 16, 32, 64].into_iter().find(|&bits| u64::from(bits) >= size && fits(bits)).unwrap_or(64);
+            format!("{}{}", if signed { "i" } else { "u" }, bits)
+        }
+        None if codes.iter().all(|&code| i32::try_from(code).is_ok()) => "i32".to_string(),
+        None => "i64".to_string(),
     }
 }
 
+// #[repr] 枚举与整数之间的转换：From<枚举> for 整数，TryFrom<整数> for 枚举（无效编码原样作为错误返回）
+pub fn enum_conversions(def: &EnumDef) -> Vec<Item> {
+    let Some(repr) = &def.repr else { return Vec::new() };
+    let arms: Vec<String> = def
+        .variants
+        .iter()
+        .filter_map(|variant| variant.discriminant.map(|code| format!("{} => Ok(Self::{}),", code, variant.name)))
+        .collect();
+    let function = |name: &str, params: Vec<Param>, return_type: &str, stmt: String| FunctionDef {
+        name: name.to_string(),
+        params,
+        return_type: Type::Named(return_type.to_string()),
+        body: Block { stmts: vec![Statement::Expr(Expr::Ident(stmt))], expr: None },
+        asyncness: false,
+        vis: Visibility::None,
+        docs: Vec::new(),
+        attrs: Vec::new(),
+    };
+    vec![
+        Item::Impl(ImplBlock {
+            target: Type::Named(repr.clone()),
+            generics: Vec::new(),
+            items: vec![ImplItem::Method(function(
+                "from",
+                vec![Param { name: "value".to_string(), ty: Type::Named(def.name.clone()) }],
+                "Self",
+                format!("return value as {}", repr),
+            ))],
+            trait_impl: Some(Type::Named(format!("From<{}>", def.name))),
+        }),
+        Item::Impl(ImplBlock {
+            target: Type::Named(def.name.clone()),
+            generics: Vec::new(),
+            items: vec![
+                ImplItem::AssocType("Error".to_string(), Type::Named(repr.clone())),
+                ImplItem::Method(function(
+                    "try_from",
+                    vec![Param { name: "value".to_string(), ty: Type::Named(repr.clone()) }],
+                    "Result<Self, Self::Error>",
+                    format!("return match value {{ {} other => Err(other) }}", arms.join(" ")),
+                )),
+            ],
+            trait_impl: Some(Type::Named(format!("TryFrom<{}>", repr))),
+        }),
+    ]
+}
+
 /// 处理带标签的联合体类型，生成带类型的枚举
 fn determine_taggedunion_type(
     type_mappings: &HashMap<String, Type>,
@@ -602,6 +888,7 @@ fn determine_taggedunion_type(
             name: variant_name,
             data: Some(vec![ty.clone()]), // 带标签的联合体变体包含数据类型
             docs: vec![],
+            discriminant: None,
         });
     }
 
@@ -613,5 +900,6 @@ fn determine_taggedunion_type(
         derives: vec!["Debug".to_string(), "Clone".to_string()],
         docs: vec![format!("// AADL Tagged Union: {}", comp.identifier)],
         vis: Visibility::Public,
+        repr: None,
     }
 }
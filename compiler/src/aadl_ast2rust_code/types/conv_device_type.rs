@@ -2,6 +2,7 @@
 use crate::aadl_ast2rust_code::intermediate_ast::*;
 
 use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::property_lookup::PropertyLookup;
 use crate::ast::aadl_ast_cj::*;
 use crate::aadl_ast2rust_code::tool::*;
 
@@ -17,9 +18,10 @@ pub fn convert_device_component(temp_converter: &AadlConverter, comp: &Component
         ty: Type::Named("u64".to_string()),
         docs: vec![format!(
             "// 周期：{}ms",
-            extract_period(comp).unwrap_or(2000)
+            extract_period(temp_converter, comp).unwrap_or(2000)
         )],
         attrs: Vec::new(),
+        vis: Visibility::Public,
     });
 
     let struct_name = format!("{}Device", to_upper_camel_case(&comp.identifier));
@@ -29,7 +31,9 @@ pub fn convert_device_component(temp_converter: &AadlConverter, comp: &Component
         properties: Vec::new(),
         generics: Vec::new(),
         derives: vec!["Debug".to_string()],
-        docs: vec![format!("// AADL Device: {}", comp.identifier)],
+        docs: std::iter::once(format!("// AADL Device: {}", comp.identifier))
+            .chain(source_location_doc(&comp.location))
+            .collect(),
         vis: Visibility::Public,
     };
     items.push(Item::Struct(struct_def));
@@ -38,7 +42,7 @@ pub fn convert_device_component(temp_converter: &AadlConverter, comp: &Component
     let mut impl_items = Vec::new();
 
     // 生成 new() 方法
-    let period_ms = extract_period(comp).unwrap_or(2000);
+    let period_ms = extract_period(temp_converter, comp).unwrap_or(2000);
     let new_method = create_device_new_method(comp, period_ms);
     impl_items.push(ImplItem::Method(new_method));
 
@@ -188,6 +192,7 @@ fn create_device_run_method(temp_converter: &AadlConverter, comp: &ComponentType
     }
 
     // 为每个输入端口尝试接收数据
+    let ranged_ports = temp_converter.ranged_in_ports(&comp.identifier);
     for port in &input_ports {
         let port_name = port.identifier.to_lowercase();
         let received_var = format!("{}_in_val", port_name.clone());
@@ -218,10 +223,9 @@ fn create_device_run_method(temp_converter: &AadlConverter, comp: &ComponentType
         let receive_block = Block {
             stmts: vec![Statement::Expr(Expr::IfLet {
                 pattern: format!("Ok({})", received_var),
-                value: Box::new(Expr::MethodCall(
-                    Box::new(Expr::Ident("rx".to_string())),
-                    "try_recv".to_string(),
-                    Vec::new(),
+                value: Box::new(AadlConverter::checked_receive(
+                    Expr::MethodCall(Box::new(Expr::Ident("rx".to_string())), "try_recv".to_string(), Vec::new()),
+                    ranged_ports.get(&port_name),
                 )),
                 then_branch: inner_then_block,
                 else_branch: None,
@@ -237,7 +241,7 @@ fn create_device_run_method(temp_converter: &AadlConverter, comp: &ComponentType
                     PathType::Member,
                 )),
                 true,
-                false,
+                true,
             )),
             then_branch: receive_block,
             else_branch: None,
@@ -410,8 +414,8 @@ fn create_device_run_method(temp_converter: &AadlConverter, comp: &ComponentType
     FunctionDef {
         name: "run".to_string(),
         params: vec![Param {
-            name: "self".to_string(),
-            ty: Type::Named("Self".to_string()),
+            name: "".to_string(),
+            ty: Type::Reference(Box::new(Type::Named("self".to_string())), false, true),
         }],
         return_type: Type::Unit,
         body: Block { stmts, expr: None },
@@ -424,20 +428,7 @@ fn create_device_run_method(temp_converter: &AadlConverter, comp: &ComponentType
     }
 }
 
-fn extract_period(comp: &ComponentType) -> Option<u64> {
-    if let PropertyClause::Properties(props) = &comp.properties {
-        for prop in props {
-            if let Property::BasicProperty(bp) = prop {
-                if bp.identifier.name.to_lowercase() == "period" {
-                    if let PropertyValue::Single(PropertyExpression::Integer(
-                        SignedIntergerOrConstant::Real(int_val),
-                    )) = &bp.value
-                    {
-                        return Some(int_val.value as u64);
-                    }
-                }
-            }
-        }
-    }
-    None
+// 设备周期（毫秒），按 Period 的时间单位换算
+fn extract_period(temp_converter: &AadlConverter, comp: &ComponentType) -> Option<u64> {
+    PropertyLookup::for_type(temp_converter, comp).time_ms("period")
 }
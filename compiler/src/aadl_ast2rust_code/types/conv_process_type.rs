@@ -12,19 +12,18 @@ pub fn convert_process_component(
     let mut items = Vec::new();
 
     // 1. 结构体定义
-    let mut fields = temp_converter.convert_type_features(&comp.features, comp.identifier.clone()); //特征列表
-    
-    // 添加 CPU ID 字段
-    fields.push(Field {
+    // 进程端口不生成字段：端口数据经语义连接直接在线程之间传递，只保留 CPU ID 字段
+    let fields = vec![Field {
         name: "cpu_id".to_string(),
         ty: Type::Named("isize".to_string()),
         docs: vec!["// 进程 CPU ID".to_string()],
         attrs: Vec::new(),
-    });
+        vis: Visibility::Public,
+    }];
 
     let struct_def = StructDef {
         name: format!("{}Process", to_upper_camel_case(&comp.identifier)),
-        fields,                                                                   //特征列表
+        fields,
         properties: temp_converter.convert_properties(ComponentRef::Type(comp)), // 属性列表，TODO:这个似乎没有作用，因为目前的例子中进程没有属性
         generics: Vec::new(),
         derives: vec!["Debug".to_string()],
@@ -1,7 +1,7 @@
 #![allow(clippy::collapsible_match)]
 use crate::aadl_ast2rust_code::intermediate_ast::*;
 
-use crate::aadl_ast2rust_code::converter::AadlConverter;
+use crate::aadl_ast2rust_code::converter::{AadlConverter, CodegenBackend};
 use crate::ast::aadl_ast_cj::*;
 
 pub fn convert_subprogram_component(
@@ -85,26 +85,17 @@ fn generate_c_function_wrapper(
                     }
 
                     // 创建包装函数
+                    let param_name = port.identifier.to_string().to_lowercase();
+                    let mut call = CCall::default();
+                    call.push_arg(temp_converter, c_func_name, &param_name, &param_type, true);
                     functions.push(FunctionDef {
                         name: func_name.to_string(),
                         params: vec![Param {
-                            name: port.identifier.to_string().to_lowercase(),
+                            name: param_name,
                             ty: param_type,
                         }],
-                        return_type: Type::Unit,
-                        body: Block {
-                            stmts: vec![Statement::Expr(Expr::Unsafe(Box::new(Block {
-                                stmts: vec![Statement::Expr(Expr::Call(
-                                    Box::new(Expr::Path(
-                                        vec![c_func_name.to_string()],
-                                        PathType::Namespace,
-                                    )),
-                                    vec![Expr::Ident(port.identifier.to_string().to_lowercase())],
-                                ))],
-                                expr: None,
-                            })))],
-                            expr: None,
-                        },
+                        return_type: call.return_type(),
+                        body: call.into_body(c_func_name),
                         asyncness: false,
                         vis: Visibility::Public,
                         docs: vec![
@@ -141,34 +132,23 @@ fn generate_c_function_wrapper(
                                         // 生成一个 call 函数，参数包含全部的数据类型
                                         if !data_types.is_empty() {
                                             let mut params = Vec::new();
-                                            let mut call_args = Vec::new();
+                                            let mut call = CCall::default();
 
                                             for (idx, data_type) in data_types.iter().enumerate() {
                                                 let param_name = format!("arg{}", idx);
+                                                let ty = Type::Reference(Box::new(Type::Named(data_type.clone())), true, true); // &mut DataType
+                                                call.push_arg(temp_converter, c_func_name, &param_name, &ty, false);
                                                 params.push(Param {
-                                                    name: param_name.clone(),
-                                                    ty: Type::Reference(Box::new(Type::Named(data_type.clone())), true, true), // &mut DataType
+                                                    name: param_name,
+                                                    ty,
                                                 });
-                                                call_args.push(Expr::Ident(param_name));
                                             }
 
                                             let call_function = FunctionDef {
                                                 name: "call".to_string(),
                                                 params,
                                                 return_type: Type::Unit,
-                                                body: Block {
-                                                    stmts: vec![Statement::Expr(Expr::Unsafe(Box::new(Block {
-                                                        stmts: vec![Statement::Expr(Expr::Call(
-                                                            Box::new(Expr::Path(
-                                                                vec![c_func_name.to_string()],
-                                                                PathType::Namespace,
-                                                            )),
-                                                            call_args, // 传递所有参数
-                                                        ))],
-                                                        expr: None,
-                                                    })))],
-                                                    expr: None,
-                                                },
+                                                body: call.into_body(c_func_name), // 传递所有参数
                                                 asyncness: false,
                                                 vis: Visibility::Public,
                                                 docs: vec![
@@ -186,7 +166,8 @@ fn generate_c_function_wrapper(
                             }
                         }
                     }
-                } //_ => {} // 忽略其他类型的特征
+                }
+                Feature::Abstract(_) => {} // 未绑定到端口/访问的抽象特征不生成包装函数
             }
         }
     }
@@ -270,6 +251,84 @@ fn generate_c_function_wrapper(
     items
 }
 
+// C 函数调用：参数表达式，以及调用前后的转换语句
+// 带 Representation 的 #[repr] 枚举经其整数类型与 C 交换：按值传入时转换为整数；
+// 按 &mut 传入时先转换为整数交给 C 写回，再转换回枚举。C 写回的编码无效时报告：
+// 输出参数的包装函数返回 Err(编码)，调用方据此不发送；数据访问保持原值
+#[derive(Default)]
+struct CCall {
+    before: Vec<Statement>,
+    args: Vec<Expr>,
+    after: Vec<Statement>,
+    checked: Option<String>, // 编码无效时返回 Err 的枚举的整数类型
+}
+
+impl CCall {
+    fn push_arg(&mut self, temp_converter: &AadlConverter, c_func_name: &str, name: &str, ty: &Type, out_param: bool) {
+        let (inner, by_ref) = match ty {
+            Type::Reference(inner, is_reference, _) => (inner.as_ref(), *is_reference),
+            other => (other, false),
+        };
+        let repr = match inner {
+            Type::Named(type_name) => temp_converter.enum_repr(type_name).map(|repr| (type_name, repr)),
+            _ => None,
+        };
+        let Some((type_name, repr)) = repr else {
+            self.args.push(Expr::Ident(name.to_string()));
+            return;
+        };
+        if by_ref {
+            let raw = format!("{}_raw", name);
+            self.before.push(Statement::Expr(Expr::Ident(format!("let mut {} = {}::from(*{})", raw, repr, name))));
+            self.args.push(Expr::Ident(format!("&mut {} as *mut {} as *mut _", raw, repr)));
+            // 编码无效时的处理；no_std 的嵌入式后端没有标准错误输出，不报告
+            let mut on_invalid = Vec::new();
+            if temp_converter.backend != CodegenBackend::Embedded {
+                on_invalid.push(format!("eprintln!(\"{}: invalid {} discriminant {{}}\", code);", c_func_name, type_name));
+            }
+            if out_param {
+                on_invalid.push("return Err(code);".to_string());
+            }
+            let invalid = if on_invalid.is_empty() {
+                "Err(_) => {}".to_string()
+            } else {
+                format!("Err(code) => {{ {} }}", on_invalid.join(" "))
+            };
+            self.after.push(Statement::Expr(Expr::Ident(format!(
+                "match {}::try_from({}) {{ Ok(value) => {{ *{} = value; }} {} }}",
+                type_name, raw, name, invalid
+            ))));
+            if out_param {
+                self.checked = Some(repr);
+            }
+        } else {
+            self.args.push(Expr::Ident(format!("{}::from({}) as _", repr, name)));
+        }
+    }
+
+    fn return_type(&self) -> Type {
+        match &self.checked {
+            Some(repr) => Type::Named(format!("Result<(), {}>", repr)),
+            None => Type::Unit,
+        }
+    }
+
+    fn into_body(self, c_func_name: &str) -> Block {
+        let call = Statement::Expr(Expr::Unsafe(Box::new(Block {
+            stmts: vec![Statement::Expr(Expr::Call(
+                Box::new(Expr::Path(vec![c_func_name.to_string()], PathType::Namespace)),
+                self.args,
+            ))],
+            expr: None,
+        })));
+        let mut stmts: Vec<Statement> = self.before.into_iter().chain(std::iter::once(call)).chain(self.after).collect();
+        if self.checked.is_some() {
+            stmts.push(Statement::Expr(Expr::Ident("return Ok(())".to_string())));
+        }
+        Block { stmts, expr: None }
+    }
+}
+
 fn extract_source_files(comp: &ComponentType) -> Vec<String> {
     let mut source_files = Vec::new();
 
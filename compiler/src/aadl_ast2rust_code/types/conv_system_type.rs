@@ -15,7 +15,9 @@ pub fn convert_system_component(temp_converter: &AadlConverter, comp: &Component
         properties: temp_converter.convert_properties(ComponentRef::Type(comp)), //TODO:这里似乎不需要
         generics: Vec::new(),
         derives: vec!["Debug".to_string()],
-        docs: vec![format!("// AADL System: {}", comp.identifier)],
+        docs: std::iter::once(format!("// AADL System: {}", comp.identifier))
+            .chain(source_location_doc(&comp.location))
+            .collect(),
         vis: Visibility::Public,
     };
     items.push(Item::Struct(struct_def));
@@ -39,12 +39,13 @@ pub fn convert_thread_component(
             if let Property::BasicProperty(bp) = prop {
                 if let Some(val) = temp_converter.parse_property_value(&bp.value) {
                     let name_lc = bp.identifier.name.to_lowercase();
-                    let ty_name = temp_converter.type_for_property(&val);
+                    let ty_name = temp_converter.property_field_type(&bp.identifier, &val);
                     fields.push(Field {
                         name: name_lc.clone(),
                         ty: Type::Named(ty_name),
-                        docs: vec![format!("// AADL属性: {}", bp.identifier.name)],
+                        docs: vec![temp_converter.property_field_doc("AADL属性", &bp.identifier.name, &val)],
                         attrs: Vec::new(),
+                        vis: Visibility::Public,
                     });
                     value_map.insert(name_lc, val);
                 }
@@ -57,6 +58,7 @@ pub fn convert_thread_component(
         ty: Type::Named("isize".to_string()),
         docs: vec!["// 结构体新增 CPU ID".to_string()],
         attrs: Vec::new(),
+        vis: Visibility::Public,
     });
 
     let struct_name = format!("{}Thread", to_upper_camel_case(&comp.identifier));
@@ -1,6 +1,8 @@
 pub mod conv_data_type;
+pub mod conv_data_range;
+pub mod conv_data_fixed;
 pub mod conv_process_type;
 pub mod conv_thread_type;
 pub mod conv_system_type;
 pub mod conv_device_type;
-pub mod conv_subprogram_type;
\ No newline at end of file
+pub mod conv_subprogram_type;pub mod conv_bus_type;
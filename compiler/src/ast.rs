@@ -79,18 +79,35 @@ pub mod aadl_ast_cj {
         Impl(&'a ComponentImplementation),
     }
 
+    // 声明在 AADL 源文件中的位置，用于诊断与生成代码中的来源注释
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct SourceLocation {
+        pub file: String,
+        pub line: usize,
+        pub col: usize,
+    }
+
+    impl std::fmt::Display for SourceLocation {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}:{}:{}", self.file, self.line, self.col)
+        }
+    }
+
     /* ========== 4.3 Component Types ========== */
     // 组件类型定义
     #[derive(Debug, Clone)]
     pub struct ComponentType {
         pub category: ComponentCategory,
         pub identifier: String,
-        pub prototypes: PrototypeClause, //“原型”，暂没见过
+        // extends 时给出的原型绑定，由 prototype 在转换结束后代入
+        pub prototype_bindings: Option<PrototypeBindings>,
+        pub prototypes: PrototypeClause,
         pub features: FeatureClause,
         //pub flows: FlowClause,
         //pub modes: Option<ModesClause>,
         pub properties: PropertyClause,
         pub annexes: Vec<AnnexSubclause>,
+        pub location: Option<SourceLocation>,
     }
 
     //sTODO 4.8 Annex Subclauses and Annex Libraries
@@ -936,7 +953,7 @@ pub mod aadl_ast_cj {
     pub enum PrototypeClause {
         None,  // 无prototypes子句
         Empty, // prototypes none;
-        Items(Vec<Prototype>),
+        Items(Vec<PrototypeDeclaration>),
     }
 
     #[derive(Debug, Clone)]
@@ -962,10 +979,13 @@ pub mod aadl_ast_cj {
         pub prototype_bindings: Option<PrototypeBindings>,
         pub prototypes: PrototypeClause,
         pub features: FeatureClause,
+        // `refined to` 精化的继承特征（完整的特征声明，按名称替换祖先中的同名特征）
+        pub feature_refinements: Vec<Feature>,
         //pub flows: FlowClause,
         //pub modes: Option<ModesClause>,
         pub properties: PropertyClause,
         pub annexes: Vec<AnnexSubclause>,
+        pub location: Option<SourceLocation>,
     }
 
     // #[derive(Debug, Clone)]
@@ -1027,6 +1047,7 @@ pub mod aadl_ast_cj {
         //pub modes: Option<ModesClause>,
         pub properties: PropertyClause,
         pub annexes: Vec<AnnexSubclause>,
+        pub location: Option<SourceLocation>,
     }
 
     // 组件实现名称（type_id.impl_id）
@@ -1057,14 +1078,17 @@ pub mod aadl_ast_cj {
         pub prototype_bindings: Option<PrototypeBindings>,
         pub prototypes: PrototypeClause,
         pub subcomponents: SubcomponentClause,
+        pub subcomponent_refinements: Vec<SubcomponentRefinement>,
         //pub internal_features: Vec<InternalFeature>,
         //pub processor_features: Vec<ProcessorFeature>,
         pub calls: CallSequenceClause,
         pub connections: ConnectionClause,
+        pub connection_refinements: Vec<ConnectionRefinement>,
         //pub flows: FlowImplementationClause,
         //pub modes: Option<ModesClause>,
         pub properties: PropertyClause,
         pub annexes: Vec<AnnexSubclause>,
+        pub location: Option<SourceLocation>,
     }
 
     // 唯一的组件实现引用（可能带包前缀）
@@ -1080,7 +1104,6 @@ pub mod aadl_ast_cj {
         None,
         Empty,
         Items(Vec<Subcomponent>),
-        Refinements(Vec<SubcomponentRefinement>),
     }
 
     #[derive(Debug, Clone)]
@@ -1095,7 +1118,6 @@ pub mod aadl_ast_cj {
         None,
         Empty,
         Items(Vec<Connection>),
-        Refinements(Vec<ConnectionRefinement>),
     }
 
     // #[derive(Debug, Clone)]
@@ -1108,9 +1130,10 @@ pub mod aadl_ast_cj {
     // }
 
     #[derive(Debug, Clone)]
+    // 连接精化只能补充属性：记录被精化的连接名与精化声明上的 { 属性 }
     pub struct ConnectionRefinement {
         pub original_name: String,
-        pub refinement: Connection,
+        pub properties: Vec<Property>,
     }
 
     // #[derive(Debug, Clone)]
@@ -1125,6 +1148,8 @@ pub mod aadl_ast_cj {
         pub identifier: String,
         pub category: ComponentCategory,
         pub classifier: SubcomponentClassifier,
+        // 分类器引用后的 ( 原型 => 实际值 )，实例化时据此生成特化的分类器
+        pub prototype_bindings: Option<PrototypeBindings>,
         pub array_spec: Option<ArraySpec>,
         pub properties: Vec<Property>,
         //pub modes: Option<ComponentInModes>
@@ -1148,6 +1173,7 @@ pub mod aadl_ast_cj {
         pub identifier: String,
         pub category: ComponentCategory,
         pub classifier: Option<SubcomponentClassifier>, // refined to可能省略引用
+        pub prototype_bindings: Option<PrototypeBindings>,
         pub array_spec: Option<ArraySpec>,
         pub properties: Vec<Property>,
         //pub modes: Option<ComponentInModes>
@@ -1364,8 +1390,8 @@ pub mod aadl_ast_cj {
     //TODO:目前只考虑port,例子在Notion中有图片
     #[derive(Debug, Clone)]
     pub enum Feature {
-        // 抽象特征 (abstract_feature_spec)
-        //Abstract(AbstractFeature),
+        // 抽象特征 (abstract_feature_spec)，通常由特征原型给出实际的端口/访问
+        Abstract(AbstractFeatureSpec),
 
         // 端口 (port_spec)
         Port(PortSpec),
@@ -1380,6 +1406,14 @@ pub mod aadl_ast_cj {
         // 精化特征 (feature_refinement)
         //Refinement(FeatureRefinement)
     }
+    /// 对应标准中的 `abstract_feature_spec`
+    #[derive(Debug, Clone)]
+    pub struct AbstractFeatureSpec {
+        pub identifier: String,
+        pub direction: Option<PortDirection>,
+        /// `feature_prototype_identifier`，实例化时替换为绑定的端口或访问特征
+        pub prototype: Option<String>,
+    }
     /* ========== 端口类型 ========== */
     /// 对应标准中的 `port_type`
     #[derive(Debug, Clone)]
@@ -1419,7 +1453,11 @@ pub mod aadl_ast_cj {
         Data(DataAccessSpec),
         /// 子程序访问 (subprogram_access_spec)
         Subprogram(SubprogramAccessSpec),
-        // TODO: SubprogramGroup, Bus, VirtualBus
+        /// 总线访问 (bus_access_spec)
+        Bus(BusAccessSpec),
+        /// 虚拟总线访问 (virtual_bus_access_spec)
+        VirtualBus(BusAccessSpec),
+        // TODO: SubprogramGroup
     }
 
     /* ========== 访问特征 ========== */
@@ -1442,6 +1480,16 @@ pub mod aadl_ast_cj {
         Prototype(String),
     }
 
+    /// 总线/虚拟总线访问规范 (bus_access_spec / virtual_bus_access_spec)
+    #[derive(Debug, Clone)]
+    pub struct BusAccessSpec {
+        /// `defining_bus_access_identifier`
+        pub identifier: String,
+        pub direction: AccessDirection, // provides | requires
+        /// `bus_unique_component_classifier_reference`
+        pub classifier: Option<UniqueComponentClassifierReference>,
+    }
+
     /// 子程序访问规范 (subprogram_access_spec)
     #[derive(Debug, Clone)]
     pub struct SubprogramAccessSpec {
@@ -1472,7 +1520,7 @@ pub mod aadl_ast_cj {
         Parameter(ParameterConnection),
         // 以下为其他连接类型（暂不实现）
         // Feature(FeatureConnection),      // feature_connection
-        Access(AccessConnection),       // access_connection（仅关注 data/subprogram）
+        Access(AccessConnection),       // access_connection（data/subprogram/bus/virtual bus）
         // FeatureGroup(FeatureGroupConnection), // feature_group_connection
     }
 
@@ -1493,6 +1541,8 @@ pub mod aadl_ast_cj {
         pub source: PortEndpoint,
         pub destination: PortEndpoint,
         pub connection_direction: ConnectionSymbol,
+        // 连接声明上的 { 属性 }，如子组件数组间的 Connection_Pattern
+        pub properties: Vec<Property>,
     }
     // 对应标准中的 `port_connection_reference`
     #[derive(Debug, Clone)]
@@ -1558,13 +1608,16 @@ pub mod aadl_ast_cj {
     /// 对应标准中的 `access_connection`
     #[derive(Debug, Clone)]
     pub struct AccessConnection {
+        pub identifier: String,
+        pub connection_type: AccessConnectionType, // data/subprogram/bus/virtual bus
         pub source: AccessEndpoint,
         pub destination: AccessEndpoint,
         pub connection_direction: ConnectionSymbol,
+        pub properties: Vec<Property>,
     }
 
     /// 对应标准中的 `source_access_reference` / `destination_access_reference`
-    /// 仅覆盖 data/subprogram 两类的常见引用形式
+    /// 覆盖 data/subprogram/bus 访问的常见引用形式（总线端点可以直接是总线子组件）
     #[derive(Debug, Clone)]
     pub enum AccessEndpoint {
         /// 组件类型上的访问特征标识符
@@ -1620,10 +1673,68 @@ pub mod aadl_ast_cj {
         BasicProperty(BasicPropertyAssociation),
         SubcomponentProperty(BasicPropertyAssociation), //TODO:暂时使用basic代替
         CallSequenceProperty(BasicPropertyAssociation),
-        //ContainedProperty(ContainedAssociation), //简化到BasicPropertyAssociation中PropertyValue中PropertyExpression里有Apply
+        /// 带 applies to / in binding / in modes 的关联：不直接作用于声明所在的分类器，由实例模型解析到目标实例
+        ContainedProperty(BasicPropertyAssociation),
         // 未来可扩展其他属性类型：
     }
 
+    /*==============11.1 属性集=============== */
+    /// 属性集只在模型加载阶段解析（见 model_loader），提供属性定义的类型、inherit、缺省值与常量
+    #[derive(Debug, Clone)]
+    pub struct PropertySet {
+        pub name: String,
+        pub types: Vec<PropertyTypeDeclaration>,
+        pub definitions: Vec<PropertyDefinition>,
+        pub constants: Vec<PropertyConstant>,
+    }
+
+    /// Name: type <属性类型>;
+    #[derive(Debug, Clone)]
+    pub struct PropertyTypeDeclaration {
+        pub name: String,
+        pub property_type: PropertyType,
+    }
+
+    /// Name: [inherit] <属性类型> [=> 缺省值] applies to (...);
+    #[derive(Debug, Clone)]
+    pub struct PropertyDefinition {
+        pub name: String,
+        pub inherit: bool,
+        pub property_type: PropertyType,
+        pub default: Option<PropertyDefault>,
+        pub applies_to: Vec<String>,
+    }
+
+    #[derive(Debug, Clone)]
+    pub enum PropertyDefault {
+        Value(PropertyValue),
+        ValueOf(String), // => value(Period)，取另一属性的值
+    }
+
+    /// Name: constant <属性类型> [单位] => 取值;
+    #[derive(Debug, Clone)]
+    pub struct PropertyConstant {
+        pub name: String,
+        pub property_type: PropertyType,
+        pub value: Option<PropertyValue>, // 取值不符合属性值语法（如 2#1#e32 这类基数字面量）时为 None
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum PropertyType {
+        Boolean,
+        String,
+        Integer,
+        Real,
+        Enumeration(Vec<String>),
+        Units(Vec<String>),
+        Range(Box<PropertyType>),
+        List(Box<PropertyType>),
+        Record(Vec<(String, PropertyType)>),
+        Classifier,
+        Reference,
+        Named(String), // 引用属性类型声明，如 Time、AADL_Project::Time_Units
+    }
+
     /* ========== 基础属性关联 ========== */
     #[derive(Debug, Clone)]
     pub struct BasicPropertyAssociation {
@@ -1638,6 +1749,15 @@ pub mod aadl_ast_cj {
 
         /// 属性值 (property_value)
         pub value: PropertyValue,
+
+        /// applies to 的目标路径 (contained_model_element_path)，点分形式，可带数组下标，如 p1.t1、sensors[3].f
+        pub applies_to: Vec<String>,
+
+        /// in binding ( ... ) 中的分类器，仅当目标绑定到这些平台组件时生效
+        pub in_binding: Vec<String>,
+
+        /// in modes ( ... ) 中的模式名
+        pub in_modes: Vec<String>,
     }
     #[derive(Debug, Clone)]
     pub struct PropertyIdentifier {
@@ -1650,11 +1770,6 @@ pub mod aadl_ast_cj {
         Assign, // =>
         Append, // +=>
     }
-    // #[derive(Debug, Clone)]
-    // pub struct ContainedAssociation {
-    //     pub contained_element_point:String,
-    //     pub contained_element:String,
-    // }
     /* ========== 属性值系统 ========== */
     #[derive(Debug, Clone)]
     pub enum PropertyValue {
@@ -1686,9 +1801,8 @@ pub mod aadl_ast_cj {
         //PropertyReference(PropertyTerm),
         ComponentClassifier(ComponentClassifierTerm),
         Reference(ReferenceTerm),
-        //Record(RecordTerm),
-        //Computed(ComputedTerm),
-        Apply(ApplyTerm), //contained_property_association（做了简化处理）
+        Record(RecordTerm),
+        Computed(ComputedTerm),
     }
     /* ========== 属性常量项 ========== */
     #[derive(Debug, Clone)]
@@ -1778,8 +1892,9 @@ pub mod aadl_ast_cj {
 
     #[derive(Debug, Clone)]
     pub struct IntegerRangeTerm {
-        pub lower: StringWithUnit,
+        pub lower: StringWithUnit, // 数值或属性常量名（可带符号），如 "-10"、"AADL_Project::Max_Time"
         pub upper: StringWithUnit,
+        pub delta: Option<StringWithUnit>, // delta 子句
     }
 
     #[derive(Debug, Clone)]
@@ -1790,12 +1905,30 @@ pub mod aadl_ast_cj {
 
     /* ========== 最小引用定义，支持 reference(identifier) ========== */
     /// AADL: reference ( contained_model_element_path )
-    /// 为满足 `reference (cpu) applies to node_a` 的需求，保存引用标识符和可选的 applies_to 目标。
+    /// 保存被引用元素的路径（相对于属性关联所在的组件），applies to 子句记录在属性关联上
     #[derive(Debug, Clone)]
     pub struct ReferenceTerm {
         pub identifier: String,
-        /// 可选的 applies to 子句，如 `applies to node_a`
-        pub applies_to: Option<String>,
+    }
+
+    /* ========== 记录值 ========== */
+    /// AADL: [ field_identifier => property_expression ; ... ]
+    #[derive(Debug, Clone)]
+    pub struct RecordTerm {
+        pub fields: Vec<RecordField>,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct RecordField {
+        pub name: String,
+        pub value: PropertyValue,
+    }
+
+    /* ========== 计算值 ========== */
+    /// AADL: compute ( function_identifier )，取值由运行时函数给出
+    #[derive(Debug, Clone)]
+    pub struct ComputedTerm {
+        pub function: String,
     }
 
     //为满足Data_Model::Base_Type => classifier (Base_Types::Integer); 的需求，保存组件分类器引用
@@ -1803,9 +1936,4 @@ pub mod aadl_ast_cj {
     pub struct ComponentClassifierTerm {
         pub unique_component_classifier_reference: UniqueComponentClassifierReference,
     }
-    #[derive(Debug, Clone)]
-    pub struct ApplyTerm {
-        pub number: String,
-        pub applies_to: String,
-    }
 } //end mod aadl_ast_cj
@@ -1,3 +1,4 @@
+use crate::aadl_ast2rust_code::converter::CodegenBackend;
 use regex::Regex;
 use std::fs;
 use std::path::Path;
@@ -10,12 +11,20 @@ pub struct TestCase {
     pub output_name: String,
 }
 
-pub fn assemble_rust_project(test_case: &TestCase) {
+pub fn assemble_rust_project(test_case: &TestCase, backend: CodegenBackend) {
     // ---------------- 项目根目录 ----------------
     let project_root = format!("generate/project/{}", test_case.output_name);
 
     // ---------------- Cargo.toml ----------------
-    generate_cargo_toml(&project_root, &test_case.output_name);
+    // 只有生成的代码用到定点数据类型时才依赖 fixed，用到有界字符串时才依赖 heapless（嵌入式后端总是依赖）
+    let fixed_point = src_uses(&project_root, "fixed::Fixed", "");
+    if backend == CodegenBackend::Embedded {
+        generate_embedded_cargo_toml(&project_root, &test_case.output_name, fixed_point);
+    } else {
+        let distributed = src_uses(&project_root, "crate::net::", "net.rs");
+        let bounded = src_uses(&project_root, "heapless::", "");
+        generate_cargo_toml(&project_root, &test_case.output_name, backend, distributed, fixed_point, bounded);
+    }
 
     // ---------------- C / H 文件拷贝 ----------------
     copy_c_sources(&test_case.path, &project_root);
@@ -44,13 +53,26 @@ pub fn assemble_rust_project(test_case: &TestCase) {
     if c_files.is_empty() || h_files.is_empty() {
         generate_empty_build_rs(&project_root);
     } else {
-        generate_build_rs_from_c_files(&project_root, &c_files, &h_files);
+        generate_build_rs_from_c_files(&project_root, &c_files, &h_files, backend == CodegenBackend::Embedded);
+    }
+
+    // ---------------- 嵌入式后端：no_std 库工程，入口由固件提供 ----------------
+    if backend == CodegenBackend::Embedded {
+        generate_rtc_rs(&project_root);
+        generate_range_rs(&project_root, true);
+        generate_lib_rs(&project_root, true);
+        println!("📦 项目生成完成(no_std): {}", project_root);
+        return;
     }
 
     // ---------------- Rust support files ----------------
     generate_common_traits_rs(&project_root);
     generate_posix_rs(&project_root);
-    generate_lib_rs(&project_root);
+    let has_bus = generate_bus_rs(&project_root, backend);
+    let distributed = generate_net_rs(&project_root, backend);
+    generate_codec_rs(&project_root); // net.rs 也使用 crate::codec，在其后生成
+    generate_range_rs(&project_root, false);
+    generate_lib_rs(&project_root, false);
 
     // ---------------- main.rs ----------------
     let (module_name, system_type) =
@@ -61,20 +83,53 @@ pub fn assemble_rust_project(test_case: &TestCase) {
         &system_type,
         &module_name,
         &test_case.output_name,
+        backend,
+        has_bus,
     );
 
+    // ---------------- 分布式部署：各节点的可执行文件与本地启动器 ----------------
+    if distributed {
+        generate_node_bins(
+            &project_root,
+            &system_type,
+            &module_name,
+            &test_case.output_name,
+            backend,
+            has_bus,
+        );
+    }
+
     println!("📦 项目生成完成: {}", project_root);
 }
 
 /// 生成 Cargo.toml
-fn generate_cargo_toml(project_root: &str, project_name: &str) {
+fn generate_cargo_toml(
+    project_root: &str,
+    project_name: &str,
+    backend: CodegenBackend,
+    distributed: bool,
+    fixed_point: bool,
+    bounded: bool,
+) {
+    // 异步后端需要 tokio 运行时、定时器与 #[tokio::main]
+    let tokio_features = match backend {
+        CodegenBackend::Async => r#"["sync", "rt-multi-thread", "macros", "time"]"#,
+        _ => r#"["sync"]"#,
+    };
+    // 分布式部署另有 src/bin 下的节点与启动器，cargo run 默认仍运行单进程的 main.rs
+    let default_run = if distributed {
+        format!("default-run = \"{}\"\n", project_name.replace('-', "_"))
+    } else {
+        String::new()
+    };
+
     let cargo_toml = format!(
         r#"[package]
 name = "{}"
 version = "0.1.0"
 edition = "2021"
 build = "build.rs"
-
+{}
 [build-dependencies]
 cc = {{ version = "1.0", features = ["parallel"] }}
 bindgen = "0.69"
@@ -84,9 +139,52 @@ libc = "0.2"
 lazy_static = "1.4"
 crossbeam-channel = "0.5"
 rand = "0.7"
-tokio = {{ version = "1.40", features = ["sync"] }}
+tokio = {{ version = "1.40", features = {} }}
+{}{}"#,
+        project_name.replace('-', "_"),
+        default_run,
+        tokio_features,
+        fixed_dependency(fixed_point),
+        if bounded { "heapless = \"0.8\"\n" } else { "" }
+    );
+
+    fs::write(format!("{}/Cargo.toml", project_root), cargo_toml)
+        .expect("Failed to write Cargo.toml");
+}
+
+/// 定点数据类型使用的 fixed 依赖（fixed 本身支持 no_std）
+fn fixed_dependency(fixed_point: bool) -> &'static str {
+    if fixed_point {
+        "fixed = \"1.24\"\n"
+    } else {
+        ""
+    }
+}
+
+/// 生成嵌入式后端的 Cargo.toml：只依赖 heapless（及定点数所需的 fixed），panic 时直接中止
+fn generate_embedded_cargo_toml(project_root: &str, project_name: &str, fixed_point: bool) {
+    let cargo_toml = format!(
+        r#"[package]
+name = "{}"
+version = "0.1.0"
+edition = "2021"
+build = "build.rs"
+
+[build-dependencies]
+cc = {{ version = "1.0", features = ["parallel"] }}
+bindgen = "0.69"
+
+[dependencies]
+heapless = "0.8"
+{}
+[profile.dev]
+panic = "abort"
+
+[profile.release]
+panic = "abort"
 "#,
-        project_name.replace('-', "_")
+        project_name.replace('-', "_"),
+        fixed_dependency(fixed_point)
     );
 
     fs::write(format!("{}/Cargo.toml", project_root), cargo_toml)
@@ -127,7 +225,7 @@ fn copy_c_sources(input_dir: &str, project_root: &str) {
 }
 
 /// 生成 build.rs
-fn generate_build_rs_from_c_files(project_root: &str, c_files: &[String], h_files: &[String]) {
+fn generate_build_rs_from_c_files(project_root: &str, c_files: &[String], h_files: &[String], no_std: bool) {
     let mut build_rs = String::new();
 
     build_rs.push_str("fn main() {\n");
@@ -164,6 +262,10 @@ fn generate_build_rs_from_c_files(project_root: &str, c_files: &[String], h_file
     for h in h_files {
         build_rs.push_str("    bindgen::Builder::default()\n");
         build_rs.push_str(&format!("        .header(\"c_include/{}\")\n", h));
+        if no_std {
+            // no_std：绑定只能引用 core 中的类型
+            build_rs.push_str("        .use_core()\n");
+        }
         build_rs.push_str(
             r#"
             .clang_arg("-Ic_include")
@@ -266,13 +368,143 @@ fn generate_posix_rs(project_root: &str) {
 
 // ---------------- cpu ----------------
 
+// 须在切换到 SCHED_DEADLINE 之前调用，内核拒绝修改 SCHED_DEADLINE 线程的亲和性
 pub fn set_thread_affinity(cpu: isize) {
-    unsafe {
+    let ret = unsafe {
         let mut cpuset: cpu_set_t = std::mem::zeroed();
         CPU_ZERO(&mut cpuset);
         CPU_SET(cpu as usize, &mut cpuset);
-        sched_setaffinity(0, std::mem::size_of::<cpu_set_t>(), &cpuset);
+        sched_setaffinity(0, std::mem::size_of::<cpu_set_t>(), &cpuset)
+    };
+    if ret != 0 {
+        eprintln!("failed to pin thread to CPU {}: {}", cpu, std::io::Error::last_os_error());
+    }
+}
+
+// ---------------- epoch ----------------
+
+// 系统公共时间基准：System::run 设置，周期线程据此计算 epoch + offset + k*period
+static SYSTEM_EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+
+pub fn set_system_epoch(epoch: std::time::Instant) {
+    let _ = SYSTEM_EPOCH.set(epoch);
+}
+
+// 未经 System::run 启动时（如单独运行进程），以首次调用时刻作为基准
+pub fn system_epoch() -> std::time::Instant {
+    *SYSTEM_EPOCH.get_or_init(std::time::Instant::now)
+}
+
+// ---------------- sched ----------------
+
+// libc 未导出 SCHED_DEADLINE，取值见 linux/sched.h
+pub const SCHED_DEADLINE: i32 = 6;
+
+// sched_setattr(2) 的参数结构，时间单位为纳秒
+#[repr(C)]
+struct SchedAttr {
+    size: u32,
+    sched_policy: u32,
+    sched_flags: u64,
+    sched_nice: i32,
+    sched_priority: u32,
+    sched_runtime: u64,
+    sched_deadline: u64,
+    sched_period: u64,
+}
+
+// 线程的 EDF 时间参数（毫秒），分别来自 Compute_Execution_Time、Deadline、Period
+#[derive(Debug, Clone, Copy)]
+pub struct SchedTiming {
+    pub runtime_ms: u64,
+    pub deadline_ms: u64,
+    pub period_ms: u64,
+}
+
+// 按所在处理器的调度策略配置当前线程：
+// SCHED_DEADLINE 走 sched_setattr，SCHED_RR 先核对时间片，其余走 pthread_setschedparam。
+// 失败时（通常是缺少 CAP_SYS_NICE）打印诊断并回退，线程照常运行
+pub fn apply_thread_sched(thread: &str, policy: i32, priority: i32, timing: SchedTiming, rr_quantum_ms: Option<u64>) {
+    if policy == SCHED_DEADLINE {
+        match set_deadline_sched(timing) {
+            Ok(()) => return,
+            Err(err) => eprintln!(
+                "{}: SCHED_DEADLINE (runtime={}ms, deadline={}ms, period={}ms) unavailable: {}; falling back to SCHED_FIFO",
+                thread, timing.runtime_ms, timing.deadline_ms, timing.period_ms, err
+            ),
+        }
+        set_fixed_priority_sched(thread, libc::SCHED_FIFO, priority);
+        return;
     }
+    if policy == libc::SCHED_RR {
+        if let Some(ms) = rr_quantum_ms {
+            set_rr_timeslice(thread, ms);
+        }
+    }
+    set_fixed_priority_sched(thread, policy, priority);
+}
+
+fn set_deadline_sched(timing: SchedTiming) -> std::io::Result<()> {
+    // 内核要求 0 < runtime <= deadline <= period
+    if timing.runtime_ms == 0 || timing.runtime_ms > timing.deadline_ms || timing.deadline_ms > timing.period_ms {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "requires 0 < Compute_Execution_Time <= Deadline <= Period",
+        ));
+    }
+    let attr = SchedAttr {
+        size: std::mem::size_of::<SchedAttr>() as u32,
+        sched_policy: SCHED_DEADLINE as u32,
+        sched_flags: 0,
+        sched_nice: 0,
+        sched_priority: 0,
+        sched_runtime: timing.runtime_ms * 1_000_000,
+        sched_deadline: timing.deadline_ms * 1_000_000,
+        sched_period: timing.period_ms * 1_000_000,
+    };
+    let ret = unsafe { libc::syscall(libc::SYS_sched_setattr, 0, &attr as *const SchedAttr, 0) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+fn set_fixed_priority_sched(thread: &str, policy: i32, priority: i32) {
+    let param = libc::sched_param { sched_priority: priority };
+    let ret = unsafe { libc::pthread_setschedparam(libc::pthread_self(), policy, &param) };
+    if ret != 0 {
+        eprintln!(
+            "{}: failed to set scheduling policy {} with priority {}: {}; running under the default time-sharing policy",
+            thread, policy, priority, std::io::Error::from_raw_os_error(ret)
+        );
+    }
+}
+
+// Linux 的 SCHED_RR 时间片是系统级参数（/proc/sys/kernel/sched_rr_timeslice_ms），修改后在应用退出后仍然生效，
+// 因此默认不修改，只在内核时间片与 Scheduler_Quantum 不一致时提示一次；
+// 设置环境变量 AADL_SET_RR_TIMESLICE=1 显式同意后才写入（需要 root，以首个设置的线程为准）
+fn set_rr_timeslice(thread: &str, ms: u64) {
+    const TIMESLICE_PATH: &str = "/proc/sys/kernel/sched_rr_timeslice_ms";
+    static RR_TIMESLICE: std::sync::Once = std::sync::Once::new();
+    RR_TIMESLICE.call_once(|| {
+        let current = std::fs::read_to_string(TIMESLICE_PATH).ok().and_then(|s| s.trim().parse::<u64>().ok());
+        if current == Some(ms) {
+            return;
+        }
+        if std::env::var("AADL_SET_RR_TIMESLICE").as_deref() != Ok("1") {
+            eprintln!(
+                "{}: Scheduler_Quantum is {}ms but the kernel SCHED_RR time slice is {}; set AADL_SET_RR_TIMESLICE=1 to change the system-wide value",
+                thread,
+                ms,
+                current.map_or("unknown".to_string(), |c| format!("{}ms", c))
+            );
+            return;
+        }
+        if let Err(err) = std::fs::write(TIMESLICE_PATH, ms.to_string()) {
+            eprintln!("{}: failed to set SCHED_RR time slice to {}ms: {}; keeping the kernel default", thread, ms, err);
+        }
+    });
 }
 "#;
 
@@ -281,8 +513,1396 @@ pub fn set_thread_affinity(cpu: isize) {
     println!("posix.rs 已生成: {}", path);
 }
 
+/// 生成 src/bus.rs（仅当端口连接经 Actual_Connection_Binding 绑定到总线时），返回是否生成
+fn generate_bus_rs(project_root: &str, backend: CodegenBackend) -> bool {
+    let src_dir = format!("{}/src", project_root);
+    let path = format!("{}/bus.rs", src_dir);
+
+    if !src_uses(project_root, "crate::bus::", "bus.rs") {
+        let _ = fs::remove_file(&path); // 清理上次生成残留的总线模块
+        return false;
+    }
+
+    // 两个后端共用的部分：仿真参数、统计与报告
+    let common = r#"use rand::Rng;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::codec::Codec;
+
+// ---------------- bus ----------------
+
+// 总线仿真：绑定到同一总线的端口连接经它转发，用于在单机上做集成测试。
+// 每条总线只有一个传输介质，消息按到达总线的顺序逐条发送：
+//   发送耗时 = Transmission_Time.Fixed + Transmission_Time.Per_Byte * 字节数 + 字节数 / Bandwidth
+// 字节数为消息经 crate::codec::Codec 编码后的长度。
+// 发送完成后再经过 Latency 交付接收端；区间参数对每条消息在区间内随机取值，交付顺序与到达顺序一致。
+
+type Delivery = Box<dyn FnOnce() + Send>;
+
+// 帧长取消息的编码长度（与 Data_Size / 编码属性一致）；编码失败时按内存大小计
+fn frame_bytes<T: Codec>(msg: &T) -> usize {
+    msg.to_bytes().map(|bytes| bytes.len()).unwrap_or(std::mem::size_of::<T>())
+}
+
+struct Frame {
+    arrival: Instant,
+    bytes: usize,
+    deliver: Delivery,
+}
+
+// 仿真参数，时间均以纳秒计
+#[derive(Debug, Clone, Copy, Default)]
+struct BusTiming {
+    latency: (u64, u64),
+    fixed: (u64, u64),
+    per_byte: (u64, u64),
+    bytes_per_sec: Option<u64>,
+}
+
+impl BusTiming {
+    fn sample(range: (u64, u64)) -> u64 {
+        let (min, max) = range;
+        if max > min {
+            rand::thread_rng().gen_range(min, max + 1)
+        } else {
+            min
+        }
+    }
+
+    // 一条消息占用介质的时间
+    fn transmission(&self, bytes: usize) -> Duration {
+        let bytes = bytes as u64;
+        let mut ns = Self::sample(self.fixed) + Self::sample(self.per_byte) * bytes;
+        if let Some(rate) = self.bytes_per_sec {
+            ns += bytes * 1_000_000_000 / rate.max(1);
+        }
+        Duration::from_nanos(ns)
+    }
+
+    fn latency(&self) -> Duration {
+        Duration::from_nanos(Self::sample(self.latency))
+    }
+}
+
+// 单条总线的统计
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BusStats {
+    pub messages: u64,
+    pub bytes: u64,
+    pub busy: Duration,         // 介质累计占用时间
+    pub max_queueing: Duration, // 到达后等待介质空闲的最长时间
+    pub delivered: u64,
+    pub total_delay: Duration, // 到达总线至交付接收端
+    pub max_delay: Duration,
+}
+
+impl BusStats {
+    pub fn mean_delay(&self) -> Duration {
+        if self.delivered == 0 {
+            Duration::ZERO
+        } else {
+            self.total_delay / self.delivered as u32
+        }
+    }
+
+    fn record_delivery(&mut self, delay: Duration) {
+        self.delivered += 1;
+        self.total_delay += delay;
+        self.max_delay = self.max_delay.max(delay);
+    }
+}
+
+struct Registered {
+    name: &'static str,
+    created: Instant,
+    stats: Arc<Mutex<BusStats>>,
+}
+
+static BUSES: Mutex<Vec<Registered>> = Mutex::new(Vec::new());
+
+// 各总线的统计快照：(名称, 统计, 介质利用率)
+pub fn stats() -> Vec<(&'static str, BusStats, f64)> {
+    BUSES
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|bus| {
+            let stats = *bus.stats.lock().unwrap();
+            let elapsed = bus.created.elapsed().as_secs_f64();
+            let utilization = if elapsed > 0.0 { stats.busy.as_secs_f64() / elapsed } else { 0.0 };
+            (bus.name, stats, utilization)
+        })
+        .collect()
+}
+
+// 打印各总线的统计
+pub fn report() {
+    for (name, stats, utilization) in stats() {
+        println!(
+            "[bus {}] messages={} bytes={} utilization={:.2}% max_queueing={:?} mean_delay={:?} max_delay={:?}",
+            name,
+            stats.messages,
+            stats.bytes,
+            utilization * 100.0,
+            stats.max_queueing,
+            stats.mean_delay(),
+            stats.max_delay
+        );
+    }
+}
+"#;
+
+    // 异步后端的介质与交付在 tokio 任务中进行，其余后端使用独立线程
+    let transport = if backend == CodegenBackend::Async {
+        r#"
+use tokio::sync::broadcast;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+pub struct Bus {
+    name: &'static str,
+    timing: BusTiming,
+    medium: OnceLock<UnboundedSender<Frame>>, // 首次建立连接时启动介质任务
+    stats: Arc<Mutex<BusStats>>,
+}
+
+impl Bus {
+    pub fn new(name: &'static str) -> Self {
+        let stats = Arc::new(Mutex::new(BusStats::default()));
+        BUSES.lock().unwrap().push(Registered { name, created: Instant::now(), stats: stats.clone() });
+        Bus { name, timing: BusTiming::default(), medium: OnceLock::new(), stats }
+    }
+
+    pub fn with_latency(mut self, min_ns: u64, max_ns: u64) -> Self {
+        self.timing.latency = (min_ns, max_ns);
+        self
+    }
+
+    pub fn with_transmission_time(mut self, fixed_min_ns: u64, fixed_max_ns: u64, per_byte_min_ns: u64, per_byte_max_ns: u64) -> Self {
+        self.timing.fixed = (fixed_min_ns, fixed_max_ns);
+        self.timing.per_byte = (per_byte_min_ns, per_byte_max_ns);
+        self
+    }
+
+    pub fn with_bandwidth(mut self, bytes_per_sec: u64) -> Self {
+        self.timing.bytes_per_sec = Some(bytes_per_sec);
+        self
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn stats(&self) -> BusStats {
+        *self.stats.lock().unwrap()
+    }
+
+    // 介质任务逐条发送，交付任务按 Latency 延后交付；tokio 定时器精度为毫秒，亚毫秒的耗时会被放大
+    fn medium(&self) -> UnboundedSender<Frame> {
+        self.medium
+            .get_or_init(|| {
+                let (tx, mut frames) = mpsc::unbounded_channel::<Frame>();
+                let (delivery_tx, mut deliveries) = mpsc::unbounded_channel::<(Instant, Instant, Delivery)>();
+                let timing = self.timing;
+                let stats = self.stats.clone();
+                tokio::spawn(async move {
+                    let mut last_delivery = Instant::now();
+                    while let Some(frame) = frames.recv().await {
+                        let start = Instant::now();
+                        let duration = timing.transmission(frame.bytes);
+                        tokio::time::sleep(duration).await;
+                        last_delivery = last_delivery.max(Instant::now() + timing.latency());
+                        {
+                            let mut stats = stats.lock().unwrap();
+                            stats.messages += 1;
+                            stats.bytes += frame.bytes as u64;
+                            stats.busy += duration;
+                            stats.max_queueing = stats.max_queueing.max(start.saturating_duration_since(frame.arrival));
+                        }
+                        if delivery_tx.send((frame.arrival, last_delivery, frame.deliver)).is_err() {
+                            break;
+                        }
+                    }
+                });
+                let stats = self.stats.clone();
+                tokio::spawn(async move {
+                    while let Some((arrival, at, deliver)) = deliveries.recv().await {
+                        tokio::time::sleep_until(at.into()).await;
+                        deliver();
+                        stats.lock().unwrap().record_delivery(arrival.elapsed());
+                    }
+                });
+                tx
+            })
+            .clone()
+    }
+
+    // 点对点连接：返回 (发送端, 接收端)
+    pub fn connect<T: Codec + Send + 'static>(&self) -> (UnboundedSender<T>, UnboundedReceiver<T>) {
+        let (tx, mut port) = mpsc::unbounded_channel::<T>();
+        let (bus_tx, rx) = mpsc::unbounded_channel::<T>();
+        let medium = self.medium();
+        tokio::spawn(async move {
+            while let Some(msg) = port.recv().await {
+                let out = bus_tx.clone();
+                let frame = Frame {
+                    arrival: Instant::now(),
+                    bytes: frame_bytes(&msg),
+                    deliver: Box::new(move || {
+                        let _ = out.send(msg);
+                    }),
+                };
+                if medium.send(frame).is_err() {
+                    break;
+                }
+            }
+        });
+        (tx, rx)
+    }
+
+    // 广播连接：返回 (发送端, 出口发送端)，各接收端通过出口 subscribe()
+    pub fn broadcast<T: Codec + Clone + Send + 'static>(&self, capacity: usize) -> (broadcast::Sender<T>, broadcast::Sender<T>) {
+        let (tx, mut port) = broadcast::channel::<T>(capacity);
+        let (bus_tx, _) = broadcast::channel::<T>(capacity);
+        let out = bus_tx.clone();
+        let medium = self.medium();
+        tokio::spawn(async move {
+            loop {
+                let msg = match port.recv().await {
+                    Ok(msg) => msg,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let out = bus_tx.clone();
+                let frame = Frame {
+                    arrival: Instant::now(),
+                    bytes: frame_bytes(&msg),
+                    deliver: Box::new(move || {
+                        let _ = out.send(msg);
+                    }),
+                };
+                if medium.send(frame).is_err() {
+                    break;
+                }
+            }
+        });
+        (tx, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 帧长按编码长度计入统计，交付顺序与发送顺序一致，时延不小于 Latency
+    #[tokio::test]
+    async fn frames_use_encoded_length_and_keep_order() {
+        let bus = Bus::new("test").with_latency(1_000_000, 2_000_000);
+        let (tx, mut rx) = bus.connect::<String>();
+        let sent: Vec<String> = ["a", "bbbb", "cccccccccccc"].iter().map(|s| s.to_string()).collect();
+        let start = Instant::now();
+        for msg in &sent {
+            tx.send(msg.clone()).unwrap();
+        }
+        let mut received = Vec::new();
+        for _ in 0..sent.len() {
+            let msg = tokio::time::timeout(Duration::from_secs(1), rx.recv()).await.unwrap().unwrap();
+            received.push(msg);
+        }
+        assert!(start.elapsed() >= Duration::from_millis(1));
+        assert_eq!(received, sent);
+        let stats = bus.stats();
+        assert_eq!(stats.messages, sent.len() as u64);
+        assert_eq!(stats.bytes, sent.iter().map(|msg| msg.to_bytes().unwrap().len() as u64).sum::<u64>());
+    }
+}
+"#
+    } else {
+        r#"
+use crossbeam_channel::{Receiver, Sender};
+use std::thread;
+use tokio::sync::broadcast;
+
+pub struct Bus {
+    name: &'static str,
+    timing: BusTiming,
+    medium: OnceLock<Sender<Frame>>, // 首次建立连接时启动介质线程
+    stats: Arc<Mutex<BusStats>>,
+}
+
+impl Bus {
+    pub fn new(name: &'static str) -> Self {
+        let stats = Arc::new(Mutex::new(BusStats::default()));
+        BUSES.lock().unwrap().push(Registered { name, created: Instant::now(), stats: stats.clone() });
+        Bus { name, timing: BusTiming::default(), medium: OnceLock::new(), stats }
+    }
+
+    pub fn with_latency(mut self, min_ns: u64, max_ns: u64) -> Self {
+        self.timing.latency = (min_ns, max_ns);
+        self
+    }
+
+    pub fn with_transmission_time(mut self, fixed_min_ns: u64, fixed_max_ns: u64, per_byte_min_ns: u64, per_byte_max_ns: u64) -> Self {
+        self.timing.fixed = (fixed_min_ns, fixed_max_ns);
+        self.timing.per_byte = (per_byte_min_ns, per_byte_max_ns);
+        self
+    }
+
+    pub fn with_bandwidth(mut self, bytes_per_sec: u64) -> Self {
+        self.timing.bytes_per_sec = Some(bytes_per_sec);
+        self
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn stats(&self) -> BusStats {
+        *self.stats.lock().unwrap()
+    }
+
+    // 介质线程逐条发送，交付线程按 Latency 延后交付
+    fn medium(&self) -> Sender<Frame> {
+        self.medium
+            .get_or_init(|| {
+                let (tx, frames) = crossbeam_channel::unbounded::<Frame>();
+                let (delivery_tx, deliveries) = crossbeam_channel::unbounded::<(Instant, Instant, Delivery)>();
+                let timing = self.timing;
+                let stats = self.stats.clone();
+                thread::Builder::new()
+                    .name(format!("bus-{}", self.name))
+                    .spawn(move || {
+                        let mut last_delivery = Instant::now();
+                        for frame in frames.iter() {
+                            let start = Instant::now();
+                            let duration = timing.transmission(frame.bytes);
+                            thread::sleep(duration);
+                            last_delivery = last_delivery.max(Instant::now() + timing.latency());
+                            {
+                                let mut stats = stats.lock().unwrap();
+                                stats.messages += 1;
+                                stats.bytes += frame.bytes as u64;
+                                stats.busy += duration;
+                                stats.max_queueing = stats.max_queueing.max(start.saturating_duration_since(frame.arrival));
+                            }
+                            if delivery_tx.send((frame.arrival, last_delivery, frame.deliver)).is_err() {
+                                break;
+                            }
+                        }
+                    })
+                    .expect("failed to spawn bus medium");
+                let stats = self.stats.clone();
+                thread::Builder::new()
+                    .name(format!("bus-{}-delivery", self.name))
+                    .spawn(move || {
+                        for (arrival, at, deliver) in deliveries.iter() {
+                            thread::sleep(at.saturating_duration_since(Instant::now()));
+                            deliver();
+                            stats.lock().unwrap().record_delivery(arrival.elapsed());
+                        }
+                    })
+                    .expect("failed to spawn bus delivery");
+                tx
+            })
+            .clone()
+    }
+
+    // 点对点连接：返回 (发送端, 接收端)
+    pub fn connect<T: Codec + Send + 'static>(&self) -> (Sender<T>, Receiver<T>) {
+        let (tx, port) = crossbeam_channel::unbounded::<T>();
+        let (bus_tx, rx) = crossbeam_channel::unbounded::<T>();
+        let medium = self.medium();
+        thread::Builder::new()
+            .name(format!("bus-{}-port", self.name))
+            .spawn(move || {
+                for msg in port.iter() {
+                    let out = bus_tx.clone();
+                    let frame = Frame {
+                        arrival: Instant::now(),
+                        bytes: frame_bytes(&msg),
+                        deliver: Box::new(move || {
+                            let _ = out.send(msg);
+                        }),
+                    };
+                    if medium.send(frame).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("failed to spawn bus port");
+        (tx, rx)
+    }
+
+    // 广播连接：返回 (发送端, 出口发送端)，各接收端通过出口 subscribe()
+    pub fn broadcast<T: Codec + Clone + Send + 'static>(&self, capacity: usize) -> (broadcast::Sender<T>, broadcast::Sender<T>) {
+        let (tx, mut port) = broadcast::channel::<T>(capacity);
+        let (bus_tx, _) = broadcast::channel::<T>(capacity);
+        let out = bus_tx.clone();
+        let medium = self.medium();
+        thread::Builder::new()
+            .name(format!("bus-{}-port", self.name))
+            .spawn(move || loop {
+                let msg = match port.blocking_recv() {
+                    Ok(msg) => msg,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let out = bus_tx.clone();
+                let frame = Frame {
+                    arrival: Instant::now(),
+                    bytes: frame_bytes(&msg),
+                    deliver: Box::new(move || {
+                        let _ = out.send(msg);
+                    }),
+                };
+                if medium.send(frame).is_err() {
+                    break;
+                }
+            })
+            .expect("failed to spawn bus port");
+        (tx, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 帧长按编码长度计入统计，交付顺序与发送顺序一致，时延不小于 Latency
+    #[test]
+    fn frames_use_encoded_length_and_keep_order() {
+        let bus = Bus::new("test").with_latency(1_000_000, 2_000_000);
+        let (tx, rx) = bus.connect::<String>();
+        let sent: Vec<String> = ["a", "bbbb", "cccccccccccc"].iter().map(|s| s.to_string()).collect();
+        let start = Instant::now();
+        for msg in &sent {
+            tx.send(msg.clone()).unwrap();
+        }
+        let received: Vec<String> = (0..sent.len()).map(|_| rx.recv_timeout(Duration::from_secs(1)).unwrap()).collect();
+        assert!(start.elapsed() >= Duration::from_millis(1));
+        assert_eq!(received, sent);
+        let stats = bus.stats();
+        assert_eq!(stats.messages, sent.len() as u64);
+        assert_eq!(stats.bytes, sent.iter().map(|msg| msg.to_bytes().unwrap().len() as u64).sum::<u64>());
+    }
+}
+"#
+    };
+
+    fs::write(&path, format!("{}{}", common, transport)).expect("Failed to write bus.rs");
+    println!("bus.rs 已生成: {}", path);
+    true
+}
+
+/// 生成 src/net.rs 与各节点的可执行文件（分布式部署），返回是否生成。
+/// 系统中出现 crate::net:: 时才生成；否则清理上次生成残留的 net.rs 与 src/bin
+fn generate_net_rs(project_root: &str, backend: CodegenBackend) -> bool {
+    let path = format!("{}/src/net.rs", project_root);
+    if !src_uses(project_root, "crate::net::", "net.rs") {
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_dir_all(format!("{}/src/bin", project_root));
+        return false;
+    }
+
+    let wire = r#"// ---------------- net ----------------
+
+// 分布式部署的节点间通信。每个节点监听自己的地址（TCP 的 host:port，或 Unix 域套接字路径），
+// 跨节点的端口连接按连接序号 link 分帧转发，帧格式为 [link: u32][长度: u32][Codec 编码的数据]，均为小端。
+// 未调用 set_node 时（单进程运行 main.rs）所有组件都在本地，link / broadcast_link 原样返回通道。
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::codec::Codec;
+
+// ---------------- nodes ----------------
+
+struct Node {
+    cpu: isize,
+    address: &'static str,
+}
+
+static NODES: OnceLock<Vec<Node>> = OnceLock::new();
+
+const UNSET: isize = isize::MIN;
+static LOCAL: AtomicIsize = AtomicIsize::new(UNSET);
+
+// 登记部署节点 (CPU ID, 节点名, 地址)，由系统的 new() 调用；首个节点承载未绑定处理器的组件
+pub fn deploy(nodes: &[(isize, &'static str, &'static str)]) {
+    let _ = NODES.set(nodes.iter().map(|&(cpu, _name, address)| Node { cpu, address }).collect());
+}
+
+// 节点可执行文件在构造系统之前调用，指定本进程运行的节点
+pub fn set_node(cpu: isize) {
+    LOCAL.store(cpu, Ordering::SeqCst);
+}
+
+// 本进程运行的节点；单进程运行时为 None
+pub fn node() -> Option<isize> {
+    match LOCAL.load(Ordering::SeqCst) {
+        UNSET => None,
+        cpu => Some(cpu),
+    }
+}
+
+fn nodes() -> &'static [Node] {
+    NODES.get().map_or(&[], Vec::as_slice)
+}
+
+// CPU ID 所在的节点；小于 0（未绑定处理器）归首个节点
+fn resolve(cpu: isize) -> isize {
+    if cpu < 0 {
+        nodes().first().map_or(cpu, |node| node.cpu)
+    } else {
+        cpu
+    }
+}
+
+// 绑定到该 CPU 的组件是否由本进程运行
+pub fn is_local(cpu: isize) -> bool {
+    node().map_or(true, |local| resolve(cpu) == local)
+}
+
+fn address(cpu: isize) -> Option<&'static str> {
+    nodes().iter().find(|node| node.cpu == cpu).map(|node| node.address)
+}
+
+// ---------------- transport ----------------
+
+type Handler = Box<dyn Fn(&[u8]) + Send>;
+
+fn handlers() -> &'static Mutex<HashMap<u32, Handler>> {
+    static HANDLERS: OnceLock<Mutex<HashMap<u32, Handler>>> = OnceLock::new();
+    HANDLERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn on_frame(link: u32, handler: impl Fn(&[u8]) + Send + 'static) {
+    handlers().lock().unwrap().insert(link, Box::new(handler));
+}
+
+// 启动本节点的监听，由系统的 run() 在启动组件之前调用；单进程运行时不做任何事
+pub fn start() {
+    let Some(local) = node() else {
+        return;
+    };
+    let Some(address) = address(local) else {
+        eprintln!("[net] cpu {} is not deployed", local);
+        return;
+    };
+    if address.contains('/') {
+        let _ = std::fs::remove_file(address); // 上次运行残留的套接字文件
+        let listener = UnixListener::bind(address).unwrap_or_else(|e| panic!("[net] bind {}: {}", address, e));
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                thread::spawn(move || serve(stream));
+            }
+        });
+    } else {
+        let listener = TcpListener::bind(address).unwrap_or_else(|e| panic!("[net] bind {}: {}", address, e));
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = stream.set_nodelay(true);
+                thread::spawn(move || serve(stream));
+            }
+        });
+    }
+    println!("[net] node {} listening on {}", local, address);
+}
+
+// 读取一个连接上的帧并交给对应连接的处理函数
+fn serve(mut stream: impl Read) {
+    let mut header = [0u8; 8];
+    while stream.read_exact(&mut header).is_ok() {
+        let link = u32::from_le_bytes(header[..4].try_into().unwrap());
+        let len = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+        let mut payload = vec![0u8; len];
+        if stream.read_exact(&mut payload).is_err() {
+            break;
+        }
+        if let Some(handler) = handlers().lock().unwrap().get(&link) {
+            handler(&payload);
+        }
+    }
+}
+
+fn writers() -> &'static Mutex<HashMap<isize, mpsc::Sender<Vec<u8>>>> {
+    static WRITERS: OnceLock<Mutex<HashMap<isize, mpsc::Sender<Vec<u8>>>>> = OnceLock::new();
+    WRITERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn send_to<T: Codec>(cpu: isize, link: u32, value: &T) {
+    let payload = match value.to_bytes() {
+        Ok(payload) => payload,
+        Err(e) => {
+            eprintln!("[net] link {}: {}", link, e);
+            return;
+        }
+    };
+    let mut frame = Vec::with_capacity(payload.len() + 8);
+    frame.extend_from_slice(&link.to_le_bytes());
+    frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    frame.extend(payload);
+    let mut writers = writers().lock().unwrap();
+    let writer = writers.entry(cpu).or_insert_with(|| spawn_writer(cpu));
+    let _ = writer.send(frame);
+}
+
+// 每个目的节点一个发送线程；对端尚未启动或连接断开时重连，期间的帧依次排队
+fn spawn_writer(cpu: isize) -> mpsc::Sender<Vec<u8>> {
+    let (tx, frames) = mpsc::channel::<Vec<u8>>();
+    let address = address(cpu).unwrap_or_else(|| panic!("[net] cpu {} is not deployed", cpu));
+    thread::spawn(move || {
+        let mut stream: Option<Box<dyn Write + Send>> = None;
+        for frame in frames {
+            loop {
+                let writer = stream.get_or_insert_with(|| connect(address));
+                if writer.write_all(&frame).is_ok() {
+                    break;
+                }
+                stream = None;
+            }
+        }
+    });
+    tx
+}
+
+fn connect(address: &str) -> Box<dyn Write + Send> {
+    loop {
+        let stream: std::io::Result<Box<dyn Write + Send>> = if address.contains('/') {
+            UnixStream::connect(address).map(|stream| Box::new(stream) as Box<dyn Write + Send>)
+        } else {
+            TcpStream::connect(address).map(|stream| {
+                let _ = stream.set_nodelay(true);
+                Box::new(stream) as Box<dyn Write + Send>
+            })
+        };
+        match stream {
+            Ok(stream) => return stream,
+            Err(_) => thread::sleep(Duration::from_millis(100)),
+        }
+    }
+}
+
+// ---------------- links ----------------
+"#;
+
+    // 点对点连接的通道类型随后端而定：异步后端为 tokio mpsc，其余后端为 crossbeam
+    let channel = if backend == CodegenBackend::Async {
+        r#"
+use tokio::sync::mpsc::{unbounded_channel as unbounded, UnboundedReceiver as Receiver, UnboundedSender as Sender};
+
+// 转发线程不在 tokio 运行时内，可以阻塞等待
+fn recv_blocking<T>(rx: &mut Receiver<T>) -> Option<T> {
+    rx.blocking_recv()
+}
+"#
+    } else {
+        r#"
+use crossbeam_channel::{unbounded, Receiver, Sender};
+
+fn recv_blocking<T>(rx: &mut Receiver<T>) -> Option<T> {
+    rx.recv().ok()
+}
+"#
+    };
+
+    let links = r#"
+// 点对点连接：发送端所在节点把通道中的数据转发给接收端节点，接收端节点把收到的帧投递到返回的接收端
+pub fn link<T: Codec + Send + 'static>(pair: (Sender<T>, Receiver<T>), link: u32, senders: &[isize], receivers: &[isize]) -> (Sender<T>, Receiver<T>) {
+    let Some(local) = node() else {
+        return pair;
+    };
+    let (tx, mut rx) = pair;
+    let target = receivers.first().map_or(local, |cpu| resolve(*cpu));
+    let (deliver_tx, deliver_rx) = unbounded::<T>();
+    if target == local {
+        let inbound = deliver_tx.clone();
+        on_frame(link, move |payload: &[u8]| match T::from_bytes(payload) {
+            Ok(value) => {
+                let _ = inbound.send(value);
+            }
+            Err(e) => eprintln!("[net] link {}: {}", link, e),
+        });
+    }
+    if senders.iter().any(|cpu| resolve(*cpu) == local) {
+        thread::spawn(move || {
+            while let Some(value) = recv_blocking(&mut rx) {
+                if target == local {
+                    let _ = deliver_tx.send(value);
+                } else {
+                    send_to(target, link, &value);
+                }
+            }
+        });
+    }
+    (tx, deliver_rx)
+}
+
+// 跨节点广播连接的 (发送段, 本地投递段)，不经总线时两者是同一个广播通道
+pub fn broadcast_pair<T: Clone>(capacity: usize) -> (broadcast::Sender<T>, broadcast::Sender<T>) {
+    let (tx, _) = broadcast::channel(capacity);
+    (tx.clone(), tx)
+}
+
+// 扇出连接：发送端所在节点订阅本地投递段，把每条消息转发给各接收端节点（本节点也有接收端时同时在本地投递）；
+// 接收端订阅返回的投递段
+pub fn broadcast_link<T: Codec + Clone + Send + 'static>(
+    pair: (broadcast::Sender<T>, broadcast::Sender<T>),
+    link: u32,
+    senders: &[isize],
+    receivers: &[isize],
+) -> (broadcast::Sender<T>, broadcast::Sender<T>) {
+    let Some(local) = node() else {
+        return pair;
+    };
+    let (tx, out) = pair;
+    let (deliver, _) = broadcast::channel::<T>(100);
+    let mut targets: Vec<isize> = receivers.iter().map(|cpu| resolve(*cpu)).collect();
+    targets.sort();
+    targets.dedup();
+    let local_receiver = targets.contains(&local);
+    targets.retain(|cpu| *cpu != local);
+    if local_receiver {
+        let inbound = deliver.clone();
+        on_frame(link, move |payload: &[u8]| match T::from_bytes(payload) {
+            Ok(value) => {
+                let _ = inbound.send(value);
+            }
+            Err(e) => eprintln!("[net] link {}: {}", link, e),
+        });
+    }
+    if senders.iter().any(|cpu| resolve(*cpu) == local) {
+        let mut rx = out.subscribe();
+        let local_deliver = deliver.clone();
+        thread::spawn(move || {
+            let _out = out;
+            loop {
+                match rx.blocking_recv() {
+                    Ok(value) => {
+                        for cpu in &targets {
+                            send_to(*cpu, link, &value);
+                        }
+                        if local_receiver {
+                            let _ = local_deliver.send(value);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+    (tx, deliver)
+}
+"#;
+
+    fs::write(&path, format!("{}{}{}", wire, channel, links)).expect("Failed to write net.rs");
+    println!("net.rs 已生成: {}", path);
+    true
+}
+
+/// 生成 src/codec.rs，返回是否生成。
+/// 数据组件生成的类型实现 crate::codec::Codec；src 中没有用到时清理上次生成残留的 codec.rs
+fn generate_codec_rs(project_root: &str) -> bool {
+    let path = format!("{}/src/codec.rs", project_root);
+    if !src_uses(project_root, "crate::codec::", "codec.rs") {
+        let _ = fs::remove_file(&path);
+        return false;
+    }
+
+    let mut content = r#"// ---------------- codec ----------------
+
+// 数据组件的二进制编解码。BitWriter / BitReader 按位读写，每个字节内先写高位；
+// 超过 8 位的整数按字节序排列各字节：大端先写高字节，小端先写低字节，不满 8 位的部分在最高字节。
+// 生成的数据类型实现 Codec；整数的位宽（Data_Size）或字节序（Byte_Order）不同于缺省编码时，
+// 生成代码直接调用 write_signed / read_signed 等。编码结果末尾不满一个字节时补 0。
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    BigEndian,
+    LittleEndian,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    // 数值超出编码位宽
+    Overflow { value: i128, bits: u32 },
+    // 输入在解码完成前结束
+    UnexpectedEnd,
+    // 枚举序号没有对应的变体
+    InvalidDiscriminant(u64),
+    // 解码得到的值不是该类型的合法值
+    InvalidValue(&'static str),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::Overflow { value, bits } => write!(f, "value {} does not fit in {} bits", value, bits),
+            CodecError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            CodecError::InvalidDiscriminant(index) => write!(f, "invalid discriminant {}", index),
+            CodecError::InvalidValue(ty) => write!(f, "invalid {} value", ty),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+fn mask(bits: u32) -> u64 {
+    if bits >= 64 { u64::MAX } else { (1u64 << bits) - 1 }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    bits: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 写入 value 的低 bits 位，高位在前
+    pub fn write_bits(&mut self, value: u64, bits: u32) {
+        for i in (0..bits).rev() {
+            if self.bits % 8 == 0 {
+                self.bytes.push(0);
+            }
+            if (value >> i) & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 0x80 >> (self.bits % 8);
+            }
+            self.bits += 1;
+        }
+    }
+
+    fn write_ordered(&mut self, mut value: u64, mut bits: u32, order: ByteOrder) {
+        if order == ByteOrder::LittleEndian {
+            while bits > 8 {
+                self.write_bits(value & 0xff, 8);
+                value >>= 8;
+                bits -= 8;
+            }
+        }
+        self.write_bits(value, bits);
+    }
+
+    pub fn write_unsigned(&mut self, value: u64, bits: u32, order: ByteOrder) -> Result<(), CodecError> {
+        if value & !mask(bits) != 0 {
+            return Err(CodecError::Overflow { value: value as i128, bits });
+        }
+        self.write_ordered(value, bits, order);
+        Ok(())
+    }
+
+    // 有符号数按补码写入低 bits 位
+    pub fn write_signed(&mut self, value: i64, bits: u32, order: ByteOrder) -> Result<(), CodecError> {
+        if bits < 64 {
+            let min = -(1i64 << (bits - 1));
+            let max = (1i64 << (bits - 1)) - 1;
+            if value < min || value > max {
+                return Err(CodecError::Overflow { value: value as i128, bits });
+            }
+        }
+        self.write_ordered(value as u64 & mask(bits), bits, order);
+        Ok(())
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.write_bits(u64::from(*byte), 8);
+        }
+    }
+
+    // 已写入的位数
+    pub fn bit_len(&self) -> usize {
+        self.bits
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    pub fn read_bits(&mut self, bits: u32) -> Result<u64, CodecError> {
+        if self.remaining_bits() < bits as usize {
+            return Err(CodecError::UnexpectedEnd);
+        }
+        let mut value = 0u64;
+        for _ in 0..bits {
+            let bit = (self.bytes[self.pos / 8] >> (7 - self.pos % 8)) & 1;
+            value = (value << 1) | u64::from(bit);
+            self.pos += 1;
+        }
+        Ok(value)
+    }
+
+    fn read_ordered(&mut self, bits: u32, order: ByteOrder) -> Result<u64, CodecError> {
+        if order == ByteOrder::BigEndian {
+            return self.read_bits(bits);
+        }
+        let mut value = 0u64;
+        let mut shift = 0;
+        let mut rest = bits;
+        while rest > 8 {
+            value |= self.read_bits(8)? << shift;
+            shift += 8;
+            rest -= 8;
+        }
+        Ok(value | (self.read_bits(rest)? << shift))
+    }
+
+    pub fn read_unsigned(&mut self, bits: u32, order: ByteOrder) -> Result<u64, CodecError> {
+        self.read_ordered(bits, order)
+    }
+
+    // 按补码读取并做符号扩展
+    pub fn read_signed(&mut self, bits: u32, order: ByteOrder) -> Result<i64, CodecError> {
+        let raw = self.read_ordered(bits, order)?;
+        if bits < 64 && (raw >> (bits - 1)) & 1 == 1 {
+            Ok((raw | !mask(bits)) as i64)
+        } else {
+            Ok(raw as i64)
+        }
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>, CodecError> {
+        (0..len).map(|_| self.read_bits(8).map(|byte| byte as u8)).collect()
+    }
+
+    pub fn remaining_bits(&self) -> usize {
+        self.bytes.len() * 8 - self.pos
+    }
+}
+
+pub trait Codec: Sized {
+    fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError>;
+    fn decode(r: &mut BitReader) -> Result<Self, CodecError>;
+
+    fn to_bytes(&self) -> Result<Vec<u8>, CodecError> {
+        let mut w = BitWriter::new();
+        self.encode(&mut w)?;
+        Ok(w.into_bytes())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, CodecError> {
+        Self::decode(&mut BitReader::new(bytes))
+    }
+}
+
+// 标准类型的缺省编码：整数按自然位宽大端，浮点按 IEEE 754 位模式，usize / isize 按 64 位
+macro_rules! codec_unsigned {
+    ($($t:ty => $bits:expr),*) => {$(
+        impl Codec for $t {
+            fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+                w.write_unsigned(*self as u64, $bits, ByteOrder::BigEndian)
+            }
+            fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+                <$t>::try_from(r.read_unsigned($bits, ByteOrder::BigEndian)?).map_err(|_| CodecError::InvalidValue(stringify!($t)))
+            }
+        }
+    )*};
+}
+
+macro_rules! codec_signed {
+    ($($t:ty => $bits:expr),*) => {$(
+        impl Codec for $t {
+            fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+                w.write_signed(*self as i64, $bits, ByteOrder::BigEndian)
+            }
+            fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+                <$t>::try_from(r.read_signed($bits, ByteOrder::BigEndian)?).map_err(|_| CodecError::InvalidValue(stringify!($t)))
+            }
+        }
+    )*};
+}
+
+codec_unsigned!(u8 => 8, u16 => 16, u32 => 32, u64 => 64, usize => 64);
+codec_signed!(i8 => 8, i16 => 16, i32 => 32, i64 => 64, isize => 64);
+
+impl Codec for f32 {
+    fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+        w.write_unsigned(u64::from(self.to_bits()), 32, ByteOrder::BigEndian)
+    }
+    fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+        Ok(f32::from_bits(r.read_unsigned(32, ByteOrder::BigEndian)? as u32))
+    }
+}
+
+impl Codec for f64 {
+    fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+        w.write_unsigned(self.to_bits(), 64, ByteOrder::BigEndian)
+    }
+    fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+        Ok(f64::from_bits(r.read_unsigned(64, ByteOrder::BigEndian)?))
+    }
+}
+
+impl Codec for bool {
+    fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+        w.write_bits(u64::from(*self), 1);
+        Ok(())
+    }
+    fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+        Ok(r.read_bits(1)? == 1)
+    }
+}
+
+impl Codec for char {
+    fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+        (*self as u32).encode(w)
+    }
+    fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+        char::from_u32(u32::decode(r)?).ok_or(CodecError::InvalidValue("char"))
+    }
+}
+
+impl Codec for () {
+    fn encode(&self, _w: &mut BitWriter) -> Result<(), CodecError> {
+        Ok(())
+    }
+    fn decode(_r: &mut BitReader) -> Result<Self, CodecError> {
+        Ok(())
+    }
+}
+
+// 变长数据先写 u32 长度
+fn encode_len(len: usize, w: &mut BitWriter) -> Result<(), CodecError> {
+    u32::try_from(len).map_err(|_| CodecError::Overflow { value: len as i128, bits: 32 })?.encode(w)
+}
+
+impl Codec for String {
+    fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+        encode_len(self.len(), w)?;
+        w.write_bytes(self.as_bytes());
+        Ok(())
+    }
+    fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+        let len = u32::decode(r)? as usize;
+        String::from_utf8(r.read_bytes(len)?).map_err(|_| CodecError::InvalidValue("String"))
+    }
+}
+
+impl<T: Codec> Codec for Vec<T> {
+    fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+        encode_len(self.len(), w)?;
+        self.iter().try_for_each(|item| item.encode(w))
+    }
+    fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+        let len = u32::decode(r)? as usize;
+        (0..len).map(|_| T::decode(r)).collect()
+    }
+}
+
+// 1 位标记，随后是值
+impl<T: Codec> Codec for Option<T> {
+    fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+        self.is_some().encode(w)?;
+        match self {
+            Some(value) => value.encode(w),
+            None => Ok(()),
+        }
+    }
+    fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+        if bool::decode(r)? { T::decode(r).map(Some) } else { Ok(None) }
+    }
+}
+
+impl<T: Codec> Codec for Box<T> {
+    fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+        (**self).encode(w)
+    }
+    fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+        T::decode(r).map(Box::new)
+    }
+}
+
+// 定长数组依次编码各元素，不写长度
+impl<T: Codec, const N: usize> Codec for [T; N] {
+    fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+        self.iter().try_for_each(|item| item.encode(w))
+    }
+    fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+        let items = (0..N).map(|_| T::decode(r)).collect::<Result<Vec<T>, _>>()?;
+        items.try_into().map_err(|_| CodecError::UnexpectedEnd)
+    }
+}
+
+macro_rules! codec_tuple {
+    ($($name:ident: $t:ident),+) => {
+        impl<$($t: Codec),+> Codec for ($($t,)+) {
+            fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+                let ($($name,)+) = self;
+                $($name.encode(w)?;)+
+                Ok(())
+            }
+            fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+                Ok(($($t::decode(r)?,)+))
+            }
+        }
+    };
+}
+
+codec_tuple!(a: A);
+codec_tuple!(a: A, b: B);
+codec_tuple!(a: A, b: B, c: C);
+codec_tuple!(a: A, b: B, c: C, d: D);
+
+// ---------------- sample ----------------
+
+// 往返测试使用的样例值：整数取极值，检验位宽与符号扩展
+pub trait Sample {
+    fn sample() -> Self;
+}
+
+// bits 位有符号数的最小值
+pub fn sample_signed(bits: u32) -> i64 {
+    if bits >= 64 { i64::MIN } else { -(1i64 << (bits - 1)) }
+}
+
+// bits 位无符号数的最大值
+pub fn sample_unsigned(bits: u32) -> u64 {
+    mask(bits)
+}
+
+macro_rules! sample_value {
+    ($($t:ty => $value:expr),*) => {$(
+        impl Sample for $t {
+            fn sample() -> Self {
+                $value
+            }
+        }
+    )*};
+}
+
+sample_value!(
+    u8 => u8::MAX, u16 => u16::MAX, u32 => u32::MAX, u64 => u64::MAX, usize => usize::MAX,
+    i8 => i8::MIN, i16 => i16::MIN, i32 => i32::MIN, i64 => i64::MIN, isize => isize::MIN,
+    f32 => -1.5, f64 => -1.5, bool => true, char => 'ä', () => (), String => "sample".to_string()
+);
+
+impl<T: Sample> Sample for Vec<T> {
+    fn sample() -> Self {
+        vec![T::sample(), T::sample()]
+    }
+}
+
+impl<T: Sample> Sample for Option<T> {
+    fn sample() -> Self {
+        Some(T::sample())
+    }
+}
+
+impl<T: Sample> Sample for Box<T> {
+    fn sample() -> Self {
+        Box::new(T::sample())
+    }
+}
+
+impl<T: Sample, const N: usize> Sample for [T; N] {
+    fn sample() -> Self {
+        std::array::from_fn(|_| T::sample())
+    }
+}
+
+macro_rules! sample_tuple {
+    ($($t:ident),+) => {
+        impl<$($t: Sample),+> Sample for ($($t,)+) {
+            fn sample() -> Self {
+                ($($t::sample(),)+)
+            }
+        }
+    };
+}
+
+sample_tuple!(A);
+sample_tuple!(A, B);
+sample_tuple!(A, B, C);
+sample_tuple!(A, B, C, D);
+"#
+    .to_string();
+
+    // Dimension 给出容量的字符串数据类型映射为 heapless::String<N>
+    if src_uses(project_root, "heapless::String", "codec.rs") {
+        content.push_str(
+            r#"
+// 有界字符串与 String 编码相同；解码出的长度超过容量时报错
+impl<const N: usize> Codec for heapless::String<N> {
+    fn encode(&self, w: &mut BitWriter) -> Result<(), CodecError> {
+        encode_len(self.len(), w)?;
+        w.write_bytes(self.as_bytes());
+        Ok(())
+    }
+    fn decode(r: &mut BitReader) -> Result<Self, CodecError> {
+        let len = u32::decode(r)? as usize;
+        let bytes = r.read_bytes(len)?;
+        let text = std::str::from_utf8(&bytes).map_err(|_| CodecError::InvalidValue("String"))?;
+        let mut value = heapless::String::new();
+        value.push_str(text).map_err(|_| CodecError::InvalidValue("bounded String over capacity"))?;
+        Ok(value)
+    }
+}
+
+// 样例值填满容量
+impl<const N: usize> Sample for heapless::String<N> {
+    fn sample() -> Self {
+        let mut value = heapless::String::new();
+        while value.push('a').is_ok() {}
+        value
+    }
+}
+"#,
+        );
+    }
+
+    fs::write(&path, content).expect("Failed to write codec.rs");
+    println!("codec.rs 已生成: {}", path);
+    true
+}
+
+/// 生成 src/range.rs（带取值范围的数据类型使用的 RangeError），返回是否生成。
+/// src 中没有用到 crate::range:: 时清理上次生成残留的 range.rs
+fn generate_range_rs(project_root: &str, no_std: bool) -> bool {
+    let path = format!("{}/src/range.rs", project_root);
+    if !src_uses(project_root, "crate::range::", "range.rs") {
+        let _ = fs::remove_file(&path);
+        return false;
+    }
+
+    let common = r#"// ---------------- range ----------------
+
+// Data_Model::Integer_Range / Real_Range 约束的数据类型生成为新类型，检查构造函数越界时返回 RangeError；
+// 端口接收时被拒绝的越界值经 report 记录后丢弃。
+
+use core::fmt;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeError {
+    pub type_name: &'static str,
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} value {} out of range {} ..= {}", self.type_name, self.value, self.min, self.max)
+    }
+}
+
+static REJECTED: AtomicUsize = AtomicUsize::new(0);
+
+// 端口接收时丢弃的越界值个数
+pub fn rejected() -> usize {
+    REJECTED.load(Ordering::Relaxed)
+}
+"#;
+
+    // 嵌入式工程没有标准输出，只计数
+    let report = if no_std {
+        r#"
+pub fn report(_error: &RangeError) {
+    REJECTED.fetch_add(1, Ordering::Relaxed);
+}
+"#
+    } else {
+        r#"
+impl std::error::Error for RangeError {}
+
+pub fn report(error: &RangeError) {
+    REJECTED.fetch_add(1, Ordering::Relaxed);
+    eprintln!("[range] rejected on receive: {}", error);
+}
+"#
+    };
+
+    fs::write(&path, format!("{}{}", common, report)).expect("Failed to write range.rs");
+    println!("range.rs 已生成: {}", path);
+    true
+}
+
+// src 下除 exclude 外是否有源文件包含 needle
+fn src_uses(project_root: &str, needle: &str, exclude: &str) -> bool {
+    fs::read_dir(format!("{}/src", project_root))
+        .expect("Failed to read src directory")
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name() != exclude)
+        .any(|entry| fs::read_to_string(entry.path()).is_ok_and(|content| content.contains(needle)))
+}
+
+// 部署节点 (CPU ID, 节点名)，取自系统 new() 中的 crate::net::deploy(...)
+fn deployed_nodes(project_root: &str) -> Vec<(isize, String)> {
+    let deploy = Regex::new(r"crate::net::deploy\(&\[(.*)\]\)").expect("invalid regex");
+    let entry = Regex::new(r#"\((-?\d+), "([^"]+)", "[^"]*"\)"#).expect("invalid regex");
+    let Ok(entries) = fs::read_dir(format!("{}/src", project_root)) else {
+        return Vec::new();
+    };
+    for file in entries.filter_map(Result::ok) {
+        let content = fs::read_to_string(file.path()).unwrap_or_default();
+        if let Some(caps) = deploy.captures(&content) {
+            return entry
+                .captures_iter(&caps[1])
+                .filter_map(|node| Some((node[1].parse().ok()?, node[2].to_string())))
+                .collect();
+        }
+    }
+    Vec::new()
+}
+
+/// 生成 src/rtc.rs（嵌入式后端的运行时：时钟接口与端口投递）
+fn generate_rtc_rs(project_root: &str) {
+    let path = format!("{}/src/rtc.rs", project_root);
+
+    let content = r#"// ---------------- clock ----------------
+
+// 时间源由用户针对具体硬件实现（如 SysTick、DWT 周期计数器）
+pub trait Clock {
+    // 自启动以来的毫秒数，必须单调递增
+    fn now_ms(&mut self) -> u64;
+
+    // 没有就绪任务时调用，可在此进入低功耗等待（如 WFI），默认立即返回
+    fn idle(&mut self) {}
+}
+
+// ---------------- port ----------------
+
+// 向目的端口队列投递数据；队列已满时丢弃最旧的数据（AADL Overflow_Handling_Protocol 默认 DropOldest）
+pub fn deliver<T, const N: usize>(queue: &mut heapless::Deque<T, N>, value: T) {
+    if queue.is_full() {
+        queue.pop_front();
+    }
+    let _ = queue.push_back(value);
+}
+"#;
+
+    fs::write(&path, content).expect("Failed to write rtc.rs");
+
+    println!("rtc.rs 已生成: {}", path);
+}
+
 /// 生成 src/lib.rs
-fn generate_lib_rs(project_root: &str) {
+fn generate_lib_rs(project_root: &str, no_std: bool) {
     let src_dir = format!("{}/src", project_root);
     let lib_rs_path = format!("{}/lib.rs", src_dir);
 
@@ -312,6 +1932,10 @@ fn generate_lib_rs(project_root: &str) {
     let mut content = String::new();
 
     // crate-level attributes
+    if no_std {
+        content.push_str("//! no_std 工程：固件入口实现 rtc::Clock，并调用 <System>::new().run(&mut clock)\n");
+        content.push_str("#![no_std]\n");
+    }
     content.push_str("#![allow(non_snake_case)]\n");
     content.push_str("#![allow(non_camel_case_types)]\n\n");
 
@@ -329,11 +1953,149 @@ fn generate_lib_rs(project_root: &str) {
 }
 
 /// 生成 src/main.rs
-fn generate_main_rs(project_root: &str, system_type: &str, module_name: &str, project_name: &str) {
+fn generate_main_rs(
+    project_root: &str,
+    system_type: &str,
+    module_name: &str,
+    project_name: &str,
+    backend: CodegenBackend,
+    has_bus: bool,
+) {
     let main_rs_path = format!("{}/src/main.rs", project_root);
+    let content = main_rs_content(system_type, module_name, project_name, backend, has_bus, None);
+    fs::write(&main_rs_path, content).expect("Failed to write main.rs");
+
+    println!("main.rs 已生成: {}", main_rs_path);
+}
+
+/// 生成分布式部署的 src/bin/node_<节点名>.rs 与 src/bin/launcher.rs
+fn generate_node_bins(
+    project_root: &str,
+    system_type: &str,
+    module_name: &str,
+    project_name: &str,
+    backend: CodegenBackend,
+    has_bus: bool,
+) {
+    let bin_dir = format!("{}/src/bin", project_root);
+    let _ = fs::remove_dir_all(&bin_dir); // 节点随模型变化，先清理上次生成的
+    fs::create_dir_all(&bin_dir).expect("Failed to create src/bin");
+
+    let nodes = deployed_nodes(project_root);
+    for (cpu, name) in &nodes {
+        let content = main_rs_content(system_type, module_name, project_name, backend, has_bus, Some(*cpu));
+        fs::write(format!("{}/node_{}.rs", bin_dir, name), content).expect("Failed to write node binary");
+    }
+
+    let names: Vec<String> = nodes.iter().map(|(_, name)| format!("\"node_{}\"", name)).collect();
+    let launcher = format!(
+        r#"use std::os::unix::process::CommandExt;
+use std::process::{{Child, Command}};
+use std::time::Duration;
 
-    let content = format!(
-        r#"use {project_name}::common_traits::System;
+// 在本机启动全部节点，用于测试分布式部署；节点可执行文件与启动器位于同一目录（先 cargo build --bins）
+const NODES: &[&str] = &[{nodes}];
+
+fn main() {{
+    let dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.to_path_buf()))
+        .expect("cannot locate launcher directory");
+    let mut children: Vec<(&str, Child)> = NODES
+        .iter()
+        .map(|name| {{
+            let path = dir.join(format!("{{}}{{}}", name, std::env::consts::EXE_SUFFIX));
+            let mut command = Command::new(&path);
+            // 启动器被结束时节点随之退出
+            unsafe {{
+                command.pre_exec(|| {{
+                    libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM);
+                    Ok(())
+                }});
+            }}
+            let child = command
+                .spawn()
+                .unwrap_or_else(|e| panic!("failed to start {{}}: {{}}", path.display(), e));
+            println!("[launcher] started {{}} (pid {{}})", name, child.id());
+            (*name, child)
+        }})
+        .collect();
+
+    // 任一节点退出时结束其余节点
+    loop {{
+        let exited = children
+            .iter_mut()
+            .find_map(|(name, child)| child.try_wait().ok().flatten().map(|status| (*name, status)));
+        if let Some((name, status)) = exited {{
+            println!("[launcher] {{}} exited: {{}}", name, status);
+            for (_, child) in children.iter_mut() {{
+                let _ = child.kill();
+                let _ = child.wait();
+            }}
+            std::process::exit(status.code().unwrap_or(1));
+        }}
+        std::thread::sleep(Duration::from_millis(200));
+    }}
+}}
+"#,
+        nodes = names.join(", ")
+    );
+    fs::write(format!("{}/launcher.rs", bin_dir), launcher).expect("Failed to write launcher.rs");
+
+    println!("节点与启动器已生成: {}", bin_dir);
+}
+
+// main.rs 的内容；node 为 Some 时是分布式部署中该节点的可执行文件，构造系统之前先指定节点
+fn main_rs_content(
+    system_type: &str,
+    module_name: &str,
+    project_name: &str,
+    backend: CodegenBackend,
+    has_bus: bool,
+    node: Option<isize>,
+) -> String {
+    let set_node = match node {
+        Some(cpu) => format!("\n    {}::net::set_node({});", project_name.replace('-', "_"), cpu),
+        None => String::new(),
+    };
+
+    // 有总线仿真时，主循环每轮打印各总线的统计
+    let bus_report = if has_bus {
+        format!("\n        {}::bus::report();", project_name.replace('-', "_"))
+    } else {
+        String::new()
+    };
+
+    // 异步后端：System::run 在 tokio 运行时内调用，各线程作为任务由 tokio::spawn 启动
+    if backend == CodegenBackend::Async {
+        format!(
+            r#"use {project_name}::common_traits::System;
+use {project_name}::{module_name}::{system_type};
+
+pub async fn boot<S: System>() {{
+    let system = S::new();
+    system.run();
+
+    // 主任务保持运行，防止运行时退出
+    loop {{
+        tokio::time::sleep(std::time::Duration::from_secs(60)).await;{bus_report}
+    }}
+}}
+
+#[tokio::main]
+async fn main() {{{set_node}
+    boot::<{system_type}>().await;
+}}
+"#,
+            system_type = system_type,
+            module_name = module_name,
+            project_name = project_name.replace('-', "_"),
+            bus_report = bus_report,
+            set_node = set_node,
+        )
+    } else {
+        format!(
+            r#"use {project_name}::common_traits::System;
 use {project_name}::{module_name}::{system_type};
 
 pub fn boot<S: System>() {{
@@ -342,22 +2104,21 @@ pub fn boot<S: System>() {{
 
     // 主线程保持运行，防止退出
     loop {{
-        std::thread::sleep(std::time::Duration::from_secs(60));
+        std::thread::sleep(std::time::Duration::from_secs(60));{bus_report}
     }}
 }}
 
-fn main() {{
+fn main() {{{set_node}
     boot::<{system_type}>();
 }}
 "#,
-        system_type = system_type,
-        module_name = module_name,
-        project_name = project_name.replace('-', "_"),
-    );
-
-    fs::write(&main_rs_path, content).expect("Failed to write main.rs");
-
-    println!("main.rs 已生成: {}", main_rs_path);
+            system_type = system_type,
+            module_name = module_name,
+            project_name = project_name.replace('-', "_"),
+            bus_report = bus_report,
+            set_node = set_node,
+        )
+    }
 }
 
 /// 返回 (module_name, system_type)
@@ -0,0 +1,185 @@
+// 前端诊断：转换与代码生成阶段遇到的问题统一收集为带严重级别、错误码和源码位置的诊断，
+// 整个模型处理完后一次性报告，而不是在第一个不支持的构造处 panic
+
+use crate::aadlight_parser;
+use crate::ast::aadl_ast_cj::SourceLocation;
+use crate::source_map::SourceMap;
+use once_cell::sync::Lazy;
+use pest::error::{InputLocation, LineColLocation};
+use pest::iterators::Pair;
+use std::fmt;
+use std::sync::Mutex;
+
+// 错误码：E01xx 模型加载与核心语法转换，E02xx 行为附件转换，E03xx 代码生成，E04xx 合法性检查；Wxxxx 为对应阶段的警告
+pub mod codes {
+    pub const SYNTAX_ERROR: &str = "E0100";
+    pub const UNSUPPORTED_DECLARATION: &str = "E0101";
+    pub const UNKNOWN_CATEGORY: &str = "E0102";
+    pub const UNKNOWN_PROTOTYPE: &str = "E0103";
+    pub const UNKNOWN_PORT_TYPE: &str = "E0104";
+    pub const UNKNOWN_ACCESS_TYPE: &str = "E0105";
+    pub const MALFORMED_FEATURE: &str = "E0106";
+    pub const MALFORMED_PROPERTY: &str = "E0107";
+    pub const MALFORMED_CONNECTION: &str = "E0108";
+    pub const MISSING_PACKAGE: &str = "E0109";
+    pub const CYCLIC_DEPENDENCY: &str = "E0110";
+    pub const INVALID_REFINEMENT: &str = "E0111";
+    pub const UNRESOLVED_ANCESTOR: &str = "E0112";
+    pub const CYCLIC_EXTENSION: &str = "E0113";
+    pub const EXTENSION_MISMATCH: &str = "E0114";
+    pub const PROTOTYPE_MISMATCH: &str = "E0115";
+    pub const UNBOUND_PROTOTYPE: &str = "E0116";
+    pub const INVALID_ARRAY_DIMENSION: &str = "E0117";
+    pub const IGNORED_CONSTRUCT: &str = "W0101";
+    pub const UNUSED_MISSING_PACKAGE: &str = "W0102";
+    pub const ARRAY_CONNECTION_MISMATCH: &str = "W0103";
+    pub const INSTANTIATION_PROBLEM: &str = "W0104";
+
+    pub const UNKNOWN_OPERATOR: &str = "E0201";
+    pub const MALFORMED_BEHAVIOR: &str = "E0202";
+
+    pub const UNRESOLVED_DATA_TYPE: &str = "E0301";
+    pub const UNSIZED_ARRAY: &str = "E0302";
+    pub const UNSCHEDULABLE_TASK_SET: &str = "E0303";
+    pub const HYPERPERIOD_OVERFLOW: &str = "E0304";
+    pub const UNSUPPORTED_BY_BACKEND: &str = "E0305";
+    pub const UNBOUNDED_SPORADIC_THREAD: &str = "E0306";
+    pub const UNMAPPED_CONNECTION: &str = "W0301";
+    pub const UNTYPED_COMPUTED_PROPERTY: &str = "W0302";
+    pub const UNBOUNDED_STRING: &str = "W0303";
+    pub const IGNORED_SCHEDULER_QUANTUM: &str = "W0304";
+    pub const CONFLICTING_BUS_BINDING: &str = "W0305";
+    pub const UNENCODABLE_DATA_SIZE: &str = "W0306";
+    pub const IGNORED_VALUE_RANGE: &str = "W0307";
+    pub const ADJUSTED_FIXED_POINT: &str = "W0308";
+    pub const IGNORED_ENUM_REPRESENTATION: &str = "W0309";
+    pub const UNEXPECTED_PROPERTY_TYPE: &str = "W0310";
+
+    pub const DUPLICATE_IDENTIFIER: &str = "E0401";
+    pub const UNRESOLVED_CLASSIFIER: &str = "E0402";
+    pub const UNDECLARED_COMPONENT_TYPE: &str = "E0403";
+    pub const CATEGORY_MISMATCH: &str = "E0404";
+    pub const MISSING_IMPLEMENTATION: &str = "E0405";
+    pub const DIRECTION_MISMATCH: &str = "E0406";
+    pub const PORT_TYPE_MISMATCH: &str = "E0407";
+    pub const UNRESOLVED_FEATURE: &str = "E0408";
+    pub const MISSING_PERIOD: &str = "E0409";
+    pub const THREAD_WITHOUT_IMPLEMENTATION: &str = "W0401";
+    pub const MISSING_DISPATCH_PROTOCOL: &str = "W0402";
+    pub const SPORADIC_WITHOUT_PERIOD: &str = "W0403";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        })
+    }
+}
+
+// 源码位置：offset 为解析缓冲区中的字节偏移，line/col 从 1 开始（均相对于缓冲区，经 SourceMap 换算回原文件）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub offset: usize,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Span {
+    pub fn of(pair: &Pair<aadlight_parser::Rule>) -> Self {
+        let start = pair.as_span().start_pos();
+        let (line, col) = start.line_col();
+        Span { offset: start.pos(), line, col }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub span: Option<Span>,
+    // 已换算好的原文件位置：AST 上的检查没有解析位置，使用声明处记录的位置
+    pub location: Option<SourceLocation>,
+}
+
+impl Diagnostic {
+    // 按 `file:line:col: error[E0101]: message` 的格式输出，位置换算回原始文件；没有位置时只给出输入路径
+    pub fn render(&self, sources: &SourceMap) -> String {
+        match self.span.and_then(|span| sources.locate(span.offset)).or_else(|| self.location.clone()) {
+            Some(location) => format!("{}: {}", location, self),
+            None => format!("{}: {}", sources.input, self),
+        }
+    }
+}
+
+impl Diagnostic {
+    // pest 语法错误 -> 诊断（位置取错误起点）
+    pub fn syntax(error: &pest::error::Error<aadlight_parser::Rule>) -> Self {
+        let offset = match error.location {
+            InputLocation::Pos(pos) => pos,
+            InputLocation::Span((start, _)) => start,
+        };
+        let (line, col) = match error.line_col {
+            LineColLocation::Pos(pos) | LineColLocation::Span(pos, _) => pos,
+        };
+        Diagnostic {
+            severity: Severity::Error,
+            code: codes::SYNTAX_ERROR,
+            message: format!("syntax error: {}", error.variant.message()),
+            span: Some(Span { offset, line, col }),
+            location: None,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)
+    }
+}
+
+// 全局诊断收集器：转换函数大多是无状态的关联函数，与全局端口管理器一样通过全局状态汇总
+static DIAGNOSTICS: Lazy<Mutex<Vec<Diagnostic>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+pub fn report(diagnostic: Diagnostic) {
+    DIAGNOSTICS.lock().unwrap().push(diagnostic);
+}
+
+pub fn error_at(pair: &Pair<aadlight_parser::Rule>, code: &'static str, message: impl Into<String>) {
+    report(Diagnostic { severity: Severity::Error, code, message: message.into(), span: Some(Span::of(pair)), location: None });
+}
+
+pub fn warning_at(pair: &Pair<aadlight_parser::Rule>, code: &'static str, message: impl Into<String>) {
+    report(Diagnostic { severity: Severity::Warning, code, message: message.into(), span: Some(Span::of(pair)), location: None });
+}
+
+// 代码生成阶段 AST 已不带位置信息，只记录错误码和消息
+pub fn error(code: &'static str, message: impl Into<String>) {
+    report(Diagnostic { severity: Severity::Error, code, message: message.into(), span: None, location: None });
+}
+
+pub fn warning(code: &'static str, message: impl Into<String>) {
+    report(Diagnostic { severity: Severity::Warning, code, message: message.into(), span: None, location: None });
+}
+
+// 针对 AST 中某个声明的错误，位置取该声明在源文件中的位置
+pub fn error_in(location: Option<&SourceLocation>, code: &'static str, message: impl Into<String>) {
+    report(Diagnostic { severity: Severity::Error, code, message: message.into(), span: None, location: location.cloned() });
+}
+
+// 取出并清空已收集的诊断（每个案例处理前后各调用一次，避免相互串扰）
+pub fn take() -> Vec<Diagnostic> {
+    std::mem::take(&mut *DIAGNOSTICS.lock().unwrap())
+}
+
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
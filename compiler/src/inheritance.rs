@@ -0,0 +1,438 @@
+// 分类器继承：把 extends 声明与其祖先链合并为等价的普通组件类型/实现，并应用 refined to 精化，
+// 转换结束后执行一次，实例化、合法性检查与各代码转换器看到的都是合并后的有效分类器
+use crate::ast::aadl_ast_cj::*;
+use crate::diagnostics::{self, codes};
+use crate::instance_model::{feature_identifier, ClassifierIndex};
+use std::collections::HashSet;
+
+// 声明在包中的位置：(包序号, 是否私有部分, 声明序号)
+type DeclarationSlot = (usize, bool, usize);
+
+pub fn resolve_extensions(packages: &mut [Package]) {
+    // 祖先本身也可能是扩展：每一轮只合并祖先已是普通分类器的扩展，直到没有进展
+    loop {
+        let pending = pending_extensions(packages);
+        if pending.is_empty() {
+            return;
+        }
+        let merged: Vec<(DeclarationSlot, AadlDeclaration)> = {
+            let index = ClassifierIndex::new(packages);
+            declaration_slots(packages)
+                .filter_map(|(slot, pkg_name, decl)| {
+                    let merged = match decl {
+                        AadlDeclaration::ComponentTypeExtension(ext) => {
+                            let prefix = ext.extends.package_prefix.as_ref();
+                            if pending.contains(&pending_key(&pkg_name, prefix, &ext.extends.identifier)) {
+                                return None;
+                            }
+                            let (_, parent) = index.find_type(&pkg_name, prefix, &ext.extends.identifier)?;
+                            AadlDeclaration::ComponentType(extend_type(parent, ext))
+                        }
+                        AadlDeclaration::ComponentImplementationExtension(ext) => {
+                            let prefix = ext.extends.package_prefix.as_ref();
+                            let parent_name = &ext.extends.implementation_name;
+                            if pending.contains(&pending_key(&pkg_name, prefix, &parent_name.to_string())) {
+                                return None;
+                            }
+                            let (_, parent) = index.find_impl(&pkg_name, prefix, parent_name)?;
+                            AadlDeclaration::ComponentImplementation(extend_impl(parent, ext))
+                        }
+                        _ => return None,
+                    };
+                    Some((slot, merged))
+                })
+                .collect()
+        };
+        if merged.is_empty() {
+            break;
+        }
+        for ((pkg, private, decl), merged) in merged {
+            if let Some(section) = section_mut(&mut packages[pkg], private) {
+                section.declarations[decl] = merged;
+            }
+        }
+    }
+
+    // 剩下的扩展找不到祖先或处在继承环上：报告后只保留其自身声明的内容
+    let pending = pending_extensions(packages);
+    for pkg in packages.iter_mut() {
+        let pkg_name = pkg.name.to_string();
+        let sections = pkg.public_section.iter_mut().chain(pkg.private_section.iter_mut());
+        for decl in sections.flat_map(|section| section.declarations.iter_mut()) {
+            match decl {
+                AadlDeclaration::ComponentTypeExtension(ext) => {
+                    let parent = ext.extends.identifier.clone();
+                    report_unresolved(&pending, &pkg_name, ext.extends.package_prefix.as_ref(), &parent, &ext.identifier, ext.location.as_ref());
+                    *decl = AadlDeclaration::ComponentType(own_type(ext));
+                }
+                AadlDeclaration::ComponentImplementationExtension(ext) => {
+                    let parent = ext.extends.implementation_name.to_string();
+                    let name = ext.name.to_string();
+                    report_unresolved(&pending, &pkg_name, ext.extends.package_prefix.as_ref(), &parent, &name, ext.location.as_ref());
+                    *decl = AadlDeclaration::ComponentImplementation(own_impl(ext));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn report_unresolved(
+    pending: &HashSet<(String, String)>,
+    pkg_name: &str,
+    prefix: Option<&PackageName>,
+    parent: &str,
+    classifier: &str,
+    location: Option<&SourceLocation>,
+) {
+    if pending.contains(&pending_key(pkg_name, prefix, parent)) {
+        diagnostics::error_in(
+            location,
+            codes::CYCLIC_EXTENSION,
+            format!("{} extends {}, which (indirectly) extends {} again", classifier, parent, classifier),
+        );
+    } else {
+        diagnostics::error_in(
+            location,
+            codes::UNRESOLVED_ANCESTOR,
+            format!("{} extends unknown classifier {}", classifier, parent),
+        );
+    }
+}
+
+// 尚未合并的扩展，key 为 (包名, 分类器名)，均为小写
+fn pending_extensions(packages: &[Package]) -> HashSet<(String, String)> {
+    declaration_slots(packages)
+        .filter_map(|(_, pkg_name, decl)| match decl {
+            AadlDeclaration::ComponentTypeExtension(ext) => Some(pending_key(&pkg_name, None, &ext.identifier)),
+            AadlDeclaration::ComponentImplementationExtension(ext) => Some(pending_key(&pkg_name, None, &ext.name.to_string())),
+            _ => None,
+        })
+        .collect()
+}
+
+fn pending_key(pkg_name: &str, prefix: Option<&PackageName>, name: &str) -> (String, String) {
+    let pkg = prefix.map(|p| p.to_string()).unwrap_or_else(|| pkg_name.to_string());
+    (pkg.to_lowercase(), name.to_lowercase())
+}
+
+fn declaration_slots(packages: &[Package]) -> impl Iterator<Item = (DeclarationSlot, String, &AadlDeclaration)> {
+    packages.iter().enumerate().flat_map(|(pkg_index, pkg)| {
+        let pkg_name = pkg.name.to_string();
+        [(false, &pkg.public_section), (true, &pkg.private_section)]
+            .into_iter()
+            .filter_map(|(private, section)| section.as_ref().map(|section| (private, section)))
+            .flat_map(move |(private, section)| {
+                let pkg_name = pkg_name.clone();
+                section
+                    .declarations
+                    .iter()
+                    .enumerate()
+                    .map(move |(decl_index, decl)| ((pkg_index, private, decl_index), pkg_name.clone(), decl))
+            })
+    })
+}
+
+fn section_mut(pkg: &mut Package, private: bool) -> Option<&mut PackageSection> {
+    if private {
+        pkg.private_section.as_mut()
+    } else {
+        pkg.public_section.as_mut()
+    }
+}
+
+// 祖先（已是有效分类器）+ 扩展 -> 有效组件类型
+fn extend_type(parent: &ComponentType, ext: &ComponentTypeExtension) -> ComponentType {
+    let location = ext.location.as_ref();
+    check_category(&ext.category, &parent.category, &ext.identifier, &parent.identifier, location);
+
+    let mut features = merge_named(
+        &ext.identifier,
+        &parent.identifier,
+        location,
+        "feature",
+        feature_items(&parent.features),
+        feature_items(&ext.features),
+        |f: &Feature| feature_identifier(f).to_string(),
+    );
+    for refined in &ext.feature_refinements {
+        let name = feature_identifier(refined);
+        match features.iter_mut().find(|f| feature_identifier(f).eq_ignore_ascii_case(name)) {
+            Some(feature) => *feature = refined.clone(),
+            None => report_missing_refined(&ext.identifier, &parent.identifier, location, "feature", name),
+        }
+    }
+
+    ComponentType {
+        category: ext.category.clone(),
+        identifier: ext.identifier.clone(),
+        prototype_bindings: ext.prototype_bindings.clone().or_else(|| parent.prototype_bindings.clone()),
+        prototypes: merge_prototypes(&parent.prototypes, &ext.prototypes),
+        features: if features.is_empty() { ext.features.clone() } else { FeatureClause::Items(features) },
+        properties: merge_properties(&parent.properties, &ext.properties),
+        annexes: merge_annexes(&parent.annexes, &ext.annexes),
+        location: ext.location.clone(),
+    }
+}
+
+// 祖先（已是有效分类器）+ 扩展 -> 有效组件实现
+fn extend_impl(parent: &ComponentImplementation, ext: &ComponentImplementationExtension) -> ComponentImplementation {
+    let location = ext.location.as_ref();
+    let name = ext.name.to_string();
+    let parent_name = parent.name.to_string();
+    check_category(&ext.category, &parent.category, &name, &parent_name, location);
+
+    let mut subcomponents = merge_named(
+        &name,
+        &parent_name,
+        location,
+        "subcomponent",
+        subcomponent_items(&parent.subcomponents),
+        subcomponent_items(&ext.subcomponents),
+        |s: &Subcomponent| s.identifier.clone(),
+    );
+    for refined in &ext.subcomponent_refinements {
+        match subcomponents.iter_mut().find(|s| s.identifier.eq_ignore_ascii_case(&refined.identifier)) {
+            Some(sub) => {
+                check_category(&refined.category, &sub.category, &refined.identifier, &sub.identifier, location);
+                sub.category = refined.category.clone();
+                if let Some(classifier) = &refined.classifier {
+                    sub.classifier = classifier.clone();
+                }
+                if refined.prototype_bindings.is_some() {
+                    sub.prototype_bindings = refined.prototype_bindings.clone();
+                }
+                if refined.array_spec.is_some() {
+                    sub.array_spec = refined.array_spec.clone();
+                }
+                sub.properties.extend(refined.properties.iter().cloned());
+            }
+            None => report_missing_refined(&name, &parent_name, location, "subcomponent", &refined.identifier),
+        }
+    }
+
+    let mut connections = merge_named(
+        &name,
+        &parent_name,
+        location,
+        "connection",
+        connection_items(&parent.connections),
+        connection_items(&ext.connections),
+        |c: &Connection| connection_identifier(c).unwrap_or_default().to_string(),
+    );
+    for refined in &ext.connection_refinements {
+        let inherited = connections
+            .iter_mut()
+            .find(|c| connection_identifier(c).is_some_and(|n| n.eq_ignore_ascii_case(&refined.original_name)));
+        match inherited.and_then(connection_properties_mut) {
+            Some(properties) => *properties = merge_property_list(properties, &refined.properties),
+            None => report_missing_refined(&name, &parent_name, location, "connection", &refined.original_name),
+        }
+    }
+
+    ComponentImplementation {
+        category: ext.category.clone(),
+        name: ext.name.clone(),
+        prototype_bindings: ext.prototype_bindings.clone().or_else(|| parent.prototype_bindings.clone()),
+        prototypes: merge_prototypes(&parent.prototypes, &ext.prototypes),
+        subcomponents: if subcomponents.is_empty() { ext.subcomponents.clone() } else { SubcomponentClause::Items(subcomponents) },
+        calls: merge_calls(&parent.calls, &ext.calls),
+        connections: if connections.is_empty() { ext.connections.clone() } else { ConnectionClause::Items(connections) },
+        properties: merge_properties(&parent.properties, &ext.properties),
+        annexes: merge_annexes(&parent.annexes, &ext.annexes),
+        location: ext.location.clone(),
+    }
+}
+
+// 找不到祖先时的退化结果：只含扩展自身声明的内容
+fn own_type(ext: &ComponentTypeExtension) -> ComponentType {
+    ComponentType {
+        category: ext.category.clone(),
+        identifier: ext.identifier.clone(),
+        prototype_bindings: ext.prototype_bindings.clone(),
+        prototypes: ext.prototypes.clone(),
+        features: ext.features.clone(),
+        properties: ext.properties.clone(),
+        annexes: ext.annexes.clone(),
+        location: ext.location.clone(),
+    }
+}
+
+fn own_impl(ext: &ComponentImplementationExtension) -> ComponentImplementation {
+    ComponentImplementation {
+        category: ext.category.clone(),
+        name: ext.name.clone(),
+        prototype_bindings: ext.prototype_bindings.clone(),
+        prototypes: ext.prototypes.clone(),
+        subcomponents: ext.subcomponents.clone(),
+        calls: ext.calls.clone(),
+        connections: ext.connections.clone(),
+        properties: ext.properties.clone(),
+        annexes: ext.annexes.clone(),
+        location: ext.location.clone(),
+    }
+}
+
+// 扩展的类别必须与祖先相同，祖先为 abstract 时可以扩展为任意类别
+fn check_category(
+    category: &ComponentCategory,
+    parent_category: &ComponentCategory,
+    name: &str,
+    parent_name: &str,
+    location: Option<&SourceLocation>,
+) {
+    if category != parent_category && *parent_category != ComponentCategory::Abstract {
+        diagnostics::error_in(
+            location,
+            codes::EXTENSION_MISMATCH,
+            format!("{:?} {} cannot extend or refine {:?} {}", category, name, parent_category, parent_name),
+        );
+    }
+}
+
+fn report_missing_refined(classifier: &str, parent: &str, location: Option<&SourceLocation>, what: &str, name: &str) {
+    diagnostics::error_in(
+        location,
+        codes::EXTENSION_MISMATCH,
+        format!("{} refines {} `{}`, which is not declared in ancestor {}", classifier, what, name, parent),
+    );
+}
+
+// 继承的项在前，扩展新声明的项追加在后；与祖先重名的新项应使用 refined to，报告后忽略
+fn merge_named<T: Clone>(
+    classifier: &str,
+    parent: &str,
+    location: Option<&SourceLocation>,
+    what: &str,
+    inherited: Vec<T>,
+    own: Vec<T>,
+    name: impl Fn(&T) -> String,
+) -> Vec<T> {
+    let mut merged = inherited;
+    for item in own {
+        let item_name = name(&item);
+        if !item_name.is_empty() && merged.iter().any(|m| name(m).eq_ignore_ascii_case(&item_name)) {
+            diagnostics::error_in(
+                location,
+                codes::EXTENSION_MISMATCH,
+                format!("{} `{}` of {} is already declared in ancestor {}; use `refined to`", what, item_name, classifier, parent),
+            );
+            continue;
+        }
+        merged.push(item);
+    }
+    merged
+}
+
+// 扩展中重新关联的属性覆盖祖先中的同名关联（+=> 追加与 applies to 的关联不覆盖）
+fn merge_properties(inherited: &PropertyClause, own: &PropertyClause) -> PropertyClause {
+    let props = |clause: &PropertyClause| match clause {
+        PropertyClause::Properties(props) => props.clone(),
+        PropertyClause::ExplicitNone => Vec::new(),
+    };
+    let merged = merge_property_list(&props(inherited), &props(own));
+    if merged.is_empty() {
+        own.clone()
+    } else {
+        PropertyClause::Properties(merged)
+    }
+}
+
+// 分类器与连接精化共用的覆盖规则：后者中重新关联的属性替换前者中的同名关联
+fn merge_property_list(inherited: &[Property], own: &[Property]) -> Vec<Property> {
+    let overridden: HashSet<(String, String)> = own.iter().filter_map(overriding_key).collect();
+    inherited
+        .iter()
+        .filter(|p| property_key(p).is_none_or(|key| !overridden.contains(&key)))
+        .chain(own.iter())
+        .cloned()
+        .collect()
+}
+
+fn property_key(property: &Property) -> Option<(String, String)> {
+    // applies to 等限定的关联各自作用于不同目标，不参与按名称覆盖
+    let Property::BasicProperty(bp) = property else { return None };
+    let set = bp.identifier.property_set.as_deref().unwrap_or_default().to_lowercase();
+    Some((set, bp.identifier.name.to_lowercase()))
+}
+
+fn overriding_key(property: &Property) -> Option<(String, String)> {
+    match property {
+        Property::BasicProperty(bp) if bp.operator == PropertyOperator::Append => None,
+        _ => property_key(property),
+    }
+}
+
+// 同一种附件（行为附件/EMV2）以扩展中的为准
+fn merge_annexes(inherited: &[AnnexSubclause], own: &[AnnexSubclause]) -> Vec<AnnexSubclause> {
+    inherited
+        .iter()
+        .filter(|a| !own.iter().any(|o| o.identifier == a.identifier))
+        .chain(own.iter())
+        .cloned()
+        .collect()
+}
+
+fn merge_prototypes(inherited: &PrototypeClause, own: &PrototypeClause) -> PrototypeClause {
+    match (inherited, own) {
+        (PrototypeClause::Items(parent), PrototypeClause::Items(items)) => {
+            PrototypeClause::Items(parent.iter().chain(items.iter()).cloned().collect())
+        }
+        (PrototypeClause::Items(_), _) => inherited.clone(),
+        _ => own.clone(),
+    }
+}
+
+// 调用序列按名称合并，扩展中的同名序列替换祖先中的
+fn merge_calls(inherited: &CallSequenceClause, own: &CallSequenceClause) -> CallSequenceClause {
+    match (inherited, own) {
+        (CallSequenceClause::Items(parent), CallSequenceClause::Items(items)) => CallSequenceClause::Items(
+            parent
+                .iter()
+                .filter(|p| !items.iter().any(|i| i.identifier.eq_ignore_ascii_case(&p.identifier)))
+                .chain(items.iter())
+                .cloned()
+                .collect(),
+        ),
+        (CallSequenceClause::Items(_), _) => inherited.clone(),
+        _ => own.clone(),
+    }
+}
+
+fn feature_items(clause: &FeatureClause) -> Vec<Feature> {
+    match clause {
+        FeatureClause::Items(items) => items.clone(),
+        _ => Vec::new(),
+    }
+}
+
+fn subcomponent_items(clause: &SubcomponentClause) -> Vec<Subcomponent> {
+    match clause {
+        SubcomponentClause::Items(items) => items.clone(),
+        _ => Vec::new(),
+    }
+}
+
+fn connection_items(clause: &ConnectionClause) -> Vec<Connection> {
+    match clause {
+        ConnectionClause::Items(items) => items.clone(),
+        _ => Vec::new(),
+    }
+}
+
+// 端口/访问连接带名称，参数连接没有可精化的名字
+fn connection_identifier(connection: &Connection) -> Option<&str> {
+    match connection {
+        Connection::Port(port) => Some(&port.identifier),
+        Connection::Access(access) => Some(&access.identifier),
+        Connection::Parameter(_) => None,
+    }
+}
+
+fn connection_properties_mut(connection: &mut Connection) -> Option<&mut Vec<Property>> {
+    match connection {
+        Connection::Port(port) => Some(&mut port.properties),
+        Connection::Access(access) => Some(&mut access.properties),
+        Connection::Parameter(_) => None,
+    }
+}
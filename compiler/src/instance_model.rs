@@ -0,0 +1,1459 @@
+// AADL 实例模型
+// 从根系统实现出发逐层实例化子组件：跨包解析子组件分类器，为每个实例汇总特征与属性，
+// 并把各层声明的连接展开为端到端的语义连接（最终源特征 -> 最终目的特征）。
+// 子组件数组按元素逐个实例化（实例名形如 sensors[3]，下标从 1 开始），
+// 数组之间的连接按 Connection_Set 列出的下标对或 Connection_Pattern 展开到元素。
+// 带 applies to / in binding 的属性关联在实例树建好后解析到目标实例，外层声明优先。
+// 总线访问连接确定各总线接入的组件，Actual_Connection_Binding 把语义连接绑定到总线上。
+// 代码生成与模型分析直接消费这棵实例树，而不必各自从声明模型重建层次关系。
+use crate::ast::aadl_ast_cj::*;
+use crate::diagnostics::{self, codes};
+use std::collections::{HashMap, HashSet};
+
+// 实例路径：从根系统开始的子组件标识符序列（根实例的路径为空）
+pub type InstancePath = Vec<String>;
+
+// 已解析的分类器：所在包 + 类型名 + 实现名（引用组件类型时为 None）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassifierName {
+    pub package: String,
+    pub type_name: String,
+    pub impl_name: Option<String>,
+}
+
+impl ClassifierName {
+    // 形如 Pkg::T.impl 的限定名
+    pub fn qualified(&self) -> String {
+        match &self.impl_name {
+            Some(impl_name) => format!("{}::{}.{}", self.package, self.type_name, impl_name),
+            None => format!("{}::{}", self.package, self.type_name),
+        }
+    }
+}
+
+// 组件实例
+#[derive(Debug, Clone)]
+pub struct ComponentInstance {
+    pub name: String, // 子组件标识符；根实例为系统实现名；数组元素带下标，如 sensors[3]
+    pub path: InstancePath,
+    pub array_index: Vec<u32>, // 数组元素在各维上的下标（从 1 开始），非数组子组件为空
+    pub category: ComponentCategory,
+    pub classifier: Option<ClassifierName>, // 未能解析时为 None
+    pub features: Vec<Feature>,            // 来自组件类型
+    pub properties: Vec<BasicPropertyAssociation>, // 类型 -> 实现 -> 子组件声明 -> applies to，后者覆盖前者
+    pub instance_properties: Vec<BasicPropertyAssociation>, // 其中由子组件声明与 applies to 施加到本实例的部分
+    pub subcomponents: Vec<ComponentInstance>,
+    pub processor_binding: Option<InstancePath>, // Actual_Processor_Binding 解析出的处理器实例
+}
+
+impl ComponentInstance {
+    // 按名称（不区分大小写、忽略属性集前缀）查找本实例上的属性
+    pub fn property(&self, name: &str) -> Option<&PropertyValue> {
+        self.properties
+            .iter()
+            .find(|p| p.identifier.name.eq_ignore_ascii_case(name) && is_unconditional(p))
+            .map(|p| &p.value)
+    }
+
+    pub fn feature(&self, name: &str) -> Option<&Feature> {
+        self.features
+            .iter()
+            .find(|f| feature_identifier(f).eq_ignore_ascii_case(name))
+    }
+
+    // 去掉数组下标后的子组件标识符
+    pub fn base_name(&self) -> &str {
+        self.name.split('[').next().unwrap_or(&self.name)
+    }
+
+    // 子组件数组 identifier 的全部元素，按下标顺序
+    pub fn array_elements(&self, identifier: &str) -> Vec<&ComponentInstance> {
+        self.subcomponents
+            .iter()
+            .filter(|sub| !sub.array_index.is_empty() && sub.base_name().eq_ignore_ascii_case(identifier))
+            .collect()
+    }
+
+    // 先序遍历本实例及其全部后代
+    pub fn walk(&self) -> Vec<&ComponentInstance> {
+        let mut out = vec![self];
+        for sub in &self.subcomponents {
+            out.extend(sub.walk());
+        }
+        out
+    }
+}
+
+pub fn feature_identifier(feature: &Feature) -> &str {
+    match feature {
+        Feature::Port(port) => &port.identifier,
+        Feature::SubcomponentAccess(SubcomponentAccessSpec::Data(access)) => &access.identifier,
+        Feature::SubcomponentAccess(SubcomponentAccessSpec::Subprogram(access)) => &access.identifier,
+        Feature::SubcomponentAccess(SubcomponentAccessSpec::Bus(access) | SubcomponentAccessSpec::VirtualBus(access)) => &access.identifier,
+        Feature::Abstract(spec) => &spec.identifier,
+    }
+}
+
+// 特征实例引用：所属组件实例路径 + 特征名（端点直接是数据子组件时特征名为空）
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FeatureRef {
+    pub component: InstancePath,
+    pub feature: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionKind {
+    Port,
+    Access,
+    Bus, // 总线/虚拟总线访问连接：只表示硬件拓扑，不形成语义连接
+}
+
+// 语义连接：沿各层连接声明从最终源追踪到最终目的
+#[derive(Debug, Clone)]
+pub struct SemanticConnection {
+    pub kind: ConnectionKind,
+    pub source: FeatureRef,
+    pub destination: FeatureRef,
+    pub declarations: Vec<String>, // 途经的连接声明，形如 组件路径.连接名
+    pub bus_binding: Vec<InstancePath>, // 途经声明上 Actual_Connection_Binding 引用的平台组件，按声明顺序
+}
+
+// 总线（或虚拟总线）实例及经总线访问连接接入它的组件
+#[derive(Debug, Clone)]
+pub struct BusInstance {
+    pub path: InstancePath,
+    pub virtual_bus: bool,
+    pub attached: Vec<InstancePath>, // 处理器、存储器、设备等，不含只转接总线访问的系统
+}
+
+// 以某个根系统实现实例化得到的完整模型
+#[derive(Debug, Clone)]
+pub struct SystemInstance {
+    pub root: ComponentInstance,
+    pub connections: Vec<SemanticConnection>,
+    pub buses: Vec<BusInstance>,
+    pub warnings: Vec<String>, // 实例化过程中无法解析的分类器、绑定等
+}
+
+impl SystemInstance {
+    // 按实例路径查找组件实例
+    pub fn component(&self, path: &[String]) -> Option<&ComponentInstance> {
+        let mut current = &self.root;
+        for name in path {
+            current = current
+                .subcomponents
+                .iter()
+                .find(|sub| sub.name.eq_ignore_ascii_case(name))?;
+        }
+        Some(current)
+    }
+
+    // 实例所在的处理器：自身未绑定时沿用最近祖先的绑定（线程随所在进程绑定）
+    pub fn bound_processor(&self, path: &[String]) -> Option<&InstancePath> {
+        (0..=path.len())
+            .rev()
+            .find_map(|len| self.component(&path[..len])?.processor_binding.as_ref())
+    }
+
+    pub fn connections_from<'a>(&'a self, source: &'a FeatureRef) -> impl Iterator<Item = &'a SemanticConnection> + 'a {
+        self.connections.iter().filter(move |c| &c.source == source)
+    }
+
+    pub fn bus(&self, path: &[String]) -> Option<&BusInstance> {
+        self.buses.iter().find(|bus| bus.path == path)
+    }
+
+    // 语义连接经由的总线：绑定目标中的第一条总线或虚拟总线
+    pub fn connection_bus(&self, connection: &SemanticConnection) -> Option<&BusInstance> {
+        connection.bus_binding.iter().find_map(|path| self.bus(path))
+    }
+}
+
+// 模型中所有包的分类器索引，key 为 (包名, 标识符)，实现的标识符形如 T.impl
+pub struct ClassifierIndex<'a> {
+    packages: Vec<&'a Package>,
+    types: HashMap<(String, String), &'a ComponentType>,
+    impls: HashMap<(String, String), &'a ComponentImplementation>,
+}
+
+impl<'a> ClassifierIndex<'a> {
+    pub fn new(packages: &'a [Package]) -> Self {
+        let mut types = HashMap::new();
+        let mut impls = HashMap::new();
+        for pkg in packages {
+            let pkg_name = pkg.name.to_string();
+            let sections = pkg.public_section.iter().chain(pkg.private_section.iter());
+            for decl in sections.flat_map(|s| s.declarations.iter()) {
+                match decl {
+                    AadlDeclaration::ComponentType(comp) => {
+                        types.insert((pkg_name.clone(), comp.identifier.to_lowercase()), comp);
+                    }
+                    AadlDeclaration::ComponentImplementation(impl_) => {
+                        impls.insert((pkg_name.clone(), impl_.name.to_string().to_lowercase()), impl_);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Self {
+            packages: packages.iter().collect(),
+            types,
+            impls,
+        }
+    }
+
+    // 引用未带包前缀时的查找顺序：当前包 -> with 引入的包 -> 其余包
+    fn candidate_packages(&self, from: &str, prefix: Option<&PackageName>) -> Vec<String> {
+        if let Some(prefix) = prefix {
+            // AADL 标识符不区分大小写，包前缀按声明时的写法归一
+            let prefix = prefix.to_string();
+            let declared = self.packages.iter().map(|p| p.name.to_string()).find(|n| n.eq_ignore_ascii_case(&prefix));
+            return vec![declared.unwrap_or(prefix)];
+        }
+        let mut order = vec![from.to_string()];
+        if let Some(pkg) = self.packages.iter().find(|p| p.name.to_string() == from) {
+            for decl in &pkg.visibility_decls {
+                if let VisibilityDeclaration::Import { packages, .. } = decl {
+                    order.extend(packages.iter().map(|p| p.to_string()));
+                }
+            }
+        }
+        for pkg in &self.packages {
+            let name = pkg.name.to_string();
+            if !order.contains(&name) {
+                order.push(name);
+            }
+        }
+        order
+    }
+
+    pub fn find_type(&self, from: &str, prefix: Option<&PackageName>, name: &str) -> Option<(String, &'a ComponentType)> {
+        self.candidate_packages(from, prefix).into_iter().find_map(|pkg| {
+            let comp = *self.types.get(&(pkg.clone(), name.to_lowercase()))?;
+            Some((pkg, comp))
+        })
+    }
+
+    pub fn find_impl(&self, from: &str, prefix: Option<&PackageName>, name: &ImplementationName) -> Option<(String, &'a ComponentImplementation)> {
+        let key = name.to_string().to_lowercase();
+        self.candidate_packages(from, prefix).into_iter().find_map(|pkg| {
+            let impl_ = *self.impls.get(&(pkg.clone(), key.clone()))?;
+            Some((pkg, impl_))
+        })
+    }
+
+    // 未被任何实现作为子组件引用的系统实现，即可作为实例化起点的根系统
+    pub fn root_systems(&self) -> Vec<(String, &'a ComponentImplementation)> {
+        let mut referenced = HashSet::new();
+        for ((pkg, _), impl_) in &self.impls {
+            if let SubcomponentClause::Items(subs) = &impl_.subcomponents {
+                for sub in subs {
+                    if let Some((ref_pkg, type_name, Some(impl_name))) = self.resolve_subcomponent(pkg, sub) {
+                        referenced.insert((ref_pkg, format!("{}.{}", type_name, impl_name).to_lowercase()));
+                    }
+                }
+            }
+        }
+
+        let mut roots = Vec::new();
+        for pkg in &self.packages {
+            let pkg_name = pkg.name.to_string();
+            let sections = pkg.public_section.iter().chain(pkg.private_section.iter());
+            for decl in sections.flat_map(|s| s.declarations.iter()) {
+                if let AadlDeclaration::ComponentImplementation(impl_) = decl {
+                    if impl_.category == ComponentCategory::System
+                        && !referenced.contains(&(pkg_name.clone(), impl_.name.to_string().to_lowercase()))
+                    {
+                        roots.push((pkg_name.clone(), impl_));
+                    }
+                }
+            }
+        }
+        roots
+    }
+
+    // 解析子组件分类器，返回 (包名, 类型名, 实现名)
+    fn resolve_subcomponent(&self, from: &str, sub: &Subcomponent) -> Option<(String, String, Option<String>)> {
+        let SubcomponentClassifier::ClassifierReference(reference) = &sub.classifier else {
+            return None;
+        };
+        let unirf = match reference {
+            UniqueComponentClassifierReference::Type(r) | UniqueComponentClassifierReference::Implementation(r) => r,
+        };
+        let name = &unirf.implementation_name;
+        if !name.implementation_identifier.is_empty() {
+            if let Some((pkg, impl_)) = self.find_impl(from, unirf.package_prefix.as_ref(), name) {
+                return Some((
+                    pkg,
+                    impl_.name.type_identifier.clone(),
+                    Some(impl_.name.implementation_identifier.clone()),
+                ));
+            }
+        }
+        let (pkg, comp) = self.find_type(from, unirf.package_prefix.as_ref(), &name.type_identifier)?;
+        Some((pkg, comp.identifier.clone(), None))
+    }
+}
+
+// 实例化所有根系统
+pub fn instantiate_all(packages: &[Package]) -> Vec<SystemInstance> {
+    let index = ClassifierIndex::new(packages);
+    index
+        .root_systems()
+        .into_iter()
+        .map(|(pkg, impl_)| instantiate(&index, &pkg, impl_))
+        .collect()
+}
+
+// 从给定的系统实现开始实例化
+pub fn instantiate(index: &ClassifierIndex, package: &str, root_impl: &ComponentImplementation) -> SystemInstance {
+    let mut builder = InstanceBuilder {
+        index,
+        warnings: Vec::new(),
+        edges: Vec::new(),
+        stack: Vec::new(),
+        connection_bindings: Vec::new(),
+        pass_through: HashSet::new(),
+    };
+    let classifier = ClassifierName {
+        package: package.to_string(),
+        type_name: root_impl.name.type_identifier.clone(),
+        impl_name: Some(root_impl.name.implementation_identifier.clone()),
+    };
+    let mut root = builder.instantiate_component(
+        root_impl.name.to_string(),
+        Vec::new(),
+        root_impl.category.clone(),
+        Some(classifier),
+        &[],
+    );
+    let bindings = builder.collect_bindings(&root);
+    apply_bindings(&mut root, &bindings, &mut builder.warnings);
+    builder.resolve_contained_properties(&mut root);
+    builder.collect_connection_bindings(&root);
+    let connections = builder.semantic_connections();
+    let buses = builder.bus_instances(&root);
+
+    let mut instance = SystemInstance {
+        root,
+        connections,
+        buses,
+        warnings: builder.warnings,
+    };
+    check_bus_reachability(&mut instance);
+    for warning in &instance.warnings {
+        diagnostics::warning(codes::INSTANTIATION_PROBLEM, format!("instantiating {}: {}", instance.root.name, warning));
+    }
+    instance
+}
+
+// 实例化过程中收集的单层连接：源特征 -> 目的特征
+struct ConnectionEdge {
+    kind: ConnectionKind,
+    source: FeatureRef,
+    destination: FeatureRef,
+    declaration: String,
+    bidirectional: bool, // 由 <-> 连接展开，另有一条反向的边
+}
+
+struct InstanceBuilder<'i, 'a> {
+    index: &'i ClassifierIndex<'a>,
+    warnings: Vec<String>,
+    edges: Vec<ConnectionEdge>,
+    stack: Vec<String>, // 正在实例化的实现，用于发现递归包含
+    connection_bindings: Vec<(String, InstancePath)>, // (连接声明, Actual_Connection_Binding 引用的实例)
+    pass_through: HashSet<FeatureRef>, // 在组件自身实现中作为连接端点的特征，双向连接不从这里开始追踪
+}
+
+impl InstanceBuilder<'_, '_> {
+    fn instantiate_component(
+        &mut self,
+        name: String,
+        path: InstancePath,
+        category: ComponentCategory,
+        classifier: Option<ClassifierName>,
+        sub_properties: &[Property],
+    ) -> ComponentInstance {
+        let comp_type = classifier.as_ref().and_then(|c| {
+            self.index
+                .find_type(&c.package, None, &c.type_name)
+                .filter(|(pkg, _)| pkg == &c.package)
+                .map(|(_, t)| t)
+        });
+        let comp_impl = classifier.as_ref().and_then(|c| {
+            let impl_name = ImplementationName {
+                type_identifier: c.type_name.clone(),
+                implementation_identifier: c.impl_name.clone()?,
+            };
+            self.index
+                .find_impl(&c.package, None, &impl_name)
+                .filter(|(pkg, _)| pkg == &c.package)
+                .map(|(_, i)| i)
+        });
+
+        let features = match comp_type.map(|t| &t.features) {
+            Some(FeatureClause::Items(features)) => features.clone(),
+            _ => Vec::new(),
+        };
+
+        let mut properties = Vec::new();
+        if let Some(t) = comp_type {
+            merge_properties(&mut properties, clause_properties(&t.properties));
+        }
+        if let Some(i) = comp_impl {
+            merge_properties(&mut properties, clause_properties(&i.properties));
+        }
+        merge_properties(&mut properties, sub_properties.iter());
+        let mut instance_properties = Vec::new();
+        merge_properties(&mut instance_properties, sub_properties.iter());
+        instance_properties.retain(is_unconditional);
+
+        let mut instance = ComponentInstance {
+            name,
+            path,
+            array_index: Vec::new(),
+            category,
+            classifier: classifier.clone(),
+            features,
+            properties,
+            instance_properties,
+            subcomponents: Vec::new(),
+            processor_binding: None,
+        };
+
+        let (Some(impl_), Some(classifier)) = (comp_impl, classifier) else {
+            return instance;
+        };
+        let key = classifier.qualified();
+        if self.stack.contains(&key) {
+            self.warnings.push(format!("{} contains itself recursively, expansion stopped", key));
+            return instance;
+        }
+        self.stack.push(key);
+
+        if let SubcomponentClause::Items(subs) = &impl_.subcomponents {
+            for sub in subs {
+                let sub_classifier = match self.index.resolve_subcomponent(&classifier.package, sub) {
+                    Some((package, type_name, impl_name)) => Some(ClassifierName { package, type_name, impl_name }),
+                    None => {
+                        self.warnings.push(format!(
+                            "{}: cannot resolve the classifier of subcomponent {}",
+                            path_string(&instance.path, &instance.name),
+                            sub.identifier
+                        ));
+                        None
+                    }
+                };
+                let dimensions = array_dimensions(sub).unwrap_or_else(|| {
+                    self.warnings.push(format!(
+                        "{}: subcomponent array {} has a dimension without size, instantiated as a single component",
+                        path_string(&instance.path, &instance.name),
+                        sub.identifier
+                    ));
+                    Vec::new()
+                });
+                for index in array_indices(&dimensions) {
+                    let name = element_name(&sub.identifier, &index);
+                    let mut sub_path = instance.path.clone();
+                    sub_path.push(name.clone());
+                    let mut child = self.instantiate_component(
+                        name,
+                        sub_path,
+                        sub.category.clone(),
+                        sub_classifier.clone(),
+                        &sub.properties,
+                    );
+                    child.array_index = index;
+                    instance.subcomponents.push(child);
+                }
+            }
+        }
+
+        if let ConnectionClause::Items(connections) = &impl_.connections {
+            for conn in connections {
+                self.collect_edge(&instance, conn);
+            }
+        }
+
+        self.stack.pop();
+        instance
+    }
+
+    // 把一条连接声明换算到实例路径上；端点为子组件数组时按 Connection_Pattern 展开到各元素
+    fn collect_edge(&mut self, owner: &ComponentInstance, conn: &Connection) {
+        let (kind, source, destination, symbol, name, properties) = match conn {
+            Connection::Port(pc) => (
+                ConnectionKind::Port,
+                port_endpoint(owner, &pc.source),
+                port_endpoint(owner, &pc.destination),
+                pc.connection_direction,
+                pc.identifier.clone(),
+                &pc.properties,
+            ),
+            Connection::Access(ac) => (
+                match ac.connection_type {
+                    AccessConnectionType::Bus | AccessConnectionType::VirtualBus => ConnectionKind::Bus,
+                    _ => ConnectionKind::Access,
+                },
+                access_endpoint(owner, &ac.source),
+                access_endpoint(owner, &ac.destination),
+                ac.connection_direction,
+                ac.identifier.clone(),
+                &ac.properties,
+            ),
+            Connection::Parameter(_) => return, // 参数连接属于子程序调用序列内部，不参与语义连接
+        };
+        let declaration = format!("{}.{}", path_string(&owner.path, &owner.name), name);
+        // 连接声明上的 { Actual_Connection_Binding => (reference (bus)); }
+        for prop in properties {
+            if let Property::BasicProperty(bp) | Property::SubcomponentProperty(bp) = prop {
+                if bp.identifier.name.eq_ignore_ascii_case("actual_connection_binding") {
+                    for target in reference_targets(&bp.value) {
+                        self.connection_bindings.push((declaration.clone(), relative_path(&owner.path, target)));
+                    }
+                }
+            }
+        }
+        for endpoint in [&source, &destination] {
+            if endpoint.component == owner.path {
+                self.pass_through.insert(endpoint.clone());
+            }
+        }
+        let bidirectional = symbol == ConnectionSymbol::Didirect;
+        let pairs = self.expand_array_endpoints(owner, &declaration, source, destination, properties);
+        for (source, destination) in pairs {
+            // 双向连接只保留端口方向允许数据流动的方向
+            if bidirectional && port_flows(owner, &destination, &source) {
+                self.edges.push(ConnectionEdge {
+                    kind,
+                    source: destination.clone(),
+                    destination: source.clone(),
+                    declaration: declaration.clone(),
+                    bidirectional,
+                });
+            }
+            if bidirectional && !port_flows(owner, &source, &destination) {
+                continue;
+            }
+            self.edges.push(ConnectionEdge {
+                kind,
+                source,
+                destination,
+                declaration: declaration.clone(),
+                bidirectional,
+            });
+        }
+    }
+
+    // 连接端点的元素配对：数组与单个组件之间一对多/多对一；两个数组之间按 Connection_Set 列出的下标对连接，
+    // 没有 Connection_Set 时按连接模式逐维匹配下标
+    fn expand_array_endpoints(
+        &self,
+        owner: &ComponentInstance,
+        declaration: &str,
+        source: FeatureRef,
+        destination: FeatureRef,
+        properties: &[Property],
+    ) -> Vec<(FeatureRef, FeatureRef)> {
+        let sources = array_endpoint(owner, &source);
+        let destinations = array_endpoint(owner, &destination);
+        let (Some(sources), Some(destinations)) = (&sources, &destinations) else {
+            let sources = sources.map_or_else(|| vec![source], |elements| elements.into_iter().map(|(_, e)| e).collect());
+            let destinations =
+                destinations.map_or_else(|| vec![destination], |elements| elements.into_iter().map(|(_, e)| e).collect());
+            return sources
+                .iter()
+                .flat_map(|s| destinations.iter().map(move |d| (s.clone(), d.clone())))
+                .collect();
+        };
+
+        if let Some(set) = connection_set(properties) {
+            return connection_set_pairs(declaration, sources, destinations, &set);
+        }
+
+        let source_dims = array_extent(sources);
+        let destination_dims = array_extent(destinations);
+        if source_dims.len() != destination_dims.len() {
+            diagnostics::warning(
+                codes::ARRAY_CONNECTION_MISMATCH,
+                format!(
+                    "{}: array endpoints have different dimensions ({:?} and {:?}), connection not expanded",
+                    declaration, source_dims, destination_dims
+                ),
+            );
+            return Vec::new();
+        }
+        let mut patterns = connection_patterns(properties);
+        if patterns.is_empty() {
+            // 未给出 Connection_Pattern：大小相同的数组逐元素对应，否则全连接
+            let default = if source_dims == destination_dims { "One_To_One" } else { "All_To_All" };
+            patterns.push(vec![default.to_string(); source_dims.len()]);
+        }
+
+        let mut pairs = Vec::new();
+        for pattern in &patterns {
+            if pattern.len() != source_dims.len() {
+                diagnostics::warning(
+                    codes::ARRAY_CONNECTION_MISMATCH,
+                    format!(
+                        "{}: Connection_Pattern {:?} does not match the {} array dimension(s), ignored",
+                        declaration,
+                        pattern,
+                        source_dims.len()
+                    ),
+                );
+                continue;
+            }
+            for (dim, name) in pattern.iter().enumerate() {
+                if name.eq_ignore_ascii_case("One_To_One") && source_dims[dim] != destination_dims[dim] {
+                    diagnostics::warning(
+                        codes::ARRAY_CONNECTION_MISMATCH,
+                        format!(
+                            "{}: One_To_One dimension {} has different sizes ({} and {}), extra elements are not connected",
+                            declaration,
+                            dim + 1,
+                            source_dims[dim],
+                            destination_dims[dim]
+                        ),
+                    );
+                }
+            }
+            for (source_index, source) in sources {
+                for (destination_index, destination) in destinations {
+                    let matched = pattern.iter().enumerate().try_fold(true, |all, (dim, name)| {
+                        let matched = pattern_matches(
+                            name,
+                            source_index[dim],
+                            source_dims[dim],
+                            destination_index[dim],
+                            destination_dims[dim],
+                        )?;
+                        Some(all && matched)
+                    });
+                    match matched {
+                        Some(true) => {
+                            let pair = (source.clone(), destination.clone());
+                            if !pairs.contains(&pair) {
+                                pairs.push(pair);
+                            }
+                        }
+                        Some(false) => {}
+                        None => {
+                            diagnostics::warning(
+                                codes::ARRAY_CONNECTION_MISMATCH,
+                                format!("{}: unsupported Connection_Pattern {:?}", declaration, pattern),
+                            );
+                            return pairs;
+                        }
+                    }
+                }
+            }
+        }
+        pairs
+    }
+
+    // 从最终源（不是任何单向连接目的的端点）出发，沿连接追踪到最终目的（不再有后续连接的端点）。
+    // 双向连接的两端都是目的，改为从不在组件自身实现中作为端点的一端（线程、设备等的端口）开始，两个方向各追踪一次
+    fn semantic_connections(&self) -> Vec<SemanticConnection> {
+        let destinations: HashSet<&FeatureRef> =
+            self.edges.iter().filter(|e| !e.bidirectional).map(|e| &e.destination).collect();
+        let mut result = Vec::new();
+        let mut seen_sources = HashSet::new();
+        for edge in &self.edges {
+            if edge.kind == ConnectionKind::Bus
+                || destinations.contains(&edge.source)
+                || (edge.bidirectional && self.pass_through.contains(&edge.source))
+                || !seen_sources.insert(&edge.source)
+            {
+                continue;
+            }
+            let mut trail = Vec::new();
+            self.trace(&edge.source, &edge.source, edge.kind, &mut trail, &mut result);
+        }
+        result
+    }
+
+    fn trace<'e>(
+        &'e self,
+        origin: &FeatureRef,
+        current: &FeatureRef,
+        kind: ConnectionKind,
+        trail: &mut Vec<&'e ConnectionEdge>,
+        result: &mut Vec<SemanticConnection>,
+    ) {
+        // 双向连接不沿刚经过的那条连接折返
+        let reverses = |e: &ConnectionEdge| {
+            e.bidirectional
+                && trail.last().is_some_and(|last| {
+                    last.declaration == e.declaration && last.source == e.destination && last.destination == e.source
+                })
+        };
+        let next: Vec<&ConnectionEdge> = self
+            .edges
+            .iter()
+            .filter(|e| &e.source == current && e.kind == kind && !reverses(e))
+            .collect();
+        if next.is_empty() {
+            if !trail.is_empty() {
+                let declarations: Vec<String> = trail.iter().map(|e| e.declaration.clone()).collect();
+                let mut bus_binding: Vec<InstancePath> = Vec::new();
+                for (declaration, target) in &self.connection_bindings {
+                    if declarations.iter().any(|d| d.eq_ignore_ascii_case(declaration)) && !bus_binding.contains(target) {
+                        bus_binding.push(target.clone());
+                    }
+                }
+                result.push(SemanticConnection {
+                    kind,
+                    source: origin.clone(),
+                    destination: current.clone(),
+                    declarations,
+                    bus_binding,
+                });
+            }
+            return;
+        }
+        for edge in next {
+            // 连接成环时停止
+            if trail.iter().any(|e| std::ptr::eq(*e, edge)) {
+                continue;
+            }
+            trail.push(edge);
+            self.trace(origin, &edge.destination, kind, trail, result);
+            trail.pop();
+        }
+    }
+
+    // 收集各组件实现中的 Actual_Processor_Binding => reference (cpu) applies to x
+    fn collect_bindings(&mut self, root: &ComponentInstance) -> Vec<(InstancePath, InstancePath)> {
+        let mut bindings = Vec::new();
+        for instance in root.walk() {
+            for prop in &instance.properties {
+                if !prop.identifier.name.eq_ignore_ascii_case("actual_processor_binding") {
+                    continue;
+                }
+                let Some(reference) = reference_targets(&prop.value).first().copied() else {
+                    continue;
+                };
+                let target = relative_path(&instance.path, reference);
+                if prop.applies_to.is_empty() {
+                    bindings.push((instance.path.clone(), target));
+                } else {
+                    for applies_to in &prop.applies_to {
+                        bindings.push((relative_path(&instance.path, applies_to), target.clone()));
+                    }
+                }
+            }
+        }
+        bindings
+    }
+
+    // 收集组件实现中以 applies to 指向连接的 Actual_Connection_Binding；
+    // 连接声明自身属性块中的绑定已在 collect_edge 中记录
+    fn collect_connection_bindings(&mut self, root: &ComponentInstance) {
+        for instance in root.walk() {
+            for prop in &instance.properties {
+                if !prop.identifier.name.eq_ignore_ascii_case("actual_connection_binding") {
+                    continue;
+                }
+                let targets: Vec<InstancePath> = reference_targets(&prop.value)
+                    .into_iter()
+                    .map(|target| relative_path(&instance.path, target))
+                    .collect();
+                for applies_to in &prop.applies_to {
+                    let path = relative_path(&instance.path, applies_to);
+                    let Some((connection, owner_path)) = path.split_last() else {
+                        continue;
+                    };
+                    let Some(owner) = find_instance(root, owner_path) else {
+                        continue;
+                    };
+                    let declaration = format!("{}.{}", path_string(&owner.path, &owner.name), connection);
+                    for target in &targets {
+                        self.connection_bindings.push((declaration.clone(), target.clone()));
+                    }
+                }
+            }
+        }
+        for (declaration, target) in &mut self.connection_bindings {
+            *target = declared_path(root, target);
+            if find_instance(root, target).is_none() {
+                self.warnings.push(format!("binding target {} of connection {} does not exist", path_string(target, &root.name), declaration));
+            }
+        }
+    }
+
+    // 每条总线经总线访问连接（不分方向，可穿过外层组件的总线访问特征）接入的组件
+    fn bus_instances(&self, root: &ComponentInstance) -> Vec<BusInstance> {
+        let bus_edges: Vec<&ConnectionEdge> = self.edges.iter().filter(|e| e.kind == ConnectionKind::Bus).collect();
+        root.walk()
+            .into_iter()
+            .filter(|instance| matches!(instance.category, ComponentCategory::Bus | ComponentCategory::VirtualBus))
+            .map(|bus| {
+                let start = FeatureRef { component: bus.path.clone(), feature: String::new() };
+                let mut reached = vec![start];
+                let mut next = 0;
+                while next < reached.len() {
+                    let current = reached[next].clone();
+                    next += 1;
+                    for edge in &bus_edges {
+                        let other = if edge.source == current {
+                            &edge.destination
+                        } else if edge.destination == current {
+                            &edge.source
+                        } else {
+                            continue;
+                        };
+                        if !reached.contains(other) {
+                            reached.push(other.clone());
+                        }
+                    }
+                }
+                let mut attached: Vec<InstancePath> = Vec::new();
+                for feature in &reached[1..] {
+                    let Some(component) = find_instance(root, &feature.component) else {
+                        continue;
+                    };
+                    if !matches!(component.category, ComponentCategory::System | ComponentCategory::Abstract)
+                        && component.path != bus.path
+                        && !attached.contains(&component.path)
+                    {
+                        attached.push(component.path.clone());
+                    }
+                }
+                BusInstance {
+                    path: bus.path.clone(),
+                    virtual_bus: bus.category == ComponentCategory::VirtualBus,
+                    attached,
+                }
+            })
+            .collect()
+    }
+
+    // 把 applies to / in binding 限定的属性关联施加到目标实例。
+    // 内层组件上的声明先施加、外层后施加，使外层（如系统实现）的关联覆盖内层及分类器上的取值；
+    // 引用值相对声明处解析（如处理器绑定），由 collect_bindings 单独处理，不复制到目标上
+    fn resolve_contained_properties(&mut self, root: &mut ComponentInstance) {
+        let mut contained: Vec<(InstancePath, BasicPropertyAssociation)> = root
+            .walk()
+            .into_iter()
+            .flat_map(|instance| {
+                instance
+                    .properties
+                    .iter()
+                    .filter(|p| is_contained(p) || !p.in_binding.is_empty())
+                    .map(|p| (instance.path.clone(), p.clone()))
+            })
+            .collect();
+        contained.sort_by_key(|(declared_in, _)| std::cmp::Reverse(declared_in.len()));
+
+        for (declared_in, bp) in contained {
+            if !reference_targets(&bp.value).is_empty() {
+                continue;
+            }
+            let mut targets = Vec::new();
+            if bp.applies_to.is_empty() {
+                targets.push(declared_in.clone());
+            }
+            for dotted in &bp.applies_to {
+                let path = relative_path(&declared_in, dotted);
+                let found = expand_array_path(root, &path);
+                if found.is_empty() && !self.names_feature_or_connection(root, &path) {
+                    self.warnings.push(format!(
+                        "applies to {} of property {} does not exist",
+                        path_string(&path, &root.name),
+                        bp.identifier.name
+                    ));
+                }
+                targets.extend(found);
+            }
+
+            let applied = BasicPropertyAssociation {
+                applies_to: Vec::new(),
+                in_binding: Vec::new(),
+                ..bp.clone()
+            };
+            for target in targets {
+                if !bp.in_binding.is_empty() && !bound_to_any(root, &target, &bp.in_binding) {
+                    continue;
+                }
+                let Some(instance) = find_instance_mut(root, &target) else {
+                    continue;
+                };
+                merge_association(&mut instance.properties, &applied);
+                if is_unconditional(&applied) {
+                    merge_association(&mut instance.instance_properties, &applied);
+                }
+            }
+        }
+    }
+
+    // applies to 的目标是特征或连接而非子组件：这类属性不记录在实例上，由各自的使用处读取
+    fn names_feature_or_connection(&self, root: &ComponentInstance, path: &[String]) -> bool {
+        let Some((last, parent_path)) = path.split_last() else {
+            return false;
+        };
+        expand_array_path(root, parent_path).iter().any(|parent_path| {
+            let Some(parent) = find_instance(root, parent_path) else {
+                return false;
+            };
+            let declaration = format!("{}.{}", path_string(&parent.path, &parent.name), last);
+            parent.feature(last).is_some() || self.edges.iter().any(|e| e.declaration.eq_ignore_ascii_case(&declaration))
+        })
+    }
+}
+
+// 绑定到总线的语义连接，其两端所在的处理器应接入该总线（虚拟总线不检查）
+fn check_bus_reachability(instance: &mut SystemInstance) {
+    let mut warnings = Vec::new();
+    for connection in &instance.connections {
+        let Some(bus) = instance.connection_bus(connection) else {
+            continue;
+        };
+        if bus.virtual_bus {
+            continue;
+        }
+        for endpoint in [&connection.source, &connection.destination] {
+            let Some(processor) = instance.bound_processor(&endpoint.component) else {
+                continue;
+            };
+            if !bus.attached.contains(processor) {
+                warnings.push(format!(
+                    "connection {} is bound to bus {}, but processor {} of {} is not attached to it",
+                    connection.declarations.join(", "),
+                    path_string(&bus.path, &instance.root.name),
+                    path_string(processor, &instance.root.name),
+                    path_string(&endpoint.component, &instance.root.name)
+                ));
+            }
+        }
+    }
+    instance.warnings.extend(warnings);
+}
+
+// 引用值中的各引用目标：reference (x) 或 (reference (x), reference (y))
+pub fn reference_targets(value: &PropertyValue) -> Vec<&str> {
+    fn reference(expr: &PropertyExpression) -> Option<&str> {
+        match expr {
+            PropertyExpression::Reference(reference) => Some(reference.identifier.as_str()),
+            _ => None,
+        }
+    }
+    match value {
+        PropertyValue::Single(expr) => reference(expr).into_iter().collect(),
+        PropertyValue::List(elements) => elements
+            .iter()
+            .filter_map(|element| match element {
+                PropertyListElement::Value(expr) => reference(expr),
+                PropertyListElement::NestedList(_) => None,
+            })
+            .collect(),
+    }
+}
+
+fn apply_bindings(root: &mut ComponentInstance, bindings: &[(InstancePath, InstancePath)], warnings: &mut Vec<String>) {
+    for (applies_to, target) in bindings {
+        if find_instance(root, target).is_none() {
+            warnings.push(format!("processor binding target {} does not exist", target.join(".")));
+            continue;
+        }
+        let target = declared_path(root, target);
+        // applies to 子组件数组时绑定其全部元素
+        let targets = expand_array_path(root, applies_to);
+        if targets.is_empty() {
+            warnings.push(format!("applies to {} of the processor binding does not exist", applies_to.join(".")));
+        }
+        for path in targets {
+            if let Some(instance) = find_instance_mut(root, &path) {
+                instance.processor_binding = Some(target.clone());
+            }
+        }
+    }
+}
+
+// 把路径中指向子组件数组的段展开为各元素，得到全部匹配的实例路径。
+// 数组段可带下标或区间选择部分元素，如 sensors、sensors[3]、grid[1..2]、grid[2][1..3]
+fn expand_array_path(root: &ComponentInstance, path: &[String]) -> Vec<InstancePath> {
+    let mut matched: Vec<&ComponentInstance> = vec![root];
+    for name in path {
+        let (base, selection) = array_selection(name);
+        matched = matched
+            .into_iter()
+            .flat_map(|instance| match instance.subcomponents.iter().find(|s| s.name.eq_ignore_ascii_case(name)) {
+                Some(sub) => vec![sub],
+                None => instance
+                    .array_elements(base)
+                    .into_iter()
+                    .filter(|element| {
+                        selection.len() <= element.array_index.len()
+                            && selection.iter().zip(&element.array_index).all(|((low, high), i)| (low..=high).contains(&i))
+                    })
+                    .collect(),
+            })
+            .collect();
+    }
+    matched.into_iter().map(|instance| instance.path.clone()).collect()
+}
+
+// grid[1..2][3] -> ("grid", [(1, 2), (3, 3)])
+fn array_selection(segment: &str) -> (&str, Vec<(u32, u32)>) {
+    let Some((base, rest)) = segment.split_once('[') else {
+        return (segment, Vec::new());
+    };
+    let selection = rest
+        .split('[')
+        .filter_map(|range| {
+            let range = range.trim_end_matches(']');
+            let (low, high) = range.split_once("..").unwrap_or((range, range));
+            Some((low.trim().parse().ok()?, high.trim().parse().ok()?))
+        })
+        .collect();
+    (base, selection)
+}
+
+// 实例（或其最近的已绑定祖先）所绑定的处理器是否为 in binding 中列出的分类器或实例之一
+fn bound_to_any(root: &ComponentInstance, path: &[String], platforms: &[String]) -> bool {
+    let Some(processor) = (0..=path.len())
+        .rev()
+        .find_map(|len| find_instance(root, &path[..len])?.processor_binding.as_ref())
+        .and_then(|binding| find_instance(root, binding))
+    else {
+        return false;
+    };
+    platforms.iter().any(|platform| {
+        let name = platform.rsplit("::").next().unwrap_or(platform);
+        processor.name.eq_ignore_ascii_case(name)
+            || processor.classifier.as_ref().is_some_and(|c| {
+                c.type_name.eq_ignore_ascii_case(name)
+                    || c.impl_name.as_ref().is_some_and(|i| format!("{}.{}", c.type_name, i).eq_ignore_ascii_case(name))
+            })
+    })
+}
+
+// 子组件数组各维的元素个数；非数组子组件为空，存在未给出大小的维度时为 None
+pub fn array_dimensions(sub: &Subcomponent) -> Option<Vec<u32>> {
+    let Some(spec) = &sub.array_spec else {
+        return Some(Vec::new());
+    };
+    spec.dimensions
+        .iter()
+        .map(|dimension| match dimension.size {
+            Some(ArrayDimensionSize::Fixed(size)) => Some(size),
+            _ => None,
+        })
+        .collect()
+}
+
+// 按行优先顺序列出全部元素下标（从 1 开始）；没有维度时只有一个空下标，即单个实例
+pub fn array_indices(dimensions: &[u32]) -> Vec<Vec<u32>> {
+    dimensions.iter().fold(vec![Vec::new()], |prefixes, &size| {
+        prefixes
+            .iter()
+            .flat_map(|prefix| {
+                (1..=size).map(move |i| {
+                    let mut index = prefix.clone();
+                    index.push(i);
+                    index
+                })
+            })
+            .collect()
+    })
+}
+
+// 数组元素的实例名：sensors + [3] -> sensors[3]
+pub fn element_name(identifier: &str, index: &[u32]) -> String {
+    let suffix: String = index.iter().map(|i| format!("[{}]", i)).collect();
+    format!("{}{}", identifier, suffix)
+}
+
+// 端点所在组件若是 owner 的子组件数组，返回各元素上的同名特征及其下标
+fn array_endpoint(owner: &ComponentInstance, endpoint: &FeatureRef) -> Option<Vec<(Vec<u32>, FeatureRef)>> {
+    if endpoint.component.len() != owner.path.len() + 1 {
+        return None;
+    }
+    let elements = owner.array_elements(endpoint.component.last()?);
+    if elements.is_empty() {
+        return None;
+    }
+    Some(
+        elements
+            .into_iter()
+            .map(|element| {
+                let reference = FeatureRef {
+                    component: element.path.clone(),
+                    feature: endpoint.feature.clone(),
+                };
+                (element.array_index.clone(), reference)
+            })
+            .collect(),
+    )
+}
+
+// 各维大小取元素下标的最大值
+fn array_extent(elements: &[(Vec<u32>, FeatureRef)]) -> Vec<u32> {
+    let mut extent: Vec<u32> = Vec::new();
+    for (index, _) in elements {
+        extent.resize(extent.len().max(index.len()), 0);
+        for (dim, i) in index.iter().enumerate() {
+            extent[dim] = extent[dim].max(*i);
+        }
+    }
+    extent
+}
+
+// Connection_Set 中的一项：(源元素下标, 目的元素下标)，下标从 1 开始；None 表示该项不是合法的记录
+type ConnectionPair = Option<(Vec<u32>, Vec<u32>)>;
+
+// Connection_Set => ([src => (1); dst => (2);], ...)：显式列出相连的元素下标对，优先于 Connection_Pattern
+fn connection_set(properties: &[Property]) -> Option<Vec<ConnectionPair>> {
+    let value = properties.iter().find_map(|prop| match prop {
+        Property::BasicProperty(bp) | Property::SubcomponentProperty(bp)
+            if bp.identifier.name.eq_ignore_ascii_case("connection_set") =>
+        {
+            Some(&bp.value)
+        }
+        _ => None,
+    })?;
+    let elements = match value {
+        PropertyValue::List(elements) => elements.iter().collect(),
+        PropertyValue::Single(_) => Vec::new(),
+    };
+    let pairs = elements
+        .into_iter()
+        .map(|element| match element {
+            PropertyListElement::Value(PropertyExpression::Record(record)) => {
+                let field = |name: &str| {
+                    record
+                        .fields
+                        .iter()
+                        .find(|f| f.name.eq_ignore_ascii_case(name))
+                        .and_then(|f| index_list(&f.value))
+                };
+                Some((field("src")?, field("dst")?))
+            }
+            _ => None,
+        })
+        .collect();
+    Some(pairs)
+}
+
+// 下标列表 (2, 3)；单个下标可以不加括号
+fn index_list(value: &PropertyValue) -> Option<Vec<u32>> {
+    let index = |expr: &PropertyExpression| match expr {
+        PropertyExpression::Integer(SignedIntergerOrConstant::Real(int))
+            if !matches!(int.sign, Some(Sign::Minus)) && int.value > 0 =>
+        {
+            u32::try_from(int.value).ok()
+        }
+        _ => None,
+    };
+    match value {
+        PropertyValue::Single(expr) => Some(vec![index(expr)?]),
+        PropertyValue::List(elements) => elements
+            .iter()
+            .map(|element| match element {
+                PropertyListElement::Value(expr) => index(expr),
+                PropertyListElement::NestedList(_) => None,
+            })
+            .collect(),
+    }
+}
+
+fn connection_set_pairs(
+    declaration: &str,
+    sources: &[(Vec<u32>, FeatureRef)],
+    destinations: &[(Vec<u32>, FeatureRef)],
+    set: &[ConnectionPair],
+) -> Vec<(FeatureRef, FeatureRef)> {
+    let element = |elements: &[(Vec<u32>, FeatureRef)], index: &[u32]| {
+        elements.iter().find(|(i, _)| i.as_slice() == index).map(|(_, e)| e.clone())
+    };
+    let mut pairs = Vec::new();
+    for (position, pair) in set.iter().enumerate() {
+        let Some((src, dst)) = pair else {
+            diagnostics::warning(
+                codes::ARRAY_CONNECTION_MISMATCH,
+                format!("{}: Connection_Set entry {} is not a [src => (...); dst => (...);] record, ignored", declaration, position + 1),
+            );
+            continue;
+        };
+        match (element(sources, src), element(destinations, dst)) {
+            (Some(source), Some(destination)) => {
+                let pair = (source, destination);
+                if !pairs.contains(&pair) {
+                    pairs.push(pair);
+                }
+            }
+            _ => diagnostics::warning(
+                codes::ARRAY_CONNECTION_MISMATCH,
+                format!("{}: Connection_Set entry {:?} -> {:?} is outside the connected arrays, ignored", declaration, src, dst),
+            ),
+        }
+    }
+    pairs
+}
+
+// Connection_Pattern => ((One_To_One, Next), ...)：每个内层列表为一组逐维的模式，多组之间取并集
+fn connection_patterns(properties: &[Property]) -> Vec<Vec<String>> {
+    let value = properties.iter().find_map(|prop| match prop {
+        Property::BasicProperty(bp) | Property::SubcomponentProperty(bp)
+            if bp.identifier.name.eq_ignore_ascii_case("connection_pattern") =>
+        {
+            Some(&bp.value)
+        }
+        _ => None,
+    });
+    match value {
+        Some(PropertyValue::List(elements)) => {
+            let mut patterns = Vec::new();
+            let mut flat = Vec::new();
+            for element in elements {
+                match element {
+                    PropertyListElement::NestedList(inner) => patterns.push(pattern_names(inner)),
+                    PropertyListElement::Value(expr) => flat.extend(expression_names(expr)),
+                }
+            }
+            if !flat.is_empty() {
+                patterns.push(flat);
+            }
+            patterns
+        }
+        Some(PropertyValue::Single(expr)) => vec![expression_names(expr)],
+        None => Vec::new(),
+    }
+}
+
+fn pattern_names(elements: &[PropertyListElement]) -> Vec<String> {
+    elements
+        .iter()
+        .flat_map(|element| match element {
+            PropertyListElement::Value(expr) => expression_names(expr),
+            PropertyListElement::NestedList(inner) => pattern_names(inner),
+        })
+        .collect()
+}
+
+// 模式名解析为枚举字面量；写成 (One_To_One, Next) 整体被当作字符串时按逗号拆分
+fn expression_names(expr: &PropertyExpression) -> Vec<String> {
+    match expr {
+        PropertyExpression::String(StringTerm::Literal(text)) => {
+            text.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+// 单一维度上源下标 i（共 n 个）与目的下标 j（共 m 个）是否相连；模式名无法识别时为 None
+fn pattern_matches(pattern: &str, i: u32, n: u32, j: u32, m: u32) -> Option<bool> {
+    let matched = match pattern.to_ascii_lowercase().as_str() {
+        "one_to_one" => i == j,
+        "all_to_all" | "one_to_all" | "all_to_one" => true,
+        "next" => j == i + 1,
+        "previous" => i == j + 1,
+        "cyclic_next" => j == i % m + 1,
+        "cyclic_previous" => i == j % n + 1,
+        _ => return None,
+    };
+    Some(matched)
+}
+
+fn find_instance<'r>(root: &'r ComponentInstance, path: &[String]) -> Option<&'r ComponentInstance> {
+    let mut current = root;
+    for name in path {
+        current = current.subcomponents.iter().find(|s| s.name.eq_ignore_ascii_case(name))?;
+    }
+    Some(current)
+}
+
+fn find_instance_mut<'r>(root: &'r mut ComponentInstance, path: &[String]) -> Option<&'r mut ComponentInstance> {
+    let mut current = root;
+    for name in path {
+        current = current.subcomponents.iter_mut().find(|s| s.name.eq_ignore_ascii_case(name))?;
+    }
+    Some(current)
+}
+
+// 把声明处的点分名称（如 p.t1、grid[1..2].t）换算为实例路径；数组区间中的 .. 不作分隔
+fn relative_path(base: &[String], dotted: &str) -> InstancePath {
+    let mut path = base.to_vec();
+    let mut depth = 0;
+    let mut segment = String::new();
+    for c in dotted.chars() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            '.' if depth == 0 => {
+                path.push(std::mem::take(&mut segment).trim().to_string());
+                continue;
+            }
+            _ => {}
+        }
+        segment.push(c);
+    }
+    path.push(segment.trim().to_string());
+    path
+}
+
+fn path_string(path: &[String], root_name: &str) -> String {
+    if path.is_empty() {
+        root_name.to_string()
+    } else {
+        path.join(".")
+    }
+}
+
+// AADL 标识符不区分大小写：连接中书写的子组件名换算为声明时的写法，数组元素保留下标部分。
+// 同时返回对应的子组件实例（数组取任一元素，各元素特征相同），用于换算特征名
+fn declared_subcomponent<'o>(owner: &'o ComponentInstance, sub: &str) -> (String, Option<&'o ComponentInstance>) {
+    let (base, suffix) = sub.split_at(sub.find('[').unwrap_or(sub.len()));
+    match owner.subcomponents.iter().find(|s| s.base_name().eq_ignore_ascii_case(base.trim())) {
+        Some(instance) => (format!("{}{}", instance.base_name(), suffix), Some(instance)),
+        None => (sub.to_string(), None),
+    }
+}
+
+// 特征名换算为组件类型中声明的写法；找不到时保留原写法
+fn declared_feature(component: Option<&ComponentInstance>, feature: &str) -> String {
+    component
+        .and_then(|c| c.feature(feature))
+        .map_or_else(|| feature.to_string(), |f| feature_identifier(f).to_string())
+}
+
+// 连接 owner 中的数据能否从 source 流向 destination：组件自身的 in 端口与子组件的 out 端口能发出，
+// 自身的 out 端口与子组件的 in 端口能接收；不是端口的端点不限制方向
+fn port_flows(owner: &ComponentInstance, source: &FeatureRef, destination: &FeatureRef) -> bool {
+    let direction = |endpoint: &FeatureRef| {
+        let component = if endpoint.component == owner.path {
+            Some(owner)
+        } else {
+            owner.subcomponents.iter().find(|sub| sub.path == endpoint.component)
+        };
+        match component.and_then(|c| c.feature(&endpoint.feature)) {
+            Some(Feature::Port(port)) => Some((port.direction, endpoint.component == owner.path)),
+            _ => None,
+        }
+    };
+    let emits = match direction(source) {
+        Some((PortDirection::InOut, _)) | None => true,
+        Some((direction, own)) => (direction == PortDirection::In) == own,
+    };
+    let receives = match direction(destination) {
+        Some((PortDirection::InOut, _)) | None => true,
+        Some((direction, own)) => (direction == PortDirection::Out) == own,
+    };
+    emits && receives
+}
+
+// 实例路径换算为实例树中的写法；路径不存在时原样返回
+fn declared_path(root: &ComponentInstance, path: &[String]) -> InstancePath {
+    find_instance(root, path).map_or_else(|| path.to_vec(), |instance| instance.path.clone())
+}
+
+fn child_path(owner: &ComponentInstance, sub: &str) -> InstancePath {
+    let mut path = owner.path.clone();
+    path.push(declared_subcomponent(owner, sub).0);
+    path
+}
+
+fn port_endpoint(owner: &ComponentInstance, endpoint: &PortEndpoint) -> FeatureRef {
+    let local = |feature: &str| FeatureRef {
+        component: owner.path.clone(),
+        feature: declared_feature(Some(owner), feature),
+    };
+    let nested = |sub: &str, feature: &str| FeatureRef {
+        component: child_path(owner, sub),
+        feature: declared_feature(declared_subcomponent(owner, sub).1, feature),
+    };
+    match endpoint {
+        PortEndpoint::ComponentPort(port) | PortEndpoint::RequiresDataAccess(port) => local_or_subcomponent(owner, port),
+        PortEndpoint::SubcomponentPort { subcomponent, port } => nested(subcomponent, port),
+        PortEndpoint::SubcomponentDataAccess { subcomponent, access } => nested(subcomponent, access),
+        PortEndpoint::DataSubcomponent(data) => nested(data, ""),
+        PortEndpoint::NestedDataAccess { container, element } => nested(container, element),
+        PortEndpoint::AggregateDataElement { port, .. } => local(port),
+        PortEndpoint::FeatureGroupPort { feature_group, element }
+        | PortEndpoint::FeatureGroupDataAccess { feature_group, element } => FeatureRef {
+            component: owner.path.clone(),
+            feature: format!("{}.{}", declared_feature(Some(owner), feature_group), element),
+        },
+        PortEndpoint::ProcessorPort { processor: Some(processor), port } => nested(processor, port),
+        PortEndpoint::ProcessorPort { processor: None, port } => local(port),
+        PortEndpoint::InternalEvent { identifier, .. } => local(identifier),
+    }
+}
+
+fn access_endpoint(owner: &ComponentInstance, endpoint: &AccessEndpoint) -> FeatureRef {
+    match endpoint {
+        AccessEndpoint::ComponentAccess(name) => local_or_subcomponent(owner, name),
+        AccessEndpoint::SubcomponentAccess { subcomponent, access } => FeatureRef {
+            component: child_path(owner, subcomponent),
+            feature: declared_feature(declared_subcomponent(owner, subcomponent).1, access),
+        },
+    }
+}
+
+// 单个标识符既可能是本组件的特征，也可能是（数据）子组件本身
+fn local_or_subcomponent(owner: &ComponentInstance, name: &str) -> FeatureRef {
+    if owner.feature(name).is_none() && owner.subcomponents.iter().any(|s| s.name.eq_ignore_ascii_case(name)) {
+        FeatureRef {
+            component: child_path(owner, name),
+            feature: String::new(),
+        }
+    } else {
+        FeatureRef {
+            component: owner.path.clone(),
+            feature: declared_feature(Some(owner), name),
+        }
+    }
+}
+
+fn clause_properties(clause: &PropertyClause) -> impl Iterator<Item = &Property> {
+    match clause {
+        PropertyClause::Properties(props) => props.iter(),
+        PropertyClause::ExplicitNone => [].iter(),
+    }
+}
+
+// 按名称合并属性：=> 覆盖之前的同名属性，+=> 在列表值后追加
+fn merge_properties<'p>(target: &mut Vec<BasicPropertyAssociation>, props: impl Iterator<Item = &'p Property>) {
+    for prop in props {
+        match prop {
+            Property::BasicProperty(bp) | Property::SubcomponentProperty(bp) | Property::ContainedProperty(bp) => {
+                merge_association(target, bp)
+            }
+            Property::CallSequenceProperty(_) => continue,
+        }
+    }
+}
+
+fn merge_association(target: &mut Vec<BasicPropertyAssociation>, bp: &BasicPropertyAssociation) {
+    // applies to / in binding / in modes 限定的关联各自作用于不同目标或条件，全部保留
+    if !is_unconditional(bp) {
+        target.push(bp.clone());
+        return;
+    }
+    let existing = target
+        .iter_mut()
+        .find(|p| p.identifier.name.eq_ignore_ascii_case(&bp.identifier.name) && is_unconditional(p));
+    match existing {
+        Some(existing) => {
+            if let (PropertyOperator::Append, PropertyValue::List(old), PropertyValue::List(new)) =
+                (bp.operator, &mut existing.value, &bp.value)
+            {
+                old.extend(new.iter().cloned());
+            } else {
+                *existing = bp.clone();
+            }
+        }
+        None => target.push(bp.clone()),
+    }
+}
+
+// 带 applies to 的属性关联（作用于子组件而非声明所在组件本身）
+pub fn is_contained(bp: &BasicPropertyAssociation) -> bool {
+    !bp.applies_to.is_empty()
+}
+
+// 无 applies to / in binding / in modes 限定、直接作用于所在实例的属性关联
+pub fn is_unconditional(bp: &BasicPropertyAssociation) -> bool {
+    bp.applies_to.is_empty() && bp.in_binding.is_empty() && bp.in_modes.is_empty()
+}
@@ -0,0 +1,583 @@
+// AADL 合法性与一致性检查
+// 在代码生成之前对声明模型与实例模型做语义检查，报告违反 AADL 合法性规则的地方：
+// 未解析的分类器、缺失的实现、重复的标识符、连接两端方向或数据类型不匹配，
+// 以及线程的分发协议所要求但未给出的属性。
+// 检查结果以 E04xx/W04xx 诊断给出，位置取所在分类器的声明处。
+use crate::ast::aadl_ast_cj::*;
+use crate::diagnostics::{codes, Diagnostic, Severity};
+use crate::instance_model::{ClassifierIndex, ComponentInstance, SystemInstance};
+use std::collections::HashSet;
+
+// Base_Types 预声明包中的数据分类器（小写），引用它们不要求模型中包含该包
+const BASE_TYPES: [&str; 16] = [
+    "boolean", "integer", "integer_8", "integer_16", "integer_32", "integer_64", "unsigned_8", "unsigned_16",
+    "unsigned_32", "unsigned_64", "natural", "float", "float_32", "float_64", "character", "string",
+];
+
+// 需要 Period 属性的分发协议（小写）
+const PERIODIC_DISPATCH: [&str; 4] = ["periodic", "sporadic", "timed", "hybrid"];
+
+// 检查入口：声明层面的规则逐包检查，线程属性在实例模型上检查（属性已按类型 -> 实现 -> 子组件合并）
+// 返回检查得到的诊断，由调用方决定是否计入（check 动作）或只作提示（生成流程）
+pub fn check_model(packages: &[Package], instances: &[SystemInstance]) -> Vec<Diagnostic> {
+    let index = ClassifierIndex::new(packages);
+    let mut checker = Checker {
+        index: &index,
+        source: None,
+        diagnostics: Vec::new(),
+    };
+    for pkg in packages {
+        checker.check_package(pkg);
+    }
+    for instance in instances {
+        checker.check_thread_properties(instance);
+    }
+    checker.diagnostics
+}
+
+struct Checker<'i, 'a> {
+    index: &'i ClassifierIndex<'a>,
+    source: Option<SourceLocation>, // 正在检查的分类器的声明位置
+    diagnostics: Vec<Diagnostic>,
+}
+
+// 连接端点解析结果：端口规格 + 是否为当前组件自身的端口
+struct ResolvedEndpoint<'a> {
+    port: &'a PortSpec,
+    own: bool,
+}
+
+impl<'a> Checker<'_, 'a> {
+    // location 为所在组件（或连接）的名字，写在消息开头
+    fn report(&mut self, severity: Severity, code: &'static str, location: String, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            code,
+            message: format!("{}: {}", location, message),
+            span: None,
+            location: self.source.clone(),
+        });
+    }
+
+    fn check_package(&mut self, pkg: &'a Package) {
+        let pkg_name = pkg.name.to_string();
+        let declarations: Vec<&AadlDeclaration> = pkg
+            .public_section
+            .iter()
+            .chain(pkg.private_section.iter())
+            .flat_map(|s| s.declarations.iter())
+            .collect();
+
+        // 包内分类器名不能重复
+        let mut seen = HashSet::new();
+        for decl in &declarations {
+            let (name, source) = match decl {
+                AadlDeclaration::ComponentType(comp) => (comp.identifier.clone(), &comp.location),
+                AadlDeclaration::ComponentImplementation(impl_) => (impl_.name.to_string(), &impl_.location),
+                _ => continue,
+            };
+            if !seen.insert(name.to_lowercase()) {
+                self.source = source.clone();
+                self.report(
+                    Severity::Error,
+                    codes::DUPLICATE_IDENTIFIER,
+                    pkg_name.clone(),
+                    format!("classifier {} is declared more than once", name),
+                );
+            }
+        }
+
+        for decl in declarations {
+            match decl {
+                AadlDeclaration::ComponentType(comp) => self.check_component_type(&pkg_name, comp),
+                AadlDeclaration::ComponentImplementation(impl_) => self.check_component_implementation(&pkg_name, impl_),
+                _ => {}
+            }
+        }
+    }
+
+    fn check_component_type(&mut self, pkg_name: &str, comp: &'a ComponentType) {
+        let location = format!("{}::{}", pkg_name, comp.identifier);
+        self.source = comp.location.clone();
+        let features = features_of(comp);
+        self.check_duplicates(&location, "feature", features.iter().map(feature_name));
+
+        for feature in features {
+            let classifier = match feature {
+                Feature::Port(PortSpec {
+                    port_type: PortType::Data { classifier } | PortType::EventData { classifier },
+                    ..
+                }) => classifier.as_ref().and_then(|c| match c {
+                    PortDataTypeReference::Classifier(reference) => Some(reference),
+                    PortDataTypeReference::Prototype(_) => None,
+                }),
+                Feature::SubcomponentAccess(SubcomponentAccessSpec::Data(access)) => {
+                    access.classifier.as_ref().and_then(|c| match c {
+                        DataAccessReference::Classifier(reference) => Some(reference),
+                        DataAccessReference::Prototype(_) => None,
+                    })
+                }
+                _ => None,
+            };
+            if let Some(reference) = classifier {
+                if self.resolve_data_classifier(pkg_name, reference).is_none() {
+                    self.report(
+                        Severity::Error,
+                        codes::UNRESOLVED_CLASSIFIER,
+                        location.clone(),
+                        format!(
+                            "feature {} refers to unknown data classifier {}",
+                            feature_name(feature),
+                            reference_name(reference)
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_component_implementation(&mut self, pkg_name: &str, impl_: &'a ComponentImplementation) {
+        let location = format!("{}::{}", pkg_name, impl_.name.to_string());
+        self.source = impl_.location.clone();
+        let Some((_, comp_type)) = self.index.find_type(pkg_name, None, &impl_.name.type_identifier) else {
+            self.report(
+                Severity::Error,
+                codes::UNDECLARED_COMPONENT_TYPE,
+                location,
+                format!("implementation of undeclared component type {}", impl_.name.type_identifier),
+            );
+            return;
+        };
+        if comp_type.category != impl_.category {
+            self.report(
+                Severity::Error,
+                codes::CATEGORY_MISMATCH,
+                location.clone(),
+                format!("implementation category {:?} differs from its type ({:?})", impl_.category, comp_type.category),
+            );
+        }
+
+        let subcomponents: &[Subcomponent] = match &impl_.subcomponents {
+            SubcomponentClause::Items(subs) => subs,
+            _ => &[],
+        };
+        self.check_duplicates(&location, "subcomponent", subcomponents.iter().map(|s| s.identifier.as_str()));
+        for sub in subcomponents {
+            if features_of(comp_type)
+                .iter()
+                .any(|f| feature_name(f).eq_ignore_ascii_case(&sub.identifier))
+            {
+                self.report(
+                    Severity::Error,
+                    codes::DUPLICATE_IDENTIFIER,
+                    location.clone(),
+                    format!("subcomponent {} has the same name as a feature of {}", sub.identifier, comp_type.identifier),
+                );
+            }
+            self.check_subcomponent(pkg_name, &location, sub);
+        }
+
+        let connections: &[Connection] = match &impl_.connections {
+            ConnectionClause::Items(conns) => conns,
+            _ => &[],
+        };
+        self.check_duplicates(
+            &location,
+            "connection",
+            connections.iter().filter_map(|c| match c {
+                Connection::Port(port_conn) => Some(port_conn.identifier.as_str()),
+                _ => None,
+            }),
+        );
+        for conn in connections {
+            if let Connection::Port(port_conn) = conn {
+                self.check_port_connection(pkg_name, &location, comp_type, subcomponents, port_conn);
+            }
+        }
+    }
+
+    // 子组件分类器必须能解析且类别一致；进程、线程、系统在代码生成时需要实现
+    fn check_subcomponent(&mut self, pkg_name: &str, location: &str, sub: &Subcomponent) {
+        let SubcomponentClassifier::ClassifierReference(reference) = &sub.classifier else {
+            return;
+        };
+        let unirf = unique_reference(reference);
+        let name = &unirf.implementation_name;
+        let prefix = unirf.package_prefix.as_ref();
+
+        if !name.implementation_identifier.is_empty() {
+            match self.index.find_impl(pkg_name, prefix, name) {
+                Some((_, impl_)) if impl_.category != sub.category => self.report(
+                    Severity::Error,
+                    codes::CATEGORY_MISMATCH,
+                    location.to_string(),
+                    format!(
+                        "subcomponent {} is declared {:?} but {} is a {:?} implementation",
+                        sub.identifier,
+                        sub.category,
+                        name.to_string(),
+                        impl_.category
+                    ),
+                ),
+                Some(_) => {}
+                None => self.report(
+                    Severity::Error,
+                    codes::UNRESOLVED_CLASSIFIER,
+                    location.to_string(),
+                    format!("subcomponent {} refers to unknown implementation {}", sub.identifier, reference_name(reference)),
+                ),
+            }
+            return;
+        }
+
+        match self.index.find_type(pkg_name, prefix, &name.type_identifier) {
+            Some((_, comp)) if comp.category != sub.category => self.report(
+                Severity::Error,
+                codes::CATEGORY_MISMATCH,
+                location.to_string(),
+                format!(
+                    "subcomponent {} is declared {:?} but {} is a {:?} type",
+                    sub.identifier, sub.category, comp.identifier, comp.category
+                ),
+            ),
+            Some(_) => {
+                // 线程仅有类型时按空实现生成；进程、系统缺少实现则无法展开其内部结构
+                let (severity, code) = match sub.category {
+                    ComponentCategory::Process | ComponentCategory::System => (Severity::Error, codes::MISSING_IMPLEMENTATION),
+                    ComponentCategory::Thread => (Severity::Warning, codes::THREAD_WITHOUT_IMPLEMENTATION),
+                    _ => return,
+                };
+                self.report(
+                    severity,
+                    code,
+                    location.to_string(),
+                    format!(
+                        "{:?} subcomponent {} names only the type {} without an implementation",
+                        sub.category, sub.identifier, name.type_identifier
+                    ),
+                );
+            }
+            None if sub.category == ComponentCategory::Data
+                && prefix.is_none_or(|p| p.to_string().eq_ignore_ascii_case("base_types"))
+                && BASE_TYPES.contains(&name.type_identifier.to_lowercase().as_str()) => {}
+            None => self.report(
+                Severity::Error,
+                codes::UNRESOLVED_CLASSIFIER,
+                location.to_string(),
+                format!("subcomponent {} refers to unknown type {}", sub.identifier, reference_name(reference)),
+            ),
+        }
+    }
+
+    fn check_port_connection(
+        &mut self,
+        pkg_name: &str,
+        location: &str,
+        comp_type: &'a ComponentType,
+        subcomponents: &[Subcomponent],
+        conn: &PortConnection,
+    ) {
+        let location = format!("{} (connection {})", location, conn.identifier);
+        let source = self.resolve_endpoint(pkg_name, &location, comp_type, subcomponents, &conn.source);
+        let destination = self.resolve_endpoint(pkg_name, &location, comp_type, subcomponents, &conn.destination);
+        let (Some(source), Some(destination)) = (source, destination) else {
+            return;
+        };
+
+        // 方向：源端要能发出（自身的 in 端口或子组件的 out 端口），目的端要能接收；双向连接任一方向成立即可
+        let legal = directions_allow(&source, &destination)
+            || (conn.connection_direction == ConnectionSymbol::Didirect && directions_allow(&destination, &source));
+        if !legal {
+            self.report(
+                Severity::Error,
+                codes::DIRECTION_MISMATCH,
+                location.clone(),
+                format!(
+                    "cannot connect {} ({}) to {} ({})",
+                    source.port.identifier,
+                    describe_direction(&source),
+                    destination.port.identifier,
+                    describe_direction(&destination)
+                ),
+            );
+        }
+
+        // 事件端口不携带数据，不能连到数据端口或事件数据端口
+        if matches!(source.port.port_type, PortType::Event) && !matches!(destination.port.port_type, PortType::Event) {
+            self.report(
+                Severity::Error,
+                codes::PORT_TYPE_MISMATCH,
+                location.clone(),
+                format!(
+                    "event port {} cannot feed data-carrying port {}",
+                    source.port.identifier, destination.port.identifier
+                ),
+            );
+        }
+
+        // 两端都声明了数据分类器时必须一致
+        if let (Some(src_ty), Some(dst_ty)) = (
+            self.port_data_type(pkg_name, source.port),
+            self.port_data_type(pkg_name, destination.port),
+        ) {
+            if src_ty != dst_ty {
+                self.report(
+                    Severity::Error,
+                    codes::PORT_TYPE_MISMATCH,
+                    location,
+                    format!(
+                        "{} carries {} but {} expects {}",
+                        source.port.identifier, src_ty, destination.port.identifier, dst_ty
+                    ),
+                );
+            }
+        }
+    }
+
+    fn resolve_endpoint(
+        &mut self,
+        pkg_name: &str,
+        location: &str,
+        comp_type: &'a ComponentType,
+        subcomponents: &[Subcomponent],
+        endpoint: &PortEndpoint,
+    ) -> Option<ResolvedEndpoint<'a>> {
+        match endpoint {
+            PortEndpoint::ComponentPort(port) => match find_port(comp_type, port) {
+                Some(spec) => Some(ResolvedEndpoint { port: spec, own: true }),
+                None => {
+                    self.report(
+                        Severity::Error,
+                        codes::UNRESOLVED_FEATURE,
+                        location.to_string(),
+                        format!("{} has no port {}", comp_type.identifier, port),
+                    );
+                    None
+                }
+            },
+            PortEndpoint::SubcomponentPort { subcomponent, port } => {
+                let Some(sub) = subcomponents.iter().find(|s| s.identifier.eq_ignore_ascii_case(subcomponent)) else {
+                    self.report(
+                        Severity::Error,
+                        codes::UNRESOLVED_FEATURE,
+                        location.to_string(),
+                        format!("unknown subcomponent {}", subcomponent),
+                    );
+                    return None;
+                };
+                // 子组件分类器无法解析时已在 check_subcomponent 中报告
+                let sub_type = self.subcomponent_type(pkg_name, sub)?;
+                match find_port(sub_type, port) {
+                    Some(spec) => Some(ResolvedEndpoint { port: spec, own: false }),
+                    None => {
+                        // 端点可能是访问特征等非端口特征，只有特征完全不存在时才报告
+                        if !features_of(sub_type).iter().any(|f| feature_name(f).eq_ignore_ascii_case(port)) {
+                            self.report(
+                                Severity::Error,
+                                codes::UNRESOLVED_FEATURE,
+                                location.to_string(),
+                                format!("subcomponent {} ({}) has no port {}", sub.identifier, sub_type.identifier, port),
+                            );
+                        }
+                        None
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn subcomponent_type(&self, pkg_name: &str, sub: &Subcomponent) -> Option<&'a ComponentType> {
+        let SubcomponentClassifier::ClassifierReference(reference) = &sub.classifier else {
+            return None;
+        };
+        let unirf = unique_reference(reference);
+        let prefix = unirf.package_prefix.as_ref();
+        let name = &unirf.implementation_name;
+        if !name.implementation_identifier.is_empty() {
+            // 实现与其类型位于同一个包中
+            let (impl_pkg, impl_) = self.index.find_impl(pkg_name, prefix, name)?;
+            return self
+                .index
+                .find_type(&impl_pkg, None, &impl_.name.type_identifier)
+                .map(|(_, comp)| comp);
+        }
+        self.index.find_type(pkg_name, prefix, &name.type_identifier).map(|(_, comp)| comp)
+    }
+
+    // 数据分类器的规范名（包::类型，小写）；Base_Types 中的预声明类型不要求模型包含该包
+    fn resolve_data_classifier(&self, pkg_name: &str, reference: &UniqueComponentClassifierReference) -> Option<String> {
+        let unirf = unique_reference(reference);
+        let prefix = unirf.package_prefix.as_ref();
+        let type_name = &unirf.implementation_name.type_identifier;
+        // 访问特征的实现引用在转换时整体保存在 type_identifier 中（如 POS.Impl）
+        let impl_name = match type_name.split_once('.') {
+            Some((type_id, impl_id)) => Some(ImplementationName {
+                type_identifier: type_id.to_string(),
+                implementation_identifier: impl_id.to_string(),
+            }),
+            None if !unirf.implementation_name.implementation_identifier.is_empty() => {
+                Some(unirf.implementation_name.clone())
+            }
+            None => None,
+        };
+        if let Some(impl_name) = impl_name {
+            let (pkg, impl_) = self.index.find_impl(pkg_name, prefix, &impl_name)?;
+            return Some(format!("{}::{}", pkg, impl_.name.to_string()).to_lowercase());
+        }
+        if let Some((pkg, comp)) = self.index.find_type(pkg_name, prefix, type_name) {
+            return Some(format!("{}::{}", pkg, comp.identifier).to_lowercase());
+        }
+        let standard = prefix.is_none_or(|p| p.to_string().eq_ignore_ascii_case("base_types"));
+        if standard && BASE_TYPES.contains(&type_name.to_lowercase().as_str()) {
+            return Some(format!("base_types::{}", type_name).to_lowercase());
+        }
+        None
+    }
+
+    fn port_data_type(&self, pkg_name: &str, port: &PortSpec) -> Option<String> {
+        match &port.port_type {
+            PortType::Data { classifier: Some(PortDataTypeReference::Classifier(reference)) }
+            | PortType::EventData { classifier: Some(PortDataTypeReference::Classifier(reference)) } => {
+                self.resolve_data_classifier(pkg_name, reference)
+            }
+            _ => None,
+        }
+    }
+
+    fn check_duplicates<'n>(&mut self, location: &str, what: &str, names: impl Iterator<Item = &'n str>) {
+        let mut seen = HashSet::new();
+        for name in names {
+            if !seen.insert(name.to_lowercase()) {
+                self.report(
+                    Severity::Error,
+                    codes::DUPLICATE_IDENTIFIER,
+                    location.to_string(),
+                    format!("{} {} is declared more than once", what, name),
+                );
+            }
+        }
+    }
+
+    // 实例的分类器（实现优先，其次类型）的声明位置
+    fn classifier_location(&self, instance: &ComponentInstance) -> Option<SourceLocation> {
+        let classifier = instance.classifier.as_ref()?;
+        if let Some(impl_name) = &classifier.impl_name {
+            let name = ImplementationName {
+                type_identifier: classifier.type_name.clone(),
+                implementation_identifier: impl_name.clone(),
+            };
+            if let Some((_, impl_)) = self.index.find_impl(&classifier.package, None, &name) {
+                return impl_.location.clone();
+            }
+        }
+        let (_, comp) = self.index.find_type(&classifier.package, None, &classifier.type_name)?;
+        comp.location.clone()
+    }
+
+    // 分发协议要求的线程属性：周期、偶发、定时、混合分发都需要 Period
+    fn check_thread_properties(&mut self, instance: &SystemInstance) {
+        for thread in instance.root.walk() {
+            if thread.category != ComponentCategory::Thread {
+                continue;
+            }
+            let location = format!("{}.{}", instance.root.name, thread.path.join("."));
+            self.source = self.classifier_location(thread);
+            let Some(protocol) = thread.property("dispatch_protocol").and_then(enumeration_value) else {
+                self.report(
+                    Severity::Warning,
+                    codes::MISSING_DISPATCH_PROTOCOL,
+                    location,
+                    "thread has no Dispatch_Protocol".to_string(),
+                );
+                continue;
+            };
+            if PERIODIC_DISPATCH.contains(&protocol.to_lowercase().as_str()) && thread.property("period").is_none() {
+                // 偶发线程的 Period 是最小到达间隔，缺省时代码生成不做限流
+                let (severity, code) = if protocol.eq_ignore_ascii_case("sporadic") {
+                    (Severity::Warning, codes::SPORADIC_WITHOUT_PERIOD)
+                } else {
+                    (Severity::Error, codes::MISSING_PERIOD)
+                };
+                self.report(
+                    severity,
+                    code,
+                    location,
+                    format!("{} thread requires a Period", protocol),
+                );
+            }
+        }
+    }
+}
+
+// 源端可发出、目的端可接收
+fn directions_allow(source: &ResolvedEndpoint, destination: &ResolvedEndpoint) -> bool {
+    let can_send = |end: &ResolvedEndpoint| match end.port.direction {
+        PortDirection::InOut => true,
+        PortDirection::In => end.own,
+        PortDirection::Out => !end.own,
+    };
+    let can_receive = |end: &ResolvedEndpoint| match end.port.direction {
+        PortDirection::InOut => true,
+        PortDirection::In => !end.own,
+        PortDirection::Out => end.own,
+    };
+    can_send(source) && can_receive(destination)
+}
+
+fn describe_direction(end: &ResolvedEndpoint) -> String {
+    let direction = match end.port.direction {
+        PortDirection::In => "in",
+        PortDirection::Out => "out",
+        PortDirection::InOut => "in out",
+    };
+    if end.own {
+        format!("own {} port", direction)
+    } else {
+        format!("subcomponent {} port", direction)
+    }
+}
+
+fn enumeration_value(value: &PropertyValue) -> Option<String> {
+    match value {
+        PropertyValue::Single(PropertyExpression::String(StringTerm::Literal(literal))) => Some(literal.clone()),
+        _ => None,
+    }
+}
+
+fn features_of(comp: &ComponentType) -> &[Feature] {
+    match &comp.features {
+        FeatureClause::Items(features) => features,
+        _ => &[],
+    }
+}
+
+fn find_port<'a>(comp: &'a ComponentType, name: &str) -> Option<&'a PortSpec> {
+    features_of(comp).iter().find_map(|f| match f {
+        Feature::Port(port) if port.identifier.eq_ignore_ascii_case(name) => Some(port),
+        _ => None,
+    })
+}
+
+fn feature_name(feature: &Feature) -> &str {
+    crate::instance_model::feature_identifier(feature)
+}
+
+fn unique_reference(reference: &UniqueComponentClassifierReference) -> &UniqueImplementationReference {
+    match reference {
+        UniqueComponentClassifierReference::Type(r) | UniqueComponentClassifierReference::Implementation(r) => r,
+    }
+}
+
+fn reference_name(reference: &UniqueComponentClassifierReference) -> String {
+    let unirf = unique_reference(reference);
+    let name = if unirf.implementation_name.implementation_identifier.is_empty() {
+        unirf.implementation_name.type_identifier.clone()
+    } else {
+        unirf.implementation_name.to_string()
+    };
+    match &unirf.package_prefix {
+        Some(prefix) => format!("{}::{}", prefix.to_string(), name),
+        None => name,
+    }
+}
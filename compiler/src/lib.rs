@@ -6,6 +6,13 @@ pub mod transform;
 pub mod transform_annex;
 
 pub mod model_statistics;
+pub mod instance_model;
+pub mod legality;
+pub mod diagnostics;
+pub mod source_map;
+pub mod inheritance;
+pub mod prototype;
+pub mod model_loader;
 
 pub mod test_mod;
 pub mod test_mod2;
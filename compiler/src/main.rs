@@ -4,10 +4,17 @@ mod aadl_ast2rust_code;
 pub mod aadlight_parser;
 mod ast;
 pub mod model_statistics;
+mod instance_model;
+mod legality;
+mod diagnostics;
+mod source_map;
+mod inheritance;
+mod prototype;
+mod model_loader;
 // pub mod printmessage;
 pub mod transform;
 pub mod transform_annex;
-// pub mod build_project_tool;
+mod build_project_tool;
 
 use compiler::printmessage::*;
 use aadl_ast2rust_code::intermediate_print::*;
@@ -19,15 +26,50 @@ use pest::error::ErrorVariant;
 use pest::Parser;
 use std::fs;
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::{aadl_ast2rust_code::converter::{AadlConverter, CodegenBackend}, ast::aadl_ast_cj::Package};
+use aadl_ast2rust_code::embedded;
+use build_project_tool::*;
+use source_map::SourceMap;
+
+// 命令行动作：生成Rust工程，或只对模型做合法性检查
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum CliAction {
+    #[default]
+    Generate,
+    Check,
+}
+
+impl FromStr for CliAction {
+    type Err = String;
 
-use crate::{aadl_ast2rust_code::converter::AadlConverter, ast::aadl_ast_cj::Package};
-use compiler::build_project_tool::*;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "generate" => Ok(CliAction::Generate),
+            "check" => Ok(CliAction::Check),
+            other => Err(format!("unknown action `{}` (expected: generate, check)", other)),
+        }
+    }
+}
 
 #[derive(ClapParser)]
 struct Args {
+    /// 动作：generate（默认，生成Rust工程）| check（只做合法性检查，发现错误时以非零状态退出）
+    #[arg(default_value = "generate")]
+    action: CliAction,
+
     #[arg(short, long)]
     input: Option<String>,
+
+    /// 代码生成后端：threads（每个AADL线程一个OS线程）| cyclic（静态循环执行器）| async（每个AADL线程一个tokio任务）| embedded（no_std运行至完成调度）
+    #[arg(short, long, default_value = "threads")]
+    backend: CodegenBackend,
+
+    /// AADL 库目录（可重复），用于查找 with 引用的包；先于内置库（Base_Types 与预声明属性集）搜索
+    #[arg(short = 'L', long = "library")]
+    library: Vec<PathBuf>,
 }
 
 fn main() {
@@ -46,6 +88,14 @@ fn main() {
         println!("CLI mode");
         println!("输入路径: {}", test_case.path);
         println!("输出名称: {}", test_case.output_name);
+        println!("代码生成后端: {:?}", args.backend);
+
+        if args.action == CliAction::Check {
+            if !check_test_case(&test_case, args.backend, &args.library) {
+                std::process::exit(1);
+            }
+            return;
+        }
 
         // 确保 generate 目录存在
         if fs::metadata("generate").is_err() {
@@ -58,7 +108,9 @@ fn main() {
             fs::remove_dir_all(&output_dir).unwrap();
         }
         
-        process_test_case(&test_case);
+        if !process_test_case(&test_case, args.backend, &args.library) {
+            std::process::exit(1);
+        }
         return; // 不进入下面的交互逻辑
     }
 
@@ -251,7 +303,7 @@ fn main() {
             }
 
             // 处理选中的测试用例
-            process_test_case(test_case);
+            process_test_case(test_case, args.backend, &args.library);
         }
         None => {
             println!("无效的选择，请输入 0-{} 之间的数字", test_cases.len());
@@ -259,18 +311,65 @@ fn main() {
     }
 }
 
-fn process_test_case(test_case: &TestCase) {
+// check 动作：解析并实例化模型，只报告合法性检查结果（含所选后端的模型限制），不生成代码。返回模型是否没有错误
+fn check_test_case(test_case: &TestCase, backend: CodegenBackend, library: &[PathBuf]) -> bool {
+    diagnostics::take();
+    let model = match model_loader::load(&test_case.path, library) {
+        Ok(model) => model,
+        Err(err) => {
+            eprintln!("读取文件失败: {}", err);
+            return false;
+        }
+    };
+    let sources = &model.sources;
+    let pairs = match AADLParser::parse(aadlight_parser::Rule::file, &model.buffer) {
+        Ok(pairs) => pairs,
+        Err(e) => {
+            diagnostics::report(diagnostics::Diagnostic::syntax(&e));
+            report_diagnostics(sources, &diagnostics::take());
+            return false;
+        }
+    };
+    let ast: Vec<ast::aadl_ast_cj::Package> = transform::AADLTransformer::transform_file(pairs.collect(), sources);
+    let instances = instance_model::instantiate_all(&ast);
+    if backend == CodegenBackend::Embedded {
+        embedded::check_profile(&ast, &instances);
+    }
+    legality::check_model(&ast, &instances).into_iter().for_each(diagnostics::report);
+    report_diagnostics(sources, &diagnostics::take())
+}
+
+// 打印前端/代码生成阶段收集的诊断（file:line:col: error[Exxxx]: ...），返回是否没有错误
+fn report_diagnostics(sources: &SourceMap, diagnostics: &[diagnostics::Diagnostic]) -> bool {
+    for diagnostic in diagnostics {
+        match diagnostic.severity {
+            diagnostics::Severity::Error => eprintln!("{}", diagnostic.render(sources)),
+            diagnostics::Severity::Warning => println!("{}", diagnostic.render(sources)),
+        }
+    }
+    let errors = diagnostics.iter().filter(|d| d.severity == diagnostics::Severity::Error).count();
+    if !diagnostics.is_empty() {
+        println!("诊断: {} 个错误, {} 个警告", errors, diagnostics.len() - errors);
+    }
+    !diagnostics::has_errors(diagnostics)
+}
+
+// 处理单个案例，返回是否成功生成（读取/解析失败或存在错误级诊断时返回 false）
+fn process_test_case(test_case: &TestCase, backend: CodegenBackend, library: &[PathBuf]) -> bool {
     println!("开始处理: {}", test_case.name);
 
-    let aadl_input = match read_aadl_inputs(&test_case.path) {
-        Ok(content) => content,
+    // 加载阶段（缺失/循环依赖的包）的诊断与转换阶段的一并报告
+    diagnostics::take();
+    let model = match model_loader::load(&test_case.path, library) {
+        Ok(model) => model,
         Err(err) => {
             eprintln!("读取文件失败: {}", err);
-            return;
+            return false;
         }
     };
+    let sources = &model.sources;
 
-    match AADLParser::parse(aadlight_parser::Rule::file, &aadl_input) {
+    match AADLParser::parse(aadlight_parser::Rule::file, &model.buffer) {
         Ok(pairs) => {
             println!("=== 解析成功，共 {} 个pair ===", pairs.clone().count());
 
@@ -298,33 +397,61 @@ fn process_test_case(test_case: &TestCase) {
                     eprintln!("写入模型统计文件失败: {}", e);
                 });
 
-            // 转换到AST
+            // 转换到AST；转换中遇到的问题全部收集后一并报告，有错误时不再生成代码
             let ast: Vec<ast::aadl_ast_cj::Package> =
-                transform::AADLTransformer::transform_file(pairs.clone().collect());
+                transform::AADLTransformer::transform_file(pairs.clone().collect(), sources);
             println!("=== 转换得到 {} 个package ===", ast.len());
+            if !report_diagnostics(sources, &diagnostics::take()) {
+                eprintln!("模型转换存在错误，未生成代码");
+                return false;
+            }
 
             // 打印AST
             // println!("\n================================== AST ==================================");
             // print_ast(&ast);
 
             println!("\n==================================== 生成Rust代码 ===================================");
+            // 内置库中的包（Base_Types）不单独生成模块，只用来确定基础数据类型到Rust类型的映射
+            let (builtin, model_packages): (Vec<&Package>, Vec<&Package>) = ast
+                .iter()
+                .partition(|pkg| model.builtin_packages.contains(&pkg.name.to_string().to_lowercase()));
             let mut converter = AadlConverter::default();
-            for package in ast.iter() {
-                generate_rust_code_for_test_case(package, test_case, ast.len(), &mut converter);
+            converter.backend = backend;
+            converter.module_packages = model_packages.iter().map(|pkg| pkg.name.to_string().to_lowercase()).collect();
+            converter.property_constants = model.property_constants.clone();
+            converter.property_definitions = model.property_definitions.clone();
+            for package in builtin {
+                converter.register_base_types(package);
+            }
+            converter.collect_packages(model_packages.iter().copied());
+
+            // 从根系统实现实例化，得到跨包解析后的实例树与语义连接
+            converter.instances = instance_model::instantiate_all(&ast);
+            if backend == CodegenBackend::Embedded {
+                embedded::check_profile(&ast, &converter.instances);
+            }
+            converter.plan_port_channels();
+            converter.plan_processors();
+            converter.plan_deployment();
+            // 生成前先报告合法性问题；生成流程本身不因此中断，严格检查请使用 check 动作
+            report_diagnostics(sources, &legality::check_model(&ast, &converter.instances));
+            let instance_debug_path = format!("generate/temp/{}_instance_debug.txt", test_case.output_name);
+            fs::write(&instance_debug_path, format!("{:#?}", converter.instances)).unwrap();
+            println!("实例模型已保存到: {}", instance_debug_path);
+            for package in model_packages.iter() {
+                generate_rust_code_for_test_case(package, test_case, model_packages.len(), &mut converter);
             }
 
             // 生成项目所需的 Cargo.toml, build.rs 等文件
-            assemble_rust_project(test_case);
+            assemble_rust_project(test_case, backend);
 
             // println!("✅ 代码生成完成！输出文件保存在 generate/ 目录下");
+            report_diagnostics(sources, &diagnostics::take())
         }
         Err(e) => {
-            eprintln!("解析失败: {}", e);
-            // 打印详细的错误信息
-            eprintln!("解析错误: {:?}", e);
-
-            // 显示错误位置和上下文
-            eprintln!("错误位置: {:?}", e.location);
+            // 错误位置换算回原始文件
+            diagnostics::report(diagnostics::Diagnostic::syntax(&e));
+            report_diagnostics(sources, &diagnostics::take());
 
             // 显示期望的规则
             if let ErrorVariant::ParsingError {
@@ -341,54 +468,11 @@ fn process_test_case(test_case: &TestCase) {
             }
 
             eprintln!("解析失败，无法继续处理");
+            false
         }
     }
 }
 
-// 读取文件/文件夹
-fn read_aadl_inputs(path: &str) -> Result<String, std::io::Error> {
-    let path = Path::new(path);
-
-    if path.is_file() {
-        // 原有行为：单文件
-        return fs::read_to_string(path);
-    }
-
-    if path.is_dir() {
-        let mut merged = String::new();
-
-        let mut entries: Vec<_> = fs::read_dir(path)?
-            .filter_map(Result::ok)
-            .map(|e| e.path())
-            .filter(|p| p.extension().map(|e| e == "aadl").unwrap_or(false))
-            .collect();
-
-        // 排序，保证确定性
-        entries.sort();
-
-        for file in entries {
-            let content = fs::read_to_string(&file)?;
-
-            // —— 合并边界处理 ——
-            merged.push_str("\n\n");
-            merged.push_str("-- ================================\n");
-            merged.push_str(&format!("-- merged from file: {}\n", file.display()));
-            merged.push_str("-- ================================\n");
-            merged.push('\n');
-
-            merged.push_str(&content);
-            merged.push('\n');
-        }
-
-        return Ok(merged);
-    }
-
-    Err(std::io::Error::new(
-        std::io::ErrorKind::InvalidInput,
-        "path is neither file nor directory",
-    ))
-}
-
 pub fn generate_rust_code_for_test_case(
     aadl_pkg: &Package,
     test_case: &TestCase,
@@ -412,7 +496,7 @@ pub fn generate_rust_code_for_test_case(
     fs::write(&merged_ast_path, format!("{:#?}", merge_rust_module)).unwrap();
     //println!("合并后AST已保存到: {}", merged_ast_path);
 
-    let mut code_generator = RustCodeGenerator::new();
+    let mut code_generator = RustCodeGenerator::with_backend(converter.backend);
     let rust_code = code_generator.generate_module_code(&merge_rust_module);
 
     // 根据包数量决定输出路径
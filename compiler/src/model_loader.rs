@@ -0,0 +1,443 @@
+// 模型加载：以输入文件/目录中的 .aadl 文件为根，按 with 子句与 renames 声明引用的包，
+// 到库搜索路径中查找定义这些包的文件并一并加入，最后拼接成一个缓冲区交给解析器。
+// 找不到的包、包之间的循环依赖作为诊断报告（位置指向引用它的 with/renames）
+use crate::aadlight_parser::{self, AADLParser};
+use crate::ast::aadl_ast_cj::{PropertyDefinition, PropertySet, PropertyType, PropertyValue};
+use crate::diagnostics::{self, codes, Diagnostic, Severity, Span};
+use crate::source_map::SourceMap;
+use crate::transform::AADLTransformer;
+use pest::iterators::Pair;
+use pest::Parser;
+use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// 随编译器发布的库目录：预声明的 Base_Types 包与标准属性集
+pub const BUILTIN_LIBRARY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/aadl_lib");
+
+// 预声明包：模型不写 with 也可以直接引用（如 Base_Types::Integer）
+const PREDECLARED_PACKAGES: [&str; 1] = ["base_types"];
+
+#[derive(Debug)]
+pub struct LoadedModel {
+    // 拼接后的解析缓冲区
+    pub buffer: String,
+    pub sources: SourceMap,
+    // 来自内置库的包（小写），参与解析、实例化与检查，但不单独生成代码
+    pub builtin_packages: HashSet<String>,
+    // 属性集中声明的常量，键为小写的 属性集::常量名
+    pub property_constants: HashMap<String, PropertyValue>,
+    // 属性集中的属性定义，类型中引用的属性类型已展开，键为小写的 属性集::属性名
+    pub property_definitions: HashMap<String, PropertyDefinition>,
+}
+
+// with/renames 引用的一个名字，span 相对于所在文件
+#[derive(Debug, Clone)]
+struct Reference {
+    name: String,
+    span: Span,
+    // 来自 renames（否则来自 with）
+    renamed: bool,
+}
+
+#[derive(Debug, Clone)]
+struct PackageInfo {
+    name: String,
+    references: Vec<Reference>,
+}
+
+#[derive(Debug, Clone)]
+struct ScannedFile {
+    path: PathBuf,
+    text: String,
+    packages: Vec<PackageInfo>,
+    property_sets: Vec<PropertySet>,
+    builtin: bool,
+}
+
+impl ScannedFile {
+    // 只包含属性集的文件不进入解析缓冲区
+    fn is_property_set_only(&self) -> bool {
+        self.packages.is_empty() && !self.property_sets.is_empty()
+    }
+}
+
+// 加载模型：input 为文件或目录，library_dirs 为命令行追加的库目录（先于内置库搜索）
+pub fn load(input: &str, library_dirs: &[PathBuf]) -> io::Result<LoadedModel> {
+    let roots = root_files(Path::new(input))?
+        .iter()
+        .map(|path| scan_file(path, false))
+        .collect::<io::Result<Vec<_>>>()?;
+    let library = library_files(library_dirs)?;
+
+    // 库中每个包名对应的第一个文件（搜索路径靠前的优先）
+    let mut library_packages: HashMap<String, usize> = HashMap::new();
+    let mut property_sets: HashSet<String> = HashSet::new();
+    for (index, file) in library.iter().enumerate() {
+        for package in &file.packages {
+            library_packages.entry(package.name.to_lowercase()).or_insert(index);
+        }
+        property_sets.extend(file.property_sets.iter().map(|s| s.name.to_lowercase()));
+    }
+    for file in &roots {
+        property_sets.extend(file.property_sets.iter().map(|s| s.name.to_lowercase()));
+    }
+    // 模型中的属性集覆盖库中的同名属性集
+    let declared_sets: Vec<&PropertySet> = library.iter().chain(roots.iter()).flat_map(|file| &file.property_sets).collect();
+    let (property_constants, property_definitions) = collect_property_sets(&declared_sets);
+
+    // 从根文件出发求依赖闭包
+    let mut files = roots;
+    let mut declared: HashMap<String, usize> = HashMap::new();
+    let mut missing: Vec<(usize, Reference)> = Vec::new();
+    let mut queue: VecDeque<(Option<(usize, Reference)>, String)> = VecDeque::new();
+    for (index, file) in files.iter().enumerate() {
+        enqueue_file(index, file, &mut declared, &mut queue);
+    }
+    for name in PREDECLARED_PACKAGES {
+        queue.push_back((None, name.to_string()));
+    }
+    while let Some((origin, name)) = queue.pop_front() {
+        if declared.contains_key(&name) || property_sets.contains(&name) {
+            continue;
+        }
+        if let Some(&library_index) = library_packages.get(&name) {
+            let index = files.len();
+            files.push(library[library_index].clone());
+            enqueue_file(index, &files[index], &mut declared, &mut queue);
+        } else if let Some(origin) = origin {
+            missing.push(origin);
+        }
+    }
+
+    // 拼接：根文件在前，库文件按加入顺序在后
+    let mut buffer = String::new();
+    let mut sources = SourceMap::new(input);
+    let mut starts = vec![0; files.len()];
+    if files.len() == 1 && !files[0].builtin {
+        // 单文件且没有引入库文件：缓冲区就是文件内容本身
+        buffer.push_str(&files[0].text);
+        sources.add_file(input, 0, &files[0].text);
+    } else {
+        for (index, file) in files.iter().enumerate() {
+            if file.is_property_set_only() {
+                continue;
+            }
+            buffer.push_str("\n\n");
+            buffer.push_str("-- ================================\n");
+            buffer.push_str(&format!("-- merged from file: {}\n", file.path.display()));
+            buffer.push_str("-- ================================\n");
+            buffer.push('\n');
+
+            starts[index] = buffer.len();
+            sources.add_file(&file.path.display().to_string(), buffer.len(), &file.text);
+            buffer.push_str(&file.text);
+            buffer.push('\n');
+        }
+    }
+
+    for (index, reference) in missing {
+        // 只有分类器引用（子组件、特征、extends 中的 `包名::分类器`）依赖缺失的包；
+        // 未使用的 with 和未随库发布的属性集（如 `ARINC653::Partition_Name => ...`）不影响转换结果，只给出警告
+        let message = format!("package `{}` is not declared in the model or in the library path", reference.name);
+        if reference.renamed || references_classifier_in(&files[index].text, &reference.name) {
+            report_at(&starts, index, &reference, Severity::Error, codes::MISSING_PACKAGE, message);
+        } else {
+            report_at(
+                &starts,
+                index,
+                &reference,
+                Severity::Warning,
+                codes::UNUSED_MISSING_PACKAGE,
+                format!("{} (no classifier references it, ignored)", message),
+            );
+        }
+    }
+    for (index, reference, cycle) in find_cycles(&files, &declared) {
+        report_at(
+            &starts,
+            index,
+            &reference,
+            Severity::Error,
+            codes::CYCLIC_DEPENDENCY,
+            format!("cyclic package dependency: {}", cycle.join(" -> ")),
+        );
+    }
+
+    let builtin_packages = files
+        .iter()
+        .filter(|file| file.builtin)
+        .flat_map(|file| file.packages.iter().map(|p| p.name.to_lowercase()))
+        .collect();
+    Ok(LoadedModel { buffer, sources, builtin_packages, property_constants, property_definitions })
+}
+
+// 汇总属性集中的常量与属性定义，键为小写的 属性集::名称，后声明的同名属性集覆盖先声明的；
+// 取值无法按属性值语法解析的常量（如 2#1#e32 这类基数字面量）不登记
+fn collect_property_sets(sets: &[&PropertySet]) -> (HashMap<String, PropertyValue>, HashMap<String, PropertyDefinition>) {
+    let key = |set: &PropertySet, name: &str| format!("{}::{}", set.name, name).to_lowercase();
+    let mut types = HashMap::new();
+    let mut constants = HashMap::new();
+    for set in sets {
+        for decl in &set.types {
+            types.insert(key(set, &decl.name), decl.property_type.clone());
+        }
+        for constant in &set.constants {
+            if let Some(value) = &constant.value {
+                constants.insert(key(set, &constant.name), value.clone());
+            }
+        }
+    }
+    let mut definitions = HashMap::new();
+    for set in sets {
+        for definition in &set.definitions {
+            let mut definition = definition.clone();
+            definition.property_type = resolve_property_type(&definition.property_type, &set.name, &types, 0);
+            definitions.insert(key(set, &definition.name), definition);
+        }
+    }
+    (constants, definitions)
+}
+
+// 展开类型中引用的属性类型声明（如 Time -> aadlinteger）；不带属性集前缀时先查所在属性集，再按名称查其他属性集
+fn resolve_property_type(ty: &PropertyType, set: &str, types: &HashMap<String, PropertyType>, depth: usize) -> PropertyType {
+    let resolve = |ty: &PropertyType| resolve_property_type(ty, set, types, depth + 1);
+    match ty {
+        // 类型声明之间成环时保留引用
+        _ if depth > 16 => ty.clone(),
+        PropertyType::Named(name) => {
+            let name = name.to_lowercase();
+            let found = if name.contains("::") {
+                types.get(&name)
+            } else {
+                let suffix = format!("::{}", name);
+                types.get(&format!("{}{}", set.to_lowercase(), suffix)).or_else(|| {
+                    types
+                        .iter()
+                        .filter(|(key, _)| key.ends_with(&suffix))
+                        .min_by_key(|(key, _)| key.as_str())
+                        .map(|(_, ty)| ty)
+                })
+            };
+            match found {
+                Some(declared) => resolve(declared),
+                None => ty.clone(),
+            }
+        }
+        PropertyType::List(element) => PropertyType::List(Box::new(resolve(element))),
+        PropertyType::Range(element) => PropertyType::Range(Box::new(resolve(element))),
+        PropertyType::Record(fields) => {
+            PropertyType::Record(fields.iter().map(|(name, ty)| (name.clone(), resolve(ty))).collect())
+        }
+        _ => ty.clone(),
+    }
+}
+
+// 登记文件中声明的包，并把它们引用的名字加入待解析队列
+fn enqueue_file(
+    index: usize,
+    file: &ScannedFile,
+    declared: &mut HashMap<String, usize>,
+    queue: &mut VecDeque<(Option<(usize, Reference)>, String)>,
+) {
+    for package in &file.packages {
+        declared.entry(package.name.to_lowercase()).or_insert(index);
+    }
+    for package in &file.packages {
+        for reference in &package.references {
+            queue.push_back((Some((index, reference.clone())), reference.name.to_lowercase()));
+        }
+    }
+}
+
+// 包依赖图上的深度优先搜索，每条回边对应一个环：(引用所在文件, 引用, 环上的包名)
+fn find_cycles(files: &[ScannedFile], declared: &HashMap<String, usize>) -> Vec<(usize, Reference, Vec<String>)> {
+    let packages: HashMap<String, (usize, &PackageInfo)> = files
+        .iter()
+        .enumerate()
+        .flat_map(|(index, file)| file.packages.iter().map(move |p| (p.name.to_lowercase(), (index, p))))
+        .filter(|(name, (index, _))| declared.get(name) == Some(index))
+        .collect();
+    let mut names: Vec<&String> = packages.keys().collect();
+    names.sort();
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Visiting,
+        Done,
+    }
+    fn visit(
+        name: &str,
+        packages: &HashMap<String, (usize, &PackageInfo)>,
+        marks: &mut HashMap<String, Mark>,
+        stack: &mut Vec<String>,
+        cycles: &mut Vec<(usize, Reference, Vec<String>)>,
+    ) {
+        let Some(&(index, package)) = packages.get(name) else { return };
+        marks.insert(name.to_string(), Mark::Visiting);
+        stack.push(package.name.clone());
+        for reference in &package.references {
+            let target = reference.name.to_lowercase();
+            if target == name || !packages.contains_key(&target) {
+                continue;
+            }
+            match marks.get(&target) {
+                Some(Mark::Visiting) => {
+                    let start = stack.iter().position(|p| p.eq_ignore_ascii_case(&target)).unwrap_or(0);
+                    let mut cycle = stack[start..].to_vec();
+                    cycle.push(packages[&target].1.name.clone());
+                    cycles.push((index, reference.clone(), cycle));
+                }
+                Some(Mark::Done) => {}
+                None => visit(&target, packages, marks, stack, cycles),
+            }
+        }
+        stack.pop();
+        marks.insert(name.to_string(), Mark::Done);
+    }
+
+    let mut marks = HashMap::new();
+    let mut cycles = Vec::new();
+    for name in names {
+        if !marks.contains_key(name) {
+            visit(name, &packages, &mut marks, &mut Vec::new(), &mut cycles);
+        }
+    }
+    cycles
+}
+
+// 文件内的位置换算到拼接缓冲区后报告
+fn report_at(
+    starts: &[usize],
+    index: usize,
+    reference: &Reference,
+    severity: Severity,
+    code: &'static str,
+    message: String,
+) {
+    let span = Span { offset: starts[index] + reference.span.offset, ..reference.span };
+    diagnostics::report(Diagnostic { severity, code, message, span: Some(span), location: None });
+}
+
+// 文本中是否以 `包名::` 引用了该包中的分类器：限定名前是组件类别、特征种类、extends 或 classifier (，
+// 而属性关联名（`包名::属性 =>`）与属性常量中的 `包名::` 是属性集限定符，不算
+fn references_classifier_in(text: &str, package: &str) -> bool {
+    let pattern = format!(
+        r"(?i)(\b(extends|data|port|parameter|access|subprogram|thread|process|system|device|bus|memory|processor|abstract|group)\s+|\bclassifier\s*\(\s*){}\s*::",
+        regex::escape(package)
+    );
+    Regex::new(&pattern).map(|re| re.is_match(text)).unwrap_or(true)
+}
+
+// 输入为文件时即该文件；为目录时取目录下的 .aadl 文件（排序，保证确定性）
+fn root_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    if path.is_dir() {
+        let mut entries = aadl_files_in(path)?;
+        entries.sort();
+        return Ok(entries);
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidInput, "path is neither file nor directory"))
+}
+
+// 库目录（递归）中的全部 .aadl 文件，命令行目录在前、内置库在后
+fn library_files(library_dirs: &[PathBuf]) -> io::Result<Vec<ScannedFile>> {
+    let builtin = PathBuf::from(BUILTIN_LIBRARY);
+    let mut files = Vec::new();
+    for dir in library_dirs.iter().chain(std::iter::once(&builtin)) {
+        if !dir.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("library directory not found: {}", dir.display()),
+            ));
+        }
+        let mut stack = vec![dir.clone()];
+        let mut paths = Vec::new();
+        while let Some(current) = stack.pop() {
+            paths.extend(aadl_files_in(&current)?);
+            for entry in fs::read_dir(&current)? {
+                let entry = entry?.path();
+                if entry.is_dir() {
+                    stack.push(entry);
+                }
+            }
+        }
+        paths.sort();
+        for path in paths {
+            files.push(scan_file(&path, *dir == builtin)?);
+        }
+    }
+    Ok(files)
+}
+
+fn aadl_files_in(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    Ok(fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().map(|e| e == "aadl").unwrap_or(false))
+        .collect())
+}
+
+// 读取文件并提取其中声明的包及各包 with/renames 引用的名字；
+// 不是包的文件按属性集解析；两者都无法解析的文件留在解析缓冲区中，语法错误由后续整体解析报告
+fn scan_file(path: &Path, builtin: bool) -> io::Result<ScannedFile> {
+    let text = fs::read_to_string(path)?;
+    let packages = match AADLParser::parse(aadlight_parser::Rule::file, &text) {
+        Ok(pairs) => pairs
+            .flat_map(|file| file.into_inner())
+            .filter(|pair| pair.as_rule() == aadlight_parser::Rule::package_declaration)
+            .map(scan_package)
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    let property_sets = if packages.is_empty() {
+        match AADLParser::parse(aadlight_parser::Rule::property_set_file, &text) {
+            Ok(pairs) => pairs
+                .flat_map(|file| file.into_inner())
+                .filter(|pair| pair.as_rule() == aadlight_parser::Rule::property_set_declaration)
+                .map(AADLTransformer::transform_property_set)
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    } else {
+        Vec::new()
+    };
+    Ok(ScannedFile { path: path.to_path_buf(), text, packages, property_sets, builtin })
+}
+
+fn scan_package(pair: Pair<aadlight_parser::Rule>) -> PackageInfo {
+    let mut inner = pair.into_inner();
+    let name = inner.next().map(|p| p.as_str().trim().to_string()).unwrap_or_default();
+    let mut references = Vec::new();
+    for decl in inner.filter(|p| p.as_rule() == aadlight_parser::Rule::visibility_declarations) {
+        let items: Vec<_> = decl.into_inner().collect();
+        match items.first().map(|p| p.as_rule()) {
+            // with A, B::C, Property_Set;
+            Some(aadlight_parser::Rule::WITH) => {
+                references.extend(items.iter().skip(1).map(|item| Reference {
+                    name: item.as_str().trim().to_string(),
+                    span: Span::of(item),
+                    renamed: false,
+                }));
+            }
+            // X renames package A::B; / X renames A::B::Component; / X renames A::all;
+            _ => {
+                if let Some(target) = items.get(1) {
+                    let text = target.as_str().trim();
+                    let package = match target.as_rule() {
+                        aadlight_parser::Rule::package_name => Some(text),
+                        _ => text.rsplit_once("::").map(|(package, _)| package),
+                    };
+                    if let Some(package) = package {
+                        references.push(Reference { name: package.to_string(), span: Span::of(target), renamed: true });
+                    }
+                }
+            }
+        }
+    }
+    PackageInfo { name, references }
+}
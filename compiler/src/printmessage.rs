@@ -67,9 +67,17 @@ pub fn print_ast(ast: &Vec<Package>) {
                                                     s.identifier, s.direction, s.classifier
                                                 );
                                             }
+                                            SubcomponentAccessSpec::Bus(b) | SubcomponentAccessSpec::VirtualBus(b) => {
+                                                println!(
+                                                    "    Bus Access: {} {:?} {:?}",
+                                                    b.identifier, b.direction, b.classifier
+                                                );
+                                            }
                                         }
                                     }
-                                    // _ => {}
+                                    Feature::Abstract(spec) => {
+                                        println!("    Abstract Feature: {} {:?} {:?}", spec.identifier, spec.direction, spec.prototype);
+                                    }
                                 }
                             }
                         }
@@ -0,0 +1,923 @@
+// 原型与原型绑定：继承合并之后执行一次，把原型的实际值代入分类器。
+// 子组件分类器后的绑定生成特化的分类器副本（Worker.impl (T => data Float) -> Worker_Float.impl），
+// 实例化、合法性检查与代码生成直接使用特化后的分类器；未绑定但给出约束分类器的组件原型取约束分类器，
+// 其余原型保留在分类器中，代码生成时输出为泛型结构体
+use crate::ast::aadl_ast_cj::*;
+use crate::diagnostics::{self, codes};
+use crate::instance_model::ClassifierIndex;
+use std::collections::{HashMap, HashSet};
+
+// 原型名（小写） -> 实际值
+type Substitution = HashMap<String, PrototypeActual>;
+
+// 子组件在包中的位置：(包序号, 是否私有部分, 声明序号, 子组件序号)
+type SubcomponentSlot = (usize, bool, usize, usize);
+
+// 特化数量上限：递归引用自身的模板每层绑定都不同，无法收敛
+const MAX_SPECIALIZATIONS: usize = 256;
+
+pub fn resolve_prototypes(packages: &mut [Package]) {
+    mark_prototype_references(packages);
+    apply_own_bindings(packages);
+    specialize_subcomponents(packages);
+    apply_constraining_classifiers(packages);
+    check_unbound(packages);
+}
+
+// 解析阶段无法区分 `data T` 中的 T 是原型还是分类器：与所在分类器（实现还包括其类型）声明的原型同名的
+// 无前缀引用改写为原型引用
+fn mark_prototype_references(packages: &mut [Package]) {
+    let type_prototypes: HashMap<(String, String), Vec<String>> = declarations(packages)
+        .filter_map(|(pkg, decl)| match decl {
+            AadlDeclaration::ComponentType(comp) => {
+                Some(((pkg, comp.identifier.to_lowercase()), prototype_names(&comp.prototypes)))
+            }
+            _ => None,
+        })
+        .collect();
+
+    for (pkg, decl) in declarations_mut(packages) {
+        match decl {
+            AadlDeclaration::ComponentType(comp) => {
+                let names = prototype_names(&comp.prototypes);
+                mark_features(&mut comp.features, &names);
+                if let Some(bindings) = &mut comp.prototype_bindings {
+                    mark_bindings(bindings, &names);
+                }
+            }
+            AadlDeclaration::ComponentImplementation(impl_) => {
+                let mut names = prototype_names(&impl_.prototypes);
+                let type_key = (pkg, impl_.name.type_identifier.to_lowercase());
+                names.extend(type_prototypes.get(&type_key).cloned().unwrap_or_default());
+                if let Some(bindings) = &mut impl_.prototype_bindings {
+                    mark_bindings(bindings, &names);
+                }
+                for sub in subcomponents_mut(&mut impl_.subcomponents) {
+                    if let SubcomponentClassifier::ClassifierReference(reference) = &sub.classifier {
+                        if let Some(name) = prototype_reference(reference, &names) {
+                            sub.classifier = SubcomponentClassifier::Prototype(name);
+                        }
+                    }
+                    if let Some(bindings) = &mut sub.prototype_bindings {
+                        mark_bindings(bindings, &names);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn prototype_reference(reference: &UniqueComponentClassifierReference, names: &[String]) -> Option<String> {
+    let reference = unique_reference(reference);
+    if reference.package_prefix.is_some() || !reference.implementation_name.implementation_identifier.is_empty() {
+        return None;
+    }
+    names
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(&reference.implementation_name.type_identifier))
+        .cloned()
+}
+
+fn mark_features(features: &mut FeatureClause, names: &[String]) {
+    if names.is_empty() {
+        return;
+    }
+    for feature in feature_items_mut(features) {
+        match feature {
+            Feature::Port(port) => {
+                if let PortType::Data { classifier } | PortType::EventData { classifier } = &mut port.port_type {
+                    mark_port_classifier(classifier, names);
+                }
+            }
+            Feature::SubcomponentAccess(SubcomponentAccessSpec::Data(access)) => {
+                if let Some(DataAccessReference::Classifier(reference)) = &access.classifier {
+                    if let Some(name) = prototype_reference(reference, names) {
+                        access.classifier = Some(DataAccessReference::Prototype(name));
+                    }
+                }
+            }
+            Feature::SubcomponentAccess(SubcomponentAccessSpec::Subprogram(access)) => {
+                if let Some(SubprogramAccessReference::Classifier(reference)) = &access.classifier {
+                    if let Some(name) = prototype_reference(reference, names) {
+                        access.classifier = Some(SubprogramAccessReference::Prototype(name));
+                    }
+                }
+            }
+            // 总线访问的分类器不引用数据/子程序原型
+            Feature::SubcomponentAccess(SubcomponentAccessSpec::Bus(_) | SubcomponentAccessSpec::VirtualBus(_))
+            | Feature::Abstract(_) => {}
+        }
+    }
+}
+
+fn mark_port_classifier(classifier: &mut Option<PortDataTypeReference>, names: &[String]) {
+    if let Some(PortDataTypeReference::Classifier(reference)) = classifier {
+        if let Some(name) = prototype_reference(reference, names) {
+            *classifier = Some(PortDataTypeReference::Prototype(name));
+        }
+    }
+}
+
+fn mark_bindings(bindings: &mut PrototypeBindings, names: &[String]) {
+    if names.is_empty() {
+        return;
+    }
+    for binding in &mut bindings.bindings {
+        match &mut binding.actual {
+            PrototypeActual::Component(actual) => mark_component_actual(actual, names),
+            PrototypeActual::ComponentList(actuals) => actuals.iter_mut().for_each(|a| mark_component_actual(a, names)),
+            PrototypeActual::Feature(FeaturePrototypeActual::Port {
+                port_type: PortType::Data { classifier } | PortType::EventData { classifier },
+                ..
+            }) => mark_port_classifier(classifier, names),
+            _ => {}
+        }
+    }
+}
+
+fn mark_component_actual(actual: &mut ComponentPrototypeActual, names: &[String]) {
+    if let Some(ComponentPrototypeReference::Classifier(reference)) = &actual.reference {
+        if let Some(name) = prototype_reference(reference, names) {
+            actual.reference = Some(ComponentPrototypeReference::Prototype(name));
+        }
+    }
+    if let Some(bindings) = &mut actual.bindings {
+        mark_bindings(bindings, names);
+    }
+}
+
+// extends 时给出的绑定直接代入合并后的分类器本身
+fn apply_own_bindings(packages: &mut [Package]) {
+    let type_prototypes = type_prototype_declarations(packages);
+    for (pkg, decl) in declarations_mut(packages) {
+        match decl {
+            AadlDeclaration::ComponentType(comp) => {
+                let Some(bindings) = comp.prototype_bindings.take() else { continue };
+                let declared = prototype_items(&comp.prototypes);
+                let subst = substitution(&comp.identifier, &declared, &bindings, comp.location.as_ref());
+                substitute_type(comp, &subst);
+            }
+            AadlDeclaration::ComponentImplementation(impl_) => {
+                let Some(bindings) = impl_.prototype_bindings.take() else { continue };
+                let mut declared = prototype_items(&impl_.prototypes);
+                let type_key = (pkg, impl_.name.type_identifier.to_lowercase());
+                declared.extend(type_prototypes.get(&type_key).cloned().unwrap_or_default());
+                let subst = substitution(&impl_.name.to_string(), &declared, &bindings, impl_.location.as_ref());
+                substitute_impl(impl_, &subst);
+            }
+            _ => {}
+        }
+    }
+}
+
+// 逐个处理实际值已确定（不再引用外层原型）的子组件绑定，为被引用的分类器生成特化副本；
+// 副本中的子组件绑定在代入后也变为确定的，随后的轮次继续特化
+fn specialize_subcomponents(packages: &mut [Package]) {
+    let mut specializer = Specializer::default();
+    while let Some((slot, site_pkg, reference, bindings, location)) = next_concrete_binding(packages) {
+        let Some(type_name) = specializer.specialize(packages, &site_pkg, &reference, bindings, location.as_ref()) else {
+            continue;
+        };
+        if let Some(sub) = subcomponent_mut(packages, slot) {
+            sub.classifier = SubcomponentClassifier::ClassifierReference(renamed(reference, type_name));
+        }
+    }
+}
+
+#[derive(Default)]
+struct Specializer {
+    // (包名, 分类器名 + 绑定) -> 特化后的类型名，相同绑定的引用共用同一个副本
+    types: HashMap<(String, String), String>,
+    impls: HashSet<(String, String)>,
+    count: usize,
+}
+
+impl Specializer {
+    // 为 分类器引用 + 绑定 生成（或复用）特化副本，返回副本的类型名；实现引用同时特化类型与实现
+    fn specialize(
+        &mut self,
+        packages: &mut [Package],
+        site_pkg: &str,
+        reference: &UniqueComponentClassifierReference,
+        mut bindings: PrototypeBindings,
+        location: Option<&SourceLocation>,
+    ) -> Option<String> {
+        // 实际值自身带绑定（data Pair.impl (T => ...)）时先特化实际值，端口等只记录分类器名的位置才能引用到它
+        for binding in &mut bindings.bindings {
+            match &mut binding.actual {
+                PrototypeActual::Component(actual) => self.specialize_actual(packages, site_pkg, actual, location),
+                PrototypeActual::ComponentList(actuals) => {
+                    for actual in actuals {
+                        self.specialize_actual(packages, site_pkg, actual, location);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if self.count == MAX_SPECIALIZATIONS {
+            diagnostics::error_in(
+                location,
+                codes::PROTOTYPE_MISMATCH,
+                format!("prototype bindings expand into more than {} specialized classifiers; is a template instantiating itself?", MAX_SPECIALIZATIONS),
+            );
+            return None;
+        }
+
+        let mut new_declarations = Vec::new();
+        let type_name = {
+            let index = ClassifierIndex::new(packages);
+            let unique = unique_reference(reference);
+            let prefix = unique.package_prefix.as_ref();
+            let name = &unique.implementation_name;
+            let Some((target_pkg, comp)) = index.find_type(site_pkg, prefix, &name.type_identifier) else {
+                report_unknown_target(&name.to_string(), location);
+                return None;
+            };
+            let impl_ = if name.implementation_identifier.is_empty() {
+                None
+            } else {
+                let Some((_, impl_)) = index.find_impl(site_pkg, prefix, name) else {
+                    report_unknown_target(&name.to_string(), location);
+                    return None;
+                };
+                Some(impl_)
+            };
+
+            let mut declared = prototype_items(&comp.prototypes);
+            if let Some(impl_) = impl_ {
+                declared.extend(prototype_items(&impl_.prototypes));
+            }
+            let target_label = impl_.map(|i| i.name.to_string()).unwrap_or_else(|| comp.identifier.clone());
+            let mut subst = substitution(&target_label, &declared, &bindings, location);
+            if subst.is_empty() {
+                return None;
+            }
+            // 实际值按绑定处的包解析，副本放在被特化分类器所在的包中
+            if !site_pkg.eq_ignore_ascii_case(&target_pkg) {
+                qualify_actuals(&mut subst, site_pkg);
+            }
+            let key = binding_key(&declared, &subst);
+
+            let type_key = (target_pkg.to_lowercase(), format!("{}{}", comp.identifier.to_lowercase(), key));
+            let type_name = match self.types.get(&type_key) {
+                Some(type_name) => type_name.clone(),
+                None => {
+                    let type_name = unique_type_name(&index, &target_pkg, &format!("{}_{}", comp.identifier, binding_label(&declared, &subst)));
+                    let mut clone = comp.clone();
+                    clone.identifier = type_name.clone();
+                    substitute_type(&mut clone, &subst);
+                    new_declarations.push((target_pkg.clone(), comp.identifier.clone(), AadlDeclaration::ComponentType(clone)));
+                    self.types.insert(type_key, type_name.clone());
+                    type_name
+                }
+            };
+            if let Some(impl_) = impl_ {
+                let impl_key = (target_pkg.to_lowercase(), format!("{}{}", impl_.name.to_string().to_lowercase(), key));
+                if self.impls.insert(impl_key) {
+                    let mut clone = impl_.clone();
+                    clone.name.type_identifier = type_name.clone();
+                    substitute_impl(&mut clone, &subst);
+                    new_declarations.push((target_pkg.clone(), comp.identifier.clone(), AadlDeclaration::ComponentImplementation(clone)));
+                }
+            }
+            type_name
+        };
+
+        self.count += 1;
+        for (pkg_name, original, declaration) in new_declarations {
+            insert_next_to(packages, &pkg_name, &original, declaration);
+        }
+        Some(type_name)
+    }
+
+    fn specialize_actual(
+        &mut self,
+        packages: &mut [Package],
+        site_pkg: &str,
+        actual: &mut ComponentPrototypeActual,
+        location: Option<&SourceLocation>,
+    ) {
+        let Some(ComponentPrototypeReference::Classifier(reference)) = &actual.reference else { return };
+        let Some(bindings) = actual.bindings.take() else { return };
+        if let Some(type_name) = self.specialize(packages, site_pkg, reference, bindings, location) {
+            actual.reference = Some(ComponentPrototypeReference::Classifier(renamed(reference.clone(), type_name)));
+        }
+    }
+}
+
+fn renamed(mut reference: UniqueComponentClassifierReference, type_name: String) -> UniqueComponentClassifierReference {
+    match &mut reference {
+        UniqueComponentClassifierReference::Type(r) | UniqueComponentClassifierReference::Implementation(r) => {
+            r.implementation_name.type_identifier = type_name;
+        }
+    }
+    reference
+}
+
+fn report_unknown_target(name: &str, location: Option<&SourceLocation>) {
+    diagnostics::error_in(
+        location,
+        codes::PROTOTYPE_MISMATCH,
+        format!("prototype bindings given for unknown classifier {}", name),
+    );
+}
+
+// 取出下一个实际值已确定的子组件绑定：(位置, 所在包, 分类器引用, 绑定, 所在实现的源码位置)
+#[allow(clippy::type_complexity)]
+fn next_concrete_binding(
+    packages: &mut [Package],
+) -> Option<(SubcomponentSlot, String, UniqueComponentClassifierReference, PrototypeBindings, Option<SourceLocation>)> {
+    for (pkg_index, pkg) in packages.iter_mut().enumerate() {
+        let pkg_name = pkg.name.to_string();
+        let sections = [(false, pkg.public_section.as_mut()), (true, pkg.private_section.as_mut())];
+        for (private, section) in sections {
+            let Some(section) = section else { continue };
+            for (decl_index, decl) in section.declarations.iter_mut().enumerate() {
+                let AadlDeclaration::ComponentImplementation(impl_) = decl else { continue };
+                let location = impl_.location.clone();
+                for (sub_index, sub) in subcomponents_mut(&mut impl_.subcomponents).enumerate() {
+                    let SubcomponentClassifier::ClassifierReference(reference) = &sub.classifier else { continue };
+                    if !sub.prototype_bindings.as_ref().is_some_and(is_concrete) {
+                        continue;
+                    }
+                    let reference = reference.clone();
+                    let bindings = sub.prototype_bindings.take()?;
+                    let slot = (pkg_index, private, decl_index, sub_index);
+                    return Some((slot, pkg_name, reference, bindings, location));
+                }
+            }
+        }
+    }
+    None
+}
+
+// 绑定中不再引用外层原型
+fn is_concrete(bindings: &PrototypeBindings) -> bool {
+    bindings.bindings.iter().all(|binding| match &binding.actual {
+        PrototypeActual::Component(actual) => is_concrete_component(actual),
+        PrototypeActual::ComponentList(actuals) => actuals.iter().all(is_concrete_component),
+        PrototypeActual::FeatureGroup(FeatureGroupPrototypeActual::Prototype(_)) => false,
+        PrototypeActual::FeatureGroup(FeatureGroupPrototypeActual::Classifier { bindings, .. }) => {
+            bindings.as_ref().is_none_or(is_concrete)
+        }
+        PrototypeActual::Feature(FeaturePrototypeActual::Prototype(_)) => false,
+        PrototypeActual::Feature(FeaturePrototypeActual::Port { port_type, .. }) => !matches!(
+            port_type,
+            PortType::Data { classifier: Some(PortDataTypeReference::Prototype(_)) }
+                | PortType::EventData { classifier: Some(PortDataTypeReference::Prototype(_)) }
+        ),
+        PrototypeActual::Feature(FeaturePrototypeActual::Access { .. }) => true,
+    })
+}
+
+fn is_concrete_component(actual: &ComponentPrototypeActual) -> bool {
+    !matches!(actual.reference, Some(ComponentPrototypeReference::Prototype(_)))
+        && actual.bindings.as_ref().is_none_or(is_concrete)
+}
+
+// 校验绑定并建立代入表：原型必须已声明，实际值的种类与类别必须与原型一致
+fn substitution(
+    classifier: &str,
+    declared: &[PrototypeDeclaration],
+    bindings: &PrototypeBindings,
+    location: Option<&SourceLocation>,
+) -> Substitution {
+    let mut subst = Substitution::new();
+    for binding in &bindings.bindings {
+        let Some(prototype) = declared.iter().find(|p| p.identifier.eq_ignore_ascii_case(&binding.identifier)) else {
+            diagnostics::error_in(
+                location,
+                codes::UNKNOWN_PROTOTYPE,
+                format!("{} has no prototype `{}` to bind", classifier, binding.identifier),
+            );
+            continue;
+        };
+        let actual = match (&prototype.prototype, &binding.actual) {
+            (Prototype::Component(_), PrototypeActual::ComponentList(actuals)) if actuals.len() == 1 => {
+                PrototypeActual::Component(actuals[0].clone())
+            }
+            (_, actual) => actual.clone(),
+        };
+        let mismatch = match (&prototype.prototype, &actual) {
+            (Prototype::Component(proto), PrototypeActual::Component(actual)) => {
+                if proto.category != ComponentCategory::Abstract && actual.category != proto.category {
+                    Some(format!("{:?} prototype `{}` of {} cannot be bound to a {:?} component", proto.category, prototype.identifier, classifier, actual.category))
+                } else {
+                    None
+                }
+            }
+            (Prototype::Component(_), PrototypeActual::ComponentList(_)) => {
+                Some(format!("prototype `{}` of {} is bound to a component list, which is only valid for subcomponent arrays", prototype.identifier, classifier))
+            }
+            (Prototype::Feature(_), PrototypeActual::Feature(_)) => None,
+            (Prototype::FeatureGroup(_), PrototypeActual::FeatureGroup(_)) => None,
+            (prototype_kind, actual_kind) => Some(format!(
+                "{} prototype `{}` of {} cannot be bound to a {} actual",
+                prototype_kind_name(prototype_kind),
+                prototype.identifier,
+                classifier,
+                actual_kind_name(actual_kind)
+            )),
+        };
+        if let Some(message) = mismatch {
+            diagnostics::error_in(location, codes::PROTOTYPE_MISMATCH, message);
+            continue;
+        }
+        // 只给出类别（`T => data`）的绑定不确定分类器，原型仍保持未绑定
+        if matches!(&actual, PrototypeActual::Component(a) if a.reference.is_none()) {
+            continue;
+        }
+        subst.insert(prototype.identifier.to_lowercase(), actual);
+    }
+    subst
+}
+
+fn prototype_kind_name(prototype: &Prototype) -> &'static str {
+    match prototype {
+        Prototype::Component(_) => "component",
+        Prototype::Feature(_) => "feature",
+        Prototype::FeatureGroup(_) => "feature group",
+    }
+}
+
+fn actual_kind_name(actual: &PrototypeActual) -> &'static str {
+    match actual {
+        PrototypeActual::Component(_) | PrototypeActual::ComponentList(_) => "component",
+        PrototypeActual::Feature(_) => "feature",
+        PrototypeActual::FeatureGroup(_) => "feature group",
+    }
+}
+
+fn substitute_type(comp: &mut ComponentType, subst: &Substitution) {
+    if subst.is_empty() {
+        return;
+    }
+    for feature in feature_items_mut(&mut comp.features) {
+        substitute_feature(feature, subst);
+    }
+    remove_bound(&mut comp.prototypes, subst);
+}
+
+fn substitute_impl(impl_: &mut ComponentImplementation, subst: &Substitution) {
+    if subst.is_empty() {
+        return;
+    }
+    for sub in subcomponents_mut(&mut impl_.subcomponents) {
+        if let SubcomponentClassifier::Prototype(name) = &sub.classifier {
+            if let Some(PrototypeActual::Component(actual)) = subst.get(&name.to_lowercase()) {
+                match &actual.reference {
+                    Some(ComponentPrototypeReference::Classifier(reference)) => {
+                        sub.classifier = SubcomponentClassifier::ClassifierReference(reference.clone());
+                    }
+                    Some(ComponentPrototypeReference::Prototype(outer)) => {
+                        sub.classifier = SubcomponentClassifier::Prototype(outer.clone());
+                    }
+                    None => {}
+                }
+                if actual.category != ComponentCategory::Abstract {
+                    sub.category = actual.category.clone();
+                }
+                if actual.bindings.is_some() {
+                    sub.prototype_bindings = actual.bindings.clone();
+                }
+            }
+        }
+        if let Some(bindings) = &mut sub.prototype_bindings {
+            substitute_bindings(bindings, subst);
+        }
+    }
+    remove_bound(&mut impl_.prototypes, subst);
+}
+
+fn substitute_feature(feature: &mut Feature, subst: &Substitution) {
+    let replacement = match feature {
+        Feature::Port(port) => {
+            if let PortType::Data { classifier } | PortType::EventData { classifier } = &mut port.port_type {
+                substitute_port_classifier(classifier, subst);
+            }
+            None
+        }
+        Feature::SubcomponentAccess(SubcomponentAccessSpec::Data(access)) => {
+            if let Some(DataAccessReference::Prototype(name)) = &access.classifier {
+                match component_reference(subst, name) {
+                    Some(ComponentPrototypeReference::Classifier(reference)) => {
+                        access.classifier = Some(DataAccessReference::Classifier(reference.clone()));
+                    }
+                    Some(ComponentPrototypeReference::Prototype(outer)) => {
+                        access.classifier = Some(DataAccessReference::Prototype(outer.clone()));
+                    }
+                    None => {}
+                }
+            }
+            None
+        }
+        Feature::SubcomponentAccess(SubcomponentAccessSpec::Subprogram(access)) => {
+            if let Some(SubprogramAccessReference::Prototype(name)) = &access.classifier {
+                match component_reference(subst, name) {
+                    Some(ComponentPrototypeReference::Classifier(reference)) => {
+                        access.classifier = Some(SubprogramAccessReference::Classifier(reference.clone()));
+                    }
+                    Some(ComponentPrototypeReference::Prototype(outer)) => {
+                        access.classifier = Some(SubprogramAccessReference::Prototype(outer.clone()));
+                    }
+                    None => {}
+                }
+            }
+            None
+        }
+        Feature::SubcomponentAccess(SubcomponentAccessSpec::Bus(_) | SubcomponentAccessSpec::VirtualBus(_)) => None,
+        // 抽象特征按特征原型的实际值变为端口或访问特征；特征自身声明的方向优先
+        Feature::Abstract(spec) => {
+            let actual = spec.prototype.as_ref().and_then(|name| subst.get(&name.to_lowercase()));
+            match actual {
+                Some(PrototypeActual::Feature(FeaturePrototypeActual::Port { direction, port_type, .. })) => {
+                    Some(Feature::Port(PortSpec {
+                        identifier: spec.identifier.clone(),
+                        direction: spec.direction.unwrap_or(*direction),
+                        port_type: port_type.clone(),
+                    }))
+                }
+                Some(PrototypeActual::Feature(FeaturePrototypeActual::Access { access_type, connection_type, classifier })) => {
+                    match connection_type {
+                        AccessConnectionType::Data => Some(Feature::SubcomponentAccess(SubcomponentAccessSpec::Data(DataAccessSpec {
+                            identifier: spec.identifier.clone(),
+                            direction: *access_type,
+                            classifier: classifier.clone().map(DataAccessReference::Classifier),
+                        }))),
+                        AccessConnectionType::Subprogram => Some(Feature::SubcomponentAccess(SubcomponentAccessSpec::Subprogram(
+                            SubprogramAccessSpec {
+                                identifier: spec.identifier.clone(),
+                                direction: *access_type,
+                                classifier: classifier.clone().map(SubprogramAccessReference::Classifier),
+                            },
+                        ))),
+                        AccessConnectionType::Bus | AccessConnectionType::VirtualBus => {
+                            let bus = BusAccessSpec {
+                                identifier: spec.identifier.clone(),
+                                direction: *access_type,
+                                classifier: classifier.clone(),
+                            };
+                            Some(Feature::SubcomponentAccess(if *connection_type == AccessConnectionType::Bus {
+                                SubcomponentAccessSpec::Bus(bus)
+                            } else {
+                                SubcomponentAccessSpec::VirtualBus(bus)
+                            }))
+                        }
+                        _ => None,
+                    }
+                }
+                Some(PrototypeActual::Feature(FeaturePrototypeActual::Prototype(outer))) => {
+                    spec.prototype = Some(outer.clone());
+                    None
+                }
+                _ => None,
+            }
+        }
+    };
+    if let Some(replacement) = replacement {
+        *feature = replacement;
+    }
+}
+
+fn component_reference<'a>(subst: &'a Substitution, name: &str) -> Option<&'a ComponentPrototypeReference> {
+    match subst.get(&name.to_lowercase()) {
+        Some(PrototypeActual::Component(actual)) => actual.reference.as_ref(),
+        _ => None,
+    }
+}
+
+fn substitute_port_classifier(classifier: &mut Option<PortDataTypeReference>, subst: &Substitution) {
+    let Some(PortDataTypeReference::Prototype(name)) = classifier else { return };
+    match component_reference(subst, name) {
+        Some(ComponentPrototypeReference::Classifier(reference)) => {
+            *classifier = Some(port_data_reference(reference));
+        }
+        Some(ComponentPrototypeReference::Prototype(outer)) => {
+            *classifier = Some(PortDataTypeReference::Prototype(outer.clone()));
+        }
+        None => {}
+    }
+}
+
+// 端口数据类型只记录类型名（与解析端口声明时一致）
+fn port_data_reference(reference: &UniqueComponentClassifierReference) -> PortDataTypeReference {
+    let reference = unique_reference(reference);
+    PortDataTypeReference::Classifier(UniqueComponentClassifierReference::Type(UniqueImplementationReference {
+        package_prefix: reference.package_prefix.clone(),
+        implementation_name: ImplementationName {
+            type_identifier: reference.implementation_name.type_identifier.clone(),
+            implementation_identifier: String::new(),
+        },
+    }))
+}
+
+// 子组件绑定中引用外层原型的实际值代入外层的实际值
+fn substitute_bindings(bindings: &mut PrototypeBindings, subst: &Substitution) {
+    for binding in &mut bindings.bindings {
+        match &mut binding.actual {
+            PrototypeActual::Component(actual) => substitute_component_actual(actual, subst),
+            PrototypeActual::ComponentList(actuals) => actuals.iter_mut().for_each(|a| substitute_component_actual(a, subst)),
+            PrototypeActual::Feature(FeaturePrototypeActual::Prototype(name)) => {
+                if let Some(PrototypeActual::Feature(outer)) = subst.get(&name.to_lowercase()) {
+                    binding.actual = PrototypeActual::Feature(outer.clone());
+                }
+            }
+            PrototypeActual::Feature(FeaturePrototypeActual::Port {
+                port_type: PortType::Data { classifier } | PortType::EventData { classifier },
+                ..
+            }) => substitute_port_classifier(classifier, subst),
+            PrototypeActual::FeatureGroup(FeatureGroupPrototypeActual::Prototype(name)) => {
+                if let Some(PrototypeActual::FeatureGroup(outer)) = subst.get(&name.to_lowercase()) {
+                    binding.actual = PrototypeActual::FeatureGroup(outer.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn substitute_component_actual(actual: &mut ComponentPrototypeActual, subst: &Substitution) {
+    if let Some(ComponentPrototypeReference::Prototype(name)) = &actual.reference {
+        if let Some(PrototypeActual::Component(outer)) = subst.get(&name.to_lowercase()) {
+            if outer.reference.is_some() {
+                actual.reference = outer.reference.clone();
+            }
+            if actual.bindings.is_none() {
+                actual.bindings = outer.bindings.clone();
+            }
+        }
+    }
+    if let Some(bindings) = &mut actual.bindings {
+        substitute_bindings(bindings, subst);
+    }
+}
+
+fn remove_bound(prototypes: &mut PrototypeClause, subst: &Substitution) {
+    if let PrototypeClause::Items(items) = prototypes {
+        items.retain(|p| !subst.contains_key(&p.identifier.to_lowercase()));
+        if items.is_empty() {
+            *prototypes = PrototypeClause::None;
+        }
+    }
+}
+
+// 跨包绑定：无前缀的实际值补上绑定处的包名，副本放到被特化分类器的包中后仍指向原处
+fn qualify_actuals(subst: &mut Substitution, site_pkg: &str) {
+    let package = PackageName(site_pkg.split("::").map(|s| s.to_string()).collect());
+    let qualify = |reference: &mut UniqueComponentClassifierReference| match reference {
+        UniqueComponentClassifierReference::Type(r) | UniqueComponentClassifierReference::Implementation(r) => {
+            if r.package_prefix.is_none() {
+                r.package_prefix = Some(package.clone());
+            }
+        }
+    };
+    for actual in subst.values_mut() {
+        match actual {
+            PrototypeActual::Component(ComponentPrototypeActual { reference: Some(ComponentPrototypeReference::Classifier(reference)), .. }) => {
+                qualify(reference)
+            }
+            PrototypeActual::Feature(FeaturePrototypeActual::Port {
+                port_type:
+                    PortType::Data { classifier: Some(PortDataTypeReference::Classifier(reference)) }
+                    | PortType::EventData { classifier: Some(PortDataTypeReference::Classifier(reference)) },
+                ..
+            }) => qualify(reference),
+            PrototypeActual::Feature(FeaturePrototypeActual::Access { classifier: Some(reference), .. }) => qualify(reference),
+            _ => {}
+        }
+    }
+}
+
+// 按原型声明顺序排列的绑定，作为特化副本的去重键
+fn binding_key(declared: &[PrototypeDeclaration], subst: &Substitution) -> String {
+    declared
+        .iter()
+        .filter_map(|p| subst.get(&p.identifier.to_lowercase()).map(|actual| format!("|{}={:?}", p.identifier.to_lowercase(), actual)))
+        .collect()
+}
+
+// 特化副本的名称后缀：各实际值的分类器名
+fn binding_label(declared: &[PrototypeDeclaration], subst: &Substitution) -> String {
+    declared
+        .iter()
+        .filter_map(|p| subst.get(&p.identifier.to_lowercase()))
+        .map(actual_label)
+        .collect::<Vec<_>>()
+        .join("_")
+        .replace(|c: char| !c.is_ascii_alphanumeric() && c != '_', "_")
+}
+
+fn actual_label(actual: &PrototypeActual) -> String {
+    let reference_label = |reference: &UniqueComponentClassifierReference| {
+        let name = &unique_reference(reference).implementation_name;
+        if name.implementation_identifier.is_empty() {
+            name.type_identifier.clone()
+        } else {
+            format!("{}_{}", name.type_identifier, name.implementation_identifier)
+        }
+    };
+    match actual {
+        PrototypeActual::Component(ComponentPrototypeActual { reference: Some(ComponentPrototypeReference::Classifier(reference)), .. }) => {
+            reference_label(reference)
+        }
+        PrototypeActual::Feature(FeaturePrototypeActual::Port { port_type, .. }) => match port_type {
+            PortType::Data { classifier: Some(PortDataTypeReference::Classifier(reference)) }
+            | PortType::EventData { classifier: Some(PortDataTypeReference::Classifier(reference)) } => reference_label(reference),
+            PortType::Event => "Event".to_string(),
+            _ => "Port".to_string(),
+        },
+        PrototypeActual::Feature(FeaturePrototypeActual::Access { classifier: Some(reference), .. }) => reference_label(reference),
+        PrototypeActual::FeatureGroup(FeatureGroupPrototypeActual::Classifier { reference, .. }) => reference.identifier.clone(),
+        _ => "Bound".to_string(),
+    }
+}
+
+fn unique_type_name(index: &ClassifierIndex, pkg: &str, base: &str) -> String {
+    let prefix = PackageName(pkg.split("::").map(|s| s.to_string()).collect());
+    let mut name = base.to_string();
+    let mut suffix = 2;
+    while index.find_type(pkg, Some(&prefix), &name).is_some() {
+        name = format!("{}_{}", base, suffix);
+        suffix += 1;
+    }
+    name
+}
+
+// 副本插入到原分类器所在的包与可见性部分
+fn insert_next_to(packages: &mut [Package], pkg_name: &str, original_type: &str, declaration: AadlDeclaration) {
+    let Some(pkg) = packages.iter_mut().find(|p| p.name.to_string().eq_ignore_ascii_case(pkg_name)) else { return };
+    let declares_original = |section: &PackageSection| {
+        section.declarations.iter().any(|d| matches!(d, AadlDeclaration::ComponentType(c) if c.identifier.eq_ignore_ascii_case(original_type)))
+    };
+    let section = if pkg.private_section.as_ref().is_some_and(declares_original) {
+        pkg.private_section.as_mut()
+    } else {
+        pkg.public_section.as_mut()
+    };
+    if let Some(section) = section {
+        section.declarations.push(declaration);
+    }
+}
+
+// 未绑定且给出约束分类器的组件原型取约束分类器
+fn apply_constraining_classifiers(packages: &mut [Package]) {
+    let type_prototypes = type_prototype_declarations(packages);
+    for (pkg, decl) in declarations_mut(packages) {
+        match decl {
+            AadlDeclaration::ComponentType(comp) => {
+                let subst = constraining_classifiers(&prototype_items(&comp.prototypes));
+                substitute_type(comp, &subst);
+            }
+            AadlDeclaration::ComponentImplementation(impl_) => {
+                let mut declared = prototype_items(&impl_.prototypes);
+                let type_key = (pkg, impl_.name.type_identifier.to_lowercase());
+                declared.extend(type_prototypes.get(&type_key).cloned().unwrap_or_default());
+                substitute_impl(impl_, &constraining_classifiers(&declared));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn constraining_classifiers(declared: &[PrototypeDeclaration]) -> Substitution {
+    declared
+        .iter()
+        .filter_map(|p| match &p.prototype {
+            Prototype::Component(ComponentPrototype { category, classifier: Some(classifier), .. }) => Some((
+                p.identifier.to_lowercase(),
+                PrototypeActual::Component(ComponentPrototypeActual {
+                    category: category.clone(),
+                    reference: Some(ComponentPrototypeReference::Classifier(classifier.clone())),
+                    bindings: None,
+                }),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+// 不含未绑定原型的实现中，子组件引用的分类器必须已绑定全部原型，否则无法实例化
+fn check_unbound(packages: &[Package]) {
+    let index = ClassifierIndex::new(packages);
+    for (pkg, decl) in declarations(packages) {
+        let AadlDeclaration::ComponentImplementation(impl_) = decl else { continue };
+        let own_unbound = !prototype_items(&impl_.prototypes).is_empty()
+            || index
+                .find_type(&pkg, None, &impl_.name.type_identifier)
+                .is_some_and(|(_, comp)| !prototype_items(&comp.prototypes).is_empty());
+        if own_unbound {
+            continue;
+        }
+        for sub in subcomponents(&impl_.subcomponents) {
+            let SubcomponentClassifier::ClassifierReference(reference) = &sub.classifier else { continue };
+            let unique = unique_reference(reference);
+            let name = &unique.implementation_name;
+            let prefix = unique.package_prefix.as_ref();
+            let mut unbound = index
+                .find_type(&pkg, prefix, &name.type_identifier)
+                .map(|(_, comp)| prototype_names(&comp.prototypes))
+                .unwrap_or_default();
+            if !name.implementation_identifier.is_empty() {
+                if let Some((_, target)) = index.find_impl(&pkg, prefix, name) {
+                    unbound.extend(prototype_names(&target.prototypes));
+                }
+            }
+            if !unbound.is_empty() {
+                diagnostics::error_in(
+                    impl_.location.as_ref(),
+                    codes::UNBOUND_PROTOTYPE,
+                    format!(
+                        "subcomponent {} of {} leaves prototype(s) {} of {} unbound",
+                        sub.identifier,
+                        impl_.name.to_string(),
+                        unbound.join(", "),
+                        name.to_string().trim_end_matches('.')
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn type_prototype_declarations(packages: &[Package]) -> HashMap<(String, String), Vec<PrototypeDeclaration>> {
+    declarations(packages)
+        .filter_map(|(pkg, decl)| match decl {
+            AadlDeclaration::ComponentType(comp) => Some(((pkg, comp.identifier.to_lowercase()), prototype_items(&comp.prototypes))),
+            _ => None,
+        })
+        .collect()
+}
+
+fn prototype_items(clause: &PrototypeClause) -> Vec<PrototypeDeclaration> {
+    match clause {
+        PrototypeClause::Items(items) => items.clone(),
+        _ => Vec::new(),
+    }
+}
+
+fn prototype_names(clause: &PrototypeClause) -> Vec<String> {
+    prototype_items(clause).into_iter().map(|p| p.identifier).collect()
+}
+
+fn unique_reference(reference: &UniqueComponentClassifierReference) -> &UniqueImplementationReference {
+    match reference {
+        UniqueComponentClassifierReference::Type(r) | UniqueComponentClassifierReference::Implementation(r) => r,
+    }
+}
+
+fn feature_items_mut(clause: &mut FeatureClause) -> impl Iterator<Item = &mut Feature> {
+    match clause {
+        FeatureClause::Items(items) => Some(items.iter_mut()),
+        _ => None,
+    }
+    .into_iter()
+    .flatten()
+}
+
+fn subcomponents(clause: &SubcomponentClause) -> impl Iterator<Item = &Subcomponent> {
+    match clause {
+        SubcomponentClause::Items(items) => Some(items.iter()),
+        _ => None,
+    }
+    .into_iter()
+    .flatten()
+}
+
+fn subcomponents_mut(clause: &mut SubcomponentClause) -> impl Iterator<Item = &mut Subcomponent> {
+    match clause {
+        SubcomponentClause::Items(items) => Some(items.iter_mut()),
+        _ => None,
+    }
+    .into_iter()
+    .flatten()
+}
+
+fn subcomponent_mut(packages: &mut [Package], (pkg, private, decl, sub): SubcomponentSlot) -> Option<&mut Subcomponent> {
+    let pkg = packages.get_mut(pkg)?;
+    let section = if private { pkg.private_section.as_mut()? } else { pkg.public_section.as_mut()? };
+    let AadlDeclaration::ComponentImplementation(impl_) = section.declarations.get_mut(decl)? else { return None };
+    subcomponents_mut(&mut impl_.subcomponents).nth(sub)
+}
+
+fn declarations(packages: &[Package]) -> impl Iterator<Item = (String, &AadlDeclaration)> {
+    packages.iter().flat_map(|pkg| {
+        let pkg_name = pkg.name.to_string();
+        pkg.public_section
+            .iter()
+            .chain(pkg.private_section.iter())
+            .flat_map(|section| section.declarations.iter())
+            .map(move |decl| (pkg_name.clone(), decl))
+    })
+}
+
+fn declarations_mut(packages: &mut [Package]) -> impl Iterator<Item = (String, &mut AadlDeclaration)> {
+    packages.iter_mut().flat_map(|pkg| {
+        let pkg_name = pkg.name.to_string();
+        pkg.public_section
+            .iter_mut()
+            .chain(pkg.private_section.iter_mut())
+            .flat_map(|section| section.declarations.iter_mut())
+            .map(move |decl| (pkg_name.clone(), decl))
+    })
+}
@@ -0,0 +1,55 @@
+// 源码映射：目录输入会把多个 .aadl 文件拼接成一个缓冲区再解析，
+// 这里记录每个文件在拼接缓冲区中的起止偏移，把解析位置换算回原文件的 (文件, 行, 列)
+use crate::ast::aadl_ast_cj::SourceLocation;
+
+#[derive(Debug, Clone)]
+struct SourceFile {
+    path: String,
+    start: usize,
+    text: String,
+    // 文件内每一行起始处相对于文件开头的偏移
+    line_starts: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    // 输入路径（文件或目录），无法定位到具体文件时用于提示
+    pub input: String,
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new(input: &str) -> Self {
+        Self { input: input.to_string(), files: Vec::new() }
+    }
+
+    // 单文件输入：缓冲区就是文件内容本身
+    pub fn single(path: &str, content: &str) -> Self {
+        let mut map = Self::new(path);
+        map.add_file(path, 0, content);
+        map
+    }
+
+    // 登记一个文件：content 在拼接缓冲区中从 start 开始
+    pub fn add_file(&mut self, path: &str, start: usize, content: &str) {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        self.files.push(SourceFile {
+            path: path.to_string(),
+            start,
+            text: content.to_string(),
+            line_starts,
+        });
+    }
+
+    // 拼接缓冲区中的字节偏移 -> 原文件位置（行、列从 1 开始，列按字符计）；落在文件之间的分隔内容时返回 None
+    pub fn locate(&self, offset: usize) -> Option<SourceLocation> {
+        let file = self.files.iter().find(|f| f.start <= offset && offset <= f.start + f.text.len())?;
+        let relative = offset - file.start;
+        let line = file.line_starts.partition_point(|&s| s <= relative);
+        let line_start = file.line_starts[line - 1];
+        let col = file.text.get(line_start..relative).map_or(relative - line_start, |s| s.chars().count()) + 1;
+        Some(SourceLocation { file: file.path.clone(), line, col })
+    }
+}
@@ -4,6 +4,7 @@ use crate::aadl_ast2rust_code::merge_utils::merge_item_defs;
 use crate::aadlight_parser::{AADLParser, Rule};
 use crate::ast::aadl_ast_cj::Package;
 use crate::transform::AADLTransformer;
+use crate::source_map::SourceMap;
 
 use diffy::{create_patch, PatchFormatter};
 use pest::Parser;
@@ -168,7 +169,8 @@ fn run_single_test_case(
     let pairs = AADLParser::parse(Rule::file, &aadl_input)
         .map_err(|e| format!("解析失败 [{}]: {}", full_path, e))?;
 
-    let ast: Vec<Package> = AADLTransformer::transform_file(pairs.clone().collect());
+    let sources = SourceMap::single(full_path, &aadl_input);
+    let ast: Vec<Package> = AADLTransformer::transform_file(pairs.clone().collect(), &sources);
 
     for pkg in ast.iter() {
         generate_rust_code_for_test_case(pkg, test_case, ast.len(), converter)?;
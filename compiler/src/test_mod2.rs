@@ -1,4 +1,5 @@
 use std::env;
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -10,13 +11,22 @@ pub fn all_case_folders() -> Vec<&'static str> {
         "ardupilot/",
         "arinc653_annex/",
         "arrays/",
+        "bidirectional_ports/",
         "bit_codec/",
+        "bounded_data/",
         "building_control_gen_mixed/",
+        "bus_connections/",
         "car/",
+        "connection_refinement/",
+        "connection_sets/",
         "cpp/",
         "data/",
+        "deep_properties/",
+        "distributed_deployment/",
+        "enum_codes/",
         "fcs/",
         "file-store/",
+        "fixed_point/",
         "flight-mgmt/",
         "isr/",
         "latency/",
@@ -24,10 +34,13 @@ pub fn all_case_folders() -> Vec<&'static str> {
         "lua/",
         "minepump/",
         "minepump_ba/",
+        "mixed_case/",
         "mixin/",
         "monitor/",
         "mosart/",
         "nested_feature_groups/",
+        "nested_platform/",
+        "package_loading/",
         "packet-store/",
         "pathfinder_system/",
         "periodicDispatch/",
@@ -40,7 +53,11 @@ pub fn all_case_folders() -> Vec<&'static str> {
         "producer_filter_consumer_mixed/",
         "producer_filter_consumer_periodic/",
         "producer_filter_consumer_sporadic/",
+        "property_expressions/",
+        "property_precedence/",
+        "prototypes/",
         "radar/",
+        "ranged_types/",
         "ravenscar/",
         "rma/",
         "rms/",
@@ -49,6 +66,8 @@ pub fn all_case_folders() -> Vec<&'static str> {
         "round_robin/",
         "rpc/",
         "satellite/",
+        "sched_protocols/",
+        "sensor_fusion/",
         "stm32discovery_ada/",
         "sunseeker/",
         "test_data_port_periodic_domains/",
@@ -62,10 +81,91 @@ pub fn all_case_folders() -> Vec<&'static str> {
         "testsubprogram/",
         "time_triggered/",
         "toy/",
+        "wire_codec/",
         "wms/",
     ]
 }
 
+/// 需要以非默认代码生成后端再跑一遍的案例：(folder, backend, 是否应生成成功)
+/// 对应 `cargo run -- --input <folder> --backend <backend>`
+pub fn backend_case_folders() -> Vec<(&'static str, &'static str, bool)> {
+    vec![
+        ("time_triggered/", "cyclic", false), // 非抢占EDF下不可调度：B2 的 WCET(250ms) 超过 B0 的周期
+        ("sensor_fusion/", "cyclic", true),
+        ("property_precedence/", "cyclic", true),
+        ("property_expressions/", "async", true),
+        ("bus_connections/", "async", true),
+        ("distributed_deployment/", "async", true),
+        ("car/", "async", true),
+        ("stm32discovery_ada/", "embedded", true),
+        ("ranged_types/", "embedded", true),
+        ("fixed_point/", "embedded", true),
+        ("bounded_data/", "async", true),
+        ("enum_codes/", "cyclic", true),
+        ("package_loading/", "cyclic", true),
+        ("periodicDispatch/", "cyclic", false), // E0306：偶发线程 B 没有 Period
+        ("nested_platform/", "embedded", false), // 嵌入式后端不支持嵌套子系统
+        ("sensor_fusion/", "embedded", false),   // 嵌入式后端不支持行为附件
+    ]
+}
+
+/// 默认后端生成的代码中必须出现的片段：(folder, 生成项目内的文件, 片段)
+/// 在默认后端的案例跑完后、其他后端覆盖生成项目之前检查
+pub fn generated_code_assertions() -> Vec<(&'static str, &'static str, &'static str)> {
+    vec![
+        ("nested_platform/", "src/nested_platform.rs", "let c1 = crossbeam_channel::unbounded();"), // 嵌套子系统中进程内部的连接
+        ("deep_properties/", "src/deep_properties.rs", "period: 500,"), // in modes 限定的 Period 不覆盖缺省取值
+        ("property_expressions/", "src/property_expressions.rs", "pub fn sensor_checksum() -> i64"), // 跨行的属性定义
+        ("bidirectional_ports/", "src/bidirectional_ports.rs", "station_b.answerer.call = Some(conn0.0.subscribe());"), // 沿 <-> 连接追踪
+        ("round_robin/", "src/posix.rs", "set_rr_timeslice(thread, ms);"), // 轮转处理器核对 SCHED_RR 时间片
+        ("round_robin/", "src/round_robin.rs", "map.insert(0, 5);"), // Scheduler_Quantum => 5 ms
+        ("ranged_types/", "src/ranged_types.rs", "pub const UNIT: &'static str = \"km/h\";"), // Measurement_Unit 常量对外公开
+        ("periodicDispatch/", "src/periodic.rs", "system_epoch() + Duration::from_millis(self.first_dispatch_time)"), // First_Dispatch_Time 按实例取值
+        ("periodicDispatch/", "src/periodic.rs", "sw.a.first_dispatch_time = 200;"),
+        ("periodicDispatch/", "src/periodic.rs", "rand::thread_rng().gen_range(0, 6)"), // Dispatch_Jitter => 5 ms
+    ]
+}
+
+/// 非默认后端生成的代码中必须出现的片段：(folder, backend, 生成项目内的文件, 片段)
+/// 在该案例以对应后端生成成功后检查
+pub fn backend_code_assertions() -> Vec<(&'static str, &'static str, &'static str, &'static str)> {
+    vec![
+        ("package_loading/", "cyclic", "src/package_loading.rs", "// 任务 0: sensor (T=200ms"), // 带包前缀的线程实现在所属包中解析
+    ]
+}
+
+/// 以 `check` 动作运行的案例：(folder, 是否应通过合法性检查)
+/// 对应 `cargo run -- --input <folder> check`
+pub fn check_case_folders() -> Vec<(&'static str, bool)> {
+    vec![
+        ("car/", true),
+        ("round_robin/", true),
+        ("mixed_case/", true),
+        ("sched_protocols/", true),
+        ("toy/", true),
+        ("sensor_fusion/", true),
+        ("deep_properties/", true),
+        ("property_precedence/", true),
+        ("property_expressions/", true),
+        ("prototypes/", true),
+        ("bus_connections/", true),
+        ("connection_refinement/", true),
+        ("connection_sets/", true),
+        ("bidirectional_ports/", true),
+        ("distributed_deployment/", true),
+        ("ranged_types/", true),
+        ("fixed_point/", true),
+        ("bounded_data/", true),
+        ("enum_codes/", true),
+        ("wire_codec/", true),
+        ("package_loading/", true),
+        ("missing_package/", false), // E0109：分类器引用的包不存在
+        ("cyclic_packages/", false), // E0110：包之间循环 with
+        ("radar/", false),
+        ("ravenscar/", false),
+    ]
+}
+
 /// 测试顶层入口：逐个执行 `cargo run -- --input <folder>`
 /// - 每个案例失败会记录，但不会立刻中断（与原来一样）
 /// - 最后如果存在失败案例，则整体测试失败（panic）
@@ -94,7 +194,7 @@ pub fn run_all_case_folders() {
             );
         }
 
-        let ok = run_single_case(&cargo_bin, &manifest_dir, &folder);
+        let ok = run_single_case(&cargo_bin, &manifest_dir, &folder, &[]);
 
         if ok {
             println!("case '{}' OK", folder);
@@ -103,6 +203,52 @@ pub fn run_all_case_folders() {
         }
     }
 
+    for (raw, file, needle) in generated_code_assertions() {
+        let folder = normalize_folder(raw);
+        let path = manifest_dir.join("generate").join("project").join(&folder).join(file);
+        let found = fs::read_to_string(&path).map(|code| code.contains(needle)).unwrap_or(false);
+
+        if found {
+            println!("case '{}' ({}) OK", folder, file);
+        } else {
+            eprintln!("case '{}': '{}' not found in {}", folder, needle, path.display());
+            failures.push(format!("{} ({})", folder, file));
+        }
+    }
+
+    for (raw, backend, generates) in backend_case_folders() {
+        let folder = normalize_folder(raw);
+        let ok = run_single_case(&cargo_bin, &manifest_dir, &folder, &["--backend", backend]);
+
+        if ok == generates {
+            println!("case '{}' ({}) OK", folder, backend);
+        } else {
+            failures.push(format!("{} ({})", folder, backend));
+        }
+
+        let assertions = backend_code_assertions().into_iter().filter(|(f, b, _, _)| normalize_folder(f) == folder && *b == backend);
+        for (_, _, file, needle) in assertions {
+            let path = manifest_dir.join("generate").join("project").join(&folder).join(file);
+            let found = fs::read_to_string(&path).map(|code| code.contains(needle)).unwrap_or(false);
+
+            if !found {
+                eprintln!("case '{}' ({}): '{}' not found in {}", folder, backend, needle, path.display());
+                failures.push(format!("{} ({}, {})", folder, backend, file));
+            }
+        }
+    }
+
+    for (raw, legal) in check_case_folders() {
+        let folder = normalize_folder(raw);
+        let ok = run_single_case(&cargo_bin, &manifest_dir, &folder, &["check"]);
+
+        if ok == legal {
+            println!("case '{}' (check) OK", folder);
+        } else {
+            failures.push(format!("{} (check)", folder));
+        }
+    }
+
     if !failures.is_empty() {
         panic!(
             "Some cases failed ({}): {}",
@@ -112,9 +258,9 @@ pub fn run_all_case_folders() {
     }
 }
 
-/// 执行单个案例：`cargo run -- --input <folder>`
+/// 执行单个案例：`cargo run -- --input <folder> [extra_args...]`
 /// 返回 true 表示成功（exit code = 0）。
-fn run_single_case(cargo_bin: &str, workdir: &Path, folder: &str) -> bool {
+fn run_single_case(cargo_bin: &str, workdir: &Path, folder: &str, extra_args: &[&str]) -> bool {
     // 为了更稳：避免测试里重复增量编译导致输出太冗长，可加 --quiet（按需）
     // 如果想保留所有输出便于定位失败，把 --quiet 去掉即可。
     let mut cmd = Command::new(cargo_bin);
@@ -123,7 +269,8 @@ fn run_single_case(cargo_bin: &str, workdir: &Path, folder: &str) -> bool {
         .arg("--quiet")
         .arg("--")
         .arg("--input")
-        .arg(folder);
+        .arg(folder)
+        .args(extra_args);
 
     let output = match cmd.output() {
         Ok(o) => o,
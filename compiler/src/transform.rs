@@ -7,6 +7,8 @@ use crate::aadlight_parser;
 use super::ast::aadl_ast_cj::*;
 use pest::{iterators::Pair};
 use crate::transform_annex::*;
+use crate::diagnostics::{self, codes};
+use crate::source_map::SourceMap;
 
 // 引入 annex 转换模块
 // transform_annex 现在在 main.rs 中声明
@@ -70,6 +72,74 @@ pub fn extract_identifier(pair: Pair<aadlight_parser::Rule>) -> String {
     pair.as_str().trim().to_string()
 }
 
+// 辅助函数：特征/子组件/连接声明是否为 `refined to` 精化
+fn is_refinement(pair: &Pair<aadlight_parser::Rule>) -> bool {
+    pair.clone().into_inner().any(|p| p.as_rule() == aadlight_parser::Rule::refined_to)
+}
+
+// 辅助函数：Pkg::Name -> (包前缀, 名称)
+fn split_package_prefix(reference: &str) -> (Option<PackageName>, String) {
+    match reference.rsplit_once("::") {
+        Some((package, name)) => (Some(PackageName(package.split("::").map(|s| s.trim().to_string()).collect())), name.trim().to_string()),
+        None => (None, reference.trim().to_string()),
+    }
+}
+
+// 辅助函数：Pkg::Type[.Impl] -> 分类器引用（与子组件一致，统一使用 Implementation，实现名可为空）
+fn classifier_reference(reference: &str) -> UniqueComponentClassifierReference {
+    let (package_prefix, name) = split_package_prefix(reference);
+    let mut name_parts = name.split('.');
+    UniqueComponentClassifierReference::Implementation(UniqueImplementationReference {
+        package_prefix,
+        implementation_name: ImplementationName {
+            type_identifier: name_parts.next().unwrap_or_default().to_string(),
+            implementation_identifier: name_parts.next().unwrap_or_default().to_string(),
+        },
+    })
+}
+
+// 辅助函数：访问类别（data/subprogram/bus/virtual bus）-> 访问连接类型
+fn access_connection_type(access_type: &str) -> AccessConnectionType {
+    match access_type {
+        "subprogram" => AccessConnectionType::Subprogram,
+        "bus" => AccessConnectionType::Bus,
+        "virtual bus" => AccessConnectionType::VirtualBus,
+        _ => AccessConnectionType::Data,
+    }
+}
+
+fn transform_direction(pair: Pair<aadlight_parser::Rule>) -> Option<PortDirection> {
+    match pair.as_str() {
+        "in" => Some(PortDirection::In),
+        "out" => Some(PortDirection::Out),
+        "in out" => Some(PortDirection::InOut),
+        _ => None,
+    }
+}
+
+// 辅助函数：端口数据类型引用，只保留类型名
+fn port_data_reference(qname: &str) -> PortDataTypeReference {
+    // 解析包前缀和类型名
+    let parts: Vec<&str> = qname.split("::").collect();
+    let (package_prefix, type_id) = if parts.len() > 1 {
+        let package_name = parts[0..parts.len()-1].join("::");
+        let type_name = parts.last().unwrap().split(".").next().unwrap().to_string();
+        (Some(package_name), type_name)
+    } else {
+        (None, qname.to_string())
+    };
+
+    PortDataTypeReference::Classifier(
+        UniqueComponentClassifierReference::Type(UniqueImplementationReference {
+            package_prefix: package_prefix.map(|p| PackageName(p.split("::").map(|s| s.to_string()).collect())),
+            implementation_name: ImplementationName {
+                type_identifier: type_id,
+                implementation_identifier: String::new(),
+            },
+        }),
+    )
+}
+
 // 辅助函数：从 Pair 中提取包名
 pub fn extract_package_name(pair: Pair<aadlight_parser::Rule>) -> PackageName {
     PackageName(
@@ -83,6 +153,8 @@ pub fn extract_package_name(pair: Pair<aadlight_parser::Rule>) -> PackageName {
 // 主转换结构体
 pub struct AADLTransformer {
     _port_manager: PortManager,
+    // 把解析位置换算回原始 .aadl 文件，记录到组件类型/实现上
+    sources: SourceMap,
 }
 
 #[warn(unused_mut)]
@@ -96,11 +168,13 @@ impl AADLTransformer {
     pub fn new() -> Self {
         Self {
             _port_manager: PortManager::new(),
+            sources: SourceMap::default(),
         }
     }
     
-    pub fn transform_file(pairs: Vec<Pair<aadlight_parser::Rule>>) -> Vec<Package> {
+    pub fn transform_file(pairs: Vec<Pair<aadlight_parser::Rule>>, sources: &SourceMap) -> Vec<Package> {
         let mut transformer = Self::new();
+        transformer.sources = sources.clone();
         let mut packages = Vec::new();
         
         // for pair in pairs {
@@ -129,6 +203,10 @@ impl AADLTransformer {
         }
 
 
+        // extends 与祖先合并、应用 refined to，之后各阶段只面对有效分类器
+        crate::inheritance::resolve_extensions(&mut packages);
+        // 原型绑定代入，子组件改为引用特化后的分类器
+        crate::prototype::resolve_prototypes(&mut packages);
         packages
     }
     
@@ -258,7 +336,7 @@ impl AADLTransformer {
                 }
                 _ => {
                     // 如果不是修饰符，则是说明其是一个声明
-                    declarations.push(self.transform_declaration(first));
+                    declarations.extend(self.transform_declaration(first));
                 }
             }
         }
@@ -267,7 +345,7 @@ impl AADLTransformer {
         for inner in inner_iter {
             match inner.as_rule() {
                 aadlight_parser::Rule::declaration => {
-                    declarations.push(self.transform_declaration(inner));
+                    declarations.extend(self.transform_declaration(inner));
                 }
                 _ => {} // 忽略其他规则
             }
@@ -279,41 +357,76 @@ impl AADLTransformer {
         }
     }
     
-    pub fn transform_declaration(&mut self, pair: Pair<aadlight_parser::Rule>) -> AadlDeclaration {
-        let inner = pair.into_inner().next().unwrap();
+    // 不支持的声明报告诊断后跳过
+    pub fn transform_declaration(&mut self, pair: Pair<aadlight_parser::Rule>) -> Option<AadlDeclaration> {
+        let inner = pair.into_inner().next()?;
+        let location = self.sources.locate(diagnostics::Span::of(&inner).offset);
         match inner.as_rule() {
             aadlight_parser::Rule::component_type => {
-                AadlDeclaration::ComponentType(self.transform_component_type(inner))
+                let mut decl = self.transform_component_type(inner);
+                match &mut decl {
+                    AadlDeclaration::ComponentType(comp) => comp.location = location,
+                    AadlDeclaration::ComponentTypeExtension(ext) => ext.location = location,
+                    _ => {}
+                }
+                Some(decl)
             }
             aadlight_parser::Rule::component_implementation => {
-                AadlDeclaration::ComponentImplementation(Self::transform_component_implementation(inner))
+                let mut decl = Self::transform_component_implementation(inner);
+                match &mut decl {
+                    AadlDeclaration::ComponentImplementation(impl_) => impl_.location = location,
+                    AadlDeclaration::ComponentImplementationExtension(ext) => ext.location = location,
+                    _ => {}
+                }
+                Some(decl)
             }
             aadlight_parser::Rule::annex_library => {
-                AadlDeclaration::AnnexLibrary(AnnexLibrary {})
+                Some(AadlDeclaration::AnnexLibrary(AnnexLibrary {}))
+            }
+            rule => {
+                diagnostics::error_at(&inner, codes::UNSUPPORTED_DECLARATION, format!("unsupported declaration {:?}", rule));
+                None
             }
-            _ => panic!("Unsupported declaration type: {:?}", inner.as_rule()),
         }
     }
-    
-    pub fn transform_component_type(&mut self, pair: Pair<aadlight_parser::Rule>) -> ComponentType {
-        let mut inner_iter = pair.into_inner();
-        
-        let category = match inner_iter.next().unwrap().as_str() {
-            "system" => ComponentCategory::System,
-            "process" => ComponentCategory::Process,
-            "thread" => ComponentCategory::Thread,
+
+    // component_category 规则 -> 组件类别；语法之外的类别报告诊断并按 abstract 处理
+    pub fn transform_component_category(pair: Pair<aadlight_parser::Rule>) -> ComponentCategory {
+        let text = pair.as_str().split_whitespace().collect::<Vec<_>>().join(" ");
+        match text.as_str() {
+            "abstract" => ComponentCategory::Abstract,
             "data" => ComponentCategory::Data,
             "subprogram" => ComponentCategory::Subprogram,
-            "processor" => ComponentCategory::Processor,
+            "subprogram group" => ComponentCategory::SubprogramGroup,
+            "thread" => ComponentCategory::Thread,
+            "thread group" => ComponentCategory::ThreadGroup,
+            "process" => ComponentCategory::Process,
             "memory" => ComponentCategory::Memory,
-            "device" => ComponentCategory::Device,
+            "processor" => ComponentCategory::Processor,
             "bus" => ComponentCategory::Bus,
-            s => panic!("Unknown component category: {}", s),
-        };
+            "device" => ComponentCategory::Device,
+            "virtual processor" => ComponentCategory::VirtualProcessor,
+            "virtual bus" => ComponentCategory::VirtualBus,
+            "system" => ComponentCategory::System,
+            s => {
+                diagnostics::error_at(&pair, codes::UNKNOWN_CATEGORY, format!("unknown component category `{}`", s));
+                ComponentCategory::Abstract
+            }
+        }
+    }
+    
+    // 带 extends 的组件类型转换为 ComponentTypeExtension，由 inheritance 在转换结束后与祖先合并
+    pub fn transform_component_type(&mut self, pair: Pair<aadlight_parser::Rule>) -> AadlDeclaration {
+        let mut inner_iter = pair.into_inner();
+        
+        let category = Self::transform_component_category(inner_iter.next().unwrap());
         
         let identifier = extract_identifier(inner_iter.next().unwrap());
+        let mut extends = None;
+        let mut prototype_bindings = None;
         let mut prototypes = PrototypeClause::None;
         let mut features = FeatureClause::None;
+        let mut feature_refinements = Vec::new();
         let mut properties = PropertyClause::ExplicitNone;
         let mut annexes = Vec::new();
         
@@ -323,7 +436,11 @@ impl AADLTransformer {
                     prototypes = Self::transform_prototypes_clause(inner);
                 }
                 aadlight_parser::Rule::features => {
-                    features = self.transform_features_clause(inner);
+                    let clause = inner.clone();
+                    (features, feature_refinements) = self.transform_features_clause(inner);
+                    if extends.is_none() {
+                        Self::reject_refinements(&clause, &identifier, feature_refinements.drain(..).map(|f| crate::instance_model::feature_identifier(&f).to_string()));
+                    }
                 }
                 aadlight_parser::Rule::properties => {
                     properties = Self::transform_properties_clause(inner);
@@ -334,20 +451,57 @@ impl AADLTransformer {
                     }
                 }
                 aadlight_parser::Rule::extends => {
-                    //TODO: 处理extends
-                    //println!("extends: {:?}", inner.as_str());
+                    let (package_prefix, name, bindings) = Self::transform_extends(inner);
+                    extends = Some(UniqueComponentReference { package_prefix, identifier: name });
+                    prototype_bindings = bindings;
                 }
                 _ => {}
             }
         }
         
-        ComponentType {
-            category,
-            identifier,
-            prototypes,
-            features,
-            properties,
-            annexes,
+        match extends {
+            Some(extends) => AadlDeclaration::ComponentTypeExtension(ComponentTypeExtension {
+                category,
+                identifier,
+                extends,
+                prototype_bindings,
+                prototypes,
+                features,
+                feature_refinements,
+                properties,
+                annexes,
+                location: None, // 由 transform_declaration 填写
+            }),
+            None => AadlDeclaration::ComponentType(ComponentType {
+                category,
+                identifier,
+                prototype_bindings: None,
+                prototypes,
+                features,
+                properties,
+                annexes,
+                location: None, // 由 transform_declaration 填写
+            }),
+        }
+    }
+
+    // extends 子句 -> (包前缀, 被扩展的分类器名, 原型绑定)
+    fn transform_extends(pair: Pair<aadlight_parser::Rule>) -> (Option<PackageName>, String, Option<PrototypeBindings>) {
+        let mut inner_iter = pair.into_inner();
+        let reference = inner_iter.next().map(|p| p.as_str().trim().to_string()).unwrap_or_default();
+        let bindings = inner_iter.next().map(Self::transform_prototype_bindings);
+        let (package_prefix, name) = split_package_prefix(&reference);
+        (package_prefix, name, bindings)
+    }
+
+    // refined to 只能出现在扩展中；其余分类器中的精化报告诊断后丢弃
+    fn reject_refinements(clause: &Pair<aadlight_parser::Rule>, classifier: &str, names: impl Iterator<Item = String>) {
+        for name in names {
+            diagnostics::error_at(
+                clause,
+                codes::INVALID_REFINEMENT,
+                format!("`{} : refined to` in {} is only allowed in a classifier that extends another one", name, classifier),
+            );
         }
     }
     
@@ -359,7 +513,7 @@ impl AADLTransformer {
         let mut prototypes = Vec::new();
         for inner in pair.into_inner() {
             if inner.as_rule() == aadlight_parser::Rule::prototype_declaration {
-                prototypes.push(Self::transform_prototype_declaration(inner));
+                prototypes.extend(Self::transform_prototype_declaration(inner));
             }
         }
         
@@ -370,55 +524,163 @@ impl AADLTransformer {
         }
     }
     
-    pub fn transform_prototype_declaration(pair: Pair<aadlight_parser::Rule>) -> Prototype {
-        let mut inner_iter = pair.into_inner();
-        let _identifier = extract_identifier(inner_iter.next().unwrap());
-        let _colon = inner_iter.next();
-        let prototype_type = inner_iter.next().unwrap();
-        
-        match prototype_type.as_str() {
-            "component" => {
-                let category = match inner_iter.next().unwrap().as_str() {
-                    "system" => ComponentCategory::System,
-                    "process" => ComponentCategory::Process,
-                    "thread" => ComponentCategory::Thread,
-                    "data" => ComponentCategory::Data,
-                    "subprogram" => ComponentCategory::Subprogram,
-                    "processor" => ComponentCategory::Processor,
-                    "memory" => ComponentCategory::Memory,
-                    s => panic!("Unknown component prototype category: {}", s),
-                };
-                
-                Prototype::Component(ComponentPrototype {
-                    category,
-                    classifier: None, // TODO: Handle classifier
-                    is_array: false,  // TODO: Handle array spec
-                })
+    pub fn transform_prototype_declaration(pair: Pair<aadlight_parser::Rule>) -> Option<PrototypeDeclaration> {
+        let mut inner_iter = pair.clone().into_inner();
+        let identifier = extract_identifier(inner_iter.next().unwrap());
+        let prototype_pair = inner_iter.next()?;
+        let rule = prototype_pair.as_rule();
+        let mut category = None;
+        let mut direction = None;
+        let mut classifier = None;
+        let mut is_array = false;
+        for inner in prototype_pair.into_inner() {
+            match inner.as_rule() {
+                aadlight_parser::Rule::component_category => category = Some(Self::transform_component_category(inner)),
+                aadlight_parser::Rule::direction => direction = transform_direction(inner),
+                aadlight_parser::Rule::qualified_identifier => classifier = Some(inner.as_str().trim().to_string()),
+                aadlight_parser::Rule::prototype_array => is_array = true,
+                _ => {}
             }
-            "feature" => {
-                Prototype::Feature(FeaturePrototype {
-                    direction: None, // TODO: Handle direction
-                    classifier: None, // TODO: Handle classifier
-                })
+        }
+
+        let prototype = match rule {
+            aadlight_parser::Rule::component_prototype => Prototype::Component(ComponentPrototype {
+                category: category.unwrap_or(ComponentCategory::Abstract),
+                classifier: classifier.as_deref().map(classifier_reference),
+                is_array,
+            }),
+            aadlight_parser::Rule::feature_prototype => Prototype::Feature(FeaturePrototype {
+                direction,
+                classifier: classifier.as_deref().map(classifier_reference),
+            }),
+            aadlight_parser::Rule::feature_group_prototype => Prototype::FeatureGroup(FeatureGroupPrototype {
+                classifier: classifier.as_deref().map(|reference| {
+                    let (package_prefix, identifier) = split_package_prefix(reference);
+                    UniqueFeatureGroupTypeReference { package_prefix, identifier }
+                }),
+            }),
+            other => {
+                diagnostics::error_at(&pair, codes::UNKNOWN_PROTOTYPE, format!("unknown prototype kind `{:?}` for {}", other, identifier));
+                return None;
             }
-            "feature group" => {
-                Prototype::FeatureGroup(FeatureGroupPrototype {
-                    classifier: None, // TODO: Handle classifier
+        };
+        Some(PrototypeDeclaration {
+            identifier,
+            prototype,
+            properties: Vec::new(),
+        })
+    }
+
+    // ( 原型名 => 实际值, ... )
+    pub fn transform_prototype_bindings(pair: Pair<aadlight_parser::Rule>) -> PrototypeBindings {
+        let bindings = pair
+            .into_inner()
+            .filter(|p| p.as_rule() == aadlight_parser::Rule::prototype_binding)
+            .filter_map(|binding| {
+                let mut inner_iter = binding.into_inner();
+                let identifier = extract_identifier(inner_iter.next()?);
+                let actual = inner_iter.next()?.into_inner().next()?;
+                Some(PrototypeBinding {
+                    identifier,
+                    actual: Self::transform_prototype_actual(actual),
                 })
+            })
+            .collect();
+        PrototypeBindings { bindings }
+    }
+
+    fn transform_prototype_actual(pair: Pair<aadlight_parser::Rule>) -> PrototypeActual {
+        match pair.as_rule() {
+            aadlight_parser::Rule::component_actual_list => PrototypeActual::ComponentList(
+                pair.into_inner().map(Self::transform_component_actual).collect(),
+            ),
+            aadlight_parser::Rule::feature_group_actual => {
+                let mut inner_iter = pair.into_inner();
+                let (package_prefix, identifier) = split_package_prefix(inner_iter.next().map(|p| p.as_str()).unwrap_or_default());
+                PrototypeActual::FeatureGroup(FeatureGroupPrototypeActual::Classifier {
+                    reference: UniqueFeatureGroupTypeReference { package_prefix, identifier },
+                    bindings: inner_iter.next().map(Self::transform_prototype_bindings),
+                })
+            }
+            aadlight_parser::Rule::feature_actual => PrototypeActual::Feature(Self::transform_feature_actual(pair)),
+            _ => PrototypeActual::Component(Self::transform_component_actual(pair)),
+        }
+    }
+
+    // 引用原型的实际值（`data T`）在这里仍是分类器引用，由 prototype 按所在分类器声明的原型名改写
+    fn transform_component_actual(pair: Pair<aadlight_parser::Rule>) -> ComponentPrototypeActual {
+        let mut category = ComponentCategory::Abstract;
+        let mut reference = None;
+        let mut bindings = None;
+        for inner in pair.into_inner() {
+            match inner.as_rule() {
+                aadlight_parser::Rule::component_category => category = Self::transform_component_category(inner),
+                aadlight_parser::Rule::qualified_identifier => {
+                    reference = Some(ComponentPrototypeReference::Classifier(classifier_reference(inner.as_str())));
+                }
+                aadlight_parser::Rule::prototype_bindings => bindings = Some(Self::transform_prototype_bindings(inner)),
+                _ => {}
+            }
+        }
+        ComponentPrototypeActual { category, reference, bindings }
+    }
+
+    fn transform_feature_actual(pair: Pair<aadlight_parser::Rule>) -> FeaturePrototypeActual {
+        let mut direction = None;
+        let mut port_type = None;
+        let mut access_direction = None;
+        let mut access_type = None;
+        let mut classifier = None;
+        for inner in pair.into_inner() {
+            match inner.as_rule() {
+                aadlight_parser::Rule::direction => direction = transform_direction(inner),
+                aadlight_parser::Rule::port_type => port_type = Some(inner.as_str().to_string()),
+                aadlight_parser::Rule::access_direction => {
+                    access_direction = Some(if inner.as_str() == "provides" { AccessDirection::Provides } else { AccessDirection::Requires });
+                }
+                aadlight_parser::Rule::access_type => access_type = Some(inner.as_str().to_string()),
+                aadlight_parser::Rule::qualified_identifier => classifier = Some(inner.as_str().trim().to_string()),
+                _ => {}
             }
-            _ => panic!("Unknown prototype type"),
         }
+
+        if let Some(port_type) = port_type {
+            let data_classifier = classifier.as_deref().map(port_data_reference);
+            return FeaturePrototypeActual::Port {
+                direction: direction.unwrap_or(PortDirection::InOut),
+                port_type: match port_type.as_str() {
+                    "event port" => PortType::Event,
+                    "event data port" => PortType::EventData { classifier: data_classifier },
+                    _ => PortType::Data { classifier: data_classifier },
+                },
+                classifier: None,
+            };
+        }
+        if let Some(access_type) = access_type {
+            return FeaturePrototypeActual::Access {
+                access_type: access_direction.unwrap_or(AccessDirection::Provides),
+                connection_type: access_connection_type(&access_type),
+                classifier: classifier.as_deref().map(classifier_reference),
+            };
+        }
+        // `feature F`：引用外层分类器的特征原型
+        FeaturePrototypeActual::Prototype(classifier.unwrap_or_default())
     }
     
-    pub fn transform_features_clause(&mut self, pair: Pair<aadlight_parser::Rule>) -> FeatureClause {
+    // 返回 (新声明的特征, refined to 精化的特征)
+    pub fn transform_features_clause(&mut self, pair: Pair<aadlight_parser::Rule>) -> (FeatureClause, Vec<Feature>) {
         if pair.as_str().contains("none") {
-            return FeatureClause::Empty;
+            return (FeatureClause::Empty, Vec::new());
         }
         
         let mut features = Vec::new();
+        let mut refinements = Vec::new();
         for inner in pair.into_inner() {
             if inner.as_rule() == aadlight_parser::Rule::feature_declaration {
-                let feature = Self::transform_feature_declaration(inner);
+                let refined = is_refinement(&inner);
+                let Some(feature) = Self::transform_feature_declaration(inner) else {
+                    continue;
+                };
                 
                 // 收集端口信息
                 if let Feature::Port(port_spec) = &feature {
@@ -427,36 +689,48 @@ impl AADLTransformer {
                     }
                 }
                 
-                features.push(feature);
+                if refined {
+                    refinements.push(feature);
+                } else {
+                    features.push(feature);
+                }
             }
         }
         
-        if features.is_empty() {
+        let features = if features.is_empty() {
             FeatureClause::None
         } else {
             FeatureClause::Items(features)
-        }
+        };
+        (features, refinements)
     }
     
-    pub fn transform_feature_declaration(pair: Pair<aadlight_parser::Rule>) -> Feature {
-        let mut inner_iter = pair.into_inner();
+    pub fn transform_feature_declaration(pair: Pair<aadlight_parser::Rule>) -> Option<Feature> {
+        let mut inner_iter = pair.clone().into_inner();
 
         let identifier = extract_identifier(inner_iter.next().unwrap()); // p
         let mut direction: Option<PortDirection> = None;
         let mut port_type_str: Option<&str> = None;
         let mut access_direction: Option<AccessDirection> = None;
-        let mut access_type_str: Option<&str> = None; // "data" | "subprogram"
+        let mut access_type_str: Option<&str> = None; // "data" | "subprogram" | "bus" | "virtual bus"
         let mut classifier_qname: Option<String> = None; // qualified_identifier or identifier
 
         for inner in inner_iter {
             match inner.as_rule() {
                 aadlight_parser::Rule::direction => {
-                    direction = match inner.as_str() {
-                        "in" => Some(PortDirection::In),
-                        "out" => Some(PortDirection::Out),
-                        "in out" => Some(PortDirection::InOut),
-                        _ => None,
-                    };
+                    direction = transform_direction(inner);
+                }
+                aadlight_parser::Rule::abstract_feature => {
+                    let mut direction = None;
+                    let mut prototype = None;
+                    for part in inner.into_inner() {
+                        match part.as_rule() {
+                            aadlight_parser::Rule::direction => direction = transform_direction(part),
+                            aadlight_parser::Rule::qualified_identifier => prototype = Some(part.as_str().trim().to_string()),
+                            _ => {}
+                        }
+                    }
+                    return Some(Feature::Abstract(AbstractFeatureSpec { identifier, direction, prototype }));
                 }
                 aadlight_parser::Rule::port_type => {
                     port_type_str = Some(inner.as_str());
@@ -474,11 +748,9 @@ impl AADLTransformer {
                 aadlight_parser::Rule::qualified_identifier => {
                     classifier_qname = Some(inner.as_str().to_string());
                 }
-                aadlight_parser::Rule::identifier => {
+                aadlight_parser::Rule::identifier if classifier_qname.is_none() => {
                     // 兼容老语法中使用 identifier 作为类型名
-                    if classifier_qname.is_none() {
-                        classifier_qname = Some(inner.as_str().to_string());
-                    }
+                    classifier_qname = Some(inner.as_str().to_string());
                 }
                 _ => {}
             }
@@ -486,46 +758,29 @@ impl AADLTransformer {
 
         // 如果是端口类特征
         if let Some(pt) = port_type_str {
-            let classifier = classifier_qname.clone().map(|qname| {
-                // 解析包前缀和类型名
-                let parts: Vec<&str> = qname.split("::").collect();
-                let (package_prefix, type_id) = if parts.len() > 1 {
-                    let package_name = parts[0..parts.len()-1].join("::");
-                    let type_name = parts.last().unwrap().split(".").next().unwrap().to_string();
-                    (Some(package_name), type_name)
-                } else {
-                    (None, qname.to_string())
-                };
-                
-                PortDataTypeReference::Classifier(
-                    UniqueComponentClassifierReference::Type(UniqueImplementationReference {
-                        package_prefix: package_prefix.map(|p| PackageName(p.split("::").map(|s| s.to_string()).collect())),
-                        implementation_name: ImplementationName {
-                            type_identifier: type_id,
-                            implementation_identifier: String::new(),
-                        },
-                    }),
-                )
-            });
+            let classifier = classifier_qname.as_deref().map(port_data_reference);
 
             let resolved_port_type = match pt {
                 "data port" | "parameter" => PortType::Data { classifier: classifier.clone() },
                 "event data port" => PortType::EventData { classifier: classifier.clone() },
                 "event port" => PortType::Event,
-                other => panic!("Unknown port type: {}", other),
+                other => {
+                    diagnostics::error_at(&pair, codes::UNKNOWN_PORT_TYPE, format!("unknown port type `{}` for feature {}", other, identifier));
+                    return None;
+                }
             };
 
-            return Feature::Port(PortSpec {
+            return Some(Feature::Port(PortSpec {
                 identifier,
                 direction: direction.unwrap_or(match resolved_port_type {
                     PortType::Data { .. } | PortType::EventData { .. } => PortDirection::InOut,
                     PortType::Event => PortDirection::In,
                 }),
                 port_type: resolved_port_type,
-            });
+            }));
         }
 
-        // 访问特征：data access / subprogram access
+        // 访问特征：data / subprogram / bus / virtual bus access
         if let Some(at) = access_type_str {
             let direction = access_direction.unwrap_or(AccessDirection::Provides);
 
@@ -560,28 +815,44 @@ impl AADLTransformer {
                 "data" => {
                     let classifier = map_classifier_to_component_classifier()
                         .map(DataAccessReference::Classifier);
-                    return Feature::SubcomponentAccess(SubcomponentAccessSpec::Data(DataAccessSpec {
+                    return Some(Feature::SubcomponentAccess(SubcomponentAccessSpec::Data(DataAccessSpec {
                         identifier,
                         direction,
                         classifier,
-                    }));
+                    })));
                 }
                 "subprogram" => {
                     let classifier = map_classifier_to_component_classifier()
                         .map(SubprogramAccessReference::Classifier);
-                    return Feature::SubcomponentAccess(SubcomponentAccessSpec::Subprogram(
+                    return Some(Feature::SubcomponentAccess(SubcomponentAccessSpec::Subprogram(
                         SubprogramAccessSpec {
                             identifier,
                             direction,
                             classifier,
                         },
-                    ));
+                    )));
+                }
+                "bus" | "virtual bus" => {
+                    let spec = BusAccessSpec {
+                        identifier,
+                        direction,
+                        classifier: classifier_qname.as_deref().map(classifier_reference),
+                    };
+                    return Some(Feature::SubcomponentAccess(if at == "bus" {
+                        SubcomponentAccessSpec::Bus(spec)
+                    } else {
+                        SubcomponentAccessSpec::VirtualBus(spec)
+                    }));
+                }
+                other => {
+                    diagnostics::error_at(&pair, codes::UNKNOWN_ACCESS_TYPE, format!("unsupported `{} access` feature {}", other, identifier));
+                    return None;
                 }
-                other => panic!("Unknown access type: {}", other),
             }
         }
 
-        panic!("Unsupported feature_declaration: missing port or access spec")
+        diagnostics::error_at(&pair, codes::MALFORMED_FEATURE, format!("feature {} declares neither a port nor an access", identifier));
+        None
     }
     pub fn transform_properties_clause(pair: Pair<aadlight_parser::Rule>) -> PropertyClause {
         if pair.as_str().contains("none") {
@@ -591,7 +862,7 @@ impl AADLTransformer {
         let mut properties = Vec::new();
         for inner in pair.into_inner() {
             if inner.as_rule() == aadlight_parser::Rule::property_association {
-                properties.push(Self::transform_property_association(inner));
+                properties.extend(Self::transform_property_association(inner));
             }
         }
         
@@ -602,14 +873,14 @@ impl AADLTransformer {
         }
     }
     
-    pub fn transform_property_association(pair: Pair<aadlight_parser::Rule>) -> Property {
+    pub fn transform_property_association(pair: Pair<aadlight_parser::Rule>) -> Option<Property> {
         // println!("=== 调试 property ===");
         // println!("pair = Rule::{:?}, text = {}", pair.as_rule(), pair.as_str());
         // for (i, inner) in pair.clone().into_inner().enumerate() {
         //     println!("  inner[{}]: Rule::{:?}, text = {}", i, inner.as_rule(), inner.as_str());
         // }
 
-        let mut inner_iter = pair.into_inner().peekable();
+        let mut inner_iter = pair.clone().into_inner().peekable();
 
         // 检查是否有属性集前缀 (property_set::property_name)
         let (property_set, identifier) = if inner_iter.peek().map(|p| p.as_rule()) == Some(aadlight_parser::Rule::identifier) {
@@ -624,14 +895,21 @@ impl AADLTransformer {
                 (None, first_identifier)
             }
         } else {
-            panic!("Expected property identifier");
+            diagnostics::error_at(&pair, codes::MALFORMED_PROPERTY, "property association without a property name");
+            return None;
         };
 
-        let operator_pair = inner_iter.next().expect("Expected property operator");
-        let operator = match operator_pair.as_str() {
-            "=>" => PropertyOperator::Assign,
-            "+=>" => PropertyOperator::Append,
-            _ => panic!("Unknown property operator"),
+        let operator = match inner_iter.next().as_ref().map(|p| p.as_str()) {
+            Some("=>") => PropertyOperator::Assign,
+            Some("+=>") => PropertyOperator::Append,
+            other => {
+                diagnostics::error_at(
+                    &pair,
+                    codes::MALFORMED_PROPERTY,
+                    format!("property {} has unknown operator `{}`", identifier, other.unwrap_or("")),
+                );
+                return None;
+            }
         };
         // === 处理 constant 标记 ===
         let mut is_constant = false;
@@ -640,9 +918,30 @@ impl AADLTransformer {
             inner_iter.next(); // 消耗 constant
         }
         // 处理 property_value
-        let value: PropertyValue = Self::transform_property_value(inner_iter.next().unwrap());
-        
-        Property::BasicProperty(BasicPropertyAssociation {
+        let value: PropertyValue = Self::transform_property_value(inner_iter.next()?)?;
+
+        // === 处理 applies to / in binding / in modes 子句 ===
+        let mut applies_to = Vec::new();
+        let mut in_binding = Vec::new();
+        let mut in_modes = Vec::new();
+        for clause in inner_iter {
+            match clause.as_rule() {
+                aadlight_parser::Rule::applies_to_clause => {
+                    // 路径中的空白去掉，使 sensors [3] . f 与实例名 sensors[3] 一致
+                    applies_to.extend(clause.into_inner().map(|path| path.as_str().split_whitespace().collect::<String>()));
+                }
+                aadlight_parser::Rule::in_binding_clause => {
+                    let list = clause.into_inner().next()?;
+                    in_binding.extend(list.into_inner().map(extract_identifier));
+                }
+                aadlight_parser::Rule::in_modes_clause => {
+                    in_modes.extend(clause.into_inner().map(extract_identifier));
+                }
+                _ => {}
+            }
+        }
+
+        let association = BasicPropertyAssociation {
             identifier: PropertyIdentifier {
                 property_set,
                 name: identifier,
@@ -650,7 +949,15 @@ impl AADLTransformer {
             operator,
             is_constant, // TODO: Handle constant
             value,
-        })
+            applies_to,
+            in_binding,
+            in_modes,
+        };
+        if association.applies_to.is_empty() && association.in_binding.is_empty() && association.in_modes.is_empty() {
+            Some(Property::BasicProperty(association))
+        } else {
+            Some(Property::ContainedProperty(association))
+        }
     }
     
     //辅助函数
@@ -664,24 +971,16 @@ impl AADLTransformer {
         }
     }
 
-    pub fn transform_property_value(pair: Pair<aadlight_parser::Rule>) -> PropertyValue {
+    // 无法识别的属性值报告诊断后返回 None，所在的属性关联随之被丢弃
+    pub fn transform_property_value(pair: Pair<aadlight_parser::Rule>) -> Option<PropertyValue> {
         // println!("=== 调试 property_value ===");
         // println!("pair = Rule::{:?}, text = {}", pair.as_rule(), pair.as_str());
         // for (i, inner) in pair.clone().into_inner().enumerate() {
         //     println!("  inner[{}]: Rule::{:?}, text = {}", i, inner.as_rule(), inner.as_str());
         // }
 
-        let inner = pair.into_inner().next().unwrap();
-        match inner.as_rule() {
-            aadlight_parser::Rule::apply_value => {
-                let mut parts = inner.into_inner();
-                let number = parts.next().unwrap().as_str().trim().to_string();
-                let applies_to = parts.next().unwrap().as_str().trim().to_string();
-                PropertyValue::Single(PropertyExpression::Apply(ApplyTerm {
-                    number,
-                    applies_to,
-                }))
-            }
+        let inner = pair.into_inner().next()?;
+        let value = match inner.as_rule() {
             aadlight_parser::Rule::range_value => {
                 // println!("=== 调试 range_value ===");
                 // println!("inner = Rule::{:?}, text = {}", inner.as_rule(), inner.as_str());
@@ -689,41 +988,21 @@ impl AADLTransformer {
                 //     println!("  inner[{}]: Rule::{:?}, text = {}", i, inner2.as_rule(), inner2.as_str());
                 // }
 
-                let mut parts = inner.into_inner();
-                let lower_val = extract_identifier(parts.next().unwrap());
-                //let lower_unit = Some(parts.next().unwrap().as_str().trim().to_string());
-                // 解析下限单位（变为可选，例如优先级它没有单位）
-                let lower_unit = if parts.peek().is_some_and(|p| p.as_rule() == aadlight_parser::Rule::unit) {
-                    Some(parts.next().unwrap().as_str().trim().to_string())
-                } else {
-                    None
-                };
-                
-                let upper_val = extract_identifier(parts.next().unwrap());
-                //let upper_unit = Some(parts.next().unwrap().as_str().trim().to_string());
-                // 解析上限单位（可选）
-                let upper_unit = if parts.peek().is_some_and(|p| p.as_rule() == aadlight_parser::Rule::unit) {
-                    Some(parts.next().unwrap().as_str().trim().to_string())
-                } else {
-                    None
+                let mut parts = inner.into_inner().peekable();
+                // 界值（数值或属性常量，去掉符号后的空白）及其可选单位
+                let bound = |parts: &mut std::iter::Peekable<pest::iterators::Pairs<aadlight_parser::Rule>>| {
+                    let value = parts.next()?.as_str().split_whitespace().collect::<String>();
+                    let unit = parts
+                        .next_if(|p| p.as_rule() == aadlight_parser::Rule::unit)
+                        .map(|p| p.as_str().trim().to_string());
+                    Some(StringWithUnit { value, unit })
                 };
+                let lower = bound(&mut parts)?;
+                let upper = bound(&mut parts)?;
+                let delta = bound(&mut parts);
 
-
-                // PropertyValue::List(vec![
-                //     PropertyListElement::Value(PropertyExpression::String(StringTerm::Literal(lower))),
-                //     PropertyListElement::Value(PropertyExpression::String(StringTerm::Literal(upper))),
-                // ])
                 PropertyValue::List(vec![PropertyListElement::Value(
-                    PropertyExpression::IntegerRange(IntegerRangeTerm {
-                        lower: StringWithUnit {
-                            value: lower_val,
-                            unit: lower_unit,
-                        },
-                        upper: StringWithUnit {
-                            value: upper_val,
-                            unit: upper_unit,
-                        },
-                    }),
+                    PropertyExpression::IntegerRange(IntegerRangeTerm { lower, upper, delta }),
                 )])
             }
             aadlight_parser::Rule::literal_value => {
@@ -736,9 +1015,10 @@ impl AADLTransformer {
                 // }
 
 
+                let inner_span = inner.clone();
                 let mut parts = inner.into_inner().peekable();
 
-                let first = parts.next().unwrap();
+                let first = parts.next()?;
                 let unit = match parts.peek() {
                     Some(p) if p.as_rule() == aadlight_parser::Rule::unit => {
                         Some(extract_identifier(parts.next().unwrap()))
@@ -771,14 +1051,20 @@ impl AADLTransformer {
 
                         // 判断是否为浮点数
                         let expr = if int_part.contains('.') {
-                            let value = int_part.parse::<f64>().unwrap();
+                            let Ok(value) = int_part.parse::<f64>() else {
+                                diagnostics::error_at(&inner_span, codes::MALFORMED_PROPERTY, format!("invalid real literal `{}`", int_part));
+                                return None;
+                            };
                             PropertyExpression::Real(SignedRealOrConstant::Real(SignedReal {
                                 sign,
                                 value,
                                 unit: unit.clone(),
                             }))
                         } else {
-                            let value = int_part.parse::<i64>().unwrap();
+                            let Ok(value) = int_part.parse::<i64>() else {
+                                diagnostics::error_at(&inner_span, codes::MALFORMED_PROPERTY, format!("invalid integer literal `{}`", int_part));
+                                return None;
+                            };
                             PropertyExpression::Integer(SignedIntergerOrConstant::Real(SignedInteger {
                                 sign,
                                 value,
@@ -802,7 +1088,10 @@ impl AADLTransformer {
                         let val = match first.as_str() {
                             "true" => true,
                             "false" => false,
-                            _ => panic!("Invalid boolean"),
+                            other => {
+                                diagnostics::error_at(&first, codes::MALFORMED_PROPERTY, format!("invalid boolean `{}`", other));
+                                return None;
+                            }
                         };
 
                         PropertyValue::Single(PropertyExpression::Boolean(BooleanTerm::Literal(val)))
@@ -816,13 +1105,18 @@ impl AADLTransformer {
                         ))
                     }
 
-                    _ => panic!("Unknown literal_value inner rule: {:?}", first.as_rule()),
+                    rule => {
+                        diagnostics::error_at(&first, codes::MALFORMED_PROPERTY, format!("unsupported literal {:?}", rule));
+                        return None;
+                    }
                 }
             }
             aadlight_parser::Rule::list_value => {
                 let mut elements = Vec::new();
                 for item in inner.into_inner() {
-                    let property_value = Self::transform_property_value(item);
+                    let Some(property_value) = Self::transform_property_value(item) else {
+                        continue;
+                    };
                     match property_value {
                         PropertyValue::Single(expr) => {
                             elements.push(PropertyListElement::Value(expr));
@@ -834,22 +1128,51 @@ impl AADLTransformer {
                 }
                 PropertyValue::List(elements)
             }
-            aadlight_parser::Rule::reference_value => {
-                let mut ref_parts = inner.into_inner();
-                let referenced_id = extract_identifier(ref_parts.next().unwrap());
-                
-                // 检查是否有 applies to 子句
-                let mut applies_to = None;
-                for part in ref_parts {
-                    if part.as_rule() == aadlight_parser::Rule::qualified_identifier {
-                        applies_to = Some(extract_identifier(part));
-                        break;
+            aadlight_parser::Rule::record_value => {
+                let mut fields = Vec::new();
+                for field in inner.into_inner() {
+                    let mut field_parts = field.into_inner();
+                    let name = extract_identifier(field_parts.next()?);
+                    // 无法识别的字段值已报告诊断，只丢弃该字段
+                    if let Some(value) = Self::transform_property_value(field_parts.next()?) {
+                        fields.push(RecordField { name, value });
                     }
                 }
-                
-                PropertyValue::Single(PropertyExpression::Reference(ReferenceTerm { 
+                PropertyValue::Single(PropertyExpression::Record(RecordTerm { fields }))
+            }
+            aadlight_parser::Rule::computed_value => {
+                let function = extract_identifier(inner.into_inner().next()?);
+                PropertyValue::Single(PropertyExpression::Computed(ComputedTerm { function }))
+            }
+            aadlight_parser::Rule::constant_value => {
+                let mut parts = inner.into_inner().peekable();
+                let sign = parts
+                    .next_if(|p| p.as_rule() == aadlight_parser::Rule::sign)
+                    .map(|p| if p.as_str() == "-" { Sign::Minus } else { Sign::Plus });
+                let name = extract_identifier(parts.next()?);
+                match name.rsplit_once("::") {
+                    // 带属性集前缀：属性常量，取值在代码生成时查属性集
+                    Some((property_set, constant)) => PropertyValue::Single(PropertyExpression::Integer(
+                        SignedIntergerOrConstant::Constant {
+                            sign,
+                            constant: PropertyConstantTerm {
+                                property_set: Some(property_set.to_string()),
+                                name: constant.to_string(),
+                            },
+                        },
+                    )),
+                    // 不带前缀：枚举字面量
+                    None if sign.is_none() => PropertyValue::Single(PropertyExpression::String(StringTerm::Literal(name))),
+                    None => PropertyValue::Single(PropertyExpression::Integer(SignedIntergerOrConstant::Constant {
+                        sign,
+                        constant: PropertyConstantTerm { property_set: None, name },
+                    })),
+                }
+            }
+            aadlight_parser::Rule::reference_value => {
+                let referenced_id = extract_identifier(inner.into_inner().next().unwrap());
+                PropertyValue::Single(PropertyExpression::Reference(ReferenceTerm {
                     identifier: referenced_id,
-                    applies_to,
                 }))
             }
             aadlight_parser::Rule::component_classifier_value => {
@@ -891,11 +1214,12 @@ impl AADLTransformer {
                     unique_component_classifier_reference: unique_ref,
                 }))
             }
-            _ => {
-                println!("Unknown property value type: {:?}", inner.as_rule());
-                panic!("Unknown property value type");
+            rule => {
+                diagnostics::error_at(&inner, codes::MALFORMED_PROPERTY, format!("unsupported property value {:?}", rule));
+                return None;
             }
-        }
+        };
+        Some(value)
     }
 
     // pub fn transform_annexes_clause(pair: Pair<aadlight_parser::Rule>) -> Vec<AnnexSubclause> {
@@ -903,7 +1227,123 @@ impl AADLTransformer {
     //     //transform_annexes(pair)
     // }
     
-    pub fn transform_component_implementation(pair: Pair<aadlight_parser::Rule>) -> ComponentImplementation {
+    // 带 extends 的组件实现转换为 ComponentImplementationExtension
+    // 属性集声明：类型声明、常量与属性定义
+    pub fn transform_property_set(pair: Pair<aadlight_parser::Rule>) -> PropertySet {
+        let mut inner = pair.into_inner();
+        let name = inner.next().map(|p| p.as_str().trim().to_string()).unwrap_or_default();
+        let mut property_set = PropertySet { name, types: Vec::new(), definitions: Vec::new(), constants: Vec::new() };
+        for decl in inner {
+            match decl.as_rule() {
+                aadlight_parser::Rule::property_type_declaration => {
+                    let mut parts = decl.into_inner();
+                    let (Some(name), Some(ty)) = (parts.next(), parts.next()) else {
+                        continue;
+                    };
+                    property_set.types.push(PropertyTypeDeclaration {
+                        name: name.as_str().trim().to_string(),
+                        property_type: Self::transform_property_type(ty),
+                    });
+                }
+                aadlight_parser::Rule::property_constant_declaration => {
+                    let mut parts = decl.into_inner();
+                    let Some(name) = parts.next() else {
+                        continue;
+                    };
+                    let mut property_type = PropertyType::Integer;
+                    let mut value = None;
+                    for part in parts {
+                        match part.as_rule() {
+                            aadlight_parser::Rule::property_type => property_type = Self::transform_property_type(part),
+                            aadlight_parser::Rule::property_value => value = Self::transform_property_value(part),
+                            _ => {}
+                        }
+                    }
+                    property_set.constants.push(PropertyConstant {
+                        name: name.as_str().trim().to_string(),
+                        property_type,
+                        value,
+                    });
+                }
+                aadlight_parser::Rule::property_definition_declaration => {
+                    let mut parts = decl.into_inner();
+                    let Some(name) = parts.next() else {
+                        continue;
+                    };
+                    let mut definition = PropertyDefinition {
+                        name: name.as_str().trim().to_string(),
+                        inherit: false,
+                        property_type: PropertyType::String,
+                        default: None,
+                        applies_to: Vec::new(),
+                    };
+                    for part in parts {
+                        match part.as_rule() {
+                            aadlight_parser::Rule::inherit => definition.inherit = true,
+                            aadlight_parser::Rule::property_type => definition.property_type = Self::transform_property_type(part),
+                            aadlight_parser::Rule::value_of => {
+                                definition.default = part.into_inner().next().map(|p| PropertyDefault::ValueOf(p.as_str().trim().to_string()));
+                            }
+                            aadlight_parser::Rule::property_value => {
+                                definition.default = Self::transform_property_value(part).map(PropertyDefault::Value);
+                            }
+                            aadlight_parser::Rule::property_owner => definition.applies_to.push(part.as_str().trim().to_string()),
+                            _ => {}
+                        }
+                    }
+                    property_set.definitions.push(definition);
+                }
+                _ => {}
+            }
+        }
+        property_set
+    }
+
+    // 属性类型；取值区间与单位只用于检查取值，这里不保留
+    pub fn transform_property_type(pair: Pair<aadlight_parser::Rule>) -> PropertyType {
+        let Some(inner) = pair.into_inner().next() else {
+            return PropertyType::String;
+        };
+        let names = |pair: Pair<aadlight_parser::Rule>| pair.into_inner().map(|p| p.as_str().trim().to_string()).collect();
+        match inner.as_rule() {
+            aadlight_parser::Rule::list_type => {
+                PropertyType::List(Box::new(inner.into_inner().next().map_or(PropertyType::String, Self::transform_property_type)))
+            }
+            aadlight_parser::Rule::range_type => {
+                PropertyType::Range(Box::new(inner.into_inner().next().map_or(PropertyType::String, Self::transform_property_type)))
+            }
+            aadlight_parser::Rule::enumeration_type => PropertyType::Enumeration(names(inner)),
+            aadlight_parser::Rule::units_type => PropertyType::Units(
+                inner
+                    .into_inner()
+                    .filter(|p| p.as_rule() == aadlight_parser::Rule::units_list)
+                    .flat_map(|list| list.into_inner())
+                    .filter_map(|item| item.into_inner().next().map(|p| p.as_str().trim().to_string()))
+                    .collect(),
+            ),
+            aadlight_parser::Rule::record_type => PropertyType::Record(
+                inner
+                    .into_inner()
+                    .filter_map(|field| {
+                        let mut parts = field.into_inner();
+                        let name = parts.next()?.as_str().trim().to_string();
+                        Some((name, Self::transform_property_type(parts.next()?)))
+                    })
+                    .collect(),
+            ),
+            aadlight_parser::Rule::number_type => match inner.into_inner().next().map(|p| p.as_rule()) {
+                Some(aadlight_parser::Rule::aadlreal) => PropertyType::Real,
+                _ => PropertyType::Integer,
+            },
+            aadlight_parser::Rule::classifier_type => PropertyType::Classifier,
+            aadlight_parser::Rule::reference_type => PropertyType::Reference,
+            aadlight_parser::Rule::boolean_type => PropertyType::Boolean,
+            aadlight_parser::Rule::string_type => PropertyType::String,
+            _ => PropertyType::Named(inner.as_str().trim().to_string()),
+        }
+    }
+
+    pub fn transform_component_implementation(pair: Pair<aadlight_parser::Rule>) -> AadlDeclaration {
         // println!("=== 调试 implementation ===");
         // println!("pair = Rule::{:?}------text = {}", pair.as_rule(),pair.as_str());
         // for (i, inner) in pair.clone().into_inner().enumerate() {
@@ -913,15 +1353,7 @@ impl AADLTransformer {
         
         let mut inner_iter = pair.into_inner();
         
-        let category = match inner_iter.next().unwrap().as_str() {
-            "system" => ComponentCategory::System,
-            "process" => ComponentCategory::Process,
-            "thread" => ComponentCategory::Thread,
-            "processor" => ComponentCategory::Processor,
-            "memory" => ComponentCategory::Memory,
-            "data" => ComponentCategory::Data,
-            s => panic!("Unknown component implementation category: {}", s),
-        };
+        let category = Self::transform_component_category(inner_iter.next().unwrap());
         
         // Skip "implementation" keyword
         //let _ = inner_iter.next();
@@ -933,26 +1365,50 @@ impl AADLTransformer {
             implementation_identifier: name_parts.next().unwrap_or("").to_string(),
         };
         
+        let mut extends = None;
+        let mut prototype_bindings = None;
         let mut prototypes = PrototypeClause::None;
         let mut subcomponents = SubcomponentClause::None;
+        let mut subcomponent_refinements = Vec::new();
         let mut calls = CallSequenceClause::None;
         let mut connections = ConnectionClause::None;
+        let mut connection_refinements = Vec::new();
         let mut properties = PropertyClause::ExplicitNone;
         let mut annexes = Vec::new();
         
         for inner in inner_iter {
             match inner.as_rule() {
+                aadlight_parser::Rule::extends => {
+                    let (package_prefix, name, bindings) = Self::transform_extends(inner);
+                    prototype_bindings = bindings;
+                    let mut name_parts = name.split('.');
+                    extends = Some(UniqueImplementationReference {
+                        package_prefix,
+                        implementation_name: ImplementationName {
+                            type_identifier: name_parts.next().unwrap_or_default().to_string(),
+                            implementation_identifier: name_parts.next().unwrap_or_default().to_string(),
+                        },
+                    });
+                }
                 aadlight_parser::Rule::prototypes => {
                     prototypes = Self::transform_prototypes_clause(inner);
                 }
                 aadlight_parser::Rule::subcomponents => {
-                    subcomponents = Self::transform_subcomponents_clause(inner);
+                    let clause = inner.clone();
+                    (subcomponents, subcomponent_refinements) = Self::transform_subcomponents_clause(inner);
+                    if extends.is_none() {
+                        Self::reject_refinements(&clause, &name.to_string(), subcomponent_refinements.drain(..).map(|r| r.identifier));
+                    }
                 }
                 aadlight_parser::Rule::calls => {
                     calls = Self::transform_calls_clause(inner);
                 }
                 aadlight_parser::Rule::connections => {
-                    connections = Self::transform_connections_clause(inner);
+                    let clause = inner.clone();
+                    (connections, connection_refinements) = Self::transform_connections_clause(inner);
+                    if extends.is_none() {
+                        Self::reject_refinements(&clause, &name.to_string(), connection_refinements.drain(..).map(|r| r.original_name));
+                    }
                 }
                 aadlight_parser::Rule::properties => {
                     properties = Self::transform_properties_clause(inner);
@@ -966,20 +1422,39 @@ impl AADLTransformer {
             }
         }
         
-        ComponentImplementation {
-            category,
-            name,
-            prototype_bindings: None,
-            prototypes,
-            subcomponents,
-            calls,
-            connections,
-            properties,
-            annexes,
+        match extends {
+            Some(extends) => AadlDeclaration::ComponentImplementationExtension(ComponentImplementationExtension {
+                category,
+                name,
+                extends,
+                prototype_bindings,
+                prototypes,
+                subcomponents,
+                subcomponent_refinements,
+                calls,
+                connections,
+                connection_refinements,
+                properties,
+                annexes,
+                location: None, // 由 transform_declaration 填写
+            }),
+            None => AadlDeclaration::ComponentImplementation(ComponentImplementation {
+                category,
+                name,
+                prototype_bindings: None,
+                prototypes,
+                subcomponents,
+                calls,
+                connections,
+                properties,
+                annexes,
+                location: None, // 由 transform_declaration 填写
+            }),
         }
     }
     
-    pub fn transform_subcomponents_clause(pair: Pair<aadlight_parser::Rule>) -> SubcomponentClause {
+    // 返回 (新声明的子组件, refined to 精化的子组件)
+    pub fn transform_subcomponents_clause(pair: Pair<aadlight_parser::Rule>) -> (SubcomponentClause, Vec<SubcomponentRefinement>) {
         // println!("=== 调试 subcomponents ===");
         // println!("pair = Rule::{:?}------text = {}", pair.as_rule(),pair.as_str());
         // for (i, inner) in pair.clone().into_inner().enumerate() {
@@ -988,21 +1463,27 @@ impl AADLTransformer {
         // }
 
         if pair.as_str().contains("none") {
-            return SubcomponentClause::Empty;
+            return (SubcomponentClause::Empty, Vec::new());
         }
         
         let mut subcomponents = Vec::new();
+        let mut refinements = Vec::new();
         for inner in pair.into_inner() {
             if inner.as_rule() == aadlight_parser::Rule::subcomponent {
-                subcomponents.push(Self::transform_subcomponent(inner));
+                if is_refinement(&inner) {
+                    refinements.push(Self::transform_subcomponent_refinement(inner));
+                } else {
+                    subcomponents.push(Self::transform_subcomponent(inner));
+                }
             }
         }
         
-        if subcomponents.is_empty() {
+        let subcomponents = if subcomponents.is_empty() {
             SubcomponentClause::None
         } else {
             SubcomponentClause::Items(subcomponents)
-        }
+        };
+        (subcomponents, refinements)
     }
     
     pub fn transform_subcomponent(pair: Pair<aadlight_parser::Rule>) -> Subcomponent {
@@ -1010,46 +1491,101 @@ impl AADLTransformer {
         let identifier = extract_identifier(inner_iter.next().unwrap());
         //let _colon = inner_iter.next();
         
-        let category = match inner_iter.next().unwrap().as_str() {
-            "system" => ComponentCategory::System,
-            "process" => ComponentCategory::Process,
-            "thread" => ComponentCategory::Thread,
-            "processor" => ComponentCategory::Processor,
-            "memory" => ComponentCategory::Memory,
-            "data" => ComponentCategory::Data,
-            "subprogram" => ComponentCategory::Subprogram,
-            "device" => ComponentCategory::Device,
-            s => panic!("Unknown subcomponent category: {}", s),
-        };
-        
-        // 处理 qualified_identifier，如果包含多个标识符就只取最后一个
-        let qualified_identifier = inner_iter.next().unwrap();
-        let name_str = if qualified_identifier.as_str().contains("::") {
-            // 如果包含 :: 分隔符，只取最后一个标识符（Base_Types::Float，只需要Float）
-            qualified_identifier.as_str().split("::").last().unwrap().trim().to_string()
-        } else {
-            // 否则直接使用原字符串
-            extract_identifier(qualified_identifier)
-        };
-        let mut name_parts = name_str.split(".");
-        let classifier = SubcomponentClassifier::ClassifierReference(
-            UniqueComponentClassifierReference::Implementation(UniqueImplementationReference {
-                package_prefix: None,
-                implementation_name: ImplementationName {
-                    type_identifier: name_parts.next().unwrap().to_string(),
-                    implementation_identifier: name_parts.next().unwrap_or("").to_string(),
-                },
-            }),
-        );
+        let category = Self::transform_component_category(inner_iter.next().unwrap());
+        let classifier = Self::transform_subcomponent_classifier(inner_iter.next().unwrap());
+        let mut inner_iter = inner_iter.peekable();
+        let prototype_bindings = inner_iter
+            .next_if(|p| p.as_rule() == aadlight_parser::Rule::prototype_bindings)
+            .map(Self::transform_prototype_bindings);
+        let array_spec = inner_iter
+            .next_if(|p| p.as_rule() == aadlight_parser::Rule::array_spec)
+            .map(Self::transform_array_spec);
+        let properties = inner_iter
+            .next_if(|p| matches!(p.as_rule(), aadlight_parser::Rule::property_block | aadlight_parser::Rule::properties))
+            .map(Self::transform_property_block)
+            .unwrap_or_default();
+        for ignored in inner_iter {
+            diagnostics::warning_at(
+                &ignored,
+                codes::IGNORED_CONSTRUCT,
+                format!("`{}` of subcomponent {} is not supported yet and is ignored", ignored.as_str().trim(), identifier),
+            );
+        }
         
         Subcomponent {
             identifier,
             category,
             classifier,
-            array_spec: None, // TODO: Handle array spec
-            properties: Vec::new(), // TODO: Handle properties
+            prototype_bindings,
+            array_spec,
+            properties,
+        }
+    }
+
+    // name : refined to category [classifier]; 省略分类器时沿用祖先中的分类器
+    pub fn transform_subcomponent_refinement(pair: Pair<aadlight_parser::Rule>) -> SubcomponentRefinement {
+        let mut inner_iter = pair.into_inner().filter(|p| p.as_rule() != aadlight_parser::Rule::refined_to);
+        let identifier = extract_identifier(inner_iter.next().unwrap());
+        let category = Self::transform_component_category(inner_iter.next().unwrap());
+        let mut inner_iter = inner_iter.peekable();
+        let classifier = inner_iter
+            .next_if(|p| p.as_rule() == aadlight_parser::Rule::qualified_identifier)
+            .map(Self::transform_subcomponent_classifier);
+        let prototype_bindings = inner_iter
+            .next_if(|p| p.as_rule() == aadlight_parser::Rule::prototype_bindings)
+            .map(Self::transform_prototype_bindings);
+        let array_spec = inner_iter
+            .next_if(|p| p.as_rule() == aadlight_parser::Rule::array_spec)
+            .map(Self::transform_array_spec);
+        let properties = inner_iter
+            .next_if(|p| matches!(p.as_rule(), aadlight_parser::Rule::property_block | aadlight_parser::Rule::properties))
+            .map(Self::transform_property_block)
+            .unwrap_or_default();
+
+        SubcomponentRefinement {
+            identifier,
+            category,
+            classifier,
+            prototype_bindings,
+            array_spec,
+            properties,
+        }
+    }
+
+    // [8] / [2, 3] / []：每一维给出元素个数，空维度留待精化时确定
+    fn transform_array_spec(pair: Pair<aadlight_parser::Rule>) -> ArraySpec {
+        let mut dimensions = Vec::new();
+        for dimension in pair.clone().into_inner() {
+            let size = match dimension.as_str().trim() {
+                "" => None,
+                text => match text.parse::<u32>() {
+                    Ok(size) if size > 0 => Some(ArrayDimensionSize::Fixed(size)),
+                    _ => {
+                        diagnostics::error_at(&dimension, codes::INVALID_ARRAY_DIMENSION, format!("invalid array dimension `{}`", text));
+                        None
+                    }
+                },
+            };
+            dimensions.push(ArrayDimension { size });
+        }
+        ArraySpec {
+            dimensions,
+            element_implementations: None,
         }
     }
+
+    // 子组件/连接声明末尾的 { ... } 属性块
+    fn transform_property_block(pair: Pair<aadlight_parser::Rule>) -> Vec<Property> {
+        pair.into_inner()
+            .filter(|p| p.as_rule() == aadlight_parser::Rule::property_association)
+            .filter_map(Self::transform_property_association)
+            .collect()
+    }
+
+    fn transform_subcomponent_classifier(qualified_identifier: Pair<aadlight_parser::Rule>) -> SubcomponentClassifier {
+        // 包前缀单独保存供跨包解析（Base_Types::Float 的类型名为 Float）
+        SubcomponentClassifier::ClassifierReference(classifier_reference(qualified_identifier.as_str()))
+    }
     
     pub fn transform_calls_clause(pair: Pair<aadlight_parser::Rule>) -> CallSequenceClause {
 
@@ -1129,26 +1665,39 @@ impl AADLTransformer {
         }
     }
     
-    pub fn transform_connections_clause(pair: Pair<aadlight_parser::Rule>) -> ConnectionClause {
+    // 返回 (新声明的连接, refined to 精化的连接)
+    pub fn transform_connections_clause(pair: Pair<aadlight_parser::Rule>) -> (ConnectionClause, Vec<ConnectionRefinement>) {
         if pair.as_str().contains("none") {
-            return ConnectionClause::Empty;
+            return (ConnectionClause::Empty, Vec::new());
         }
         
         let mut connections = Vec::new();
+        let mut refinements = Vec::new();
         for inner in pair.into_inner() {
             if inner.as_rule() == aadlight_parser::Rule::connection {
-                connections.push(Self::transform_connection(inner));
+                if is_refinement(&inner) {
+                    let mut parts = inner.into_inner();
+                    let original_name = parts.next().map(extract_identifier).unwrap_or_default();
+                    let properties = parts
+                        .find(|p| p.as_rule() == aadlight_parser::Rule::property_block)
+                        .map(Self::transform_property_block)
+                        .unwrap_or_default();
+                    refinements.push(ConnectionRefinement { original_name, properties });
+                } else {
+                    connections.extend(Self::transform_connection(inner));
+                }
             }
         }
         
-        if connections.is_empty() {
+        let connections = if connections.is_empty() {
             ConnectionClause::None
         } else {
             ConnectionClause::Items(connections)
-        }
+        };
+        (connections, refinements)
     }
     
-    pub fn transform_connection(pair: Pair<aadlight_parser::Rule>) -> Connection {
+    pub fn transform_connection(pair: Pair<aadlight_parser::Rule>) -> Option<Connection> {
         // println!("=== 调试 connection ===");
         // println!("pair = Rule::{:?}, text = {}", pair.as_rule(), pair.as_str());
 
@@ -1157,67 +1706,78 @@ impl AADLTransformer {
         // }
 
 
-        let mut inner_iter = pair.into_inner();
-        let identifier = extract_identifier(inner_iter.next().unwrap());
+        let mut inner_iter = pair.clone().into_inner();
+        let identifier = extract_identifier(inner_iter.next()?);
         //let _colon = inner_iter.next();
         
-        let connection_type = inner_iter.next().unwrap();
-        let connection_body = inner_iter.next().unwrap(); // port_connection or parameter_connection
+        let connection_type = inner_iter.next()?;
+        let connection_body = inner_iter.next()?; // port_connection or parameter_connection
+        let properties = inner_iter.next().map(Self::transform_property_block).unwrap_or_default();
 
         match connection_type.as_str() {
             "port" => {
             let mut port_iter = connection_body.into_inner();
 
-            let source = Self::transform_port_reference(port_iter.next().unwrap());
-            let direction = match port_iter.next().unwrap().as_str() {
-                "->" => ConnectionSymbol::Direct,
-                "<->" => ConnectionSymbol::Didirect,
-                _ => panic!("Unknown connection direction"),
-            };
-            let destination = Self::transform_port_reference(port_iter.next().unwrap());
+            let source = Self::transform_port_reference(port_iter.next()?);
+            let direction = Self::transform_connection_symbol(port_iter.next()?)?;
+            let destination = Self::transform_port_reference(port_iter.next()?);
 
-            Connection::Port(PortConnection {
+            Some(Connection::Port(PortConnection {
                 identifier,
                 source,
                 destination,
                 connection_direction: direction,
-            })
+                properties,
+            }))
         }
             "parameter" => {
                 let mut port_iter = connection_body.into_inner();
 
-                let source = Self::transform_parameterport_reference(port_iter.next().unwrap());
-                let direction = match port_iter.next().unwrap().as_str() {
-                    "->" => ConnectionSymbol::Direct,
-                    "<->" => ConnectionSymbol::Didirect,
-                    _ => panic!("Unknown connection direction"),
-                };
-                let destination = Self::transform_parameterport_reference(port_iter.next().unwrap());
-                Connection::Parameter(ParameterConnection {
+                let source = Self::transform_parameterport_reference(port_iter.next()?);
+                let direction = Self::transform_connection_symbol(port_iter.next()?)?;
+                let destination = Self::transform_parameterport_reference(port_iter.next()?);
+                Some(Connection::Parameter(ParameterConnection {
                     source,
                     destination,
                     connection_direction: direction,
-                })
+                }))
             }
-            "data access" | "subprogram access" => {
+            "data access" | "subprogram access" | "bus access" | "virtual bus access" => {
                 let mut port_iter = connection_body.into_inner();
 
-                let source = Self::transform_access_reference(port_iter.next().unwrap());
-                let direction = match port_iter.next().unwrap().as_str() {
-                    "->" => ConnectionSymbol::Direct,
-                    "<->" => ConnectionSymbol::Didirect,
-                    _ => panic!("Unknown connection direction"),
-                };
-                let destination = Self::transform_access_reference(port_iter.next().unwrap());
+                let source = Self::transform_access_reference(port_iter.next()?);
+                let direction = Self::transform_connection_symbol(port_iter.next()?)?;
+                let destination = Self::transform_access_reference(port_iter.next()?);
 
-                Connection::Access(AccessConnection {
+                Some(Connection::Access(AccessConnection {
+                    identifier,
+                    connection_type: access_connection_type(connection_type.as_str().trim_end_matches(" access")),
                     source,
                     destination,
                     connection_direction: direction,
-                })
+                    properties,
+                }))
             }
             
-            _ => panic!("Unknown connection type"),
+            other => {
+                diagnostics::error_at(
+                    &pair,
+                    codes::MALFORMED_CONNECTION,
+                    format!("connection {} has unsupported kind `{}`", identifier, other),
+                );
+                None
+            }
+        }
+    }
+
+    pub fn transform_connection_symbol(pair: Pair<aadlight_parser::Rule>) -> Option<ConnectionSymbol> {
+        match pair.as_str() {
+            "->" => Some(ConnectionSymbol::Direct),
+            "<->" => Some(ConnectionSymbol::Didirect),
+            other => {
+                diagnostics::error_at(&pair, codes::MALFORMED_CONNECTION, format!("unknown connection symbol `{}`", other));
+                None
+            }
         }
     }
     
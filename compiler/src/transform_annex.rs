@@ -5,6 +5,7 @@
 use crate::aadlight_parser;
 use super::ast::aadl_ast_cj::*;
 use pest::{iterators::Pair};
+use crate::diagnostics::{self, codes};
 
 // 辅助函数：从 Pair 中提取标识符
 pub fn extract_identifier(pair: Pair<aadlight_parser::Rule>) -> String {
@@ -798,7 +799,10 @@ pub fn transform_behavior_expression(pair: Pair<aadlight_parser::Rule>) -> Value
             "and" => LogicalOperator::And,
             "or" => LogicalOperator::Or,
             "xor" => LogicalOperator::Xor,
-            _ => panic!("Unknown logical operator: {}", logical_op.as_str()),
+            _ => {
+                diagnostics::error_at(&logical_op, codes::UNKNOWN_OPERATOR, format!("unknown logical operator `{}`", logical_op.as_str()));
+                LogicalOperator::And
+            }
         };
         
         let right_relation = inner_iter.next().unwrap();
@@ -823,7 +827,10 @@ fn transform_relation(pair: Pair<aadlight_parser::Rule>) -> Relation {
             "<=" => RelationalOperator::LessThanOrEqual,
             ">" => RelationalOperator::GreaterThan,
             ">=" => RelationalOperator::GreaterThanOrEqual,
-            _ => panic!("Unknown relational operator: {}", relational_op.as_str()),
+            _ => {
+                diagnostics::error_at(&relational_op, codes::UNKNOWN_OPERATOR, format!("unknown relational operator `{}`", relational_op.as_str()));
+                RelationalOperator::Equal
+            }
         };
         
         let right = transform_simple_expression(inner_iter.next().unwrap());
@@ -846,7 +853,10 @@ fn transform_simple_expression(pair: Pair<aadlight_parser::Rule>) -> SimpleExpre
             match op.as_str() {
                 "+" => Some(UnaryAddingOperator::Plus),
                 "-" => Some(UnaryAddingOperator::Minus),
-                _ => panic!("Unknown unary adding operator: {}", op.as_str()),
+                _ => {
+                    diagnostics::error_at(&op, codes::UNKNOWN_OPERATOR, format!("unknown unary adding operator `{}`", op.as_str()));
+                    None
+                }
             }
         } else {
             None
@@ -865,7 +875,10 @@ fn transform_simple_expression(pair: Pair<aadlight_parser::Rule>) -> SimpleExpre
             let operator = match binary_op.as_str() {
                 "+" => AdditiveOperator::Add,
                 "-" => AdditiveOperator::Subtract,
-                _ => panic!("Unknown binary adding operator: {}", binary_op.as_str()),
+                _ => {
+                    diagnostics::error_at(&binary_op, codes::UNKNOWN_OPERATOR, format!("unknown binary adding operator `{}`", binary_op.as_str()));
+                    AdditiveOperator::Add
+                }
             };
             
             let right_term = transform_term(inner_iter.next().unwrap());
@@ -911,7 +924,10 @@ fn transform_term(pair: Pair<aadlight_parser::Rule>) -> Term {
                 "/" => MultiplicativeOperator::Divide,
                 "mod" => MultiplicativeOperator::Modulo,
                 "rem" => MultiplicativeOperator::Remainder,
-                _ => panic!("Unknown multiplying operator: {}", mult_op.as_str()),
+                _ => {
+                    diagnostics::error_at(&mult_op, codes::UNKNOWN_OPERATOR, format!("unknown multiplying operator `{}`", mult_op.as_str()));
+                    MultiplicativeOperator::Multiply
+                }
             };
             
             let right_factor = transform_factor(inner_iter.next().unwrap());
@@ -952,7 +968,10 @@ fn transform_factor(pair: Pair<aadlight_parser::Rule>) -> Factor {
                 if binary_op.as_rule() == aadlight_parser::Rule::binary_numeric_operator {
                     let operator = match binary_op.as_str() {
                         "**" => BinaryNumericOperator::Power,
-                        _ => panic!("Unknown binary numeric operator: {}", binary_op.as_str()),
+                        _ => {
+                            diagnostics::error_at(&binary_op, codes::UNKNOWN_OPERATOR, format!("unknown binary numeric operator `{}`", binary_op.as_str()));
+                            BinaryNumericOperator::Power
+                        }
                     };
                     
                     let right = transform_value(inner_iter.next().unwrap());
@@ -968,7 +987,10 @@ fn transform_factor(pair: Pair<aadlight_parser::Rule>) -> Factor {
         aadlight_parser::Rule::unary_numeric_operator => {
             let operator = match first.as_str() {
                 "abs" => UnaryNumericOperator::Abs,
-                _ => panic!("Unknown unary numeric operator: {}", first.as_str()),
+                _ => {
+                    diagnostics::error_at(&first, codes::UNKNOWN_OPERATOR, format!("unknown unary numeric operator `{}`", first.as_str()));
+                    UnaryNumericOperator::Abs
+                }
             };
             
             let value = transform_value(inner_iter.next().unwrap());
@@ -977,7 +999,10 @@ fn transform_factor(pair: Pair<aadlight_parser::Rule>) -> Factor {
         aadlight_parser::Rule::unary_boolean_operator => {
             let operator = match first.as_str() {
                 "not" => UnaryBooleanOperator::Not,
-                _ => panic!("Unknown unary boolean operator: {}", first.as_str()),
+                _ => {
+                    diagnostics::error_at(&first, codes::UNKNOWN_OPERATOR, format!("unknown unary boolean operator `{}`", first.as_str()));
+                    UnaryBooleanOperator::Not
+                }
             };
             
             let value = transform_value(inner_iter.next().unwrap());
@@ -987,7 +1012,10 @@ fn transform_factor(pair: Pair<aadlight_parser::Rule>) -> Factor {
             // 处理嵌套的 factor 规则
             transform_factor(first)
         }
-        _ => panic!("Unexpected factor rule: {:?}", first.as_rule()),
+        rule => {
+            diagnostics::error_at(&first, codes::MALFORMED_BEHAVIOR, format!("unexpected factor {:?}", rule));
+            Factor::Value(Value::Constant(ValueConstant::Boolean(false)))
+        }
     }
 }
 
@@ -1017,7 +1045,10 @@ fn transform_value(pair: Pair<aadlight_parser::Rule>) -> Value {
             };
             Value::Expression(Box::new(value_expr))
         }
-        _ => panic!("Unknown value rule: {:?}", inner.as_rule()),
+        rule => {
+            diagnostics::error_at(&inner, codes::MALFORMED_BEHAVIOR, format!("unexpected value {:?}", rule));
+            Value::Constant(ValueConstant::Boolean(false))
+        }
     }
 }
 
@@ -1033,7 +1064,10 @@ fn transform_value_constant(pair: Pair<aadlight_parser::Rule>) -> ValueConstant
             let val = match inner.as_str() {
                 "true" => true,
                 "false" => false,
-                _ => panic!("Invalid boolean: {}", inner.as_str()),
+                other => {
+                    diagnostics::error_at(&inner, codes::MALFORMED_BEHAVIOR, format!("invalid boolean `{}`", other));
+                    false
+                }
             };
             ValueConstant::Boolean(val)
         }
@@ -1046,7 +1080,10 @@ fn transform_value_constant(pair: Pair<aadlight_parser::Rule>) -> ValueConstant
             };
             ValueConstant::String(value)
         }
-        _ => panic!("Unknown value constant rule: {:?}", inner.as_rule()),
+        rule => {
+            diagnostics::error_at(&inner, codes::MALFORMED_BEHAVIOR, format!("unexpected constant {:?}", rule));
+            ValueConstant::Boolean(false)
+        }
     }
 }
 
@@ -1,3 +1,4 @@
+#[allow(unused_imports)]
 use compiler::test_mod;
 use compiler::test_mod2;
 